@@ -0,0 +1,165 @@
+//! A smoke test that exercises the crate against a live database.
+//!
+//! It connects, creates a scratch table for each supported type, round-trips a value through it
+//! and prints a report of what worked. With no configuration it expects the database used by the
+//! crate tests, e.g. the [Docker container][1] started via:
+//!
+//! ```text
+//! docker run -d -p 1521:1521 wnameless/oracle-xe-11g
+//! ```
+//!
+//! The connection details can be overridden with the `OCI_RS_CONNECTION`, `OCI_RS_USER` and
+//! `OCI_RS_PASSWORD` environment variables.
+//!
+//! ```text
+//! cargo run --example smoke --features examples
+//! ```
+//!
+//! [1]: https://github.com/wnameless/docker-oracle-xe-11g
+#![allow(deprecated)]
+
+use chrono::{Date, DateTime, FixedOffset, TimeZone, Utc};
+use oci_rs::connection::Connection;
+use oci_rs::types::{FromSqlValue, ToSqlValue};
+use std::env;
+use std::fmt::Debug;
+use std::process;
+
+const TABLE: &str = "OciRsSmoke";
+
+/// The result of a single check.
+enum Outcome {
+    Passed,
+    Failed(String),
+    Unsupported,
+}
+
+/// Reads a setting from the environment, falling back to the test database defaults.
+fn setting(name: &str, default: &str) -> String {
+    env::var(name).unwrap_or_else(|_| default.to_string())
+}
+
+/// Creates a scratch table with a single column, inserts `value` and reads it back.
+fn round_trip<T>(conn: &Connection, column_type: &str, value: T) -> Outcome
+where
+    T: ToSqlValue + FromSqlValue + PartialEq + Debug,
+{
+    match try_round_trip(conn, column_type, &value) {
+        Ok(ref fetched) if *fetched == value => Outcome::Passed,
+        Ok(fetched) => Outcome::Failed(format!("expected {:?}, fetched {:?}", value, fetched)),
+        Err(err) => Outcome::Failed(err),
+    }
+}
+
+fn try_round_trip<T>(conn: &Connection, column_type: &str, value: &T) -> Result<T, String>
+where
+    T: ToSqlValue + FromSqlValue,
+{
+    drop_table(conn);
+    let sql_create = format!("CREATE TABLE {} (Value {})", TABLE, column_type);
+    let mut create = conn
+        .create_prepared_statement(&sql_create)
+        .map_err(|err| err.to_string())?;
+    create.execute().map_err(|err| err.to_string())?;
+
+    let sql_insert = format!("INSERT INTO {} (Value) VALUES (:value)", TABLE);
+    let mut insert = conn
+        .create_prepared_statement(&sql_insert)
+        .map_err(|err| err.to_string())?;
+    insert.bind(&[value]).map_err(|err| err.to_string())?;
+    insert.execute().map_err(|err| err.to_string())?;
+    insert.commit().map_err(|err| err.to_string())?;
+
+    let sql_select = format!("SELECT Value FROM {}", TABLE);
+    let mut select = conn
+        .create_prepared_statement(&sql_select)
+        .map_err(|err| err.to_string())?;
+    select.execute().map_err(|err| err.to_string())?;
+    let result_set = select.result_set().map_err(|err| err.to_string())?;
+    let fetched = match result_set.first() {
+        Some(row) => row[0].value::<T>(),
+        None => return Err("no rows returned".to_string()),
+    };
+    fetched.ok_or_else(|| "value could not be converted".to_string())
+}
+
+/// Every byte value in turn, repeated to make up `length` bytes.
+fn bytes(length: usize) -> Vec<u8> {
+    (0..=u8::MAX).cycle().take(length).collect()
+}
+
+fn drop_table(conn: &Connection) {
+    let sql_drop = format!("DROP TABLE {}", TABLE);
+    if let Ok(mut drop) = conn.create_prepared_statement(&sql_drop) {
+        drop.execute().ok();
+    }
+}
+
+fn main() {
+    let connection = setting("OCI_RS_CONNECTION", "localhost:1521/xe");
+    let user = setting("OCI_RS_USER", "oci_rs");
+    let password = setting("OCI_RS_PASSWORD", "test");
+
+    let conn = match Connection::new(&connection, &user, &password) {
+        Ok(conn) => conn,
+        Err(err) => {
+            eprintln!("Failed to connect to {}: {}", connection, err);
+            process::exit(1);
+        }
+    };
+    println!("Connected to {} as {}", connection, user);
+
+    let now = Utc::now();
+    let checks = vec![
+        (
+            "VARCHAR2",
+            round_trip(&conn, "VARCHAR2(20)", "Smoke".to_string()),
+        ),
+        ("CHAR", round_trip(&conn, "CHAR(5)", "Smoke".to_string())),
+        ("NUMBER (integer)", round_trip(&conn, "INTEGER", 42_i64)),
+        ("NUMBER (float)", round_trip(&conn, "FLOAT", 42.5_f64)),
+        (
+            "DATE",
+            round_trip::<Date<Utc>>(&conn, "DATE", Utc.ymd(2017, 6, 30)),
+        ),
+        (
+            "TIMESTAMP",
+            round_trip::<DateTime<Utc>>(&conn, "TIMESTAMP(9)", now),
+        ),
+        (
+            "TIMESTAMP WITH TIME ZONE",
+            round_trip::<DateTime<FixedOffset>>(
+                &conn,
+                "TIMESTAMP(9) WITH TIME ZONE",
+                now.with_timezone(&FixedOffset::east(3600)),
+            ),
+        ),
+        // longer than a RAW can be, so they are bound and fetched as LOBs
+        ("BLOB", round_trip(&conn, "BLOB", bytes(5000))),
+        ("CLOB", round_trip(&conn, "CLOB", "Smoke ".repeat(1000))),
+        ("RAW", round_trip(&conn, "RAW(16)", bytes(16))),
+        // intervals have no Rust type, they can only be fetched as text by a registered codec
+        ("INTERVAL YEAR TO MONTH", Outcome::Unsupported),
+        ("INTERVAL DAY TO SECOND", Outcome::Unsupported),
+    ];
+    drop_table(&conn);
+
+    println!();
+    println!("{:<28} Result", "Oracle type");
+    let mut failures = 0;
+    for (name, outcome) in &checks {
+        let result = match *outcome {
+            Outcome::Passed => "ok".to_string(),
+            Outcome::Unsupported => "not supported yet".to_string(),
+            Outcome::Failed(ref reason) => {
+                failures += 1;
+                format!("FAILED: {}", reason)
+            }
+        };
+        println!("{:<28} {}", name, result);
+    }
+
+    if failures > 0 {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,186 @@
+//! Derive macros for the [`oci_rs`][1] crate, enabled with its `derive` feature.
+//!
+//! `#[derive(FromRow)]` converts the rows returned by a query into a struct and
+//! `#[derive(ToParams)]` binds a struct's fields as named parameters. Both are re-exported by
+//! `oci_rs` next to the traits they implement, `oci_rs::row::FromRow` and
+//! `oci_rs::types::ToParams`, which describe the `#[oci(...)]` attributes they take.
+//!
+//! [1]: https://crates.io/crates/oci_rs
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, quote_spanned};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Field, Fields, LitInt, LitStr};
+
+/// Implements `oci_rs::row::FromRow`, reading each field from the column of the same name, or
+/// for a tuple struct the column at the same position.
+///
+#[proc_macro_derive(FromRow, attributes(oci))]
+pub fn derive_from_row(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    from_row(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Implements `oci_rs::types::ToParams`, binding each field to the placeholder of the same name.
+///
+#[proc_macro_derive(ToParams, attributes(oci))]
+pub fn derive_to_params(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    to_params(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// The settings given to a field with `#[oci(...)]`.
+#[derive(Default)]
+struct FieldOptions {
+    rename: Option<LitStr>,
+    position: Option<LitInt>,
+    skip: bool,
+}
+
+impl FieldOptions {
+    fn parse(field: &Field) -> Result<FieldOptions, Error> {
+        let mut options = FieldOptions::default();
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("oci"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    options.rename = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("position") {
+                    let position: LitInt = meta.value()?.parse()?;
+                    position.base10_parse::<usize>()?;
+                    options.position = Some(position);
+                    Ok(())
+                } else if meta.path.is_ident("skip") {
+                    options.skip = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `rename`, `position` or `skip`"))
+                }
+            })?;
+        }
+        if let (Some(rename), Some(_)) = (&options.rename, &options.position) {
+            return Err(Error::new(
+                rename.span(),
+                "a field can be read by name or by position, not both",
+            ));
+        }
+        Ok(options)
+    }
+
+    /// The column or placeholder name of a named field.
+    fn name(&self, field: &Field) -> Option<String> {
+        match self.rename {
+            Some(ref rename) => Some(rename.value()),
+            None => field.ident.as_ref().map(|ident| ident.unraw().to_string()),
+        }
+    }
+}
+
+fn from_row(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let fields = match input.data {
+        Data::Struct(ref data) => &data.fields,
+        _ => {
+            return Err(Error::new(
+                input.ident.span(),
+                "FromRow can only be derived for a struct",
+            ))
+        }
+    };
+    let mut values = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        let options = FieldOptions::parse(field)?;
+        let span = field.ty.span();
+        let value = if options.skip {
+            quote_spanned! {span=> ::std::default::Default::default() }
+        } else if let Some(position) = options.position {
+            quote_spanned! {span=> row.try_get_at(#position)? }
+        } else if let Some(name) = options.name(field) {
+            quote_spanned! {span=> row.try_get(#name)? }
+        } else {
+            quote_spanned! {span=> row.try_get_at(#index)? }
+        };
+        values.push(match field.ident {
+            Some(ref ident) => quote! { #ident: #value },
+            None => value,
+        });
+    }
+    let construct = match *fields {
+        Fields::Named(_) => quote! { Self { #(#values),* } },
+        Fields::Unnamed(_) => quote! { Self(#(#values),*) },
+        Fields::Unit => quote! { Self },
+    };
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::oci_rs::row::FromRow for #name #type_generics #where_clause {
+            fn from_row(
+                row: &::oci_rs::row::Row,
+            ) -> ::std::result::Result<Self, ::oci_rs::oci_error::OciError> {
+                ::std::result::Result::Ok(#construct)
+            }
+        }
+    })
+}
+
+fn to_params(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let fields = match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => &fields.named,
+            _ => {
+                return Err(Error::new(
+                    input.ident.span(),
+                    "ToParams binds fields by name, so needs a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new(
+                input.ident.span(),
+                "ToParams can only be derived for a struct",
+            ))
+        }
+    };
+    let mut params = Vec::new();
+    for field in fields {
+        let options = FieldOptions::parse(field)?;
+        if let Some(position) = options.position {
+            return Err(Error::new(
+                position.span(),
+                "parameters are bound by name, so `position` can't be used with ToParams",
+            ));
+        }
+        if options.skip {
+            continue;
+        }
+        let ident = &field.ident;
+        let name = options.name(field);
+        params.push(quote_spanned! {field.ty.span()=>
+            (
+                ::std::string::String::from(#name),
+                ::oci_rs::types::ToSqlValue::to_sql_value(&self.#ident),
+            )
+        });
+    }
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::oci_rs::types::ToParams for #name #type_generics #where_clause {
+            fn to_params(
+                &self,
+            ) -> ::std::vec::Vec<(::std::string::String, ::oci_rs::types::SqlValue)> {
+                ::std::vec![#(#params),*]
+            }
+        }
+    })
+}
@@ -0,0 +1,348 @@
+use crate::connection::{Connection, ConnectionBuilder};
+use crate::oci_error::OciError;
+#[cfg(feature = "deadpool")]
+use deadpool::managed::Object;
+#[cfg(feature = "deadpool")]
+use log::{debug, warn};
+#[cfg(feature = "deadpool")]
+use std::collections::HashSet;
+use std::panic;
+use std::sync::{Arc, Mutex, MutexGuard};
+#[cfg(feature = "deadpool")]
+use std::time::Duration;
+use tokio::task;
+#[cfg(feature = "deadpool")]
+use tokio::time;
+
+/// A `Connection` that can be used from async code.
+///
+/// Every call into the OCI library blocks, so the work is moved onto Tokio's blocking thread
+/// pool via `spawn_blocking` and the async task only waits for the result. This keeps the
+/// runtime's worker threads free while the database is busy.
+///
+/// The connection is shared behind a mutex, so work submitted through clones of the same
+/// `AsyncConnection` runs one closure at a time.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # async fn example() -> Result<(), oci_rs::oci_error::OciError> {
+/// use oci_rs::async_connection::AsyncConnection;
+/// use oci_rs::auth::StaticPassword;
+/// use oci_rs::connection::ConnectionBuilder;
+///
+/// let builder = ConnectionBuilder::new("localhost:1521/xe")
+///     .auth(StaticPassword::new("oci_rs", "test"));
+/// let conn = AsyncConnection::connect(builder).await?;
+///
+/// let count: i64 = conn
+///     .run(|conn| {
+///         let mut select = conn.create_prepared_statement("SELECT COUNT(*) FROM dual")?;
+///         select.execute()?;
+///         let result_set = select.result_set()?;
+///         Ok(result_set[0][0].value().unwrap_or(0))
+///     })
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct AsyncConnection {
+    connection: Arc<Mutex<Connection>>,
+}
+impl AsyncConnection {
+    /// Creates a new `Connection` on the blocking thread pool.
+    ///
+    /// # Errors
+    ///
+    /// Any error in creating the connection will be returned.
+    ///
+    pub async fn connect(builder: ConnectionBuilder) -> Result<AsyncConnection, OciError> {
+        let connection = spawn(move || builder.build()).await?;
+        Ok(AsyncConnection::from(connection))
+    }
+
+    /// Runs a closure with the connection on the blocking thread pool and returns its result.
+    ///
+    /// The closure is free to create statements, execute them and fetch results. Statements can't
+    /// outlive the closure because they borrow the connection.
+    ///
+    /// # Errors
+    ///
+    /// Any error returned by the closure.
+    ///
+    /// # Panics
+    ///
+    /// If the closure panics then the panic is resumed in the calling task.
+    ///
+    pub async fn run<F, T>(&self, f: F) -> Result<T, OciError>
+    where
+        F: FnOnce(&Connection) -> Result<T, OciError> + Send + 'static,
+        T: Send + 'static,
+    {
+        let connection = Arc::clone(&self.connection);
+        spawn(move || f(&lock(&connection))).await
+    }
+
+    /// Checks that the connection to the database is still alive.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the connection is broken or the server is not available.
+    ///
+    pub async fn ping(&self) -> Result<(), OciError> {
+        self.run(|conn| conn.ping()).await
+    }
+}
+
+impl From<Connection> for AsyncConnection {
+    fn from(connection: Connection) -> Self {
+        AsyncConnection {
+            connection: Arc::new(Mutex::new(connection)),
+        }
+    }
+}
+
+/// Locks the connection. A panic in an earlier closure does not leave the connection in a
+/// state that Rust cares about, so a poisoned lock is simply taken over.
+fn lock(connection: &Mutex<Connection>) -> MutexGuard<'_, Connection> {
+    connection
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Runs blocking work on the blocking thread pool, resuming any panic in the calling task.
+async fn spawn<F, T>(f: F) -> Result<T, OciError>
+where
+    F: FnOnce() -> Result<T, OciError> + Send + 'static,
+    T: Send + 'static,
+{
+    match task::spawn_blocking(f).await {
+        Ok(result) => result,
+        Err(err) => match err.try_into_panic() {
+            Ok(payload) => panic::resume_unwind(payload),
+            Err(err) => panic!("Blocking database task failed: {}", err),
+        },
+    }
+}
+
+/// How a pooled connection is checked before it is handed out again.
+///
+#[cfg(any(feature = "deadpool", feature = "bb8"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Validation {
+    /// Connections are not checked.
+    None,
+    /// A light-weight round trip to the server that doesn't run any SQL, the default.
+    Ping,
+    /// Runs `SELECT 1 FROM dual`, which also checks that the session can run SQL.
+    Query,
+}
+
+/// A connection manager for the [`deadpool`][1] and [`bb8`][2] pools.
+///
+/// New connections are created from the `ConnectionBuilder`, so the builder's `AuthProvider` is
+/// asked for fresh credentials each time the pool grows. Connections are validated, by default
+/// with a ping, before being handed out again. With bb8 this needs `test_on_check_out`, which is
+/// on by default, and bb8's own `idle_timeout` evicts idle connections. With deadpool
+/// [`spawn_health_check`][3] can be used to evict dead and idle connections in the background.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # #[cfg(feature = "deadpool")]
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// use oci_rs::async_connection::ConnectionManager;
+/// use oci_rs::auth::StaticPassword;
+/// use oci_rs::connection::ConnectionBuilder;
+///
+/// let builder = ConnectionBuilder::new("localhost:1521/xe")
+///     .auth(StaticPassword::new("oci_rs", "test"));
+/// let pool = deadpool::managed::Pool::builder(ConnectionManager::new(builder))
+///     .max_size(8)
+///     .build()?;
+///
+/// let conn = pool.get().await?;
+/// conn.run(|conn| conn.ping()).await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [1]: https://crates.io/crates/deadpool
+/// [2]: https://crates.io/crates/bb8
+/// [3]: fn.spawn_health_check.html
+#[cfg(any(feature = "deadpool", feature = "bb8"))]
+#[derive(Debug, Clone)]
+pub struct ConnectionManager {
+    builder: ConnectionBuilder,
+    validation: Validation,
+}
+#[cfg(any(feature = "deadpool", feature = "bb8"))]
+impl ConnectionManager {
+    /// Creates a new `ConnectionManager`.
+    ///
+    pub fn new(builder: ConnectionBuilder) -> ConnectionManager {
+        ConnectionManager {
+            builder,
+            validation: Validation::Ping,
+        }
+    }
+
+    /// Sets how connections are validated when they are taken from the pool.
+    ///
+    pub fn validation(mut self, validation: Validation) -> ConnectionManager {
+        self.validation = validation;
+        self
+    }
+
+    /// Checks that a connection is still usable, in the way set by `.validation`.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the connection is broken or the server is not available.
+    ///
+    pub async fn validate(&self, conn: &AsyncConnection) -> Result<(), OciError> {
+        match self.validation {
+            Validation::None => Ok(()),
+            Validation::Ping => conn.ping().await,
+            Validation::Query => {
+                conn.run(|conn| {
+                    let mut select = conn.create_prepared_statement("SELECT 1 FROM dual")?;
+                    select.execute()?;
+                    select.result_set().map(|_| ())
+                })
+                .await
+            }
+        }
+    }
+}
+
+#[cfg(feature = "deadpool")]
+impl deadpool::managed::Manager for ConnectionManager {
+    type Type = AsyncConnection;
+    type Error = OciError;
+
+    async fn create(&self) -> Result<AsyncConnection, OciError> {
+        AsyncConnection::connect(self.builder.clone()).await
+    }
+
+    async fn recycle(
+        &self,
+        conn: &mut AsyncConnection,
+        _: &deadpool::managed::Metrics,
+    ) -> deadpool::managed::RecycleResult<OciError> {
+        Ok(self.validate(conn).await?)
+    }
+}
+
+#[cfg(feature = "bb8")]
+impl bb8::ManageConnection for ConnectionManager {
+    type Connection = AsyncConnection;
+    type Error = OciError;
+
+    async fn connect(&self) -> Result<AsyncConnection, OciError> {
+        AsyncConnection::connect(self.builder.clone()).await
+    }
+
+    async fn is_valid(&self, conn: &mut AsyncConnection) -> Result<(), OciError> {
+        self.validate(conn).await
+    }
+
+    fn has_broken(&self, _: &mut AsyncConnection) -> bool {
+        false
+    }
+}
+
+/// Settings for the background health check of a deadpool pool.
+///
+#[cfg(feature = "deadpool")]
+#[derive(Debug, Clone, Copy)]
+pub struct HealthCheck {
+    interval: Duration,
+    idle_timeout: Option<Duration>,
+}
+#[cfg(feature = "deadpool")]
+impl HealthCheck {
+    /// Creates a new `HealthCheck` that runs every `interval`.
+    ///
+    pub fn new(interval: Duration) -> HealthCheck {
+        HealthCheck {
+            interval,
+            idle_timeout: None,
+        }
+    }
+
+    /// Evicts connections that have not been used for longer than `idle_timeout`.
+    ///
+    pub fn idle_timeout(mut self, idle_timeout: Duration) -> HealthCheck {
+        self.idle_timeout = Some(idle_timeout);
+        self
+    }
+}
+
+/// Starts a task that regularly checks the idle connections in a deadpool pool.
+///
+/// Each time it runs, connections that have been idle for longer than the idle timeout are
+/// closed and the remaining idle connections are taken out of the pool and validated, which
+/// drops any that have died, e.g. after a database restart or a firewall timing them out. Only
+/// one connection is out of the pool at a time, each is put back as soon as it has been checked.
+/// With the pool's default first in, first out queue every idle connection is checked, with
+/// `QueueMode::Lifo` the same connection comes straight back and only it is checked. The task
+/// runs until it is aborted via the returned handle.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// use oci_rs::async_connection::{spawn_health_check, ConnectionManager, HealthCheck};
+/// use oci_rs::auth::StaticPassword;
+/// use oci_rs::connection::ConnectionBuilder;
+/// use std::time::Duration;
+///
+/// let builder = ConnectionBuilder::new("localhost:1521/xe")
+///     .auth(StaticPassword::new("oci_rs", "test"));
+/// let pool = deadpool::managed::Pool::builder(ConnectionManager::new(builder)).build()?;
+///
+/// let health_check = HealthCheck::new(Duration::from_secs(60))
+///     .idle_timeout(Duration::from_secs(600));
+/// let handle = spawn_health_check(pool.clone(), health_check);
+/// # Ok(())
+/// # }
+/// ```
+///
+#[cfg(feature = "deadpool")]
+pub fn spawn_health_check(
+    pool: deadpool::managed::Pool<ConnectionManager>,
+    health_check: HealthCheck,
+) -> task::JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            time::sleep(health_check.interval).await;
+            if let Some(idle_timeout) = health_check.idle_timeout {
+                let result = pool.retain(|_, metrics| metrics.last_used() < idle_timeout);
+                if !result.removed.is_empty() {
+                    debug!("Evicted {} idle connections", result.removed.len());
+                }
+            }
+            // Taking a connection out recycles, and so validates, it. Each one goes straight
+            // back so that the pool is never left without its idle connections, and the check
+            // stops once one comes round again.
+            let mut checked = HashSet::new();
+            for _ in 0..pool.status().available {
+                if pool.status().available == 0 {
+                    break;
+                }
+                match time::timeout(health_check.interval, pool.get()).await {
+                    Ok(Ok(conn)) => {
+                        if !checked.insert(Object::id(&conn)) {
+                            break;
+                        }
+                    }
+                    Ok(Err(err)) => warn!("Health check could not get a connection: {}", err),
+                    Err(_) => break,
+                }
+            }
+        }
+    })
+}
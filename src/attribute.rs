@@ -0,0 +1,53 @@
+/// The OCI handle that an attribute belongs to.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeHandle {
+    /// The environment handle.
+    Environment,
+    /// The server handle, i.e. the network connection.
+    Server,
+    /// The service context handle.
+    Service,
+    /// The user session handle.
+    Session,
+}
+
+/// The value of an OCI attribute.
+///
+/// The variant has to match the C type that OCI documents for the attribute, e.g. `ub4` is
+/// `UInt` and `oratext *` is `Text`.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttributeValue {
+    /// `oratext *`, text together with its length.
+    Text(String),
+    /// `ub1`
+    UByte(u8),
+    /// `ub2`
+    UShort(u16),
+    /// `ub4`
+    UInt(u32),
+    /// `sb4`
+    Int(i32),
+    /// `boolean`
+    Bool(bool),
+}
+
+/// The C type of an attribute that is being read, see [`AttributeValue`][1].
+///
+/// [1]: enum.AttributeValue.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeKind {
+    /// `oratext *`
+    Text,
+    /// `ub1`
+    UByte,
+    /// `ub2`
+    UShort,
+    /// `ub4`
+    UInt,
+    /// `sb4`
+    Int,
+    /// `boolean`
+    Bool,
+}
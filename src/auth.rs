@@ -0,0 +1,382 @@
+use crate::oci_error::OciError;
+use crate::secret::SecretString;
+use std::env;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+/// The credentials used to start a user session.
+///
+#[derive(Debug, Clone)]
+pub enum Credentials {
+    /// A database user name and password.
+    Password {
+        /// The database user.
+        user_name: String,
+        /// The password for the user.
+        password: SecretString,
+    },
+    /// Authentication is handled outside of the database, e.g. by the operating system, an
+    /// Oracle wallet or Kerberos. Which one is used depends on the Oracle Net configuration
+    /// (`sqlnet.ora`) on the client.
+    External,
+    /// An OAuth2 or IAM database access token.
+    Token {
+        /// The token.
+        token: SecretString,
+        /// The private key that goes with an IAM token, not needed for OAuth2 tokens.
+        private_key: Option<SecretString>,
+    },
+}
+impl Credentials {
+    /// Returns the user name, if the credentials carry one.
+    ///
+    pub fn user_name(&self) -> Option<&str> {
+        match *self {
+            Credentials::Password { ref user_name, .. } => Some(user_name),
+            Credentials::External | Credentials::Token { .. } => None,
+        }
+    }
+}
+
+/// Supplies the credentials needed to start a user session.
+///
+/// A provider is asked for credentials every time a connection is created, which allows
+/// passwords or tokens to be rotated without any changes to the code that creates connections.
+/// A [`ConnectionBuilder`][1] holds on to its provider so that pools and reconnect logic built on
+/// top of it always use fresh credentials.
+///
+/// # Examples
+///
+/// A provider that reads the password from the environment on each connection:
+///
+/// ```rust,no_run
+/// use oci_rs::auth::{AuthProvider, Credentials};
+/// use oci_rs::connection::ConnectionBuilder;
+/// use oci_rs::oci_error::OciError;
+/// use std::env;
+///
+/// #[derive(Debug)]
+/// struct EnvPassword;
+///
+/// impl AuthProvider for EnvPassword {
+///     fn credentials(&self) -> Result<Credentials, OciError> {
+///         let password = env::var("APP_DB_PASSWORD")
+///             .map_err(|err| OciError::Conversion(Box::new(err)))?;
+///         Ok(Credentials::Password {
+///             user_name: "app".to_string(),
+///             password: password.into(),
+///         })
+///     }
+/// }
+///
+/// let connection = ConnectionBuilder::new("localhost:1521/xe")
+///     .auth(EnvPassword)
+///     .build()
+///     .unwrap();
+/// ```
+///
+/// [1]: ../connection/struct.ConnectionBuilder.html
+pub trait AuthProvider: fmt::Debug + Send + Sync {
+    /// Returns the credentials to use for a new session.
+    ///
+    fn credentials(&self) -> Result<Credentials, OciError>;
+
+    /// Lets the provider add context to an error from connecting or starting the session.
+    ///
+    /// The Oracle errors raised by external authentication are often terse, so providers can use
+    /// this to point at the likely cause. By default the error is returned unchanged.
+    ///
+    fn explain_error(&self, err: OciError) -> OciError {
+        err
+    }
+}
+
+impl<T: AuthProvider + ?Sized> AuthProvider for Arc<T> {
+    fn credentials(&self) -> Result<Credentials, OciError> {
+        (**self).credentials()
+    }
+
+    fn explain_error(&self, err: OciError) -> OciError {
+        (**self).explain_error(err)
+    }
+}
+
+/// A fixed user name and password.
+///
+#[derive(Debug, Clone)]
+pub struct StaticPassword {
+    user_name: String,
+    password: SecretString,
+}
+impl StaticPassword {
+    /// Creates a new `StaticPassword`.
+    ///
+    pub fn new<P: Into<SecretString>>(user_name: &str, password: P) -> StaticPassword {
+        StaticPassword {
+            user_name: user_name.to_string(),
+            password: password.into(),
+        }
+    }
+}
+
+impl AuthProvider for StaticPassword {
+    fn credentials(&self) -> Result<Credentials, OciError> {
+        Ok(Credentials::Password {
+            user_name: self.user_name.clone(),
+            password: self.password.clone(),
+        })
+    }
+}
+
+/// Credentials held in an Oracle wallet.
+///
+/// The wallet location is configured in `sqlnet.ora` and the connection string must be one of
+/// the aliases stored in the wallet.
+///
+#[derive(Debug, Clone, Default)]
+pub struct Wallet;
+
+impl AuthProvider for Wallet {
+    fn credentials(&self) -> Result<Credentials, OciError> {
+        Ok(Credentials::External)
+    }
+}
+
+/// Kerberos single sign-on using the ticket cache of the current user.
+///
+/// Kerberos has to be enabled as an authentication service in `sqlnet.ora`, for example:
+///
+/// ```text
+/// SQLNET.AUTHENTICATION_SERVICES = (BEQ, KERBEROS5)
+/// SQLNET.KERBEROS5_CONF = /etc/krb5.conf
+/// SQLNET.KERBEROS5_CONF_MIT = TRUE
+/// SQLNET.KERBEROS5_CC_NAME = /tmp/krb5cc_1000
+/// ```
+///
+/// and the database user has to be identified externally by the Kerberos principal. A ticket
+/// is obtained with `kinit` before connecting.
+///
+/// If `KRB5CCNAME` points to a ticket cache file that doesn't exist then an
+/// `OciError::Authentication` is returned before trying to connect. Errors from the Kerberos
+/// adapter during the connection, such as ORA-12638, are returned with a hint as to what is
+/// likely to be wrong.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use oci_rs::auth::Kerberos;
+/// use oci_rs::connection::ConnectionBuilder;
+///
+/// let connection = ConnectionBuilder::new("db.example.com:1521/orcl")
+///     .auth(Kerberos)
+///     .build()
+///     .unwrap();
+/// ```
+///
+#[derive(Debug, Clone, Default)]
+pub struct Kerberos;
+
+impl AuthProvider for Kerberos {
+    fn credentials(&self) -> Result<Credentials, OciError> {
+        if let Ok(cache_name) = env::var("KRB5CCNAME") {
+            check_ticket_cache(&cache_name)?;
+        }
+        Ok(Credentials::External)
+    }
+
+    fn explain_error(&self, err: OciError) -> OciError {
+        match err {
+            OciError::Oracle(mut record) => {
+                let hint = KERBEROS_HINTS
+                    .iter()
+                    .find(|&&(code, _)| record.has_code(code))
+                    .map(|&(_, hint)| hint);
+                if let Some(hint) = hint {
+                    record.add_hint(hint);
+                }
+                OciError::Oracle(record)
+            }
+            err => err,
+        }
+    }
+}
+
+/// Oracle errors that are commonly raised when Kerberos single sign-on fails, with hints.
+const KERBEROS_HINTS: &[(i32, &str)] = &[
+    (
+        12631,
+        "Kerberos user name retrieval failed, check the ticket cache with klist and run kinit",
+    ),
+    (
+        12638,
+        "Kerberos credentials could not be retrieved, the ticket may be missing or expired, \
+         check with klist and run kinit",
+    ),
+    (
+        12641,
+        "the Kerberos authentication service failed to initialize, check \
+         SQLNET.AUTHENTICATION_SERVICES and SQLNET.KERBEROS5_CONF in sqlnet.ora",
+    ),
+    (
+        12649,
+        "the Kerberos authentication service is not available on the client or server",
+    ),
+    (
+        1017,
+        "the Kerberos principal is not mapped to a database user identified externally",
+    ),
+];
+
+/// Checks that a `FILE:` ticket cache exists. Other cache types, such as `KEYRING:` or `KCM:`,
+/// can't be checked here and are left to the Kerberos library.
+///
+pub(crate) fn check_ticket_cache(cache_name: &str) -> Result<(), OciError> {
+    let path = match cache_name.strip_prefix("FILE:") {
+        Some(path) => path,
+        None if !cache_name.contains(':') => cache_name,
+        None => return Ok(()),
+    };
+    if Path::new(path).exists() {
+        Ok(())
+    } else {
+        Err(OciError::Authentication(format!(
+            "No Kerberos ticket cache found at {}, run kinit to obtain a ticket",
+            path
+        )))
+    }
+}
+
+/// RADIUS authentication.
+///
+/// The user name and password are passed through the database to the RADIUS server, which is
+/// configured by adding `RADIUS` to the authentication services in `sqlnet.ora`. Challenge and
+/// response modes, such as token cards, are not supported.
+///
+/// To the client RADIUS looks the same as a database password, so this only differs from
+/// [`StaticPassword`][1] in naming the intent.
+///
+/// [1]: struct.StaticPassword.html
+#[derive(Debug, Clone)]
+pub struct Radius(StaticPassword);
+
+impl Radius {
+    /// Creates a new `Radius`.
+    ///
+    pub fn new<P: Into<SecretString>>(user_name: &str, password: P) -> Radius {
+        Radius(StaticPassword::new(user_name, password))
+    }
+}
+
+impl AuthProvider for Radius {
+    fn credentials(&self) -> Result<Credentials, OciError> {
+        self.0.credentials()
+    }
+}
+
+/// The way a session was authenticated, as reported by the database.
+///
+/// See [`Connection.authentication_method`][1].
+///
+/// [1]: ../connection/struct.Connection.html#method.authentication_method
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthenticationMethod {
+    /// Database user name and password.
+    Password,
+    /// Operating system authentication.
+    Os,
+    /// Kerberos.
+    Kerberos,
+    /// RADIUS.
+    Radius,
+    /// SSL/TLS certificates, e.g. from a wallet.
+    Ssl,
+    /// Any other method reported by the database.
+    Other(String),
+}
+
+impl From<&str> for AuthenticationMethod {
+    fn from(method: &str) -> Self {
+        match method {
+            "PASSWORD" => AuthenticationMethod::Password,
+            "OS" => AuthenticationMethod::Os,
+            "KERBEROS" => AuthenticationMethod::Kerberos,
+            "RADIUS" => AuthenticationMethod::Radius,
+            "SSL" => AuthenticationMethod::Ssl,
+            other => AuthenticationMethod::Other(other.to_string()),
+        }
+    }
+}
+
+/// Fetches a database access token each time a connection is created.
+///
+/// The fetcher is any closure that returns the token, so it can read it from a file, call a
+/// cloud SDK or cache it until it expires.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use oci_rs::auth::TokenFetcher;
+/// use oci_rs::connection::ConnectionBuilder;
+/// use oci_rs::oci_error::OciError;
+/// use std::fs;
+///
+/// let fetcher = TokenFetcher::new(|| {
+///     fs::read_to_string("/run/secrets/db_token")
+///         .map(|token| token.trim().into())
+///         .map_err(|err| OciError::Conversion(Box::new(err)))
+/// });
+///
+/// let connection = ConnectionBuilder::new("tcps://example.com:1522/db")
+///     .auth(fetcher)
+///     .build()
+///     .unwrap();
+/// ```
+///
+pub struct TokenFetcher<F> {
+    fetch: F,
+    private_key: Option<SecretString>,
+}
+impl<F> TokenFetcher<F>
+where
+    F: Fn() -> Result<SecretString, OciError> + Send + Sync,
+{
+    /// Creates a new `TokenFetcher` for OAuth2 tokens.
+    ///
+    pub fn new(fetch: F) -> TokenFetcher<F> {
+        TokenFetcher {
+            fetch,
+            private_key: None,
+        }
+    }
+
+    /// Creates a new `TokenFetcher` for IAM tokens, which need the private key that was used to
+    /// request the token.
+    ///
+    pub fn with_private_key<P: Into<SecretString>>(fetch: F, private_key: P) -> TokenFetcher<F> {
+        TokenFetcher {
+            fetch,
+            private_key: Some(private_key.into()),
+        }
+    }
+}
+
+impl<F> fmt::Debug for TokenFetcher<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TokenFetcher")
+            .field("private_key", &self.private_key)
+            .finish()
+    }
+}
+
+impl<F> AuthProvider for TokenFetcher<F>
+where
+    F: Fn() -> Result<SecretString, OciError> + Send + Sync,
+{
+    fn credentials(&self) -> Result<Credentials, OciError> {
+        Ok(Credentials::Token {
+            token: (self.fetch)()?,
+            private_key: self.private_key.clone(),
+        })
+    }
+}
@@ -0,0 +1,376 @@
+use crate::common::wait_for_call;
+use crate::connection::Connection;
+use crate::oci_bindings::{
+    DescriptorType, HandleType, OCIDescriptorAlloc, OCIDescriptorFree, OCIEnv, OCIError,
+    OCILobFileClose, OCILobFileExists, OCILobFileGetName, OCILobFileOpen, OCILobFileSetName,
+    OCILobGetLength2, OCILobLocator, OCILobRead2, ReturnCode,
+};
+use crate::oci_error::{get_error, OciError};
+use crate::types::{FromSqlValue, SqlValue};
+use libc::{c_int, c_uchar, c_ushort, c_void};
+use log::error;
+use std::fmt;
+use std::io::{self, Read};
+use std::ptr;
+
+/// OCI_FILE_READONLY, the only mode a BFILE can be opened in.
+const OCI_FILE_READONLY: c_uchar = 1;
+/// OCI_ONE_PIECE, read in a single call.
+const OCI_ONE_PIECE: c_uchar = 0;
+/// The longest directory alias or file name a BFILE locator holds, in bytes.
+const MAX_NAME_LENGTH: usize = 1024;
+
+/// The value of a `BFILE` column, a pointer to a file stored outside the database.
+///
+/// A `BFile` only holds the directory alias and the file name, the contents are read from the
+/// server by [`.open`][1], which returns a reader. `BFILE`s are read only, they are set in
+/// SQL with `BFILENAME`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use oci_rs::bfile::BFile;
+/// use oci_rs::connection::Connection;
+/// use std::io::Read;
+///
+/// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+/// let mut select = conn
+///     .create_prepared_statement("SELECT Scan FROM Documents WHERE DocumentId = 1")
+///     .unwrap();
+/// select.execute().unwrap();
+/// let results = select.result_set().unwrap();
+/// let scan: BFile = results[0][0].value().unwrap();
+/// println!("{}/{}", scan.directory(), scan.file_name());
+///
+/// let mut contents = Vec::new();
+/// scan.open(&conn).unwrap().read_to_end(&mut contents).unwrap();
+/// ```
+///
+/// [1]: #method.open
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BFile {
+    directory: String,
+    file_name: String,
+}
+impl BFile {
+    /// Creates a `BFile` pointing at `file_name` in the directory with the alias `directory`,
+    /// in the same way as `BFILENAME` does in SQL.
+    ///
+    pub fn new(directory: &str, file_name: &str) -> BFile {
+        BFile {
+            directory: directory.to_string(),
+            file_name: file_name.to_string(),
+        }
+    }
+
+    /// The alias of the directory object the file is in.
+    ///
+    pub fn directory(&self) -> &str {
+        &self.directory
+    }
+
+    /// The name of the file within the directory.
+    ///
+    pub fn file_name(&self) -> &str {
+        &self.file_name
+    }
+
+    /// Checks whether the file exists on the server.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned, such as the
+    /// directory alias not existing or the user not having read access to it.
+    ///
+    pub fn exists(&self, connection: &Connection) -> Result<bool, OciError> {
+        let locator = Locator::new(connection, self)?;
+        let mut flag: c_int = 0;
+        let exists_result = wait_for_call(|| unsafe {
+            OCILobFileExists(
+                connection.service(),
+                connection.error(),
+                locator.locator,
+                &mut flag,
+            )
+        });
+        match exists_result.into() {
+            ReturnCode::Success => Ok(flag != 0),
+            _ => Err(get_error(
+                connection.error_as_mut_void(),
+                HandleType::Error,
+                "Checking BFILE exists",
+            )),
+        }
+    }
+
+    /// Opens the file on the server for reading. The [`BFileReader`][1] returned reads the
+    /// contents in pieces, as much as the buffer it is given holds, so large files are not
+    /// held in memory all at once.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned, such as the
+    /// file not existing.
+    ///
+    /// [1]: struct.BFileReader.html
+    pub fn open<'conn>(
+        &self,
+        connection: &'conn Connection,
+    ) -> Result<BFileReader<'conn>, OciError> {
+        let locator = Locator::new(connection, self)?;
+        let open_result = wait_for_call(|| unsafe {
+            OCILobFileOpen(
+                connection.service(),
+                connection.error(),
+                locator.locator,
+                OCI_FILE_READONLY,
+            )
+        });
+        match open_result.into() {
+            ReturnCode::Success => (),
+            _ => {
+                return Err(get_error(
+                    connection.error_as_mut_void(),
+                    HandleType::Error,
+                    "Opening BFILE",
+                ))
+            }
+        }
+        let mut length: u64 = 0;
+        let length_result = wait_for_call(|| unsafe {
+            OCILobGetLength2(
+                connection.service(),
+                connection.error(),
+                locator.locator,
+                &mut length,
+            )
+        });
+        let reader = BFileReader {
+            connection,
+            locator,
+            length,
+            position: 0,
+        };
+        match length_result.into() {
+            ReturnCode::Success => Ok(reader),
+            _ => Err(get_error(
+                connection.error_as_mut_void(),
+                HandleType::Error,
+                "Getting BFILE length",
+            )),
+        }
+    }
+
+    /// Reads the directory alias and file name out of a locator fetched from a `BFILE` column.
+    ///
+    pub(crate) fn from_locator(
+        environment: *mut OCIEnv,
+        error: *mut OCIError,
+        locator: *mut OCILobLocator,
+    ) -> Result<BFile, OciError> {
+        let mut directory = vec![0; MAX_NAME_LENGTH];
+        let mut directory_length = MAX_NAME_LENGTH as c_ushort;
+        let mut file_name = vec![0; MAX_NAME_LENGTH];
+        let mut file_name_length = MAX_NAME_LENGTH as c_ushort;
+        let name_result = unsafe {
+            OCILobFileGetName(
+                environment,
+                error,
+                locator,
+                directory.as_mut_ptr(),
+                &mut directory_length,
+                file_name.as_mut_ptr(),
+                &mut file_name_length,
+            )
+        };
+        match name_result.into() {
+            ReturnCode::Success => (),
+            _ => {
+                return Err(get_error(
+                    error as *mut c_void,
+                    HandleType::Error,
+                    "Getting BFILE name",
+                ))
+            }
+        }
+        directory.truncate(directory_length as usize);
+        file_name.truncate(file_name_length as usize);
+        match (String::from_utf8(directory), String::from_utf8(file_name)) {
+            (Ok(directory), Ok(file_name)) => Ok(BFile {
+                directory,
+                file_name,
+            }),
+            (Err(err), _) | (_, Err(err)) => Err(OciError::Conversion(Box::new(err))),
+        }
+    }
+}
+
+impl fmt::Display for BFile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BFILENAME('{}', '{}')", self.directory, self.file_name)
+    }
+}
+
+impl FromSqlValue for BFile {
+    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
+        match *sql_value {
+            SqlValue::BFile(ref bfile) => Some(bfile.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Reads the contents of a [`BFile`][1], it is created by [`BFile.open`][2].
+///
+/// Each call to `read` is a round trip to the server, so wrapping it in a
+/// `std::io::BufReader` is worthwhile when reading a little at a time. The file is closed
+/// when the reader is dropped.
+///
+/// [1]: struct.BFile.html
+/// [2]: struct.BFile.html#method.open
+pub struct BFileReader<'conn> {
+    connection: &'conn Connection,
+    locator: Locator,
+    length: u64,
+    position: u64,
+}
+impl<'conn> BFileReader<'conn> {
+    /// The size of the file in bytes.
+    ///
+    pub fn len(&self) -> u64 {
+        self.length
+    }
+
+    /// Whether the file is empty.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// The number of bytes read so far.
+    ///
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+}
+
+impl<'conn> fmt::Debug for BFileReader<'conn> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BFileReader")
+            .field("length", &self.length)
+            .field("position", &self.position)
+            .finish()
+    }
+}
+
+impl<'conn> Read for BFileReader<'conn> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() || self.position >= self.length {
+            return Ok(0);
+        }
+        let mut byte_amount = (buf.len() as u64).min(self.length - self.position);
+        let mut char_amount: u64 = 0;
+        let read_result = wait_for_call(|| unsafe {
+            OCILobRead2(
+                self.connection.service(),
+                self.connection.error(),
+                self.locator.locator,
+                &mut byte_amount,
+                &mut char_amount,
+                self.position + 1,
+                buf.as_mut_ptr() as *mut c_void,
+                buf.len() as u64,
+                OCI_ONE_PIECE,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                0,
+                0,
+            )
+        });
+        match read_result.into() {
+            ReturnCode::Success => {
+                self.position += byte_amount;
+                Ok(byte_amount as usize)
+            }
+            _ => Err(io::Error::other(get_error(
+                self.connection.error_as_mut_void(),
+                HandleType::Error,
+                "Reading BFILE",
+            ))),
+        }
+    }
+}
+
+impl<'conn> Drop for BFileReader<'conn> {
+    fn drop(&mut self) {
+        let close_result = wait_for_call(|| unsafe {
+            OCILobFileClose(
+                self.connection.service(),
+                self.connection.error(),
+                self.locator.locator,
+            )
+        });
+        if let ReturnCode::Error = close_result.into() {
+            error!("Could not close the BFILE in BFileReader")
+        }
+    }
+}
+
+/// A BFILE locator set to a directory alias and file name, freed when it is dropped.
+struct Locator {
+    locator: *mut OCILobLocator,
+}
+impl Locator {
+    fn new(connection: &Connection, bfile: &BFile) -> Result<Locator, OciError> {
+        let mut locator: *mut OCILobLocator = ptr::null_mut();
+        let alloc_result = unsafe {
+            OCIDescriptorAlloc(
+                connection.environment() as *const c_void,
+                &mut locator as *mut *mut OCILobLocator as *mut *mut c_void,
+                DescriptorType::File.into(),
+                0,
+                ptr::null_mut(),
+            )
+        };
+        match alloc_result.into() {
+            ReturnCode::Success => (),
+            _ => {
+                return Err(get_error(
+                    connection.error_as_mut_void(),
+                    HandleType::Error,
+                    "Allocating BFILE locator",
+                ))
+            }
+        }
+        let mut locator = Locator { locator };
+        let set_result = unsafe {
+            OCILobFileSetName(
+                connection.environment(),
+                connection.error(),
+                &mut locator.locator,
+                bfile.directory.as_ptr(),
+                bfile.directory.len() as c_ushort,
+                bfile.file_name.as_ptr(),
+                bfile.file_name.len() as c_ushort,
+            )
+        };
+        match set_result.into() {
+            ReturnCode::Success => Ok(locator),
+            _ => Err(get_error(
+                connection.error_as_mut_void(),
+                HandleType::Error,
+                "Setting BFILE name",
+            )),
+        }
+    }
+}
+
+impl Drop for Locator {
+    fn drop(&mut self) {
+        let free_result =
+            unsafe { OCIDescriptorFree(self.locator as *mut c_void, DescriptorType::File.into()) };
+        if let ReturnCode::Error = free_result.into() {
+            error!("Could not free the BFILE locator")
+        }
+    }
+}
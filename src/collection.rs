@@ -0,0 +1,715 @@
+use crate::common::wait_for_call;
+use crate::connection::Connection;
+use crate::describe::split_identifiers;
+use crate::oci_bindings::{
+    HandleType, OCIBind, OCIBindObject, OCIColl, OCICollAppend, OCICollGetElem, OCICollSize,
+    OCIDate, OCIDefine, OCIDefineObject, OCIEnv, OCIError, OCINumberFromInt, OCINumberFromReal,
+    OCIObjectFree, OCIObjectNew, OCIString, OCIStringAssignText, OCIStringPtr, OCIStringResize,
+    OCIStringSize, OCIType, OCITypeByName, OCITypeCollElem, OCITypeElem, OCITypeElemTypeCode,
+    OciNumberType, ReturnCode,
+};
+use crate::oci_error::{get_error, ConversionError, OciError};
+use crate::types::{
+    create_date_value_from_raw, create_number_from_oci, FromSqlValue, SqlValue, ToSqlValue,
+    ValueType, ORACLE_NUMBER_SIZE,
+};
+use libc::{c_int, c_short, c_uint, c_ushort, c_void};
+use log::error;
+use std::fmt;
+use std::mem;
+use std::ptr;
+use std::slice;
+
+/// OCI_TYPECODE_NAMEDCOLLECTION, the type code of an instance of a `VARRAY` or nested table
+/// type.
+const OCI_TYPECODE_NAMEDCOLLECTION: c_ushort = 122;
+/// OCI_DURATION_SESSION, type descriptors and collections last until the session ends, unless
+/// they are freed first.
+const OCI_DURATION_SESSION: c_ushort = 10;
+/// OCI_TYPEGET_ALL, load the whole of a type's description, including its element type.
+const OCI_TYPEGET_ALL: c_int = 1;
+/// OCI_OBJECTFREE_FORCE, free a collection even if it has been changed.
+const OCI_OBJECTFREE_FORCE: c_ushort = 1;
+/// OCI_IND_NULL, the indicator of a null collection or element.
+const OCI_IND_NULL: c_short = -1;
+/// OCI_IND_NOTNULL
+const OCI_IND_NOTNULL: c_short = 0;
+
+const OCI_TYPECODE_VARCHAR: c_ushort = 1;
+const OCI_TYPECODE_NUMBER: c_ushort = 2;
+const OCI_TYPECODE_INTEGER: c_ushort = 3;
+const OCI_TYPECODE_FLOAT: c_ushort = 4;
+const OCI_TYPECODE_DECIMAL: c_ushort = 7;
+const OCI_TYPECODE_VARCHAR2: c_ushort = 9;
+const OCI_TYPECODE_DATE: c_ushort = 12;
+const OCI_TYPECODE_REAL: c_ushort = 21;
+const OCI_TYPECODE_DOUBLE: c_ushort = 22;
+const OCI_TYPECODE_CHAR: c_ushort = 96;
+const OCI_TYPECODE_SMALLINT: c_ushort = 246;
+
+/// The types of element a [`Collection`][1] can hold.
+///
+/// [1]: struct.Collection.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElementType {
+    /// `NUMBER`, or any other numeric type held as one, such as `INTEGER` or `FLOAT`
+    Number,
+    /// `VARCHAR2`
+    VarChar,
+    /// `CHAR`
+    Char,
+    /// `DATE`
+    Date,
+}
+impl ElementType {
+    fn from_type_code(type_code: c_ushort) -> Option<ElementType> {
+        match type_code {
+            OCI_TYPECODE_NUMBER
+            | OCI_TYPECODE_INTEGER
+            | OCI_TYPECODE_FLOAT
+            | OCI_TYPECODE_DECIMAL
+            | OCI_TYPECODE_REAL
+            | OCI_TYPECODE_DOUBLE
+            | OCI_TYPECODE_SMALLINT => Some(ElementType::Number),
+            OCI_TYPECODE_VARCHAR | OCI_TYPECODE_VARCHAR2 => Some(ElementType::VarChar),
+            OCI_TYPECODE_CHAR => Some(ElementType::Char),
+            OCI_TYPECODE_DATE => Some(ElementType::Date),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ElementType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ElementType::Number => write!(f, "NUMBER"),
+            ElementType::VarChar => write!(f, "VARCHAR2"),
+            ElementType::Char => write!(f, "CHAR"),
+            ElementType::Date => write!(f, "DATE"),
+        }
+    }
+}
+
+/// The value of a `VARRAY` or nested table, such as a column of a collection type or a
+/// parameter of one in PL/SQL.
+///
+/// The collection type has to be created in SQL, with `CREATE TYPE`, and its elements have to
+/// be a `NUMBER`, `VARCHAR2`, `CHAR` or `DATE`. Each element is held as a `SqlValue` and an
+/// element can be null. PL/SQL index-by tables and collections of objects aren't supported.
+///
+/// A collection is fetched along with the name of its type, including the schema, and the type
+/// of its elements. When one is bound the type is looked up by its name, which can be schema
+/// qualified, and the elements are converted to the type's element type.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use oci_rs::collection::{Collection, ElementType};
+/// use oci_rs::connection::Connection;
+///
+/// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+///
+/// // CREATE TYPE Scores AS VARRAY(10) OF NUMBER
+/// let scores = Collection::from_values("Scores", ElementType::Number, &[12, 15, 9]);
+/// let mut insert = conn
+///     .create_prepared_statement("INSERT INTO Games (GameId, Scores) VALUES (:id, :scores)")
+///     .unwrap();
+/// insert.bind(&[&1, &scores]).unwrap();
+/// insert.execute().unwrap();
+///
+/// let mut select = conn
+///     .create_prepared_statement("SELECT Scores FROM Games WHERE GameId = 1")
+///     .unwrap();
+/// select.execute().unwrap();
+/// let results = select.result_set().unwrap();
+/// let scores: Collection = results[0][0].value().unwrap();
+/// assert_eq!(scores.element_type(), ElementType::Number);
+/// assert_eq!(scores.values::<i64>().unwrap(), vec![12, 15, 9]);
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct Collection {
+    type_name: String,
+    element_type: ElementType,
+    elements: Vec<SqlValue>,
+}
+impl Collection {
+    /// Creates a collection of the type named `type_name`, holding `elements`.
+    ///
+    pub fn new(type_name: &str, element_type: ElementType, elements: Vec<SqlValue>) -> Collection {
+        Collection {
+            type_name: type_name.to_string(),
+            element_type,
+            elements,
+        }
+    }
+
+    /// Creates a collection of the type named `type_name`, converting each value to a
+    /// `SqlValue`.
+    ///
+    pub fn from_values<T: ToSqlValue>(
+        type_name: &str,
+        element_type: ElementType,
+        values: &[T],
+    ) -> Collection {
+        Collection::new(
+            type_name,
+            element_type,
+            values.iter().map(ToSqlValue::to_sql_value).collect(),
+        )
+    }
+
+    /// The name of the collection type.
+    ///
+    pub fn type_name(&self) -> &str {
+        &self.type_name
+    }
+
+    /// The type of the collection's elements.
+    ///
+    pub fn element_type(&self) -> ElementType {
+        self.element_type
+    }
+
+    /// The elements, in order.
+    ///
+    pub fn elements(&self) -> &[SqlValue] {
+        &self.elements
+    }
+
+    /// Converts every element to `T`, or returns `None` if any of them can't be converted.
+    ///
+    pub fn values<T: FromSqlValue>(&self) -> Option<Vec<T>> {
+        self.elements.iter().map(SqlValue::value).collect()
+    }
+
+    /// Gives up the elements.
+    ///
+    pub fn into_elements(self) -> Vec<SqlValue> {
+        self.elements
+    }
+
+    /// The number of elements.
+    ///
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Whether the collection has no elements.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+}
+
+impl ToSqlValue for Collection {
+    fn to_sql_value(&self) -> SqlValue {
+        SqlValue::Collection(self.clone())
+    }
+}
+
+impl FromSqlValue for Collection {
+    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
+        match *sql_value {
+            SqlValue::Collection(ref collection) => Some(collection.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// A collection type looked up in the database, used to fetch and bind its collections.
+#[derive(Debug)]
+pub(crate) struct CollectionType {
+    environment: *mut OCIEnv,
+    error: *mut OCIError,
+    tdo: *mut OCIType,
+    type_name: String,
+    element_type: ElementType,
+}
+impl CollectionType {
+    /// The type of the collection's elements.
+    ///
+    pub(crate) fn element_type(&self) -> ElementType {
+        self.element_type
+    }
+
+    /// Looks up a collection type by its name, which can be schema qualified and quoted.
+    ///
+    pub(crate) fn by_name(
+        connection: &Connection,
+        type_name: &str,
+    ) -> Result<CollectionType, OciError> {
+        let mut identifiers = split_identifiers(type_name);
+        let name = identifiers.pop().unwrap_or_default();
+        let schema = identifiers.pop();
+        CollectionType::lookup(connection, schema.as_deref(), &name)
+    }
+
+    /// Looks up a collection type in a schema, or in the current schema if none is given.
+    ///
+    pub(crate) fn lookup(
+        connection: &Connection,
+        schema: Option<&str>,
+        name: &str,
+    ) -> Result<CollectionType, OciError> {
+        let environment = connection.environment();
+        let error = connection.error();
+        let (schema_ptr, schema_length) = match schema {
+            Some(schema) => (schema.as_ptr(), schema.len() as c_uint),
+            None => (ptr::null(), 0),
+        };
+        let mut tdo: *mut OCIType = ptr::null_mut();
+        let type_result = wait_for_call(|| unsafe {
+            OCITypeByName(
+                environment,
+                error,
+                connection.service(),
+                schema_ptr,
+                schema_length,
+                name.as_ptr(),
+                name.len() as c_uint,
+                ptr::null(),
+                0,
+                OCI_DURATION_SESSION,
+                OCI_TYPEGET_ALL,
+                &mut tdo,
+            )
+        });
+        match type_result.into() {
+            ReturnCode::Success => (),
+            _ => {
+                return Err(get_error(
+                    error as *mut c_void,
+                    HandleType::Error,
+                    "Looking up collection type",
+                ))
+            }
+        }
+        let mut element: *mut OCITypeElem = ptr::null_mut();
+        let element_result = unsafe { OCITypeCollElem(environment, error, tdo, &mut element) };
+        match element_result.into() {
+            ReturnCode::Success => (),
+            _ => {
+                return Err(get_error(
+                    error as *mut c_void,
+                    HandleType::Error,
+                    "Getting collection element type",
+                ))
+            }
+        }
+        let type_name = match schema {
+            Some(schema) => format!("{}.{}", schema, name),
+            None => name.to_string(),
+        };
+        let type_code = unsafe { OCITypeElemTypeCode(environment, error, element) };
+        let element_type = match ElementType::from_type_code(type_code) {
+            Some(element_type) => element_type,
+            None => {
+                return Err(OciError::Conversion(
+                    format!(
+                        "Collection type {} has elements with OCI type code {}, only NUMBER, \
+                         VARCHAR2, CHAR and DATE elements are supported",
+                        type_name, type_code
+                    )
+                    .into(),
+                ))
+            }
+        };
+        Ok(CollectionType {
+            environment,
+            error,
+            tdo,
+            type_name,
+            element_type,
+        })
+    }
+
+    pub(crate) fn type_name(&self) -> &str {
+        &self.type_name
+    }
+
+    /// Has collections fetched into `objects`, with their null indicators in `indicators`. OCI
+    /// creates a collection for each row the first time it is fetched into and reuses it after.
+    ///
+    pub(crate) fn define(
+        &self,
+        define: *mut OCIDefine,
+        objects: &mut [*mut c_void],
+        indicators: &mut [*mut c_void],
+    ) -> Result<(), OciError> {
+        let define_result = unsafe {
+            OCIDefineObject(
+                define,
+                self.error,
+                self.tdo,
+                objects.as_mut_ptr(),
+                ptr::null_mut(),
+                indicators.as_mut_ptr(),
+                ptr::null_mut(),
+            )
+        };
+        match define_result.into() {
+            ReturnCode::Success => Ok(()),
+            _ => Err(get_error(
+                self.error as *mut c_void,
+                HandleType::Error,
+                "Defining collection",
+            )),
+        }
+    }
+
+    /// Converts a fetched collection, which is null if its indicator says so.
+    ///
+    pub(crate) fn fetched_value(
+        &self,
+        object: *mut c_void,
+        indicator: *mut c_void,
+    ) -> Result<SqlValue, OciError> {
+        if object.is_null()
+            || (!indicator.is_null() && unsafe { *(indicator as *const c_short) } == OCI_IND_NULL)
+        {
+            return Ok(SqlValue::Null(Some(ValueType::Collection(
+                self.element_type,
+            ))));
+        }
+        self.read(object as *const OCIColl)
+            .map(SqlValue::Collection)
+    }
+
+    fn read(&self, collection: *const OCIColl) -> Result<Collection, OciError> {
+        let mut size: c_int = 0;
+        let size_result =
+            unsafe { OCICollSize(self.environment, self.error, collection, &mut size) };
+        match size_result.into() {
+            ReturnCode::Success => (),
+            _ => return Err(self.oci_error("Getting collection size")),
+        }
+        let mut elements = Vec::with_capacity(size.max(0) as usize);
+        for index in 0..size {
+            let mut exists: c_int = 0;
+            let mut element: *mut c_void = ptr::null_mut();
+            let mut indicator: *mut c_void = ptr::null_mut();
+            let element_result = unsafe {
+                OCICollGetElem(
+                    self.environment,
+                    self.error,
+                    collection,
+                    index,
+                    &mut exists,
+                    &mut element,
+                    &mut indicator,
+                )
+            };
+            match element_result.into() {
+                ReturnCode::Success => (),
+                _ => return Err(self.oci_error("Getting collection element")),
+            }
+            // elements deleted from a nested table leave a gap
+            if exists == 0 {
+                continue;
+            }
+            if !indicator.is_null() && unsafe { *(indicator as *const c_short) } == OCI_IND_NULL {
+                elements.push(SqlValue::Null(Some(self.element_type.into())));
+                continue;
+            }
+            elements.push(self.read_element(element)?);
+        }
+        Ok(Collection {
+            type_name: self.type_name.clone(),
+            element_type: self.element_type,
+            elements,
+        })
+    }
+
+    fn read_element(&self, element: *mut c_void) -> Result<SqlValue, OciError> {
+        match self.element_type {
+            ElementType::Number => {
+                let number =
+                    unsafe { slice::from_raw_parts(element as *const u8, ORACLE_NUMBER_SIZE) };
+                create_number_from_oci(self.error, number)
+            }
+            ElementType::VarChar | ElementType::Char => {
+                let string = unsafe { *(element as *const *mut OCIString) };
+                let text = unsafe {
+                    let size = OCIStringSize(self.environment, string) as usize;
+                    match size {
+                        0 => Vec::new(),
+                        _ => slice::from_raw_parts(OCIStringPtr(self.environment, string), size)
+                            .to_vec(),
+                    }
+                };
+                match (String::from_utf8(text), self.element_type) {
+                    (Ok(text), ElementType::Char) => Ok(SqlValue::Char(text)),
+                    (Ok(text), _) => Ok(SqlValue::VarChar(text)),
+                    (Err(err), _) => Err(OciError::Conversion(Box::new(err))),
+                }
+            }
+            ElementType::Date => {
+                let date = unsafe { *(element as *const OCIDate) };
+                Ok(create_date_value_from_raw(&raw_from_oci_date(&date)))
+            }
+        }
+    }
+
+    /// Frees a collection created by OCI or by `CollectionObject`.
+    ///
+    pub(crate) fn free(&self, object: *mut c_void) {
+        if object.is_null() {
+            return;
+        }
+        let free_result =
+            unsafe { OCIObjectFree(self.environment, self.error, object, OCI_OBJECTFREE_FORCE) };
+        if let ReturnCode::Error = free_result.into() {
+            error!("Could not free a collection of type {}", self.type_name)
+        }
+    }
+
+    fn oci_error(&self, description: &str) -> OciError {
+        get_error(self.error as *mut c_void, HandleType::Error, description)
+    }
+}
+
+/// A collection built from a `Collection` to be bound, freed when it is dropped.
+#[derive(Debug)]
+pub(crate) struct CollectionObject {
+    collection_type: CollectionType,
+    // OCI keeps the address of the pointer until the statement is executed
+    object: Box<*mut c_void>,
+}
+impl CollectionObject {
+    /// Looks up the collection's type and builds a collection of it holding its elements.
+    ///
+    pub(crate) fn new(
+        connection: &Connection,
+        collection: &Collection,
+    ) -> Result<CollectionObject, OciError> {
+        let collection_type = CollectionType::by_name(connection, &collection.type_name)?;
+        if collection_type.element_type != collection.element_type {
+            return Err(OciError::Conversion(
+                format!(
+                    "Collection type {} has {:?} elements, not {:?}",
+                    collection_type.type_name,
+                    collection_type.element_type,
+                    collection.element_type
+                )
+                .into(),
+            ));
+        }
+        let mut object: *mut c_void = ptr::null_mut();
+        let new_result = unsafe {
+            OCIObjectNew(
+                collection_type.environment,
+                collection_type.error,
+                connection.service(),
+                OCI_TYPECODE_NAMEDCOLLECTION,
+                collection_type.tdo,
+                ptr::null_mut(),
+                OCI_DURATION_SESSION,
+                1,
+                &mut object,
+            )
+        };
+        match new_result.into() {
+            ReturnCode::Success => (),
+            _ => return Err(collection_type.oci_error("Creating collection")),
+        }
+        let collection_object = CollectionObject {
+            collection_type,
+            object: Box::new(object),
+        };
+        for element in collection.elements() {
+            collection_object.append(element)?;
+        }
+        Ok(collection_object)
+    }
+
+    /// Binds the collection to a placeholder already bound as a named type.
+    ///
+    pub(crate) fn bind(&mut self, binding: *mut OCIBind) -> Result<(), OciError> {
+        let bind_result = unsafe {
+            OCIBindObject(
+                binding,
+                self.collection_type.error,
+                self.collection_type.tdo,
+                &mut *self.object,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+            )
+        };
+        match bind_result.into() {
+            ReturnCode::Success => Ok(()),
+            _ => Err(self.collection_type.oci_error("Binding collection")),
+        }
+    }
+
+    /// Appends a copy of an element, converted to the collection's element type.
+    fn append(&self, element: &SqlValue) -> Result<(), OciError> {
+        let collection_type = &self.collection_type;
+        let indicator = match *element {
+            SqlValue::Null(..) => OCI_IND_NULL,
+            _ => OCI_IND_NOTNULL,
+        };
+        match collection_type.element_type {
+            ElementType::Number => {
+                let number = number_to_oci(collection_type.error, element)?;
+                self.append_element(number.as_ptr() as *const c_void, &indicator)
+            }
+            ElementType::VarChar | ElementType::Char => {
+                let text = match *element {
+                    SqlValue::Null(..) => String::new(),
+                    _ => match element.value::<String>() {
+                        Some(text) => text,
+                        None => return Err(conversion_failed(element, "a text element")),
+                    },
+                };
+                let mut string: *mut OCIString = ptr::null_mut();
+                let assign_result = unsafe {
+                    OCIStringAssignText(
+                        collection_type.environment,
+                        collection_type.error,
+                        text.as_ptr(),
+                        text.len() as c_uint,
+                        &mut string,
+                    )
+                };
+                match assign_result.into() {
+                    ReturnCode::Success => (),
+                    _ => return Err(collection_type.oci_error("Creating collection element")),
+                }
+                let append_result = self.append_element(string as *const c_void, &indicator);
+                // the collection holds a copy, so the string is freed straight away
+                let resize_result = unsafe {
+                    OCIStringResize(
+                        collection_type.environment,
+                        collection_type.error,
+                        0,
+                        &mut string,
+                    )
+                };
+                if let ReturnCode::Error = resize_result.into() {
+                    error!("Could not free a collection element")
+                }
+                append_result
+            }
+            ElementType::Date => {
+                let date = match *element {
+                    // OCI still wants a valid date alongside the null indicator
+                    SqlValue::Null(..) => OCIDate {
+                        year: 1,
+                        month: 1,
+                        day: 1,
+                        ..OCIDate::default()
+                    },
+                    SqlValue::Date(_, ref raw) => oci_date_from_raw(raw),
+                    SqlValue::Timestamp(_, ref raw) => oci_date_from_raw(raw),
+                    _ => return Err(conversion_failed(element, "a DATE element")),
+                };
+                self.append_element(&date as *const OCIDate as *const c_void, &indicator)
+            }
+        }
+    }
+
+    fn append_element(&self, element: *const c_void, indicator: &c_short) -> Result<(), OciError> {
+        let append_result = unsafe {
+            OCICollAppend(
+                self.collection_type.environment,
+                self.collection_type.error,
+                element,
+                indicator as *const c_short as *const c_void,
+                *self.object as *mut OCIColl,
+            )
+        };
+        match append_result.into() {
+            ReturnCode::Success => Ok(()),
+            _ => Err(self
+                .collection_type
+                .oci_error("Appending collection element")),
+        }
+    }
+}
+
+impl Drop for CollectionObject {
+    fn drop(&mut self) {
+        self.collection_type.free(*self.object)
+    }
+}
+
+/// Converts an element to an Oracle number. A decimal is already in Oracle's format.
+fn number_to_oci(
+    error: *mut OCIError,
+    element: &SqlValue,
+) -> Result<[u8; ORACLE_NUMBER_SIZE], OciError> {
+    let mut number = [0u8; ORACLE_NUMBER_SIZE];
+    let number_ptr = number.as_mut_ptr() as *mut c_void;
+    let convert_result = match *element {
+        SqlValue::Null(..) => return Ok(number),
+        SqlValue::Integer(ref integer) => unsafe {
+            OCINumberFromInt(
+                error,
+                integer as *const i64 as *const c_void,
+                mem::size_of::<i64>() as c_uint,
+                OciNumberType::Signed.into(),
+                number_ptr,
+            )
+        },
+        SqlValue::Float(ref float) => unsafe {
+            OCINumberFromReal(
+                error,
+                float as *const f64 as *const c_void,
+                mem::size_of::<f64>() as c_uint,
+                number_ptr,
+            )
+        },
+        #[cfg(feature = "decimal")]
+        SqlValue::Decimal(_, ref raw) => return Ok(copy_number(raw)),
+        #[cfg(feature = "bigdecimal")]
+        SqlValue::BigDecimal(_, ref raw) => return Ok(copy_number(raw)),
+        _ => return Err(conversion_failed(element, "a NUMBER element")),
+    };
+    match convert_result.into() {
+        ReturnCode::Success => Ok(number),
+        _ => Err(get_error(
+            error as *mut c_void,
+            HandleType::Error,
+            "Converting collection element to a NUMBER",
+        )),
+    }
+}
+
+#[cfg(any(feature = "decimal", feature = "bigdecimal"))]
+fn copy_number(raw: &[u8]) -> [u8; ORACLE_NUMBER_SIZE] {
+    let mut number = [0u8; ORACLE_NUMBER_SIZE];
+    let length = raw.len().min(ORACLE_NUMBER_SIZE);
+    number[..length].copy_from_slice(&raw[..length]);
+    number
+}
+
+fn conversion_failed(element: &SqlValue, target: &'static str) -> OciError {
+    ConversionError::new(element, target).into()
+}
+
+/// Converts the first seven bytes of a date or timestamp in Oracle's format to an `OCIDate`.
+fn oci_date_from_raw(raw: &[u8]) -> OCIDate {
+    OCIDate {
+        year: (i16::from(raw[0]) - 100) * 100 + i16::from(raw[1]) - 100,
+        month: raw[2],
+        day: raw[3],
+        hour: raw[4] - 1,
+        minute: raw[5] - 1,
+        second: raw[6] - 1,
+    }
+}
+
+/// Converts an `OCIDate` to a date in Oracle's seven byte format.
+fn raw_from_oci_date(date: &OCIDate) -> [u8; 7] {
+    [
+        (date.year / 100 + 100) as u8,
+        (date.year % 100 + 100) as u8,
+        date.month,
+        date.day,
+        date.hour + 1,
+        date.minute + 1,
+        date.second + 1,
+    ]
+}
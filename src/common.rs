@@ -1,6 +1,24 @@
 use crate::oci_bindings::{AttributeType, HandleType, OCIAttrSet, OCIError, ReturnCode};
 use crate::oci_error::{get_error, OciError};
-use libc::{c_uint, c_void};
+use libc::{c_int, c_uint, c_void};
+use std::thread;
+use std::time::Duration;
+
+/// How long to wait before calling an OCI function again while it is still executing on a
+/// connection in non-blocking mode.
+pub const STILL_EXECUTING_WAIT: Duration = Duration::from_millis(1);
+
+/// Makes a call to an OCI function, repeating it until it has finished if the connection is in
+/// non-blocking mode.
+pub fn wait_for_call<F: FnMut() -> c_int>(mut call: F) -> c_int {
+    loop {
+        let result = call();
+        match result.into() {
+            ReturnCode::StillExecuting => thread::sleep(STILL_EXECUTING_WAIT),
+            _ => return result,
+        }
+    }
+}
 
 /// Set handle attribute
 pub fn set_handle_attribute(
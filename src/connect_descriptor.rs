@@ -0,0 +1,229 @@
+use std::fmt;
+use std::time::Duration;
+
+/// The network protocol used to reach the database.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    /// Plain TCP, the default.
+    Tcp,
+    /// TCP with TLS.
+    Tcps,
+}
+
+/// The type of server process that handles the connection.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServerType {
+    /// A dedicated server process.
+    Dedicated,
+    /// A shared server process.
+    Shared,
+    /// A pooled server from Database Resident Connection Pooling.
+    Pooled,
+}
+
+/// A structured Easy Connect Plus connection string.
+///
+/// Building connection strings by hand is error prone once timeouts, retries or proxies are
+/// needed. A `ConnectDescriptor` holds the parts separately and formats them, via `Display`,
+/// as `[protocol://]host[:port][/service][:server][?name=value&...]`.
+///
+/// # Examples
+///
+/// ```rust
+/// use oci_rs::connect_descriptor::{ConnectDescriptor, Protocol};
+/// use std::time::Duration;
+///
+/// let descriptor = ConnectDescriptor::new("db.example.com", "orclpdb")
+///     .protocol(Protocol::Tcps)
+///     .port(1522)
+///     .retry_count(3)
+///     .transport_connect_timeout(Duration::from_secs(5));
+///
+/// assert_eq!(
+///     descriptor.to_string(),
+///     "tcps://db.example.com:1522/orclpdb?retry_count=3&transport_connect_timeout=5"
+/// );
+/// ```
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectDescriptor {
+    protocol: Option<Protocol>,
+    hosts: Vec<String>,
+    port: Option<u16>,
+    service_name: String,
+    server_type: Option<ServerType>,
+    instance_name: Option<String>,
+    parameters: Vec<(String, String)>,
+}
+impl ConnectDescriptor {
+    /// Creates a new `ConnectDescriptor` for a service on a host.
+    ///
+    pub fn new(host: &str, service_name: &str) -> ConnectDescriptor {
+        ConnectDescriptor {
+            protocol: None,
+            hosts: vec![host.to_string()],
+            port: None,
+            service_name: service_name.to_string(),
+            server_type: None,
+            instance_name: None,
+            parameters: Vec::new(),
+        }
+    }
+
+    /// Sets the protocol, if not set the client default of TCP is used.
+    ///
+    pub fn protocol(mut self, protocol: Protocol) -> ConnectDescriptor {
+        self.protocol = Some(protocol);
+        self
+    }
+
+    /// Adds another host to try, for example the standby of a Data Guard pair. All hosts share
+    /// the same port.
+    ///
+    pub fn host(mut self, host: &str) -> ConnectDescriptor {
+        self.hosts.push(host.to_string());
+        self
+    }
+
+    /// Sets the port, if not set the default of 1521 is used.
+    ///
+    pub fn port(mut self, port: u16) -> ConnectDescriptor {
+        self.port = Some(port);
+        self
+    }
+
+    /// Sets the type of server process.
+    ///
+    pub fn server_type(mut self, server_type: ServerType) -> ConnectDescriptor {
+        self.server_type = Some(server_type);
+        self
+    }
+
+    /// Sets the instance to connect to.
+    ///
+    pub fn instance_name(mut self, instance_name: &str) -> ConnectDescriptor {
+        self.instance_name = Some(instance_name.to_string());
+        self
+    }
+
+    /// Sets the number of times the connection is retried.
+    ///
+    pub fn retry_count(self, retry_count: u32) -> ConnectDescriptor {
+        self.parameter("retry_count", &retry_count.to_string())
+    }
+
+    /// Sets the delay between connection retries.
+    ///
+    /// Oracle takes the delay in whole seconds, so a delay with a fraction of a second is
+    /// rounded up rather than cut short, e.g. half a second becomes one second, not none.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use oci_rs::connect_descriptor::ConnectDescriptor;
+    /// use std::time::Duration;
+    ///
+    /// let descriptor =
+    ///     ConnectDescriptor::new("db.example.com", "orclpdb").retry_delay(Duration::from_millis(500));
+    ///
+    /// assert_eq!(descriptor.to_string(), "db.example.com/orclpdb?retry_delay=1");
+    /// ```
+    ///
+    pub fn retry_delay(self, retry_delay: Duration) -> ConnectDescriptor {
+        let seconds = retry_delay.as_secs() + u64::from(retry_delay.subsec_nanos() > 0);
+        self.parameter("retry_delay", &seconds.to_string())
+    }
+
+    /// Sets how long to wait for the network connection to be established.
+    ///
+    pub fn transport_connect_timeout(self, timeout: Duration) -> ConnectDescriptor {
+        self.parameter("transport_connect_timeout", &format_timeout(timeout))
+    }
+
+    /// Sets how long to wait for the whole connection, including authentication, to be
+    /// established.
+    ///
+    pub fn connect_timeout(self, timeout: Duration) -> ConnectDescriptor {
+        self.parameter("connect_timeout", &format_timeout(timeout))
+    }
+
+    /// Sets an HTTPS proxy to connect through, e.g. to reach a cloud database from behind a
+    /// firewall. Only used with `Protocol::Tcps`.
+    ///
+    pub fn https_proxy(self, host: &str, port: u16) -> ConnectDescriptor {
+        self.parameter("https_proxy", host)
+            .parameter("https_proxy_port", &port.to_string())
+    }
+
+    /// Sets the distinguished name that the server certificate must match.
+    ///
+    pub fn ssl_server_cert_dn(self, dn: &str) -> ConnectDescriptor {
+        self.parameter("ssl_server_cert_dn", &format!("\"{}\"", dn))
+    }
+
+    /// Sets the directory that holds the wallet used for TLS.
+    ///
+    pub fn wallet_location(self, directory: &str) -> ConnectDescriptor {
+        self.parameter("wallet_location", directory)
+    }
+
+    /// Sets any other Easy Connect Plus parameter. Setting a parameter again replaces its value.
+    ///
+    pub fn parameter(mut self, name: &str, value: &str) -> ConnectDescriptor {
+        let name = name.to_lowercase();
+        match self.parameters.iter_mut().find(|param| param.0 == name) {
+            Some(param) => param.1 = value.to_string(),
+            None => self.parameters.push((name, value.to_string())),
+        }
+        self
+    }
+}
+
+impl fmt::Display for ConnectDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.protocol {
+            Some(Protocol::Tcp) => write!(f, "tcp://")?,
+            Some(Protocol::Tcps) => write!(f, "tcps://")?,
+            None => (),
+        }
+        write!(f, "{}", self.hosts.join(","))?;
+        if let Some(port) = self.port {
+            write!(f, ":{}", port)?;
+        }
+        write!(f, "/{}", self.service_name)?;
+        match self.server_type {
+            Some(ServerType::Dedicated) => write!(f, ":dedicated")?,
+            Some(ServerType::Shared) => write!(f, ":shared")?,
+            Some(ServerType::Pooled) => write!(f, ":pooled")?,
+            None => (),
+        }
+        if let Some(ref instance_name) = self.instance_name {
+            write!(f, "/{}", instance_name)?;
+        }
+        for (index, (name, value)) in self.parameters.iter().enumerate() {
+            let separator = if index == 0 { '?' } else { '&' };
+            write!(f, "{}{}={}", separator, name, value)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<ConnectDescriptor> for String {
+    fn from(descriptor: ConnectDescriptor) -> Self {
+        descriptor.to_string()
+    }
+}
+
+/// Timeouts are given in seconds, unless they need millisecond precision. Any fraction of a
+/// millisecond is rounded up, so that a short timeout isn't written as 0, which turns it off.
+///
+pub(crate) fn format_timeout(timeout: Duration) -> String {
+    let millis = timeout.as_nanos().div_ceil(1_000_000);
+    if millis.is_multiple_of(1000) {
+        (millis / 1000).to_string()
+    } else {
+        format!("{}ms", millis)
+    }
+}
@@ -1,14 +1,39 @@
-use crate::common::set_handle_attribute;
+use crate::attribute::{AttributeHandle, AttributeKind, AttributeValue};
+use crate::auth::{AuthProvider, AuthenticationMethod, Credentials, StaticPassword};
+use crate::common::{set_handle_attribute, wait_for_call};
+use crate::connect_descriptor::ConnectDescriptor;
+use crate::diagnostics::CallLog;
 use crate::oci_bindings::{
-    AttributeType, CredentialsType, EnvironmentMode, HandleType, OCIEnv, OCIEnvCreate, OCIError,
-    OCIHandleAlloc, OCIHandleFree, OCIServer, OCIServerAttach, OCIServerDetach, OCISession,
-    OCISessionBegin, OCISessionEnd, OCISvcCtx, ReturnCode,
+    AttributeType, CredentialsType, EnvironmentMode, HandleType, OCIAttrGet, OCIEnv, OCIEnvCreate,
+    OCIError, OCIHandleAlloc, OCIHandleFree, OCINlsNumericInfoGet, OCIPing, OCIServer,
+    OCIServerAttach, OCIServerDetach, OCIServerVersion, OCISession, OCISessionBegin, OCISessionEnd,
+    OCISvcCtx, OCITransCommit, OCITransRollback, ReturnCode, StatementType,
 };
 use crate::oci_error::{get_error, OciError};
-use crate::statement::Statement;
-use libc::{c_int, c_uint, c_void, size_t};
-use log::{error, info};
+use crate::plsql::{call_procedure, Param};
+use crate::secret::redact_connect_string;
+use crate::statement::{Statement, StatementOptions, SyntaxCheck};
+use crate::transaction::Transaction;
+use crate::types::{
+    CodecRegistry, SqlValue, TextTrim, TypeCodec, DEFAULT_CHAR_TRIM, DEFAULT_VARCHAR_TRIM,
+};
+use libc::{c_int, c_uchar, c_uint, c_ushort, c_void, size_t};
+use log::{debug, error, info, warn};
+use std::cell::{Cell, RefCell};
+use std::fmt;
 use std::ptr;
+use std::slice;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Large enough for the version banner of any server.
+const SERVER_VERSION_SIZE: usize = 512;
+/// OCI_NLS_CHARSET_MAXBYTESZ, the most bytes a character takes up in a character set.
+const OCI_NLS_CHARSET_MAXBYTESZ: c_ushort = 91;
+/// OCI_ATTR_NONBLOCKING_MODE, of the server handle.
+const OCI_ATTR_NONBLOCKING_MODE: u32 = 3;
+/// ORA-03156: OCI call timed out
+const CALL_TIMEOUT_EXCEEDED: i32 = 3156;
 
 /// Represents a connection to a database.
 ///
@@ -16,90 +41,1213 @@ use std::ptr;
 /// a connection to the database. Once it goes out of scope it will free these handles using
 /// the relevant OCI calls via a Drop implementation.
 ///
-#[derive(Debug)]
+/// The `Debug` output never contains the password and any credentials embedded in the
+/// connection string are redacted.
+///
 pub struct Connection {
+    handles: Arc<SessionHandles>,
+    error: *mut OCIError,
+    service: *mut OCISvcCtx,
+    session: *mut OCISession,
+    connection_str: String,
+    user_name: String,
+    migratable: bool,
+    module_action: RefCell<(String, String)>,
+    transaction: RefCell<Option<TransactionState>>,
+    deadline: Cell<Option<Instant>>,
+    call_log: Arc<CallLog>,
+    codecs: CodecRegistry,
+    numbers_as_text: bool,
+    varchar_trim: TextTrim,
+    char_trim: TextTrim,
+}
+
+// The environment is created in threaded mode, so the OCI library serialises access to the
+// handles and they can safely be moved to another thread.
+unsafe impl Send for Connection {}
+
+/// The log target used for transaction boundaries, so they can be filtered separately.
+const TRANSACTION_TARGET: &str = "oci_rs::transaction";
+
+/// Tracks the transaction that Oracle implicitly starts on the first change to the data.
+///
+#[derive(Debug)]
+struct TransactionState {
+    started: Instant,
+    statements: u32,
+}
+
+/// The environment and the physical connection to the database, which are shared by all the
+/// sessions created on it.
+///
+#[derive(Debug)]
+struct ServerHandles {
     environment: *mut OCIEnv,
     server: *mut OCIServer,
     error: *mut OCIError,
+}
+
+// As for `Connection`, the threaded environment serialises access to the handles.
+unsafe impl Send for ServerHandles {}
+unsafe impl Sync for ServerHandles {}
+
+/// The handles of a user session, which end the session and are freed once it is dropped.
+///
+/// A session migrated from a primary session, see `Connection.new_session`, holds on to the
+/// primary's handles, as OCI needs the primary session for as long as the migrated one is in
+/// use. Handles that were never allocated are null.
+///
+#[derive(Debug)]
+struct SessionHandles {
+    server: Arc<ServerHandles>,
+    error: *mut OCIError,
     service: *mut OCISvcCtx,
     session: *mut OCISession,
+    started: bool,
+    primary: Option<Arc<SessionHandles>>,
+}
+
+// As for `Connection`, the threaded environment serialises access to the handles.
+unsafe impl Send for SessionHandles {}
+unsafe impl Sync for SessionHandles {}
+
+impl Drop for SessionHandles {
+    /// Ends the session, if it was started, and frees the handles. The primary session and the
+    /// physical connection are only let go of afterwards, as the fields are dropped.
+    ///
+    fn drop(&mut self) {
+        if self.started {
+            let session_end_result = wait_for_call(|| unsafe {
+                OCISessionEnd(
+                    self.service,
+                    self.error,
+                    self.session,
+                    EnvironmentMode::Default.into(),
+                )
+            });
+
+            match session_end_result.into() {
+                ReturnCode::Success => (),
+                _ => error!("Could not end user session"),
+            }
+        }
+
+        for &(handle, handle_type) in &[
+            (self.session as *mut c_void, HandleType::Session),
+            (self.service as *mut c_void, HandleType::Service),
+            (self.error as *mut c_void, HandleType::Error),
+        ] {
+            if handle.is_null() {
+                continue;
+            }
+            let free_result = unsafe { OCIHandleFree(handle, handle_type.into()) };
+            match free_result.into() {
+                ReturnCode::Success => (),
+                _ => error!("Could not free the handles in Connection"),
+            }
+        }
+    }
 }
+
+impl Drop for ServerHandles {
+    /// Disconnects from the database and frees the environment, which frees any handles
+    /// allocated from it.
+    ///
+    fn drop(&mut self) {
+        let disconnect_result = wait_for_call(|| unsafe {
+            OCIServerDetach(self.server, self.error, EnvironmentMode::Default.into())
+        });
+
+        match disconnect_result.into() {
+            ReturnCode::Success => (),
+            _ => error!("Could not disconnect"),
+        }
+
+        let free_result = unsafe {
+            OCIHandleFree(
+                self.environment as *mut c_void,
+                HandleType::Environment.into(),
+            )
+        };
+
+        match free_result.into() {
+            ReturnCode::Success => (),
+            _ => error!("Could not free the handles in Connection"),
+        }
+    }
+}
+
 impl Connection {
     /// Creates a new `Connection`.
     ///
-    /// # Errors
+    /// # Errors
+    ///
+    /// Any errors encounter when trying to allocate handles in OCI library will bubble up here.
+    /// The [`OciError`][1] will return the relevant Oracle error codes and text when available.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    ///
+    /// let connection = Connection::new("localhost:1521/xe",
+    ///                                  "user",
+    ///                                  "password")
+    ///                                  .unwrap();
+    /// ```
+    ///
+    /// [1]: ../oci_error/enum.OciError.html
+    ///
+    pub fn new(
+        connection_str: &str,
+        user_name: &str,
+        password: &str,
+    ) -> Result<Connection, OciError> {
+        ConnectionBuilder::new(connection_str)
+            .auth(StaticPassword::new(user_name, password))
+            .build()
+    }
+
+    /// Connects and starts a session using the credentials given.
+    ///
+    fn connect(
+        connection_str: &str,
+        credentials: &Credentials,
+        migratable: bool,
+    ) -> Result<Connection, OciError> {
+        let environment = create_environment_handle()?;
+        let server = create_server_handle(environment)?;
+        let server_error = create_error_handle(environment)?;
+        let call_log = CallLog::new();
+        call_log.watch(server_error);
+        connect_to_database(server, connection_str, server_error, &call_log)?;
+        let server = Arc::new(ServerHandles {
+            environment,
+            server,
+            error: server_error,
+        });
+        let connection = Connection::start(
+            server,
+            connection_str,
+            credentials,
+            migratable,
+            None,
+            call_log,
+        )?;
+        let server_version =
+            read_server_version(connection.handles.server.server, connection.error);
+        connection.call_log.record_server_version(server_version);
+        Ok(connection)
+    }
+
+    /// Starts a user session on a physical connection, migrated from the primary session if
+    /// one is given.
+    ///
+    /// Should anything fail the handles allocated so far are freed as `handles` is dropped.
+    ///
+    fn start(
+        server: Arc<ServerHandles>,
+        connection_str: &str,
+        credentials: &Credentials,
+        migratable: bool,
+        primary: Option<Arc<SessionHandles>>,
+        call_log: Arc<CallLog>,
+    ) -> Result<Connection, OciError> {
+        let environment = server.environment;
+        let mut handles = SessionHandles {
+            error: create_error_handle(environment)?,
+            server,
+            service: ptr::null_mut(),
+            session: ptr::null_mut(),
+            started: false,
+            primary: None,
+        };
+        let error = handles.error;
+        call_log.watch(error);
+        handles.service = create_service_handle(environment)?;
+        handles.session = create_session_handle(environment)?;
+        let (service, session) = (handles.service, handles.session);
+        set_server_in_service(service, handles.server.server, error)?;
+        let credentials_type = set_credentials_in_session(session, credentials, error)?;
+        if let Some(primary) = primary {
+            set_migration_session(session, primary.session, error)?;
+            handles.primary = Some(primary);
+        }
+        start_session(
+            service,
+            session,
+            credentials_type,
+            migratable,
+            error,
+            &call_log,
+        )?;
+        handles.started = true;
+        set_session_in_service(service, session, error)?;
+        Ok(Connection {
+            handles: Arc::new(handles),
+            error,
+            service,
+            session,
+            connection_str: connection_str.to_string(),
+            user_name: credentials.user_name().unwrap_or_default().to_string(),
+            migratable,
+            module_action: RefCell::new((String::new(), String::new())),
+            transaction: RefCell::new(None),
+            deadline: Cell::new(None),
+            call_log,
+            codecs: CodecRegistry::new(),
+            numbers_as_text: false,
+            varchar_trim: DEFAULT_VARCHAR_TRIM,
+            char_trim: DEFAULT_CHAR_TRIM,
+        })
+    }
+
+    /// Starts another user session that shares this connection's physical connection to the
+    /// database.
+    ///
+    /// Lightweight sessions like this save a server process, and a network connection, for each
+    /// user, which helps when many users are connected but few are busy at the same time. Calls
+    /// from the sessions are serialised over the shared connection, which is closed once the
+    /// last of them is dropped.
+    ///
+    /// If this connection was created with [`ConnectionBuilder.migratable`][1] then the new
+    /// session is also migratable, using this session as the one it is migrated from. This
+    /// session is then kept open until the new one has been dropped, even if this `Connection`
+    /// is dropped first. Any codecs registered on this connection are registered on the new
+    /// session too.
+    ///
+    /// # Errors
+    ///
+    /// Any error returned by the `AuthProvider` or the underlying calls to the OCI library will
+    /// be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::auth::StaticPassword;
+    /// use oci_rs::connection::ConnectionBuilder;
+    ///
+    /// let primary = ConnectionBuilder::new("localhost:1521/xe")
+    ///     .auth(StaticPassword::new("app", "password"))
+    ///     .migratable(true)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let alice = primary.new_session(StaticPassword::new("alice", "secret")).unwrap();
+    /// let bob = primary.new_session(StaticPassword::new("bob", "secret")).unwrap();
+    /// ```
+    ///
+    /// [1]: struct.ConnectionBuilder.html#method.migratable
+    pub fn new_session<A: AuthProvider>(&self, provider: A) -> Result<Connection, OciError> {
+        let credentials = provider.credentials()?;
+        let primary = if self.migratable {
+            Some(Arc::clone(&self.handles))
+        } else {
+            None
+        };
+        let call_log = CallLog::new();
+        call_log.set_record_sql(self.call_log.records_sql());
+        call_log.record_server_version(self.call_log.server_version());
+        let mut session = Connection::start(
+            Arc::clone(&self.handles.server),
+            &self.connection_str,
+            &credentials,
+            self.migratable,
+            primary,
+            call_log,
+        )
+        .map_err(|err| provider.explain_error(err))?;
+        session.codecs = self.codecs.clone();
+        session.numbers_as_text = self.numbers_as_text;
+        session.varchar_trim = self.varchar_trim;
+        session.char_trim = self.char_trim;
+        Ok(session)
+    }
+
+    /// Creates a new [`Statement`][2].
+    ///
+    /// A `Statement` can only live as long as the `Connection` that created it. The SQL
+    /// statement that needs to be executed is supplied. A connection can have multiple
+    /// statements active.
+    ///
+    /// # Errors
+    ///
+    /// Any OCI failures will be reported and the relevant Oracle error codes available.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    ///
+    /// let connection = Connection::new("localhost:1521/xe",
+    ///                                  "user",
+    ///                                  "password")
+    ///                                  .unwrap();
+    ///
+    /// let sql_select = "SELECT * FROM SomeTable";
+    /// let select_stmt = match connection.create_prepared_statement(sql_select) {
+    ///     Ok(stmt) => stmt,
+    ///     Err(err) => panic!("Oracle error: {}", err),
+    /// };
+    /// ```
+    ///
+    /// [2]: ../statement/struct.Statement.html
+    pub fn create_prepared_statement(&self, sql: &str) -> Result<Statement, OciError> {
+        Statement::new(self, sql)
+    }
+
+    /// Creates a new [`Statement`][1] with the given [`StatementOptions`][2] applied.
+    ///
+    /// # Errors
+    ///
+    /// Any OCI failures will be reported and the relevant Oracle error codes available.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    /// use oci_rs::statement::StatementOptions;
+    /// use std::time::Duration;
+    ///
+    /// let connection = Connection::new("localhost:1521/xe", "user", "password").unwrap();
+    ///
+    /// let options = StatementOptions::new()
+    ///     .prefetch_rows(500)
+    ///     .fetch_array_size(500)
+    ///     .call_timeout(Duration::from_secs(30));
+    /// let select = connection
+    ///     .create_prepared_statement_with("SELECT * FROM SomeTable", &options)
+    ///     .unwrap();
+    /// ```
+    ///
+    /// [1]: ../statement/struct.Statement.html
+    /// [2]: ../statement/struct.StatementOptions.html
+    pub fn create_prepared_statement_with(
+        &self,
+        sql: &str,
+        options: &StatementOptions,
+    ) -> Result<Statement<'_>, OciError> {
+        Statement::with_options(self, sql, options)
+    }
+
+    /// Checks SQL without running it, by having the database parse it.
+    ///
+    /// If the SQL is valid the kind of statement, its bind placeholders and, for a query, the
+    /// columns it would return are given back. This is handy for checking SQL written by users,
+    /// e.g. in a reporting tool, before it is saved. Only queries, DML and PL/SQL can be checked,
+    /// as Oracle runs DDL, e.g. `CREATE TABLE`, as soon as it is parsed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the parse error if the SQL isn't valid, with the offset at which parsing failed
+    /// when it is known, see `ErrorRecord.parse_error_offset`. Returns an error for DDL and
+    /// other statements that can't be checked without running them.
+    ///
+    /// Any other error in the underlying calls to the OCI library will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    /// use oci_rs::oci_error::OciError;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// match conn.check_syntax("SELECT Name, Breed FROM Dogs WHERE DogId = :id") {
+    ///     Ok(check) => {
+    ///         for column in check.columns() {
+    ///             println!("{} {}", column.name(), column.data_type());
+    ///         }
+    ///     }
+    ///     Err(OciError::Oracle(record)) => println!("{}", record),
+    ///     Err(err) => panic!("{}", err),
+    /// }
+    /// ```
+    ///
+    pub fn check_syntax(&self, sql: &str) -> Result<SyntaxCheck, OciError> {
+        let mut statement = Statement::new(self, sql)?;
+        statement.check_syntax()
+    }
+
+    /// Sets the number of prepared statements held in the session's statement cache.
+    ///
+    /// Statements are cached using their SQL text as the key, so preparing the same SQL again
+    /// re-uses the cached cursor instead of the database having to parse it again. The OCI
+    /// default is to cache 20 statements. Setting the size to zero turns the cache off.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    ///
+    /// let connection = Connection::new("localhost:1521/xe",
+    ///                                  "user",
+    ///                                  "password")
+    ///                                  .unwrap();
+    ///
+    /// connection.set_statement_cache_size(50).unwrap();
+    /// ```
+    ///
+    pub fn set_statement_cache_size(&self, size: u32) -> Result<(), OciError> {
+        let attribute_size: c_uint = 0;
+        let cache_size: c_uint = size as c_uint;
+        let cache_size_ptr: *const c_uint = &cache_size;
+        set_handle_attribute(
+            self.service as *mut c_void,
+            HandleType::Service,
+            cache_size_ptr as *mut c_void,
+            attribute_size,
+            AttributeType::StatementCacheSize,
+            self.error,
+            "Setting statement cache size in service handle",
+        )
+    }
+
+    /// Turns non-blocking mode on or off.
+    ///
+    /// In non-blocking mode a call to the database returns straight away instead of waiting for
+    /// the database to answer. `Statement.poll_execute` and `RowIter.poll_next` then report
+    /// that the call is still in progress, and are called again until it has finished, which
+    /// lets an event loop drive queries without a thread waiting on each one. The rest of the
+    /// API keeps working by waiting for each call to finish.
+    ///
+    /// The mode belongs to the physical connection, so it applies to every session sharing it.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    /// use std::task::Poll;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    /// conn.set_nonblocking(true).unwrap();
+    ///
+    /// let mut report = conn
+    ///     .create_prepared_statement("BEGIN build_monthly_report; END;")
+    ///     .unwrap();
+    /// while let Poll::Pending = report.poll_execute().unwrap() {
+    ///     // do other work
+    /// }
+    /// ```
+    ///
+    pub fn set_nonblocking(&self, nonblocking: bool) -> Result<(), OciError> {
+        if self.is_nonblocking()? != nonblocking {
+            // setting the attribute toggles the mode, whatever the value
+            // SAFETY: OCI_ATTR_NONBLOCKING_MODE is a ub1 attribute of the server handle.
+            unsafe {
+                self.set_attribute(
+                    AttributeHandle::Server,
+                    OCI_ATTR_NONBLOCKING_MODE,
+                    &AttributeValue::UByte(0),
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns true if the connection is in non-blocking mode, see `.set_nonblocking`.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned.
+    ///
+    pub fn is_nonblocking(&self) -> Result<bool, OciError> {
+        // SAFETY: OCI_ATTR_NONBLOCKING_MODE is a ub1 attribute of the server handle.
+        let mode = unsafe {
+            self.get_attribute(
+                AttributeHandle::Server,
+                OCI_ATTR_NONBLOCKING_MODE,
+                AttributeKind::UByte,
+            )?
+        };
+        Ok(mode == AttributeValue::UByte(1))
+    }
+
+    /// Returns how the database authenticated the current session.
+    ///
+    /// This is useful to confirm that external authentication such as Kerberos or RADIUS was
+    /// actually used, rather than a fallback to a database password.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned.
+    ///
+    pub fn authentication_method(&self) -> Result<AuthenticationMethod, OciError> {
+        let sql = "SELECT SYS_CONTEXT('USERENV', 'AUTHENTICATION_METHOD') FROM dual";
+        let mut select = self.create_prepared_statement(sql)?;
+        select.execute()?;
+        let method = match select.lazy_result_set()?.next() {
+            Some(row) => row?[0].value::<String>().unwrap_or_default(),
+            None => String::new(),
+        };
+        Ok(AuthenticationMethod::from(method.as_str()))
+    }
+
+    /// Checks that the connection to the database is still alive.
+    ///
+    /// This makes a light-weight round trip to the server without executing any SQL.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the connection is broken or the server is not available.
+    ///
+    pub fn ping(&self) -> Result<(), OciError> {
+        self.apply_deadline()?;
+        let ping_result = wait_for_call(|| unsafe {
+            OCIPing(self.service, self.error, EnvironmentMode::Default.into())
+        });
+        self.call_log.record_call(
+            "OCIPing",
+            HandleType::Service,
+            self.service as *mut c_void,
+            ping_result,
+        );
+        check_result(ping_result, self.error, "Pinging the database")
+            .map_err(|err| self.check_deadline(err))
+    }
+
+    /// Commits the current transaction.
+    ///
+    /// Oracle implicitly starts a transaction with the first statement that changes data. Each
+    /// transaction is logged, under the `oci_rs::transaction` target, when it begins and when it
+    /// is committed or rolled back, together with how long it was open and how many statements
+    /// ran in it. This helps find transactions that hold locks for too long.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned.
+    ///
+    pub fn commit(&self) -> Result<(), OciError> {
+        self.apply_deadline()?;
+        let commit_result = wait_for_call(|| unsafe {
+            OCITransCommit(self.service, self.error, EnvironmentMode::Default.into())
+        });
+        self.call_log.record_call(
+            "OCITransCommit",
+            HandleType::Service,
+            self.service as *mut c_void,
+            commit_result,
+        );
+        check_result(commit_result, self.error, "Commiting transaction")
+            .map_err(|err| self.check_deadline(err))?;
+        self.end_transaction("commit");
+        Ok(())
+    }
+
+    /// Rolls back the current transaction, undoing any changes since the last commit.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned.
+    ///
+    pub fn rollback(&self) -> Result<(), OciError> {
+        self.apply_deadline()?;
+        let rollback_result = wait_for_call(|| unsafe {
+            OCITransRollback(self.service, self.error, EnvironmentMode::Default.into())
+        });
+        self.call_log.record_call(
+            "OCITransRollback",
+            HandleType::Service,
+            self.service as *mut c_void,
+            rollback_result,
+        );
+        check_result(rollback_result, self.error, "Rolling back transaction")
+            .map_err(|err| self.check_deadline(err))?;
+        self.end_transaction("rollback");
+        Ok(())
+    }
+
+    /// Starts a [`Transaction`][1], which is rolled back when dropped unless it is committed.
+    ///
+    /// Oracle has no statement to begin a transaction, so this only marks the point from which
+    /// changes are rolled back. Any uncommitted changes made before it are part of it too.
+    ///
+    /// [1]: ../transaction/struct.Transaction.html
+    pub fn transaction(&self) -> Transaction<'_> {
+        Transaction::new(self)
+    }
+
+    /// Calls a stored procedure, optionally qualified with its schema and package, and returns
+    /// the values of its `OUT` and `IN OUT` parameters in order.
+    ///
+    /// The parameters are passed by position. After the call each [`Param::InOut`][1] holds the
+    /// value the procedure set it to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the procedure name isn't a valid dotted name.
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    /// use oci_rs::plsql::Param;
+    /// use oci_rs::types::{SqlType, SqlValue};
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// let mut params = [
+    ///     Param::In(&"Poodle"),
+    ///     Param::Out(SqlType::Integer),
+    ///     Param::InOut(SqlValue::Integer(1)),
+    /// ];
+    /// let outputs = conn.call("kennel.dogs.register", &mut params).unwrap();
+    /// let dog_id: i64 = outputs[0].value().unwrap();
+    /// ```
+    ///
+    /// [1]: ../plsql/enum.Param.html#variant.InOut
+    pub fn call(&self, procedure: &str, params: &mut [Param]) -> Result<Vec<SqlValue>, OciError> {
+        call_procedure(self, procedure, params)
+    }
+
+    /// Records that a statement was executed, to follow the implicit transaction boundaries.
+    ///
+    /// DML, `SELECT ... FOR UPDATE`, which locks the rows, and PL/SQL blocks start a
+    /// transaction. There is no telling from the outside whether a block changed any data, so
+    /// it is assumed to have done so.
+    ///
+    pub(crate) fn statement_executed(&self, statement_type: &StatementType, sql: &str) {
+        let mut transaction = self.transaction.borrow_mut();
+        match *statement_type {
+            StatementType::Select if !locks_rows(sql) => (),
+            StatementType::Insert
+            | StatementType::Update
+            | StatementType::Delete
+            | StatementType::Merge
+            | StatementType::Select
+            | StatementType::Begin
+            | StatementType::Declare => {
+                let state = transaction.get_or_insert_with(|| {
+                    info!(target: TRANSACTION_TARGET, "transaction=begin");
+                    TransactionState {
+                        started: Instant::now(),
+                        statements: 0,
+                    }
+                });
+                state.statements += 1;
+            }
+            StatementType::Create | StatementType::Drop | StatementType::Alter => {
+                drop(transaction);
+                self.end_transaction("implicit commit by DDL");
+            }
+            StatementType::Unknown => (),
+        }
+    }
+
+    /// Returns the number of statements run in the current transaction, or `None` if there
+    /// isn't one.
+    ///
+    #[cfg(test)]
+    pub(crate) fn transaction_statements(&self) -> Option<u32> {
+        self.transaction
+            .borrow()
+            .as_ref()
+            .map(|state| state.statements)
+    }
+
+    /// Logs the end of the current transaction, if there is one.
+    ///
+    pub(crate) fn end_transaction(&self, outcome: &str) {
+        if let Some(state) = self.transaction.borrow_mut().take() {
+            info!(
+                target: TRANSACTION_TARGET,
+                "transaction={} duration_ms={} statements={}",
+                outcome,
+                state.started.elapsed().as_millis(),
+                state.statements
+            );
+        }
+    }
+
+    /// Sets the module and action that the session is reported under.
+    ///
+    /// They show up in `V$SESSION` and in tools such as AWR and ASH, which makes it possible to
+    /// attribute database work to the part of an application that did it. The values are sent
+    /// with the next call to the database, so this does not cost a round trip.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned.
+    ///
+    pub fn set_module_action(&self, module: &str, action: &str) -> Result<(), OciError> {
+        set_session_text(self.session, module, AttributeType::Module, self.error)?;
+        set_session_text(self.session, action, AttributeType::Action, self.error)?;
+        *self.module_action.borrow_mut() = (module.to_string(), action.to_string());
+        Ok(())
+    }
+
+    /// Sets the module and action until the returned guard is dropped, when the previous values
+    /// are put back.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned.
+    ///
+    pub fn with_module_action(
+        &self,
+        module: &str,
+        action: &str,
+    ) -> Result<ModuleActionGuard<'_>, OciError> {
+        let previous = self.module_action.borrow().clone();
+        self.set_module_action(module, action)?;
+        Ok(ModuleActionGuard {
+            connection: self,
+            previous,
+        })
+    }
+
+    /// Runs a closure with the module and action set, then puts the previous values back, and
+    /// returns what the closure returns.
+    ///
+    /// Calls can be nested, so a library can label its own work without disturbing the labels
+    /// set by the code calling it.
+    ///
+    /// # Errors
+    ///
+    /// Any error in setting the module and action is returned, in which case the closure is
+    /// not run, as is any error returned by the closure.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// conn.in_action("PAYMENTS", "settle", || {
+    ///     let mut update = conn.create_prepared_statement("UPDATE Payments SET Settled = 1")?;
+    ///     update.execute()?;
+    ///     update.commit()
+    /// })
+    /// .unwrap();
+    /// ```
+    ///
+    pub fn in_action<F, T>(&self, module: &str, action: &str, f: F) -> Result<T, OciError>
+    where
+        F: FnOnce() -> Result<T, OciError>,
+    {
+        let _guard = self.with_module_action(module, action)?;
+        f()
+    }
+
+    /// Bounds the time spent in the database by the calls made until the guard that is returned
+    /// is dropped.
+    ///
+    /// Before each call to the database, executing a statement, fetching rows, committing and so
+    /// on, the time left until the deadline is set as the OCI call timeout. This way a latency
+    /// budget for a request, e.g. in a web service, carries over to all the database work done
+    /// for it. A call that runs past the deadline is interrupted by Oracle and returns an error,
+    /// and either way the error is [`OciError::DeadlineExceeded`][1]. Once the deadline has
+    /// passed calls fail straight away.
+    ///
+    /// Deadlines can be nested but an inner one can't extend the outer one, the earlier of the
+    /// two applies. The previous deadline comes back into force when the guard is dropped.
+    /// Call timeouts need an Oracle 18c or later client.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// let _deadline = conn.with_deadline(Instant::now() + Duration::from_millis(250));
+    /// let mut select = conn.create_prepared_statement("SELECT Name FROM Dogs").unwrap();
+    /// select.execute().unwrap();
+    /// let dogs = select.result_set().unwrap();
+    /// ```
+    ///
+    /// [1]: ../oci_error/enum.OciError.html#variant.DeadlineExceeded
+    pub fn with_deadline(&self, deadline: Instant) -> DeadlineGuard<'_> {
+        let previous = self.deadline.get();
+        let deadline = match previous {
+            Some(previous) => previous.min(deadline),
+            None => deadline,
+        };
+        self.deadline.set(Some(deadline));
+        DeadlineGuard {
+            connection: self,
+            previous,
+        }
+    }
+
+    /// Registers a codec for columns that Oracle describes with the given OCI type code.
+    ///
+    /// This adds support for a type the crate doesn't handle yet, or changes how a supported type
+    /// is fetched, for the statements created on this connection afterwards. See
+    /// [`TypeCodec`][1] for an example.
+    ///
+    /// [1]: ../types/trait.TypeCodec.html
+    pub fn register_codec<C: TypeCodec + 'static>(&mut self, type_code: u16, codec: C) {
+        self.codecs.register(type_code, codec);
+    }
+
+    /// Registers a codec for the columns whose names match a pattern, for the statements created
+    /// on this connection afterwards.
+    ///
+    /// The codec is used for a matching column whatever its type, ahead of any codec registered
+    /// for the type code, so a column of a type the crate doesn't know about can be fetched
+    /// without knowing its type code, or particular columns can be fetched differently from the
+    /// rest. Names are matched without regard to case and a `*` matches any number of
+    /// characters. See [`CodecRegistry`][1] for how patterns are chosen between.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    /// use oci_rs::oci_error::OciError;
+    /// use oci_rs::types::{ColumnType, SqlValue, TypeCodec};
+    ///
+    /// /// Fetches a column as text, however Oracle describes it.
+    /// struct AsText;
+    ///
+    /// impl TypeCodec for AsText {
+    ///     fn fetch_as(&self, _column: &ColumnType) -> (u16, usize) {
+    ///         (1, 64)
+    ///     }
+    ///
+    ///     fn decode(&self, _type_code: u16, data: &[u8]) -> Result<SqlValue, OciError> {
+    ///         Ok(SqlValue::VarChar(String::from_utf8_lossy(data).into_owned()))
+    ///     }
+    /// }
+    ///
+    /// let mut conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    /// conn.register_column_codec("*_INTERVAL", AsText);
+    /// ```
+    ///
+    /// [1]: ../types/struct.CodecRegistry.html
+    pub fn register_column_codec<C: TypeCodec + 'static>(&mut self, pattern: &str, codec: C) {
+        self.codecs.register_column_codec(pattern, codec);
+    }
+
+    /// Fetches `NUMBER` columns as their exact decimal text, for the statements created on this
+    /// connection afterwards.
+    ///
+    /// Each statement can change this with [`Statement.set_numbers_as_text`][1], which describes
+    /// the text given. It is off by default.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    ///
+    /// let mut conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    /// conn.set_numbers_as_text(true);
     ///
-    /// Any errors encounter when trying to allocate handles in OCI library will bubble up here.
-    /// The [`OciError`][1] will return the relevant Oracle error codes and text when available.
+    /// let mut query = conn.create_prepared_statement("SELECT 1.5 FROM dual").unwrap();
+    /// query.execute().unwrap();
+    /// let results = query.result_set().unwrap();
+    /// let amount: String = results[0][0].value().unwrap();
+    /// assert_eq!(amount, "1.5");
+    /// ```
+    ///
+    /// [1]: ../statement/struct.Statement.html#method.set_numbers_as_text
+    pub fn set_numbers_as_text(&mut self, enabled: bool) {
+        self.numbers_as_text = enabled;
+    }
+
+    /// Returns whether statements fetch `NUMBER` columns as text by default.
+    ///
+    pub(crate) fn numbers_as_text(&self) -> bool {
+        self.numbers_as_text
+    }
+
+    /// Sets whether the last SQL prepared on this connection is included in the
+    /// [`Diagnostics`][1] attached to errors where the OCI library failed unexpectedly.
+    ///
+    /// It is left out by default, as the SQL may contain data, such as literals, that shouldn't
+    /// end up in logs or bug reports. Sessions started with `.new_session` take the setting from
+    /// this connection.
+    ///
+    /// [1]: ../diagnostics/struct.Diagnostics.html
+    pub fn set_sql_in_diagnostics(&mut self, enabled: bool) {
+        self.call_log.set_record_sql(enabled);
+    }
+
+    /// Returns the log of recent calls kept for diagnostics.
+    ///
+    pub(crate) fn call_log(&self) -> &Arc<CallLog> {
+        &self.call_log
+    }
+
+    /// Sets how text fetched from `VARCHAR2` columns is trimmed, for the statements created on
+    /// this connection afterwards.
+    ///
+    /// It is trimmed at both ends by default. Each statement can change this with
+    /// [`Statement.set_varchar_trim`][1].
     ///
     /// # Examples
     ///
     /// ```rust,no_run
     /// use oci_rs::connection::Connection;
+    /// use oci_rs::types::TextTrim;
     ///
-    /// let connection = Connection::new("localhost:1521/xe",
-    ///                                  "user",
-    ///                                  "password")
-    ///                                  .unwrap();
+    /// let mut conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    /// conn.set_varchar_trim(TextTrim::Preserve);
+    ///
+    /// let mut query = conn.create_prepared_statement("SELECT '  indented' FROM dual").unwrap();
+    /// query.execute().unwrap();
+    /// let results = query.result_set().unwrap();
+    /// let text: String = results[0][0].value().unwrap();
+    /// assert_eq!(text, "  indented");
     /// ```
     ///
-    /// [1]: ../oci_error/enum.OciError.html
+    /// [1]: ../statement/struct.Statement.html#method.set_varchar_trim
+    pub fn set_varchar_trim(&mut self, trim: TextTrim) {
+        self.varchar_trim = trim;
+    }
+
+    /// Sets how text fetched from `CHAR` columns is trimmed, for the statements created on this
+    /// connection afterwards.
     ///
-    pub fn new(
-        connection_str: &str,
-        user_name: &str,
-        password: &str,
-    ) -> Result<Connection, OciError> {
-        let environment = create_environment_handle()?;
-        let server = create_server_handle(environment)?;
-        let error = create_error_handle(environment)?;
-        let service = create_service_handle(environment)?;
-        let session = create_session_handle(environment)?;
-        connect_to_database(server, connection_str, error)?;
-        set_server_in_service(service, server, error)?;
-        set_user_name_in_session(session, user_name, error)?;
-        set_password_in_session(session, password, error)?;
-        start_session(service, session, error)?;
-        set_session_in_service(service, session, error)?;
-        Ok(Connection {
-            environment,
-            server,
-            error,
-            service,
-            session,
-        })
+    /// It keeps the padding Oracle stores it with by default. Each statement can change this
+    /// with [`Statement.set_char_trim`][1].
+    ///
+    /// [1]: ../statement/struct.Statement.html#method.set_char_trim
+    pub fn set_char_trim(&mut self, trim: TextTrim) {
+        self.char_trim = trim;
     }
 
-    /// Creates a new [`Statement`][2].
+    /// Returns how statements trim `VARCHAR2` and `CHAR` text by default.
     ///
-    /// A `Statement` can only live as long as the `Connection` that created it. The SQL
-    /// statement that needs to be executed is supplied. A connection can have multiple
-    /// statements active.
+    pub(crate) fn text_trim(&self) -> (TextTrim, TextTrim) {
+        (self.varchar_trim, self.char_trim)
+    }
+
+    /// Returns the codecs used to fetch columns.
+    ///
+    pub(crate) fn codecs(&self) -> &CodecRegistry {
+        &self.codecs
+    }
+
+    /// Sets the OCI call timeout to the time left until the deadline, if there is one.
+    ///
+    pub(crate) fn apply_deadline(&self) -> Result<(), OciError> {
+        match self.deadline.get() {
+            Some(deadline) => {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.as_millis() == 0 {
+                    return Err(OciError::DeadlineExceeded(None));
+                }
+                let millis = remaining.as_millis().min(c_uint::MAX as u128) as c_uint;
+                set_call_timeout(self.service, millis, self.error)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Turns the error from a call interrupted by the call timeout into
+    /// `OciError::DeadlineExceeded`, if there is a deadline.
+    ///
+    pub(crate) fn check_deadline(&self, err: OciError) -> OciError {
+        match err {
+            OciError::Oracle(record)
+                if self.deadline.get().is_some() && record.has_code(CALL_TIMEOUT_EXCEEDED) =>
+            {
+                OciError::DeadlineExceeded(Some(record))
+            }
+            err => err,
+        }
+    }
+
+    /// Sets the OCI call timeout for a statement with its own timeout, keeping to the deadline if
+    /// that is sooner.
+    ///
+    pub(crate) fn apply_call_timeout(&self, timeout: Duration) -> Result<(), OciError> {
+        let timeout = match self.deadline.get() {
+            Some(deadline) => timeout.min(deadline.saturating_duration_since(Instant::now())),
+            None => timeout,
+        };
+        let millis = timeout.as_millis().clamp(1, c_uint::MAX as u128) as c_uint;
+        set_call_timeout(self.service, millis, self.error)
+    }
+
+    /// Turns the call timeout off after a statement with its own timeout, unless there is a
+    /// deadline, which is applied again before the next call anyway.
+    ///
+    pub(crate) fn clear_call_timeout(&self) {
+        if self.deadline.get().is_none() {
+            if let Err(err) = set_call_timeout(self.service, 0, self.error) {
+                error!("Could not clear the call timeout: {}", err)
+            }
+        }
+    }
+
+    /// Sets an OCI attribute that this crate doesn't wrap yet.
+    ///
+    /// The attribute is given as its number from the OCI headers (`oci.h`), e.g. 366 for
+    /// `OCI_ATTR_MODULE`, along with the handle it belongs to. This is an escape hatch: it is up
+    /// to the caller to check the OCI documentation and pass a value of the right type. An
+    /// attribute number that the handle doesn't have is rejected by OCI with an error.
+    ///
+    /// # Safety
+    ///
+    /// OCI trusts the caller about the type of the value, so `value` has to be the C type that
+    /// the OCI documentation gives for the attribute on that handle. Anything else makes OCI
+    /// read memory that isn't there, e.g. a `UByte` for a `ub4` attribute, or dereference an
+    /// integer, e.g. a `UInt` for an attribute that takes a handle or a pointer, which no
+    /// `AttributeValue` can hold. Attributes that change the state the crate keeps track of,
+    /// such as the handles of the session, can also break the safe parts of the `Connection`.
     ///
     /// # Errors
     ///
-    /// Any OCI failures will be reported and the relevant Oracle error codes available.
+    /// Any error in the underlying calls to the OCI library will be returned.
     ///
     /// # Examples
     ///
     /// ```rust,no_run
+    /// use oci_rs::attribute::{AttributeHandle, AttributeValue};
     /// use oci_rs::connection::Connection;
     ///
-    /// let connection = Connection::new("localhost:1521/xe",
-    ///                                  "user",
-    ///                                  "password")
-    ///                                  .unwrap();
+    /// let connection = Connection::new("localhost:1521/xe", "user", "password").unwrap();
     ///
-    /// let sql_select = "SELECT * FROM SomeTable";
-    /// let select_stmt = match connection.create_prepared_statement(sql_select) {
-    ///     Ok(stmt) => stmt,
-    ///     Err(err) => panic!("Oracle error: {}", err),
-    /// };
+    /// const OCI_ATTR_MODULE: u32 = 366;
+    /// // SAFETY: OCI_ATTR_MODULE is an oratext * attribute of the session handle.
+    /// unsafe {
+    ///     connection
+    ///         .set_attribute(
+    ///             AttributeHandle::Session,
+    ///             OCI_ATTR_MODULE,
+    ///             &AttributeValue::Text("payments".to_string()),
+    ///         )
+    ///         .unwrap();
+    /// }
     /// ```
     ///
-    /// [2]: ../statement/struct.Statement.html
-    pub fn create_prepared_statement(&self, sql: &str) -> Result<Statement, OciError> {
-        Statement::new(self, sql)
+    pub unsafe fn set_attribute(
+        &self,
+        handle: AttributeHandle,
+        attribute: u32,
+        value: &AttributeValue,
+    ) -> Result<(), OciError> {
+        let (handle_ptr, handle_type) = self.attribute_handle(handle);
+        let (ubyte, ushort, uint, int, boolean);
+        let (value_ptr, size): (*const c_void, c_uint) = match *value {
+            AttributeValue::Text(ref text) => {
+                (text.as_ptr() as *const c_void, text.len() as c_uint)
+            }
+            AttributeValue::UByte(value) => {
+                ubyte = value;
+                (&ubyte as *const u8 as *const c_void, 1)
+            }
+            AttributeValue::UShort(value) => {
+                ushort = value;
+                (&ushort as *const u16 as *const c_void, 2)
+            }
+            AttributeValue::UInt(value) => {
+                uint = value;
+                (&uint as *const u32 as *const c_void, 4)
+            }
+            AttributeValue::Int(value) => {
+                int = value;
+                (&int as *const i32 as *const c_void, 4)
+            }
+            AttributeValue::Bool(value) => {
+                boolean = c_int::from(value);
+                (&boolean as *const c_int as *const c_void, 4)
+            }
+        };
+        set_handle_attribute(
+            handle_ptr,
+            handle_type,
+            value_ptr as *mut c_void,
+            size,
+            AttributeType::Other(attribute),
+            self.error,
+            "Setting attribute",
+        )
+    }
+
+    /// Reads an OCI attribute that this crate doesn't wrap yet.
+    ///
+    /// See `.set_attribute` for how attributes are identified. The `kind` has to match the C
+    /// type of the attribute in the OCI documentation.
+    ///
+    /// # Safety
+    ///
+    /// The `kind` has to be the C type of the attribute on that handle. The value is read from
+    /// a buffer large enough for any scalar kind, so a scalar of the wrong size is misread
+    /// rather than written out of bounds, but for `Text` OCI hands back a pointer and a length
+    /// that are read as they are. Asking for `Text` from an attribute that isn't `oratext *`
+    /// reads arbitrary memory.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned, or an
+    /// `OciError::Conversion` if text is not valid UTF-8.
+    ///
+    pub unsafe fn get_attribute(
+        &self,
+        handle: AttributeHandle,
+        attribute: u32,
+        kind: AttributeKind,
+    ) -> Result<AttributeValue, OciError> {
+        let (handle_ptr, handle_type) = self.attribute_handle(handle);
+        // Large enough for any of the scalar kinds, so a wrong kind can't write past the end.
+        let mut buffer: u64 = 0;
+        let mut text_ptr: *mut u8 = ptr::null_mut();
+        let mut size: c_uint = 0;
+        let value_ptr = match kind {
+            AttributeKind::Text => &mut text_ptr as *mut *mut u8 as *mut c_void,
+            _ => &mut buffer as *mut u64 as *mut c_void,
+        };
+        let get_result = unsafe {
+            OCIAttrGet(
+                handle_ptr,
+                handle_type.into(),
+                value_ptr,
+                &mut size,
+                AttributeType::Other(attribute).into(),
+                self.error,
+            )
+        };
+        check_result(get_result, self.error, "Getting attribute")?;
+        let buffer_ptr = &buffer as *const u64;
+        let value = unsafe {
+            match kind {
+                AttributeKind::Text if text_ptr.is_null() => AttributeValue::Text(String::new()),
+                AttributeKind::Text => {
+                    let text = slice::from_raw_parts(text_ptr, size as usize);
+                    let text = String::from_utf8(text.to_vec())
+                        .map_err(|err| OciError::Conversion(Box::new(err)))?;
+                    AttributeValue::Text(text)
+                }
+                AttributeKind::UByte => AttributeValue::UByte(*(buffer_ptr as *const u8)),
+                AttributeKind::UShort => AttributeValue::UShort(*(buffer_ptr as *const u16)),
+                AttributeKind::UInt => AttributeValue::UInt(*(buffer_ptr as *const u32)),
+                AttributeKind::Int => AttributeValue::Int(*(buffer_ptr as *const i32)),
+                AttributeKind::Bool => AttributeValue::Bool(*(buffer_ptr as *const c_int) != 0),
+            }
+        };
+        Ok(value)
+    }
+
+    /// Returns the handle that an attribute is set on or read from.
+    ///
+    fn attribute_handle(&self, handle: AttributeHandle) -> (*mut c_void, HandleType) {
+        match handle {
+            AttributeHandle::Environment => (
+                self.handles.server.environment as *mut c_void,
+                HandleType::Environment,
+            ),
+            AttributeHandle::Server => (
+                self.handles.server.server as *mut c_void,
+                HandleType::Server,
+            ),
+            AttributeHandle::Service => (self.service as *mut c_void, HandleType::Service),
+            AttributeHandle::Session => (self.session as *mut c_void, HandleType::Session),
+        }
     }
 
     /// Returns the error handle for the connection.
@@ -120,56 +1268,228 @@ impl Connection {
     pub(crate) fn service(&self) -> *mut OCISvcCtx {
         self.service
     }
-}
 
-impl Drop for Connection {
-    /// Ends the current user session, disconnects from the database and frees the handles
-    /// allocated by the OCI library.
-    ///
-    /// This should ensure there are no remaining processes or memory allocated.
-    ///
-    /// # Panics
+    /// Returns the environment handle that the connection was created in.
     ///
-    /// Panics if the resources can't be freed. This would be
-    /// a failure of the underlying OCI resource freeing function.
+    pub(crate) fn environment(&self) -> *mut OCIEnv {
+        self.handles.server.environment
+    }
+
+    /// Returns the most bytes a character takes up in the client character set, which is what
+    /// text is converted to when it is fetched.
     ///
-    fn drop(&mut self) {
-        let session_end_result = unsafe {
-            OCISessionEnd(
-                self.service,
+    pub(crate) fn charset_max_width(&self) -> Result<c_int, OciError> {
+        let mut width: c_int = 0;
+        let info_result = unsafe {
+            OCINlsNumericInfoGet(
+                self.environment() as *mut c_void,
                 self.error,
-                self.session,
-                EnvironmentMode::Default.into(),
+                &mut width,
+                OCI_NLS_CHARSET_MAXBYTESZ,
             )
         };
+        match info_result.into() {
+            ReturnCode::Success => Ok(width.max(1)),
+            _ => Err(get_error(
+                self.error_as_mut_void(),
+                HandleType::Error,
+                "Getting maximum character width of client character set",
+            )),
+        }
+    }
+}
 
-        match session_end_result.into() {
-            ReturnCode::Success => (),
-            _ => error!("Could not end user session"),
+/// Restores the previous module and action of a session when dropped.
+///
+/// Created by [`Connection.with_module_action`][1].
+///
+/// [1]: struct.Connection.html#method.with_module_action
+#[derive(Debug)]
+pub struct ModuleActionGuard<'conn> {
+    connection: &'conn Connection,
+    previous: (String, String),
+}
+
+impl<'conn> Drop for ModuleActionGuard<'conn> {
+    fn drop(&mut self) {
+        let (ref module, ref action) = self.previous;
+        if let Err(err) = self.connection.set_module_action(module, action) {
+            error!("Could not restore the module and action: {}", err)
+        }
+    }
+}
+
+/// Puts the previous deadline back when dropped, see [`Connection.with_deadline`][1].
+///
+/// [1]: struct.Connection.html#method.with_deadline
+#[derive(Debug)]
+pub struct DeadlineGuard<'conn> {
+    connection: &'conn Connection,
+    previous: Option<Instant>,
+}
+
+impl<'conn> Drop for DeadlineGuard<'conn> {
+    fn drop(&mut self) {
+        self.connection.deadline.set(self.previous);
+        if self.previous.is_none() {
+            if let Err(err) = set_call_timeout(self.connection.service, 0, self.connection.error) {
+                error!("Could not clear the call timeout: {}", err)
+            }
+        }
+    }
+}
+
+/// Configures and creates a [`Connection`][1].
+///
+/// The builder holds an [`AuthProvider`][2] that is asked for credentials each time `.build` is
+/// called, so a single builder can be kept around to create new connections as tokens or
+/// passwords are rotated. If no provider is given then `Credentials::External` is used, see
+/// [`.build`][3].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use oci_rs::auth::StaticPassword;
+/// use oci_rs::connection::ConnectionBuilder;
+///
+/// let builder = ConnectionBuilder::new("localhost:1521/xe")
+///     .auth(StaticPassword::new("user", "password"));
+///
+/// let connection = builder.build().unwrap();
+/// ```
+///
+/// [1]: struct.Connection.html
+/// [2]: ../auth/trait.AuthProvider.html
+/// [3]: struct.ConnectionBuilder.html#method.build
+#[derive(Debug, Clone)]
+pub struct ConnectionBuilder {
+    connection_str: String,
+    auth: Option<Arc<dyn AuthProvider>>,
+    migratable: bool,
+}
+impl ConnectionBuilder {
+    /// Creates a new `ConnectionBuilder` for the database identified by the connection string.
+    ///
+    pub fn new(connection_str: &str) -> ConnectionBuilder {
+        ConnectionBuilder {
+            connection_str: connection_str.to_string(),
+            auth: None,
+            migratable: false,
         }
+    }
 
-        let disconnect_result =
-            unsafe { OCIServerDetach(self.server, self.error, EnvironmentMode::Default.into()) };
+    /// Creates a new `ConnectionBuilder` for the database identified by a `ConnectDescriptor`.
+    ///
+    pub fn from_descriptor(descriptor: &ConnectDescriptor) -> ConnectionBuilder {
+        ConnectionBuilder::new(&descriptor.to_string())
+    }
 
-        match disconnect_result.into() {
-            ReturnCode::Success => (),
-            _ => error!("Could not disconnect"),
+    /// Sets the provider of the credentials used to start the user session.
+    ///
+    pub fn auth<A: AuthProvider + 'static>(mut self, provider: A) -> ConnectionBuilder {
+        self.auth = Some(Arc::new(provider));
+        self
+    }
+
+    /// Starts the session in migratable mode, so that it can be moved between physical
+    /// connections and used as the primary session for [`Connection.new_session`][1].
+    ///
+    /// [1]: struct.Connection.html#method.new_session
+    pub fn migratable(mut self, migratable: bool) -> ConnectionBuilder {
+        self.migratable = migratable;
+        self
+    }
+
+    /// Creates a new `Connection`.
+    ///
+    /// When no provider has been set with `.auth` the session is started with
+    /// `Credentials::External`, so it is authenticated by whatever Oracle Net is configured to
+    /// use on the client, e.g. the operating system user or a wallet. It never falls back to a
+    /// user name and password.
+    ///
+    /// # Errors
+    ///
+    /// Any error returned by the `AuthProvider` or the underlying calls to the OCI library will
+    /// be returned.
+    ///
+    pub fn build(&self) -> Result<Connection, OciError> {
+        let credentials = self.credentials()?;
+        let connection = Connection::connect(&self.connection_str, &credentials, self.migratable);
+        match self.auth {
+            Some(ref provider) => connection.map_err(|err| provider.explain_error(err)),
+            None => connection,
         }
+    }
 
-        let free_result = unsafe {
-            OCIHandleFree(
-                self.environment as *mut c_void,
-                HandleType::Environment.into(),
+    /// Returns the credentials `.build` will start the session with.
+    ///
+    pub(crate) fn credentials(&self) -> Result<Credentials, OciError> {
+        match self.auth {
+            Some(ref provider) => provider.credentials(),
+            None => Ok(Credentials::External),
+        }
+    }
+}
+
+impl fmt::Debug for Connection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Connection")
+            .field(
+                "connection_str",
+                &redact_connect_string(&self.connection_str),
             )
-        };
+            .field("user_name", &self.user_name)
+            .field("password", &"********")
+            .field("environment", &self.handles.server.environment)
+            .field("server", &self.handles.server.server)
+            .field("migratable", &self.migratable)
+            .field("error", &self.error)
+            .field("service", &self.service)
+            .field("session", &self.session)
+            .finish()
+    }
+}
 
-        match free_result.into() {
-            ReturnCode::Success => (),
-            _ => error!("Could not free the handles in Connection"),
+impl Drop for Connection {
+    /// Ends the current user session and frees the handles allocated by the OCI library. The
+    /// physical connection is closed once no other sessions are using it, and a primary session
+    /// is only ended once the sessions migrated from it have been.
+    ///
+    /// This should ensure there are no remaining processes or memory allocated.
+    ///
+    fn drop(&mut self) {
+        if let Some(ref state) = *self.transaction.borrow() {
+            warn!(
+                target: TRANSACTION_TARGET,
+                "transaction=open_at_session_end duration_ms={} statements={}",
+                state.started.elapsed().as_millis(),
+                state.statements
+            );
         }
+        // the session is ended by `SessionHandles` once no migrated session needs it
     }
 }
 
+/// Checks whether a query locks the rows it selects with `FOR UPDATE`. Like the other checks of
+/// the SQL this is a heuristic, the words are only looked for outside of string literals.
+///
+pub(crate) fn locks_rows(sql: &str) -> bool {
+    let mut outside_strings = String::with_capacity(sql.len());
+    for (index, part) in sql.split('\'').enumerate() {
+        if index % 2 == 0 {
+            outside_strings.push_str(part);
+        }
+        outside_strings.push(' ');
+    }
+    let words: Vec<String> = outside_strings
+        .split_whitespace()
+        .map(str::to_uppercase)
+        .collect();
+    words
+        .windows(2)
+        .any(|pair| pair[0] == "FOR" && pair[1] == "UPDATE")
+}
+
 /// Creates an environment handle
 fn create_environment_handle() -> Result<*mut OCIEnv, OciError> {
     let env: *mut OCIEnv = ptr::null_mut();
@@ -200,7 +1520,7 @@ fn create_server_handle(env: *const OCIEnv) -> Result<*mut OCIServer, OciError>
 }
 
 /// Creates a error handle
-fn create_error_handle(env: *const OCIEnv) -> Result<*mut OCIError, OciError> {
+pub(crate) fn create_error_handle(env: *const OCIEnv) -> Result<*mut OCIError, OciError> {
     match allocate_handle(env, HandleType::Error) {
         Ok(error) => Ok(error as *mut OCIError),
         Err(err) => Err(err),
@@ -284,6 +1604,101 @@ fn set_password_in_session(
     Ok(())
 }
 
+/// Sets the credentials in the session handle and returns the type of credentials that the
+/// session needs to be started with.
+fn set_credentials_in_session(
+    session: *mut OCISession,
+    credentials: &Credentials,
+    error: *mut OCIError,
+) -> Result<CredentialsType, OciError> {
+    match *credentials {
+        Credentials::Password {
+            ref user_name,
+            ref password,
+        } => {
+            set_user_name_in_session(session, user_name, error)?;
+            set_password_in_session(session, password.expose_secret(), error)?;
+            Ok(CredentialsType::Rdbms)
+        }
+        Credentials::External => Ok(CredentialsType::External),
+        Credentials::Token {
+            ref token,
+            ref private_key,
+        } => {
+            set_token_in_session(session, token.expose_secret(), AttributeType::Token, error)?;
+            if let Some(ref private_key) = *private_key {
+                set_token_in_session(
+                    session,
+                    private_key.expose_secret(),
+                    AttributeType::IamPrivateKey,
+                    error,
+                )?;
+            }
+            Ok(CredentialsType::External)
+        }
+    }
+}
+
+/// set text attribute such as the module or action
+fn set_session_text(
+    session: *mut OCISession,
+    text: &str,
+    attribute_type: AttributeType,
+    error: *mut OCIError,
+) -> Result<(), OciError> {
+    let text_ptr = text.as_ptr();
+    let text_len = text.len() as c_uint;
+
+    set_handle_attribute(
+        session as *mut c_void,
+        HandleType::Session,
+        text_ptr as *mut c_void,
+        text_len,
+        attribute_type,
+        error,
+        "Setting session attribute",
+    )
+}
+
+/// Sets the call timeout in milliseconds, zero turns it off.
+fn set_call_timeout(
+    service: *mut OCISvcCtx,
+    millis: c_uint,
+    error: *mut OCIError,
+) -> Result<(), OciError> {
+    let millis_ptr: *const c_uint = &millis;
+    set_handle_attribute(
+        service as *mut c_void,
+        HandleType::Service,
+        millis_ptr as *mut c_void,
+        0,
+        AttributeType::CallTimeout,
+        error,
+        "Setting call timeout in service handle",
+    )
+}
+
+/// set token or private key
+fn set_token_in_session(
+    session: *mut OCISession,
+    token: &str,
+    attribute_type: AttributeType,
+    error: *mut OCIError,
+) -> Result<(), OciError> {
+    let token_ptr = token.as_ptr();
+    let token_len = token.len() as c_uint;
+
+    set_handle_attribute(
+        session as *mut c_void,
+        HandleType::Session,
+        token_ptr as *mut c_void,
+        token_len,
+        attribute_type,
+        error,
+        "Setting access token",
+    )
+}
+
 /// Set user session in service handle
 fn set_session_in_service(
     service: *mut OCISvcCtx,
@@ -332,6 +1747,7 @@ fn connect_to_database(
     server: *mut OCIServer,
     connection_str: &str,
     error: *mut OCIError,
+    call_log: &CallLog,
 ) -> Result<(), OciError> {
     let conn_ptr = connection_str.as_ptr();
     let conn_len = connection_str.len() as c_int;
@@ -345,24 +1761,87 @@ fn connect_to_database(
             EnvironmentMode::Default.into(),
         )
     };
+    call_log.record_call(
+        "OCIServerAttach",
+        HandleType::Server,
+        server as *mut c_void,
+        connect_result,
+    );
     check_result(connect_result, error, "Connection to the database")
 }
 
+/// Reads the version banner of the server, which is kept for diagnostics.
+fn read_server_version(server: *mut OCIServer, error: *mut OCIError) -> Option<String> {
+    let mut banner: [c_uchar; SERVER_VERSION_SIZE] = [0; SERVER_VERSION_SIZE];
+    let version_result = wait_for_call(|| unsafe {
+        OCIServerVersion(
+            server as *mut c_void,
+            error,
+            banner.as_mut_ptr(),
+            SERVER_VERSION_SIZE as c_uint,
+            c_uint::from(HandleType::Server) as c_uchar,
+        )
+    });
+    match version_result.into() {
+        ReturnCode::Success => {
+            let end = banner.iter().position(|&b| b == 0).unwrap_or(banner.len());
+            Some(String::from_utf8_lossy(&banner[..end]).into_owned())
+        }
+        _ => {
+            debug!(
+                "{}",
+                get_error(
+                    error as *mut c_void,
+                    HandleType::Error,
+                    "Reading server version"
+                )
+            );
+            None
+        }
+    }
+}
+
+/// Set the session that a new migratable session is migrated from
+fn set_migration_session(
+    session: *mut OCISession,
+    primary_session: *mut OCISession,
+    error: *mut OCIError,
+) -> Result<(), OciError> {
+    let size: c_uint = 0;
+    set_handle_attribute(
+        session as *mut c_void,
+        HandleType::Session,
+        primary_session as *mut c_void,
+        size,
+        AttributeType::MigrationSession,
+        error,
+        "Setting migration session",
+    )
+}
+
 /// start user session
 fn start_session(
     service: *mut OCISvcCtx,
     session: *mut OCISession,
+    credentials_type: CredentialsType,
+    migratable: bool,
     error: *mut OCIError,
+    call_log: &CallLog,
 ) -> Result<(), OciError> {
-    let session_result = unsafe {
-        OCISessionBegin(
-            service,
-            error,
-            session,
-            CredentialsType::Rdbms.into(),
-            EnvironmentMode::Default.into(),
-        )
-    };
+    let mut mode: c_uint = EnvironmentMode::StatementCache.into();
+    if migratable {
+        mode |= c_uint::from(EnvironmentMode::Migrate);
+    }
+    let credentials_type: c_uint = credentials_type.into();
+    let session_result = wait_for_call(|| unsafe {
+        OCISessionBegin(service, error, session, credentials_type, mode)
+    });
+    call_log.record_call(
+        "OCISessionBegin",
+        HandleType::Session,
+        session as *mut c_void,
+        session_result,
+    );
     check_result(session_result, error, "Starting user session")
 }
 
@@ -0,0 +1,377 @@
+use crate::connection::Connection;
+use crate::oci_error::{OciError, UsageError};
+use crate::row::Row;
+use crate::types::ToSqlValue;
+use std::collections::HashSet;
+
+/// Oracle allows at most 1000 entries in an `IN` list.
+const MAX_TABLES_PER_QUERY: usize = 1000;
+const DESCRIBE_PREFETCH_ROWS: i32 = 1000;
+
+/// The description of a table or view.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableDescription {
+    owner: String,
+    name: String,
+    columns: Vec<ColumnDescription>,
+}
+impl TableDescription {
+    /// Returns the schema that owns the table.
+    ///
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+
+    /// Returns the name of the table.
+    ///
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the columns, in the order they were defined.
+    ///
+    pub fn columns(&self) -> &[ColumnDescription] {
+        &self.columns
+    }
+}
+
+/// The description of a column in a table or view.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnDescription {
+    name: String,
+    data_type: String,
+    data_length: i64,
+    precision: Option<i64>,
+    scale: Option<i64>,
+    nullable: bool,
+}
+impl ColumnDescription {
+    /// Creates a new `ColumnDescription`.
+    ///
+    pub(crate) fn new(
+        name: String,
+        data_type: String,
+        data_length: i64,
+        precision: Option<i64>,
+        scale: Option<i64>,
+        nullable: bool,
+    ) -> ColumnDescription {
+        ColumnDescription {
+            name,
+            data_type,
+            data_length,
+            precision,
+            scale,
+            nullable,
+        }
+    }
+
+    /// Returns the name of the column.
+    ///
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the Oracle data type, e.g. `VARCHAR2` or `NUMBER`.
+    ///
+    pub fn data_type(&self) -> &str {
+        &self.data_type
+    }
+
+    /// Returns the length of the column in bytes.
+    ///
+    pub fn data_length(&self) -> i64 {
+        self.data_length
+    }
+
+    /// Returns the precision of a `NUMBER` or `FLOAT` column, if it has one.
+    ///
+    pub fn precision(&self) -> Option<i64> {
+        self.precision
+    }
+
+    /// Returns the scale of a `NUMBER` column, if it has one.
+    ///
+    pub fn scale(&self) -> Option<i64> {
+        self.scale
+    }
+
+    /// Returns true if the column allows nulls.
+    ///
+    pub fn nullable(&self) -> bool {
+        self.nullable
+    }
+}
+
+/// Describes a single table or view.
+///
+/// See `describe_tables` for how the name is interpreted.
+///
+/// # Errors
+///
+/// Any error in the underlying calls to the OCI library will be returned. An
+/// `OciError::Conversion` is returned if the table can't be found.
+///
+pub fn describe_table(conn: &Connection, name: &str) -> Result<TableDescription, OciError> {
+    describe_tables(conn, &[name])?
+        .pop()
+        .ok_or_else(|| OciError::Conversion(format!("Table {} could not be found", name).into()))
+}
+
+/// Describes many tables or views in one round trip.
+///
+/// Rather than describing each table in turn the columns of all of them are fetched with a
+/// single query over `ALL_TAB_COLUMNS`, which makes scanning hundreds of tables, for example in
+/// a code generator, much quicker. Only very long lists, of more than 1000 tables, need more
+/// than one query.
+///
+/// Names can be qualified with a schema, e.g. `HR.EMPLOYEES`, otherwise the current schema is
+/// used. Unquoted names are converted to upper case as they are by Oracle, while quoted ones
+/// are used as is. Tables that can't be found, or that the user has no access to, are left out
+/// of the results. The descriptions are returned in the order the tables were given.
+///
+/// Each table is described once. A table that is given more than once, e.g. as `Mugs` and
+/// `MUGS`, or with and without its schema, is returned at the position it was first given.
+///
+/// # Errors
+///
+/// Any error in the underlying calls to the OCI library will be returned.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use oci_rs::connection::Connection;
+/// use oci_rs::describe::describe_tables;
+///
+/// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+/// let tables = describe_tables(&conn, &["Dogs", "Countries", "HR.EMPLOYEES"]).unwrap();
+/// for table in &tables {
+///     println!("{}.{}", table.owner(), table.name());
+///     for column in table.columns() {
+///         println!("    {} {}", column.name(), column.data_type());
+///     }
+/// }
+/// ```
+///
+pub fn describe_tables(
+    conn: &Connection,
+    names: &[&str],
+) -> Result<Vec<TableDescription>, OciError> {
+    let mut names: Vec<(Option<String>, String)> =
+        names.iter().map(|name| split_name(name)).collect();
+    let mut seen = HashSet::new();
+    names.retain(|name| seen.insert(name.clone()));
+    let mut tables: Vec<TableDescription> = Vec::new();
+    for chunk in names.chunks(MAX_TABLES_PER_QUERY) {
+        describe_chunk(conn, chunk, &mut tables)?;
+    }
+    let mut ordered = Vec::with_capacity(tables.len());
+    // A table is taken out once it has been placed, so the same table named in two different
+    // ways, with and without a schema, is only returned the first time.
+    for (owner, name) in &names {
+        let position = tables.iter().position(|table| {
+            table.name == *name && owner.iter().all(|owner| table.owner == *owner)
+        });
+        if let Some(position) = position {
+            ordered.push(tables.swap_remove(position));
+        }
+    }
+    Ok(ordered)
+}
+
+fn describe_chunk(
+    conn: &Connection,
+    names: &[(Option<String>, String)],
+    tables: &mut Vec<TableDescription>,
+) -> Result<(), OciError> {
+    let current_schema = "SYS_CONTEXT('USERENV', 'CURRENT_SCHEMA')";
+    let mut params: Vec<String> = Vec::new();
+    let conditions: Vec<String> = names
+        .iter()
+        .map(|(owner, name)| {
+            let owner = match owner {
+                Some(owner) => {
+                    params.push(owner.clone());
+                    format!(":{}", params.len())
+                }
+                None => current_schema.to_string(),
+            };
+            params.push(name.clone());
+            format!("({}, :{})", owner, params.len())
+        })
+        .collect();
+    let sql = format!(
+        "SELECT Owner, Table_Name, Column_Name, Data_Type, Data_Length, Data_Precision, \
+         Data_Scale, Nullable \
+         FROM All_Tab_Columns \
+         WHERE (Owner, Table_Name) IN ({}) \
+         ORDER BY Owner, Table_Name, Column_Id",
+        conditions.join(", ")
+    );
+
+    let mut select = conn.create_prepared_statement(&sql)?;
+    let bind_values: Vec<&dyn ToSqlValue> = params.iter().map(|p| p as &dyn ToSqlValue).collect();
+    if !bind_values.is_empty() {
+        select.bind(&bind_values)?;
+    }
+    select.set_prefetch(DESCRIBE_PREFETCH_ROWS)?;
+    select.execute()?;
+
+    for row in select.lazy_result_set()? {
+        let row = row?;
+        let owner: String = row.try_get("Owner")?;
+        let name: String = row.try_get("Table_Name")?;
+        let column = column_description(&row)?;
+        match tables.last_mut() {
+            Some(ref mut table) if table.owner == owner && table.name == name => {
+                table.columns.push(column)
+            }
+            _ => tables.push(TableDescription {
+                owner,
+                name,
+                columns: vec![column],
+            }),
+        }
+    }
+    Ok(())
+}
+
+fn column_description(row: &Row) -> Result<ColumnDescription, OciError> {
+    let nullable: String = row.try_get("Nullable")?;
+    Ok(ColumnDescription {
+        name: row.try_get("Column_Name")?,
+        data_type: row.try_get("Data_Type")?,
+        data_length: row.try_get("Data_Length")?,
+        precision: row.get("Data_Precision").and_then(|value| value.value()),
+        scale: row.get("Data_Scale").and_then(|value| value.value()),
+        nullable: nullable == "Y",
+    })
+}
+
+/// Gives the name Oracle uses for the type of a column described by OCI, as found in
+/// `ALL_TAB_COLUMNS`.
+///
+pub(crate) fn type_name(type_code: u16) -> String {
+    let name = match type_code {
+        1 => "VARCHAR2",
+        2 => "NUMBER",
+        8 => "LONG",
+        12 => "DATE",
+        23 => "RAW",
+        24 => "LONG RAW",
+        96 => "CHAR",
+        100 => "BINARY_FLOAT",
+        101 => "BINARY_DOUBLE",
+        104 => "ROWID",
+        112 => "CLOB",
+        113 => "BLOB",
+        114 => "BFILE",
+        119 => "JSON",
+        187 => "TIMESTAMP",
+        188 => "TIMESTAMP WITH TIME ZONE",
+        189 => "INTERVAL YEAR TO MONTH",
+        190 => "INTERVAL DAY TO SECOND",
+        208 => "UROWID",
+        232 => "TIMESTAMP WITH LOCAL TIME ZONE",
+        _ => return format!("TYPE {}", type_code),
+    };
+    name.to_string()
+}
+
+/// Splits an optionally schema qualified name into the owner and the object name, following
+/// Oracle's rules for quoted and unquoted identifiers.
+///
+fn split_name(name: &str) -> (Option<String>, String) {
+    let mut parts = split_identifiers(name);
+    let object_name = parts.pop().unwrap_or_default();
+    (parts.pop(), object_name)
+}
+
+/// Splits a dotted name into its identifiers. Unquoted identifiers are converted to upper case
+/// and quoted ones have their quotes removed.
+///
+pub(crate) fn split_identifiers(name: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut part = String::new();
+    let mut quoted = false;
+    let mut was_quoted = false;
+    for c in name.trim().chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                was_quoted = true;
+            }
+            '.' if !quoted => {
+                parts.push(normalise_identifier(&part, was_quoted));
+                part.clear();
+                was_quoted = false;
+            }
+            c => part.push(c),
+        }
+    }
+    parts.push(normalise_identifier(&part, was_quoted));
+    parts
+}
+
+/// The longest identifier Oracle accepts, in bytes.
+const MAX_IDENTIFIER_LENGTH: usize = 128;
+
+/// Checks a dotted name of up to `max_parts` identifiers and returns it with every identifier
+/// quoted, so that it can be put into SQL safely.
+///
+/// Unquoted identifiers have to start with a letter, followed by letters, digits, `_`, `$` or
+/// `#`, and are converted to upper case. Quoted identifiers can hold anything but `"` and NUL.
+/// No identifier can be empty or longer than 128 bytes. `kind` names what the name is for in
+/// the error.
+///
+pub(crate) fn quote_name(name: &str, max_parts: usize, kind: &str) -> Result<String, OciError> {
+    let invalid = || UsageError::InvalidName(format!("{:?} is not a valid {}", name, kind));
+    let mut parts = Vec::new();
+    let mut chars = name.trim().chars().peekable();
+    loop {
+        let mut part = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\0') | None => return Err(invalid().into()),
+                    Some(c) => part.push(c),
+                }
+            }
+        } else {
+            while let Some(&c) = chars.peek() {
+                let valid = if part.is_empty() {
+                    c.is_alphabetic()
+                } else {
+                    c.is_alphanumeric() || c == '_' || c == '$' || c == '#'
+                };
+                if !valid {
+                    break;
+                }
+                part.push(c);
+                chars.next();
+            }
+            part = part.to_uppercase();
+        }
+        if part.is_empty() || part.len() > MAX_IDENTIFIER_LENGTH {
+            return Err(invalid().into());
+        }
+        parts.push(format!("\"{}\"", part));
+        match chars.next() {
+            Some('.') if parts.len() < max_parts => (),
+            None => return Ok(parts.join(".")),
+            Some(_) => return Err(invalid().into()),
+        }
+    }
+}
+
+fn normalise_identifier(identifier: &str, quoted: bool) -> String {
+    if quoted {
+        identifier.to_string()
+    } else {
+        identifier.trim().to_uppercase()
+    }
+}
@@ -0,0 +1,289 @@
+use crate::oci_bindings::{HandleType, OCIClientVersion, OCIError};
+use libc::{c_int, c_void};
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+
+/// The number of OCI calls remembered for each connection.
+const RECENT_CALL_CAPACITY: usize = 32;
+
+/// The call logs of the open connections, along with the error handles that their errors are
+/// read from. This is how an error is matched up with the connection it came from.
+static CALL_LOGS: Mutex<Vec<(usize, Weak<CallLog>)>> = Mutex::new(Vec::new());
+
+/// The recent calls a connection made to the OCI library, together with the version of the
+/// server it is connected to and, if asked for, the last SQL it prepared.
+#[derive(Debug, Default)]
+pub(crate) struct CallLog {
+    entries: Mutex<CallLogEntries>,
+}
+
+#[derive(Debug, Default)]
+struct CallLogEntries {
+    calls: VecDeque<CallRecord>,
+    record_sql: bool,
+    last_sql: Option<String>,
+    server_version: Option<String>,
+}
+
+impl CallLog {
+    /// Creates an empty log, which doesn't record SQL.
+    ///
+    pub(crate) fn new() -> Arc<CallLog> {
+        Arc::new(CallLog::default())
+    }
+
+    /// Has errors read from the error handle include a snapshot of this log.
+    ///
+    pub(crate) fn watch(self: &Arc<Self>, error: *mut OCIError) {
+        let mut logs = lock(&CALL_LOGS);
+        logs.retain(|(_, log)| log.strong_count() > 0);
+        logs.push((error as usize, Arc::downgrade(self)));
+    }
+
+    /// Remembers a call to the OCI library, forgetting the oldest once there are too many.
+    ///
+    pub(crate) fn record_call(
+        &self,
+        function: &'static str,
+        handle_type: HandleType,
+        handle: *mut c_void,
+        return_code: c_int,
+    ) {
+        let mut entries = lock(&self.entries);
+        if entries.calls.len() == RECENT_CALL_CAPACITY {
+            entries.calls.pop_front();
+        }
+        entries.calls.push_back(CallRecord {
+            function,
+            handle_type: format!("{:?}", handle_type),
+            handle: handle as usize,
+            return_code,
+        });
+    }
+
+    /// Sets whether the SQL of statements being prepared is remembered.
+    ///
+    pub(crate) fn set_record_sql(&self, record_sql: bool) {
+        let mut entries = lock(&self.entries);
+        entries.record_sql = record_sql;
+        if !record_sql {
+            entries.last_sql = None;
+        }
+    }
+
+    /// Returns whether the SQL of statements being prepared is remembered.
+    ///
+    pub(crate) fn records_sql(&self) -> bool {
+        lock(&self.entries).record_sql
+    }
+
+    /// Remembers the SQL of a statement being prepared, if that has been turned on.
+    ///
+    pub(crate) fn record_sql(&self, sql: &str) {
+        let mut entries = lock(&self.entries);
+        if entries.record_sql {
+            entries.last_sql = Some(sql.to_string());
+        }
+    }
+
+    /// Remembers the version of the server the connection is connected to.
+    ///
+    pub(crate) fn record_server_version(&self, version: Option<String>) {
+        lock(&self.entries).server_version = version;
+    }
+
+    /// Returns the version of the server the connection is connected to, if it is known.
+    ///
+    pub(crate) fn server_version(&self) -> Option<String> {
+        lock(&self.entries).server_version.clone()
+    }
+}
+
+/// Locks part of the call logs. Nothing is left half done by a panic while they are locked, so a
+/// poisoned lock is simply taken over.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Finds the call log of the connection that the error handle belongs to.
+///
+fn find_call_log(error: *mut c_void) -> Option<Arc<CallLog>> {
+    lock(&CALL_LOGS)
+        .iter()
+        .rev()
+        .find(|(handle, _)| *handle == error as usize)
+        .and_then(|(_, log)| log.upgrade())
+}
+
+/// A call made to the OCI library, as remembered for [`Diagnostics`][1].
+///
+/// [1]: struct.Diagnostics.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallRecord {
+    function: &'static str,
+    handle_type: String,
+    handle: usize,
+    return_code: i32,
+}
+impl CallRecord {
+    /// Returns the name of the OCI function, e.g. `OCIStmtExecute`.
+    ///
+    pub fn function(&self) -> &str {
+        self.function
+    }
+
+    /// Returns the type of the main handle passed to the function and its address, which is
+    /// zero for a null handle.
+    ///
+    pub fn handle(&self) -> (&str, usize) {
+        (&self.handle_type, self.handle)
+    }
+
+    /// Returns the code the function returned, e.g. 0 for `OCI_SUCCESS` or -2 for
+    /// `OCI_INVALID_HANDLE`.
+    ///
+    pub fn return_code(&self) -> i32 {
+        self.return_code
+    }
+}
+
+impl fmt::Display for CallRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} on {} handle {:#x} returned {}",
+            self.function, self.handle_type, self.handle, self.return_code
+        )
+    }
+}
+
+/// A snapshot of the state of the OCI library, attached to an [`ErrorRecord`][1] when a call
+/// fails in a way that points to a bug rather than a problem with the SQL or the data.
+///
+/// This is the case when a handle is invalid, when a call returns a code the crate doesn't
+/// know about or when Oracle doesn't say what went wrong. The snapshot holds what is needed to
+/// report the problem, against this crate or to Oracle support: the versions of the client and
+/// server and the most recent OCI calls on the connection with the handles they used and what
+/// they returned. It is included when the error is displayed.
+///
+/// The last SQL prepared on the connection is only included once it has been turned on with
+/// [`Connection.set_sql_in_diagnostics`][2], as the SQL may hold data that shouldn't end up in
+/// logs or bug reports.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use oci_rs::connection::Connection;
+/// use oci_rs::oci_error::OciError;
+///
+/// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+/// if let Err(OciError::Oracle(err)) = conn.ping() {
+///     if let Some(diagnostics) = err.diagnostics() {
+///         eprintln!("Please include this in the bug report:\n{}", diagnostics);
+///     }
+/// }
+/// ```
+///
+/// [1]: ../oci_error/struct.ErrorRecord.html
+/// [2]: ../connection/struct.Connection.html#method.set_sql_in_diagnostics
+#[derive(Debug, Clone)]
+pub struct Diagnostics {
+    handle_type: String,
+    handle: usize,
+    client_version: String,
+    server_version: Option<String>,
+    last_sql: Option<String>,
+    recent_calls: Vec<CallRecord>,
+}
+impl Diagnostics {
+    /// Takes a snapshot, for an error read from the given handle.
+    ///
+    pub(crate) fn capture(handle: *mut c_void, handle_type: HandleType) -> Diagnostics {
+        let mut diagnostics = Diagnostics {
+            handle_type: format!("{:?}", handle_type),
+            handle: handle as usize,
+            client_version: client_version(),
+            server_version: None,
+            last_sql: None,
+            recent_calls: Vec::new(),
+        };
+        if let HandleType::Error = handle_type {
+            if let Some(log) = find_call_log(handle) {
+                let entries = lock(&log.entries);
+                diagnostics.server_version = entries.server_version.clone();
+                diagnostics.last_sql = entries.last_sql.clone();
+                diagnostics.recent_calls = entries.calls.iter().cloned().collect();
+            }
+        }
+        diagnostics
+    }
+
+    /// Returns the type and address of the handle the error was read from.
+    ///
+    pub fn handle(&self) -> (&str, usize) {
+        (&self.handle_type, self.handle)
+    }
+
+    /// Returns the version of the OCI client library, e.g. `12.2.0.1.0`.
+    ///
+    pub fn client_version(&self) -> &str {
+        &self.client_version
+    }
+
+    /// Returns the version banner of the database server the connection is connected to, if it
+    /// got as far as reading it.
+    ///
+    pub fn server_version(&self) -> Option<&str> {
+        self.server_version.as_deref()
+    }
+
+    /// Returns the SQL that was last prepared on the connection, if that is recorded.
+    ///
+    pub fn last_sql(&self) -> Option<&str> {
+        self.last_sql.as_deref()
+    }
+
+    /// Returns the most recent OCI calls made on the connection, oldest first.
+    ///
+    pub fn recent_calls(&self) -> &[CallRecord] {
+        &self.recent_calls
+    }
+}
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Error handle: {} {:#x}", self.handle_type, self.handle)?;
+        writeln!(f, "Client version: {}", self.client_version)?;
+        writeln!(
+            f,
+            "Server version: {}",
+            self.server_version().unwrap_or("unknown")
+        )?;
+        if let Some(sql) = self.last_sql() {
+            writeln!(f, "Last SQL: {}", sql)?;
+        }
+        write!(f, "Recent OCI calls:")?;
+        for call in &self.recent_calls {
+            write!(f, "\n    {}", call)?;
+        }
+        Ok(())
+    }
+}
+
+fn client_version() -> String {
+    let mut version: [c_int; 5] = [0; 5];
+    unsafe {
+        OCIClientVersion(
+            &mut version[0],
+            &mut version[1],
+            &mut version[2],
+            &mut version[3],
+            &mut version[4],
+        )
+    };
+    let parts: Vec<String> = version.iter().map(|part| part.to_string()).collect();
+    parts.join(".")
+}
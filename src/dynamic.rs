@@ -0,0 +1,155 @@
+use crate::types::{SqlValue, ToSqlValue};
+use chrono::{DateTime, FixedOffset};
+
+/// A value whose type is only known at run time, e.g. one read from a JSON document or a
+/// configuration file.
+///
+/// A `DynValue` can be bound like any other value and picks a sensible Oracle type for what it
+/// holds. This lets tools that load both SQL and parameters at run time bind them without
+/// knowing their types in advance.
+///
+/// Oracle SQL has no boolean type, so `Bool` is bound as the integer 1 or 0. A `Timestamp` is
+/// bound as a `TIMESTAMP WITH TIME ZONE`.
+///
+/// # Examples
+///
+/// ```rust
+/// use oci_rs::dynamic::DynValue;
+///
+/// assert_eq!(DynValue::from_text("42"), DynValue::Integer(42));
+/// assert_eq!(DynValue::from_text("0042"), DynValue::Text("0042".to_string()));
+/// assert_eq!(DynValue::from_text("true"), DynValue::Bool(true));
+/// assert_eq!(DynValue::from_text("Poodle"), DynValue::Text("Poodle".to_string()));
+/// ```
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum DynValue {
+    /// A SQL `NULL`.
+    Null,
+    /// A boolean, bound as 1 or 0.
+    Bool(bool),
+    /// An integer.
+    Integer(i64),
+    /// A floating point number.
+    Float(f64),
+    /// Text.
+    Text(String),
+    /// A point in time with its offset from UTC.
+    Timestamp(DateTime<FixedOffset>),
+}
+impl DynValue {
+    /// Infers a value from text, as found in a configuration file or on a command line.
+    ///
+    /// Text that is `true` or `false`, an integer, a decimal number or an RFC 3339 date and
+    /// time, such as `2017-08-12T10:30:00+01:00`, is converted to that type and anything else
+    /// is kept as text. Numbers are only converted if nothing would be lost, so `0042` or
+    /// `1e3` stay as text and an identifier with leading zeros isn't mangled.
+    ///
+    pub fn from_text(text: &str) -> DynValue {
+        match text {
+            "true" => return DynValue::Bool(true),
+            "false" => return DynValue::Bool(false),
+            _ => (),
+        }
+        if let Ok(integer) = text.parse::<i64>() {
+            if integer.to_string() == text {
+                return DynValue::Integer(integer);
+            }
+        }
+        if let Ok(float) = text.parse::<f64>() {
+            if float.is_finite() && float.to_string() == text {
+                return DynValue::Float(float);
+            }
+        }
+        DynValue::from_string(text)
+    }
+
+    /// Keeps a string as text unless it is an RFC 3339 date and time, as used in JSON.
+    ///
+    fn from_string(text: &str) -> DynValue {
+        match DateTime::parse_from_rfc3339(text) {
+            Ok(timestamp) => DynValue::Timestamp(timestamp),
+            Err(_) => DynValue::Text(text.to_string()),
+        }
+    }
+}
+
+impl ToSqlValue for DynValue {
+    fn to_sql_value(&self) -> SqlValue {
+        match *self {
+            DynValue::Null => SqlValue::Null(None),
+            DynValue::Bool(b) => SqlValue::Integer(if b { 1 } else { 0 }),
+            DynValue::Integer(i) => i.to_sql_value(),
+            DynValue::Float(f) => f.to_sql_value(),
+            DynValue::Text(ref s) => s.to_sql_value(),
+            DynValue::Timestamp(ref t) => t.to_sql_value(),
+        }
+    }
+}
+
+impl From<bool> for DynValue {
+    fn from(value: bool) -> Self {
+        DynValue::Bool(value)
+    }
+}
+
+impl From<i64> for DynValue {
+    fn from(value: i64) -> Self {
+        DynValue::Integer(value)
+    }
+}
+
+impl From<f64> for DynValue {
+    fn from(value: f64) -> Self {
+        DynValue::Float(value)
+    }
+}
+
+impl<'a> From<&'a str> for DynValue {
+    fn from(value: &'a str) -> Self {
+        DynValue::Text(value.to_string())
+    }
+}
+
+impl From<String> for DynValue {
+    fn from(value: String) -> Self {
+        DynValue::Text(value)
+    }
+}
+
+impl From<DateTime<FixedOffset>> for DynValue {
+    fn from(value: DateTime<FixedOffset>) -> Self {
+        DynValue::Timestamp(value)
+    }
+}
+
+impl<T: Into<DynValue>> From<Option<T>> for DynValue {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(value) => value.into(),
+            None => DynValue::Null,
+        }
+    }
+}
+
+/// Converts a JSON value. Strings that hold an RFC 3339 date and time become a `Timestamp`
+/// and other strings stay as text. Arrays and objects are bound as their JSON text, which
+/// suits columns that hold JSON documents.
+///
+#[cfg(feature = "json")]
+impl<'a> From<&'a serde_json::Value> for DynValue {
+    fn from(value: &'a serde_json::Value) -> Self {
+        use serde_json::Value;
+
+        match *value {
+            Value::Null => DynValue::Null,
+            Value::Bool(b) => DynValue::Bool(b),
+            Value::Number(ref n) => match n.as_i64() {
+                Some(i) => DynValue::Integer(i),
+                None => DynValue::Float(n.as_f64().unwrap_or(f64::NAN)),
+            },
+            Value::String(ref s) => DynValue::from_string(s),
+            Value::Array(..) | Value::Object(..) => DynValue::Text(value.to_string()),
+        }
+    }
+}
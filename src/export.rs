@@ -0,0 +1,245 @@
+use crate::connection::Connection;
+use crate::oci_error::OciError;
+use crate::row::Row;
+use crate::types::{SqlValue, ToSqlValue};
+use log::debug;
+
+const DEFAULT_CHECKPOINT_EVERY: u64 = 1000;
+
+/// The name given to the key column that is added to each exported row.
+pub const EXPORT_KEY: &str = "EXPORT_KEY";
+
+/// Records how far an export has got.
+///
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    key: SqlValue,
+    rows: u64,
+}
+impl Checkpoint {
+    /// Creates a new `Checkpoint`, e.g. when loading one that was saved earlier.
+    ///
+    pub fn new(key: SqlValue, rows: u64) -> Checkpoint {
+        Checkpoint { key, rows }
+    }
+
+    /// Returns the key of the last row exported. For a `ROWID` export this is the `ROWID` as
+    /// text.
+    ///
+    pub fn key(&self) -> &SqlValue {
+        &self.key
+    }
+
+    /// Returns the total number of rows exported so far.
+    ///
+    pub fn rows(&self) -> u64 {
+        self.rows
+    }
+}
+
+/// Somewhere to keep checkpoints so that an interrupted export can be resumed, e.g. a file or a
+/// table in another database.
+///
+pub trait CheckpointStore {
+    /// Loads the last checkpoint saved, if there is one.
+    ///
+    fn load(&mut self) -> Result<Option<Checkpoint>, OciError>;
+
+    /// Saves a checkpoint, replacing the previous one.
+    ///
+    fn save(&mut self, checkpoint: &Checkpoint) -> Result<(), OciError>;
+}
+
+/// A `CheckpointStore` that keeps the checkpoint in memory.
+///
+/// This is enough to resume after a dropped connection within the same process.
+///
+#[derive(Debug, Default)]
+pub struct MemoryCheckpointStore {
+    checkpoint: Option<Checkpoint>,
+}
+impl MemoryCheckpointStore {
+    /// Creates a new, empty `MemoryCheckpointStore`.
+    ///
+    pub fn new() -> MemoryCheckpointStore {
+        MemoryCheckpointStore::default()
+    }
+
+    /// Returns the last checkpoint saved.
+    ///
+    pub fn checkpoint(&self) -> Option<&Checkpoint> {
+        self.checkpoint.as_ref()
+    }
+}
+
+impl CheckpointStore for MemoryCheckpointStore {
+    fn load(&mut self) -> Result<Option<Checkpoint>, OciError> {
+        Ok(self.checkpoint.clone())
+    }
+
+    fn save(&mut self, checkpoint: &Checkpoint) -> Result<(), OciError> {
+        self.checkpoint = Some(checkpoint.clone());
+        Ok(())
+    }
+}
+
+/// Streams the rows of a table in key order, saving a checkpoint every so often so that an
+/// interrupted export can carry on where it left off.
+///
+/// Rows are read with keyset pagination, i.e. `WHERE key > :last_key ORDER BY key`, so the key
+/// needs to be unique and ideally indexed. `ROWID` can be used as the key for tables without
+/// one. The key is added to the end of each row as a column named `EXPORT_KEY`.
+///
+/// A checkpoint is only saved once the rows before it have been handled, so after a failure
+/// some rows may be handed over again but none will be skipped.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use oci_rs::connection::Connection;
+/// use oci_rs::export::{Export, MemoryCheckpointStore};
+///
+/// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+/// let export = Export::new("Orders", "OrderId")
+///     .columns(&["OrderId", "Customer", "Total"])
+///     .checkpoint_every(10_000);
+///
+/// let mut store = MemoryCheckpointStore::new();
+/// let rows = export
+///     .run(&conn, &mut store, |row| {
+///         println!("{:?}", row.columns());
+///         Ok(())
+///     })
+///     .unwrap();
+/// println!("Exported {} rows", rows);
+/// ```
+///
+#[derive(Debug, Clone)]
+pub struct Export {
+    table: String,
+    key_column: String,
+    columns: Vec<String>,
+    checkpoint_every: u64,
+}
+impl Export {
+    /// Creates a new `Export` of a table, ordered by the key column.
+    ///
+    pub fn new(table: &str, key_column: &str) -> Export {
+        Export {
+            table: table.to_string(),
+            key_column: key_column.to_string(),
+            columns: Vec::new(),
+            checkpoint_every: DEFAULT_CHECKPOINT_EVERY,
+        }
+    }
+
+    /// Sets the columns to export. By default all columns are exported.
+    ///
+    pub fn columns(mut self, columns: &[&str]) -> Export {
+        self.columns = columns.iter().map(|column| column.to_string()).collect();
+        self
+    }
+
+    /// Sets how many rows are exported between checkpoints, the default is 1000.
+    ///
+    pub fn checkpoint_every(mut self, rows: u64) -> Export {
+        self.checkpoint_every = rows.max(1);
+        self
+    }
+
+    /// Runs the export, passing each row to `f`.
+    ///
+    /// If the store holds a checkpoint then the export starts from the row after it. Returns
+    /// the total number of rows exported, including those from earlier runs.
+    ///
+    /// # Errors
+    ///
+    /// Any error from the OCI library, the store or `f` stops the export and is returned. The
+    /// last checkpoint saved can be used to resume it.
+    ///
+    pub fn run<S, F>(&self, conn: &Connection, store: &mut S, mut f: F) -> Result<u64, OciError>
+    where
+        S: CheckpointStore,
+        F: FnMut(&Row) -> Result<(), OciError>,
+    {
+        let checkpoint = store.load()?;
+        let mut rows = checkpoint.as_ref().map_or(0, |checkpoint| checkpoint.rows);
+        let sql = self.sql(checkpoint.is_some());
+        debug!("Export: {}", sql);
+
+        let mut select = conn.create_prepared_statement(&sql)?;
+        if let Some(ref checkpoint) = checkpoint {
+            select.bind(&[&checkpoint.key as &dyn ToSqlValue])?;
+        }
+        select.execute()?;
+
+        let mut last_key = None;
+        let mut since_checkpoint = 0;
+        for row in select.lazy_result_set()? {
+            let row = row?;
+            f(&row)?;
+            rows += 1;
+            since_checkpoint += 1;
+            last_key = row.get(EXPORT_KEY).cloned();
+            if since_checkpoint == self.checkpoint_every {
+                save_checkpoint(store, &last_key, rows)?;
+                since_checkpoint = 0;
+            }
+        }
+        if since_checkpoint > 0 {
+            save_checkpoint(store, &last_key, rows)?;
+        }
+        Ok(rows)
+    }
+
+    /// Builds the query, with a `WHERE` clause to skip rows up to the checkpoint when resuming.
+    ///
+    fn sql(&self, resume: bool) -> String {
+        let columns = if self.columns.is_empty() {
+            "t.*".to_string()
+        } else {
+            self.columns.join(", ")
+        };
+        let is_rowid = self.key_column.eq_ignore_ascii_case("ROWID");
+        let (key, order_by, resume_clause) = if is_rowid {
+            (
+                "ROWIDTOCHAR(t.ROWID)".to_string(),
+                "t.ROWID".to_string(),
+                "t.ROWID > CHARTOROWID(:key)".to_string(),
+            )
+        } else {
+            (
+                format!("t.{}", self.key_column),
+                format!("t.{}", self.key_column),
+                format!("t.{} > :key", self.key_column),
+            )
+        };
+        let mut sql = format!(
+            "SELECT {}, {} AS {} FROM {} t",
+            columns, key, EXPORT_KEY, self.table
+        );
+        if resume {
+            sql.push_str(" WHERE ");
+            sql.push_str(&resume_clause);
+        }
+        sql.push_str(" ORDER BY ");
+        sql.push_str(&order_by);
+        sql
+    }
+}
+
+fn save_checkpoint<S: CheckpointStore>(
+    store: &mut S,
+    key: &Option<SqlValue>,
+    rows: u64,
+) -> Result<(), OciError> {
+    match *key {
+        Some(ref key) => {
+            debug!("Export: checkpoint after {} rows", rows);
+            store.save(&Checkpoint::new(key.clone(), rows))
+        }
+        None => Err(OciError::Conversion(
+            format!("The export key column {} was not returned", EXPORT_KEY).into(),
+        )),
+    }
+}
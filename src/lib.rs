@@ -32,15 +32,22 @@
 //! As Oracle uses `NUMBER` to represent all number types then all integer and floating point
 //! types convert to it. Smaller integers or floats needed on the Rust side can be downcast.
 //!
-//! | Oracle SQL type          | Rust type               |
-//! |--------------------------|-------------------------|
-//! | VARCHAR                  | `String`                |
-//! | VARCHAR2                 | `String`                |
-//! | CHAR                     | `String`                |
-//! | NUMBER                   | `i64`, `f64`            |
-//! | DATE                     | `Date<Utc>`             |
-//! | TIMESTAMP                | `DateTime<Utc>`         |
-//! | TIMESTAMP WITH TIME ZONE | `DateTime<FixedOffset>` |
+//! | Oracle SQL type          | Rust type                   |
+//! |--------------------------|-----------------------------|
+//! | VARCHAR                  | `String`                    |
+//! | VARCHAR2                 | `String`                    |
+//! | CHAR                     | `String`                    |
+//! | NUMBER                   | `i64`, `f64`                |
+//! | NUMBER(p, s)             | `Decimal` (`decimal`)       |
+//! | NUMBER(p, s), NUMBER(38) | `BigDecimal` (`bigdecimal`) |
+//! | DATE                     | `Date<Utc>`                 |
+//! | TIMESTAMP                | `DateTime<Utc>`             |
+//! | TIMESTAMP WITH TIME ZONE | `DateTime<FixedOffset>`     |
+//! | CLOB                     | `String`                    |
+//! | BLOB                     | `Vec<u8>`                   |
+//! | RAW                      | `Vec<u8>`                   |
+//! | LONG RAW                 | `Vec<u8>`                   |
+//! | BFILE                    | `BFile` (read only)         |
 //!
 //! Over time more types will be added.
 //!
@@ -188,8 +195,16 @@
 /// therefore the OCI library takes care of concurrency. The cost of this is that a purely single
 /// threaded client application might run slower.
 ///
-/// More advanced connection options such as connection and statement pooling are not yet
-/// available.
+/// Prepared statements are held in a per-session statement cache, keyed by their SQL, so
+/// preparing the same SQL again is cheap. The size of the cache can be changed via
+/// `.set_statement_cache_size`. Connection pooling is available for async code through the
+/// `deadpool` and `bb8` features, see the [`async_connection`][1] module.
+///
+/// The start and end of each transaction are logged under the `oci_rs::transaction` target,
+/// with how long the transaction was open and how many statements it ran, so that long running
+/// or forgotten transactions can be spotted.
+///
+/// [1]: ../async_connection/index.html
 ///
 /// # Examples
 ///
@@ -270,38 +285,112 @@ pub mod oci_error;
 /// numbers are stored as a `NUMBER`. This is an Oracle format that can handle all integer and
 /// float values with a precision of 38 digits. Regardless of whether the SQL statement specifies
 /// an `INTEGER` or `FLOAT` or `LONG`, Oracle will store it as a `NUMBER`. The OCI library then
-/// allows you
-/// to convert it into any numeric type you like, but that forces you to explicitly state the type
-/// of the columns when retrieving the values. To avoid this, this crate makes some executive
-/// decisions based on the `NUMBER` value. As per the OCI documentation the basic type of a number
-/// can be
-/// determined by the scale and precision of the `NUMBER` value. If the precision is non-zero and
-/// scale is -127 then the number is a `FLOAT` otherwise we can consider it an `INTEGER`.
-/// So, according to this logic the caller will receive either `SqlValue::Integer` or
-/// `SqlValue::Float`.
-/// These two variants contain an `i64` and `f64` respectively. If a smaller type is needed in
-/// Rust code,
-/// then further conversions can be made. This appears to be sufficient to allow retrieval of data
-/// in
-/// queries, without having specify column types on the Rust side ahead of time.
-///
-/// Note: Oracle also supports types known as `BINARY_FLOAT` and `BINARY_DOUBLE`. These can also be
-/// used to store numbers inside the database as an alternative to `NUMBER`. They are not currently
-/// supported.
+/// allows you to convert it into any numeric type you like, but that forces you to explicitly
+/// state the type of the columns when retrieving the values. To avoid this, this crate fetches
+/// each `NUMBER` in Oracle's own format and decides from the value itself: a whole number that
+/// fits in an `i64` becomes a `SqlValue::Integer`, any other number a `SqlValue::Float` and a
+/// whole number too big for an `i64` becomes text, so that none of its digits are lost.
+/// The decision can be reversed, an integer converts to an `f64` and a float that is a whole
+/// number converts to an `i64`. The smaller `i8`, `i16`, `i32`, `u32` and `f32` types can be
+/// used as well, along with `u64` and `i128`, a value that is out of their range doesn't
+/// convert. An `i128` holds any whole number of up to 38 digits, such as a `NUMBER(38)` key.
+/// `SqlValue.try_value`, or `TryFrom`, returns an error saying why instead of a `None`. This allows retrieval
+/// of data in queries, without having specify column types on the Rust side ahead of time.
+///
+/// Oracle also supports types known as `BINARY_FLOAT` and `BINARY_DOUBLE`, which store IEEE
+/// floating point numbers as an alternative to `NUMBER`. They are fetched as a `SqlValue::Float`,
+/// including NaN and the infinities, which a `NUMBER` can't hold. A NaN or infinite `f64` is
+/// bound as a `BINARY_DOUBLE` so that it is stored unchanged in either type, while Oracle raises
+/// an error if it has to be converted to a `NUMBER`. On the Rust side such a float converts to
+/// an `f64` or `f32` and to text, but not to an integer or decimal.
 ///
 /// The traits allow conversion to and from Rust types into `SqlValue`.
 ///
 /// ## Type conversions
 ///
-/// | Oracle SQL type          | Rust type               |
-/// |--------------------------|-------------------------|
-/// | VARCHAR                  | `String`                |
-/// | VARCHAR2                 | `String`                |
-/// | CHAR                     | `String`                |
-/// | NUMBER                   | `i64`, `f64`            |
-/// | DATE                     | `Date<Utc>`             |
-/// | TIMESTAMP                | `DateTime<Utc>`         |
-/// | TIMESTAMP WITH TIME ZONE | `DateTime<FixedOffset>` |
+/// | Oracle SQL type          | Rust type                   |
+/// |--------------------------|-----------------------------|
+/// | VARCHAR                  | `String`                    |
+/// | VARCHAR2                 | `String`                    |
+/// | CHAR                     | `String`                    |
+/// | NCHAR, NVARCHAR2         | `String`                    |
+/// | NUMBER                   | `i64`, `f64`                |
+/// | NUMBER(p, s)             | `Decimal` (`decimal`)       |
+/// | NUMBER(p, s), NUMBER(38) | `BigDecimal` (`bigdecimal`) |
+/// | BINARY_FLOAT             | `f64`, `f32`                |
+/// | BINARY_DOUBLE            | `f64`                       |
+/// | DATE                     | `Date<Utc>`                 |
+/// | TIMESTAMP                | `DateTime<Utc>`             |
+/// | TIMESTAMP WITH TIME ZONE | `DateTime<FixedOffset>`     |
+/// | CLOB                     | `String`                    |
+/// | BLOB                     | `Vec<u8>`                   |
+/// | RAW                      | `Vec<u8>`                   |
+/// | LONG RAW                 | `Vec<u8>`                   |
+/// | BFILE                    | `BFile` (read only)         |
+/// | ROWID, UROWID            | `RowId`                     |
+/// | XMLTYPE                  | `String`                    |
+/// | JSON                     | `String`, `Value` (`json`)  |
+/// | NUMBER(1), CHAR(1) flag  | `bool`                      |
+/// | VARRAY, nested table     | `Collection`                |
+/// | RAW(16)                  | `Uuid` (`uuid`)             |
+///
+/// With the `decimal` feature a `NUMBER` declared with decimal places is fetched as a
+/// `rust_decimal::Decimal`, so no precision is lost. The `bigdecimal` feature does the same
+/// with a `bigdecimal::BigDecimal`, which also holds a `NUMBER` declared with more digits than
+/// an `i64` can, and is used when both features are enabled.
+///
+/// A statement, or every statement on a connection, can instead fetch `NUMBER` columns as their
+/// exact decimal text with `.set_numbers_as_text`. All 38 digits are kept and a column declared
+/// with decimal places is given that many, so the text can be passed on unchanged.
+///
+/// With the `time` feature the `time` crate's types can be used instead of chrono's, a `DATE`
+/// converts to and from a `time::Date`, a `TIMESTAMP` a `time::PrimitiveDateTime`, taken to be
+/// in UTC, and a `TIMESTAMP WITH TIME ZONE` a `time::OffsetDateTime`.
+///
+/// Dates cover Oracle's whole range, 4712 BC to 9999 AD. Oracle has no year zero, where chrono
+/// and `time` count 1 BC as year 0 and 2 BC as year -1, so `Utc.ymd(-4711, 1, 1)` is Oracle's
+/// first day. The day and month are passed on as they are, though Oracle reckons days before
+/// 15 October 1582 by the Julian calendar rather than the proleptic Gregorian one used here.
+///
+/// A `TIMESTAMP WITH TIME ZONE` set in a named region, such as `Europe/London`, is fetched as a
+/// `SqlValue::TimestampTzRegion`, which holds the region's name as well as the offset in effect
+/// at the time. It still converts to a `DateTime<FixedOffset>`, and with the `chrono-tz`
+/// feature to a `DateTime<chrono_tz::Tz>` in that region.
+///
+/// With the `uuid` feature a `uuid::Uuid` is bound as a `RAW(16)`, the usual way of storing a
+/// UUID key, and converts back from one.
+///
+/// A null is fetched as a `SqlValue::Null` holding the type of its column, given by
+/// `.null_type`, so that a null can be written with the right type by code that handles values
+/// generically, such as a serializer building a schema from the first row.
+///
+/// A `VARCHAR2` is fetched with the white space at both ends trimmed and a `CHAR` with the
+/// padding Oracle stores it with. Either can be changed, for text whose spaces matter, with
+/// `TextTrim`.
+///
+/// Oracle has no boolean column type, so a `bool` is bound as `1` or `0` and read from either
+/// `1`/`0` or `'Y'`/`'N'`. `BoolEncoding` binds a `'Y'`/`'N'` flag.
+///
+/// `CLOB` and `BLOB` columns are fetched through a LOB locator and read in full, so the whole
+/// value is returned however long it is.
+///
+/// An `XMLType` is converted to text by Oracle, which only allows a document as long as a
+/// `VARCHAR2`. Longer documents can be fetched as a `CLOB` with
+/// `XMLSERIALIZE(DOCUMENT column AS CLOB)`.
+///
+/// A `JSON` column, from Oracle 21c, is fetched as its JSON text, however long it is. With the
+/// `json` feature it converts to a `serde_json::Value`, and a `Value` can be bound to insert a
+/// document.
+///
+/// A `VARRAY` or nested table of `NUMBER`, `VARCHAR2`, `CHAR` or `DATE` elements is fetched as a
+/// `SqlValue::Collection`, whose elements are `SqlValue`s, and a `Collection` can be bound to
+/// a column or a PL/SQL parameter of its type.
+///
+/// ## Codecs
+///
+/// Fetched columns are converted into `SqlValue`s by a [`TypeCodec`][3], chosen by the OCI type
+/// code that Oracle describes the column with. Other types can be supported by registering a
+/// codec for them with `Connection.register_codec`.
 ///
 /// # Examples
 ///
@@ -374,6 +463,7 @@ pub mod oci_error;
 ///
 /// [1]: https://github.com/jaheba/stuff/blob/master/communicating_intent.md
 /// [2]: https://crates.io/crates/postgres
+/// [3]: trait.TypeCodec.html
 pub mod types;
 
 /// Rows of data returned from a query
@@ -382,8 +472,171 @@ pub mod types;
 /// and their values. It implements the `Index` trait and so columns can be accessed via an index
 /// number.
 ///
+/// Columns can also be looked up by the name the database describes them with. `Row.try_get`
+/// takes a name or a position and returns a `Result` whose error names the column, its SQL type
+/// and the Rust type asked for, rather than the bare `None` of `SqlValue.value`. The
+/// `FromRow` trait turns a `Row` into a struct for use with `Statement.typed_result_set`. With
+/// the `derive` feature `#[derive(FromRow)]` writes the conversion, and `#[derive(ToParams)]`
+/// lets a struct be bound as named parameters with `Statement.bind_params`. For quick scripts
+/// `Row.into_tuple` converts a row into a tuple of its columns without a struct at all.
+///
 pub mod row;
 
+/// Authentication.
+///
+/// Connections created through a [`ConnectionBuilder`][1] get their credentials from an
+/// [`AuthProvider`][2]. Providers are included for a static user name and password, Oracle
+/// wallets, Kerberos, RADIUS and access tokens, and custom ones can be written to fetch or rotate
+/// credentials as needed. Kerberos and RADIUS also need to be enabled as authentication services
+/// in the client's `sqlnet.ora`.
+///
+/// [1]: ../connection/struct.ConnectionBuilder.html
+/// [2]: trait.AuthProvider.html
+pub mod auth;
+
+/// Handling of credentials.
+///
+/// Passwords and tokens are held in a [`SecretString`][1] so that they are redacted from `Debug`
+/// output and can't be leaked by accidentally logging a struct that holds them.
+///
+/// [1]: struct.SecretString.html
+pub mod secret;
+
+/// Using connections from async code.
+///
+/// Only available with the `async` feature. An [`AsyncConnection`][1] runs the blocking OCI calls
+/// on Tokio's blocking thread pool so that they don't stall the runtime. With the `deadpool` or
+/// `bb8` features the [`ConnectionManager`][2] allows these connections to be pooled.
+///
+/// [1]: struct.AsyncConnection.html
+/// [2]: struct.ConnectionManager.html
+#[cfg(feature = "async")]
+pub mod async_connection;
+
+/// Setting and reading OCI attributes directly.
+///
+/// The types here are used with [`Connection.set_attribute`][1] and
+/// [`Connection.get_attribute`][2] to reach OCI attributes that the crate does not wrap yet.
+/// Both are `unsafe`, as OCI can't check that a value has the type of its attribute.
+///
+/// [1]: ../connection/struct.Connection.html#method.set_attribute
+/// [2]: ../connection/struct.Connection.html#method.get_attribute
+pub mod attribute;
+
+/// Structured connection strings.
+///
+/// A [`ConnectDescriptor`][1] builds an Easy Connect Plus string from its parts, including
+/// options such as retries, timeouts and HTTPS proxies, so that they don't have to be
+/// assembled by hand.
+///
+/// [1]: struct.ConnectDescriptor.html
+pub mod connect_descriptor;
+
+/// Describing tables and views.
+///
+/// The functions here look up the columns of tables from the data dictionary.
+/// [`describe_tables`][1] describes a whole list of tables in a single round trip, which is a
+/// lot faster than describing them one at a time when there are many of them.
+///
+/// [1]: fn.describe_tables.html
+pub mod describe;
+
+/// Long running exports that can be resumed.
+///
+/// An [`Export`][1] streams a table in key order and records its progress in a
+/// [`CheckpointStore`][2] every so often. If the export is interrupted, for example by a dropped
+/// network connection, running it again picks up after the last checkpoint.
+///
+/// [1]: struct.Export.html
+/// [2]: trait.CheckpointStore.html
+pub mod export;
+
+/// Change tracking with `ORA_ROWSCN`.
+///
+/// Oracle records the system change number, or SCN, of the last change to each row in the
+/// `ORA_ROWSCN` pseudocolumn. [`Versioned`][1] reads it alongside a typed row for optimistic
+/// concurrency and [`changed_since_query`][2] builds queries for incremental extraction.
+/// There are also functions to convert between SCNs and timestamps.
+///
+/// [1]: struct.Versioned.html
+/// [2]: fn.changed_since_query.html
+pub mod scn;
+
+/// Values whose type is only known at run time.
+///
+/// A [`DynValue`][1] can be built from text or, with the `json` feature, from a JSON value and
+/// is bound with a suitable Oracle type, for tools that load queries and their parameters at
+/// run time.
+///
+/// [1]: enum.DynValue.html
+pub mod dynamic;
+
+/// Explicit transactions.
+///
+/// A [`Transaction`][1] is committed or rolled back as a whole and is rolled back automatically
+/// if it goes out of scope without being committed. It also controls when deferrable
+/// constraints are checked.
+///
+/// [1]: struct.Transaction.html
+pub mod transaction;
+
+/// Diagnostic snapshots for reporting bugs.
+///
+/// When the OCI library fails in a way that points to a bug, such as an invalid handle, the
+/// error carries a [`Diagnostics`][1] snapshot with the versions in use, the last SQL and the
+/// most recent OCI calls.
+///
+/// [1]: struct.Diagnostics.html
+pub mod diagnostics;
+
+/// Calling stored procedures and running anonymous PL/SQL blocks.
+///
+/// A procedure is called with the `.call` method of a [`Connection`][1], passing a [`Param`][2]
+/// for each of its parameters. The anonymous PL/SQL block to call it is written for you. Other
+/// blocks, with declarations, several statements and function calls, can be put together with
+/// a [`BlockBuilder`][3].
+///
+/// [1]: ../connection/struct.Connection.html
+/// [2]: enum.Param.html
+/// [3]: struct.BlockBuilder.html
+pub mod plsql;
+
+/// Running queries a page at a time.
+///
+/// A [`Paginator`][1] wraps a `SELECT` so that it returns one page of rows at a time, using
+/// `OFFSET` and `FETCH NEXT` or, for older databases, `ROWNUM`.
+///
+/// [1]: struct.Paginator.html
+pub mod paginate;
+
+/// Sending several statements to the database in one round trip.
+///
+/// A [`StatementQueue`][1] collects DML statements and their parameters and runs them all
+/// together when it is flushed.
+///
+/// [1]: struct.StatementQueue.html
+pub mod pipeline;
+
+/// Reading files that `BFILE` columns point to.
+///
+/// A [`BFile`][1] holds the directory alias and file name fetched from a `BFILE` column and
+/// is opened to stream the file's contents from the server.
+///
+/// [1]: struct.BFile.html
+pub mod bfile;
+
+/// `VARRAY` and nested table collections.
+///
+/// A [`Collection`][1] holds the elements of a column or parameter of a collection type,
+/// along with the name of the type and the type of its elements.
+///
+/// [1]: struct.Collection.html
+pub mod collection;
+
+// lets the `::oci_rs` paths written by the derive macros resolve in this crate's own tests
+#[cfg(all(test, feature = "derive"))]
+extern crate self as oci_rs;
+
 mod common;
 mod oci_bindings;
 /// SQL statements run against the database.
@@ -399,9 +652,8 @@ mod oci_bindings;
 /// 1. Create a `Statement` from a connection with a given SQL statement. This will create a
 ///    prepared statement on the Oracle side.
 /// 2. If the SQL contains bind variable placeholders then these values should now be set via a
-///    call to `.bind`. Although OCI supports both positional and named bind variables, only
-///    positional are curently support by `Statement`. Oracle uses the form `:name` where `name`is
-///    the bind variable.
+///    call to `.bind`, which binds them by position, or `.bind_named`, which binds them by name.
+///    Oracle uses the form `:name` where `name`is the bind variable.
 /// 3. Execute the statement.
 /// 4. Commit the transaction if data was changed. Oracle implicitly creates a transaction when data
 ///    is changed and commits automatically with a normal session close and log-off. If we
@@ -468,7 +720,7 @@ mod oci_bindings;
 /// select.execute().unwrap();
 ///
 /// // Get the result set row by row from an iterator
-/// for (index, row_result) in select.lazy_result_set().enumerate(){
+/// for (index, row_result) in select.lazy_result_set().unwrap().enumerate(){
 ///     let row = row_result.unwrap();
 ///     let city_id: i64 = row[0].value().unwrap();
 ///     let city_name: String = row[1].value().unwrap();
@@ -483,6 +735,7 @@ mod oci_bindings;
 ///
 /// // Get cities containing an 'a':
 /// let results: Vec<String> = select.lazy_result_set()
+///                                  .unwrap()
 ///                                  .map(|row_result| {
 ///                                           let row = row_result.unwrap();
 ///                                           row[1].value::<String>().unwrap()
@@ -504,6 +757,8 @@ pub mod statement;
 mod tests {
     use crate::connection::Connection;
     use crate::oci_error::OciError;
+    use crate::row::{FromRow, Row};
+    use crate::types::{SqlValue, ToSqlValue};
     use chrono::{Date, DateTime, FixedOffset, TimeZone, Timelike, Utc};
     const CONNECTION: &str = "localhost:1521/xe";
     const BAD_CONNECTION: &str = "localhost:1521/xp";
@@ -528,63 +783,5513 @@ mod tests {
             Ok(conn) => conn,
             Err(err) => panic!("Failed to create a connection: {}", err),
         };
-    }
+    }
+
+    #[test]
+    #[allow(unused_variables)]
+    fn create_connection_with_bad_connection_string() {
+        let error = match Connection::new(BAD_CONNECTION, USER, PASSWORD) {
+            Ok(conn) => panic!("Should not have been able to create a connection, test is wrong."),
+            Err(err) => err,
+        };
+        let code = match error {
+            OciError::Oracle(ref error_record) => &error_record.error_records()[0].0,
+            OciError::Conversion(_) => {
+                panic!("Should not have found a conversion error, test is wrong.")
+            }
+            _ => panic!("Should only have found an Oracle error, test is wrong."),
+        };
+        let tns_listener_error: i32 = 12514;
+        assert_eq!(&tns_listener_error, code)
+    }
+
+    #[test]
+    fn connection_debug_hides_password() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let debug = format!("{:?}", conn);
+        assert!(debug.contains(USER));
+        assert!(!debug.contains(PASSWORD));
+    }
+
+    #[test]
+    fn in_action_restores_previous_module_and_action() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let current = || -> (String, String) {
+            let sql = "SELECT SYS_CONTEXT('USERENV', 'MODULE'), \
+                       SYS_CONTEXT('USERENV', 'ACTION') FROM dual";
+            let mut select = match conn.create_prepared_statement(sql) {
+                Ok(stmt) => stmt,
+                Err(err) => panic!("{}", err),
+            };
+            if let Err(err) = select.execute() {
+                panic!("{}", err)
+            }
+            let result_set = match select.result_set() {
+                Ok(res) => res,
+                Err(err) => panic!("{}", err),
+            };
+            (
+                result_set[0][0].value().unwrap_or_default(),
+                result_set[0][1].value().unwrap_or_default(),
+            )
+        };
+
+        if let Err(err) = conn.set_module_action("BILLING", "run") {
+            panic!("{}", err)
+        }
+        let inner = match conn.in_action("PAYMENTS", "settle", || Ok(current())) {
+            Ok(inner) => inner,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(inner, ("PAYMENTS".to_string(), "settle".to_string()));
+        assert_eq!(current(), ("BILLING".to_string(), "run".to_string()));
+
+        let failed = conn.in_action("PAYMENTS", "refund", || {
+            let mut select = conn.create_prepared_statement("SELECT * FROM NoSuchRefunds")?;
+            select.execute()?;
+            Ok(())
+        });
+        match failed {
+            Err(OciError::Oracle(..)) => (),
+            Err(err) => panic!("Expected an Oracle error, found {}", err),
+            Ok(()) => panic!("Selecting from a missing table should fail"),
+        }
+        assert_eq!(current(), ("BILLING".to_string(), "run".to_string()));
+    }
+
+    #[test]
+    fn calls_fail_once_the_deadline_has_passed() {
+        use std::time::{Duration, Instant};
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let mut select = match conn.create_prepared_statement("SELECT 1 FROM dual") {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        {
+            let _deadline = conn.with_deadline(Instant::now() + Duration::from_secs(60));
+            if let Err(err) = select.execute() {
+                panic!("{}", err)
+            }
+            let _expired = conn.with_deadline(Instant::now());
+            match select.execute() {
+                Err(OciError::DeadlineExceeded(None)) => (),
+                Err(err) => panic!("Expected the deadline to be exceeded: {}", err),
+                Ok(()) => panic!("Expected the deadline to be exceeded"),
+            }
+        }
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+    }
+
+    #[test]
+    fn calls_running_past_the_deadline_are_interrupted() {
+        use std::time::{Duration, Instant};
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_sleep = "BEGIN DBMS_SESSION.SLEEP(30); END;";
+        let mut sleep = match conn.create_prepared_statement(sql_sleep) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        let started = Instant::now();
+        {
+            let _deadline = conn.with_deadline(Instant::now() + Duration::from_secs(1));
+            match sleep.execute() {
+                Err(OciError::DeadlineExceeded(Some(ref record))) => {
+                    assert_eq!(record.sql(), Some(sql_sleep))
+                }
+                Err(err) => panic!("Expected the deadline to be exceeded: {}", err),
+                Ok(()) => panic!("Expected the deadline to be exceeded"),
+            }
+        }
+        assert!(started.elapsed() < Duration::from_secs(30));
+        if let Err(err) = conn.ping() {
+            panic!("{}", err)
+        }
+    }
+
+    #[test]
+    fn new_session_shares_the_connection() {
+        use crate::auth::StaticPassword;
+        use crate::connection::ConnectionBuilder;
+
+        let primary = match ConnectionBuilder::new(CONNECTION)
+            .auth(StaticPassword::new(USER, PASSWORD))
+            .migratable(true)
+            .build()
+        {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        assert!(primary
+            .new_session(StaticPassword::new(USER, BAD_PASSWORD))
+            .is_err());
+        let session = match primary.new_session(StaticPassword::new(USER, PASSWORD)) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to start a session: {}", err),
+        };
+        // the primary session is kept open for the migrated one
+        drop(primary);
+        if let Err(err) = session.ping() {
+            panic!("{}", err)
+        }
+        let mut select = match session.create_prepared_statement("SELECT USER FROM dual") {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        match select.result_set() {
+            Ok(rows) => assert_eq!(rows[0][0].value::<String>(), Some(USER.to_uppercase())),
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    #[cfg(feature = "async")]
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        match tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+        {
+            Ok(runtime) => runtime.block_on(future),
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn async_connection_runs_on_the_blocking_pool() {
+        use crate::async_connection::AsyncConnection;
+        use crate::auth::StaticPassword;
+        use crate::connection::ConnectionBuilder;
+
+        block_on(async {
+            let builder =
+                ConnectionBuilder::new(CONNECTION).auth(StaticPassword::new(USER, PASSWORD));
+            let conn = match AsyncConnection::connect(builder).await {
+                Ok(conn) => conn,
+                Err(err) => panic!("{}", err),
+            };
+            if let Err(err) = conn.ping().await {
+                panic!("{}", err)
+            }
+            let answer = conn
+                .clone()
+                .run(|conn| {
+                    let mut select = conn.create_prepared_statement("SELECT 42 FROM dual")?;
+                    select.execute()?;
+                    let result_set = select.result_set()?;
+                    Ok(result_set[0][0].value::<i64>())
+                })
+                .await;
+            match answer {
+                Ok(answer) => assert_eq!(answer, Some(42)),
+                Err(err) => panic!("{}", err),
+            }
+
+            let bad_builder =
+                ConnectionBuilder::new(CONNECTION).auth(StaticPassword::new(USER, BAD_PASSWORD));
+            assert!(AsyncConnection::connect(bad_builder).await.is_err());
+        })
+    }
+
+    #[test]
+    #[cfg(any(feature = "deadpool", feature = "bb8"))]
+    fn connection_manager_validates_connections() {
+        use crate::async_connection::{AsyncConnection, ConnectionManager, Validation};
+        use crate::auth::StaticPassword;
+        use crate::connection::ConnectionBuilder;
+
+        block_on(async {
+            let builder =
+                ConnectionBuilder::new(CONNECTION).auth(StaticPassword::new(USER, PASSWORD));
+            let conn = match AsyncConnection::connect(builder.clone()).await {
+                Ok(conn) => conn,
+                Err(err) => panic!("{}", err),
+            };
+            for validation in &[Validation::None, Validation::Ping, Validation::Query] {
+                let manager = ConnectionManager::new(builder.clone()).validation(*validation);
+                if let Err(err) = manager.validate(&conn).await {
+                    panic!("{:?} validation failed: {}", validation, err)
+                }
+            }
+        })
+    }
+
+    #[test]
+    #[cfg(feature = "deadpool")]
+    fn deadpool_manager_creates_and_recycles_connections() {
+        use crate::async_connection::ConnectionManager;
+        use crate::auth::StaticPassword;
+        use crate::connection::ConnectionBuilder;
+        use deadpool::managed::Pool;
+
+        block_on(async {
+            let builder =
+                ConnectionBuilder::new(CONNECTION).auth(StaticPassword::new(USER, PASSWORD));
+            let pool: Pool<ConnectionManager> = match Pool::builder(ConnectionManager::new(builder))
+                .max_size(1)
+                .build()
+            {
+                Ok(pool) => pool,
+                Err(err) => panic!("{}", err),
+            };
+            for _ in 0..2 {
+                let conn = match pool.get().await {
+                    Ok(conn) => conn,
+                    Err(err) => panic!("{}", err),
+                };
+                if let Err(err) = conn.ping().await {
+                    panic!("{}", err)
+                }
+            }
+            assert_eq!(pool.status().size, 1);
+
+            let bad_builder =
+                ConnectionBuilder::new(CONNECTION).auth(StaticPassword::new(USER, BAD_PASSWORD));
+            let bad_pool: Pool<ConnectionManager> =
+                match Pool::builder(ConnectionManager::new(bad_builder)).build() {
+                    Ok(pool) => pool,
+                    Err(err) => panic!("{}", err),
+                };
+            assert!(bad_pool.get().await.is_err());
+        })
+    }
+
+    #[test]
+    #[cfg(feature = "bb8")]
+    fn bb8_manager_creates_and_validates_connections() {
+        use crate::async_connection::ConnectionManager;
+        use crate::auth::StaticPassword;
+        use crate::connection::ConnectionBuilder;
+        use bb8::Pool;
+
+        block_on(async {
+            let builder =
+                ConnectionBuilder::new(CONNECTION).auth(StaticPassword::new(USER, PASSWORD));
+            let pool = match Pool::builder()
+                .max_size(1)
+                .build(ConnectionManager::new(builder))
+                .await
+            {
+                Ok(pool) => pool,
+                Err(err) => panic!("{}", err),
+            };
+            for _ in 0..2 {
+                let conn = match pool.get().await {
+                    Ok(conn) => conn,
+                    Err(err) => panic!("{}", err),
+                };
+                if let Err(err) = conn.ping().await {
+                    panic!("{}", err)
+                }
+            }
+            assert_eq!(pool.state().connections, 1);
+        })
+    }
+
+    /// Creates the Satchels table with one committed row, so that no transaction is open.
+    fn satchels_connection() -> Connection {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        for (index, sql) in [
+            "DROP TABLE Satchels",
+            "CREATE TABLE Satchels(SatchelId integer, Colour varchar(20))",
+            "INSERT INTO Satchels(SatchelId, Colour) VALUES(1, 'Tan')",
+        ]
+        .iter()
+        .enumerate()
+        {
+            let mut stmt = match conn.create_prepared_statement(sql) {
+                Ok(stmt) => stmt,
+                Err(err) => panic!("{}", err),
+            };
+            match stmt.execute() {
+                // the table may not be there to drop
+                Err(_) if index == 0 => (),
+                Err(err) => panic!("{}", err),
+                Ok(()) => (),
+            }
+        }
+        if let Err(err) = conn.commit() {
+            panic!("{}", err)
+        }
+        assert_eq!(conn.transaction_statements(), None);
+        conn
+    }
+
+    #[test]
+    fn merge_starts_a_transaction() {
+        let conn = satchels_connection();
+        let sql_merge = "MERGE INTO Satchels s \
+                         USING (SELECT 1 AS SatchelId FROM dual) d \
+                         ON (s.SatchelId = d.SatchelId) \
+                         WHEN MATCHED THEN UPDATE SET s.Colour = 'Brown'";
+        let mut merge = match conn.create_prepared_statement(sql_merge) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = merge.execute() {
+            panic!("{}", err)
+        }
+        assert_eq!(conn.transaction_statements(), Some(1));
+        if let Err(err) = conn.rollback() {
+            panic!("{}", err)
+        }
+        assert_eq!(conn.transaction_statements(), None);
+    }
+
+    #[test]
+    fn select_for_update_starts_a_transaction() {
+        use crate::connection::locks_rows;
+
+        assert!(locks_rows("SELECT * FROM Satchels FOR UPDATE"));
+        assert!(locks_rows(
+            "select Colour from Satchels for\n update of Colour nowait"
+        ));
+        assert!(!locks_rows("SELECT 'for update' FROM dual"));
+        assert!(!locks_rows("SELECT Colour FROM Satchels"));
+
+        let conn = satchels_connection();
+        for sql in &[
+            "SELECT Colour FROM Satchels",
+            "SELECT Colour FROM Satchels FOR UPDATE",
+        ] {
+            let mut select = match conn.create_prepared_statement(sql) {
+                Ok(stmt) => stmt,
+                Err(err) => panic!("{}", err),
+            };
+            if let Err(err) = select.execute() {
+                panic!("{}", err)
+            }
+        }
+        assert_eq!(conn.transaction_statements(), Some(1));
+        if let Err(err) = conn.rollback() {
+            panic!("{}", err)
+        }
+    }
+
+    #[test]
+    fn plsql_block_starts_a_transaction() {
+        let conn = satchels_connection();
+        let sql_block = "BEGIN UPDATE Satchels SET Colour = 'Black'; END;";
+        let mut block = match conn.create_prepared_statement(sql_block) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = block.execute() {
+            panic!("{}", err)
+        }
+        assert_eq!(conn.transaction_statements(), Some(1));
+        if let Err(err) = conn.commit() {
+            panic!("{}", err)
+        }
+        assert_eq!(conn.transaction_statements(), None);
+    }
+
+    #[test]
+    fn constraint_names_are_validated_and_quoted() {
+        use crate::oci_error::UsageError;
+        use crate::transaction::quote_constraint_name;
+
+        let quoted = |name| match quote_constraint_name(name) {
+            Ok(quoted) => quoted,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(quoted("Kittens_Cat_Fk"), "\"KITTENS_CAT_FK\"");
+        assert_eq!(quoted(" oci_rs.fk$1# "), "\"OCI_RS\".\"FK$1#\"");
+        assert_eq!(
+            quoted("oci_rs.\"Mixed case.fk\""),
+            "\"OCI_RS\".\"Mixed case.fk\""
+        );
+
+        let too_long = "C".repeat(129);
+        for name in &[
+            "",
+            "a.b.c",
+            "1_fk",
+            "_fk",
+            "fk name",
+            "fk;DROP TABLE Kittens",
+            "fk\" OR 1=1 --",
+            "\"\"",
+            "\"unterminated",
+            "\"a\"\"b\"",
+            "\"nul\0\"",
+            "schema.",
+            ".fk",
+            "schema..fk",
+            too_long.as_str(),
+        ] {
+            match quote_constraint_name(name) {
+                Err(OciError::Usage(UsageError::InvalidName(_))) => (),
+                other => panic!("{:?} should have been rejected, found {:?}", name, other),
+            }
+        }
+        assert!(quote_constraint_name(&"C".repeat(128)).is_ok());
+    }
+
+    #[test]
+    fn deferred_constraints_are_checked_on_commit() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        for sql_drop in &["DROP TABLE Kittens", "DROP TABLE Cats"] {
+            let mut drop = match conn.create_prepared_statement(sql_drop) {
+                Ok(stmt) => stmt,
+                Err(err) => panic!("{}", err),
+            };
+            drop.execute().ok();
+        }
+        let sql_creates = [
+            "CREATE TABLE Cats(CatId integer PRIMARY KEY)",
+            "CREATE TABLE Kittens(KittenId integer, CatId integer,
+                                  CONSTRAINT Kittens_Cat_Fk FOREIGN KEY (CatId)
+                                  REFERENCES Cats(CatId) DEFERRABLE)",
+        ];
+        for sql_create in &sql_creates {
+            let mut create = match conn.create_prepared_statement(sql_create) {
+                Ok(stmt) => stmt,
+                Err(err) => panic!("{}", err),
+            };
+            if let Err(err) = create.execute() {
+                panic!("{}", err)
+            }
+        }
+
+        let transaction = conn.transaction();
+        if let Err(err) = transaction.set_constraints_deferred(&["kittens_cat_fk"]) {
+            panic!("{}", err)
+        }
+        let sql_inserts = [
+            "INSERT INTO Kittens(KittenId, CatId) VALUES(1, 7)",
+            "INSERT INTO Cats(CatId) VALUES(7)",
+        ];
+        for sql_insert in &sql_inserts {
+            let mut insert = match transaction.create_prepared_statement(sql_insert) {
+                Ok(stmt) => stmt,
+                Err(err) => panic!("{}", err),
+            };
+            if let Err(err) = insert.execute() {
+                panic!("{}", err)
+            }
+        }
+        if let Err(err) = transaction.commit() {
+            panic!("{}", err)
+        }
+
+        let transaction = conn.transaction();
+        assert!(transaction
+            .set_constraints_deferred(&["\"No.Such\".\"Constraint\".Name"])
+            .is_err());
+    }
+
+    #[test]
+    fn auth_providers_supply_credentials() {
+        use crate::auth::{AuthProvider, Credentials, StaticPassword, TokenFetcher, Wallet};
+
+        match StaticPassword::new(USER, PASSWORD).credentials() {
+            Ok(Credentials::Password {
+                ref user_name,
+                ref password,
+            }) => {
+                assert_eq!(user_name, USER);
+                assert_eq!(password.expose_secret(), PASSWORD);
+            }
+            other => panic!("Expected a password, found {:?}", other),
+        }
+        match Wallet.credentials() {
+            Ok(Credentials::External) => (),
+            other => panic!("Expected external credentials, found {:?}", other),
+        }
+        let fetcher = TokenFetcher::with_private_key(|| Ok("token".into()), "key");
+        match fetcher.credentials() {
+            Ok(Credentials::Token {
+                ref token,
+                private_key: Some(ref private_key),
+            }) => {
+                assert_eq!(token.expose_secret(), "token");
+                assert_eq!(private_key.expose_secret(), "key");
+            }
+            other => panic!("Expected a token, found {:?}", other),
+        }
+        let failing = TokenFetcher::new(|| Err(OciError::Conversion("no token".into())));
+        assert!(failing.credentials().is_err());
+    }
+
+    #[test]
+    fn radius_passes_the_user_name_and_password() {
+        use crate::auth::{AuthProvider, AuthenticationMethod, Credentials, Radius};
+
+        match Radius::new(USER, PASSWORD).credentials() {
+            Ok(Credentials::Password {
+                ref user_name,
+                ref password,
+            }) => {
+                assert_eq!(user_name, USER);
+                assert_eq!(password.expose_secret(), PASSWORD);
+            }
+            other => panic!("Expected a password, found {:?}", other),
+        }
+        assert_eq!(
+            AuthenticationMethod::from("RADIUS"),
+            AuthenticationMethod::Radius
+        );
+        assert_eq!(
+            AuthenticationMethod::from("NONE"),
+            AuthenticationMethod::Other("NONE".to_string())
+        );
+    }
+
+    #[test]
+    fn missing_kerberos_ticket_cache_is_an_authentication_error() {
+        use crate::auth::check_ticket_cache;
+        use std::env;
+        use std::fs::{self, File};
+
+        let cache = env::temp_dir().join("oci_rs_krb5cc_test");
+        if let Err(err) = File::create(&cache) {
+            panic!("{}", err)
+        }
+        let cache_name = cache.to_string_lossy().into_owned();
+        assert!(check_ticket_cache(&cache_name).is_ok());
+        assert!(check_ticket_cache(&format!("FILE:{}", cache_name)).is_ok());
+        fs::remove_file(&cache).ok();
+
+        for missing in &[cache_name.clone(), format!("FILE:{}", cache_name)] {
+            match check_ticket_cache(missing) {
+                Err(OciError::Authentication(ref message)) => {
+                    assert!(message.contains(&cache_name), "{}", message)
+                }
+                other => panic!("Expected an authentication error, found {:?}", other),
+            }
+        }
+        assert!(check_ticket_cache("KEYRING:persistent:1000").is_ok());
+        assert!(check_ticket_cache("KCM:").is_ok());
+    }
+
+    #[test]
+    fn kerberos_explains_sign_on_failures() {
+        use crate::auth::{AuthProvider, Kerberos};
+        use crate::oci_error::ErrorRecord;
+
+        let oracle_error = |code| {
+            let mut record = ErrorRecord::new("Starting the session");
+            record.add_error(code, format!("ORA-{}: failure", code));
+            OciError::Oracle(record)
+        };
+        let explained = Kerberos.explain_error(oracle_error(12638)).to_string();
+        assert!(explained.contains("run kinit"), "{}", explained);
+        let explained = Kerberos.explain_error(oracle_error(1017)).to_string();
+        assert!(explained.contains("identified externally"), "{}", explained);
+
+        let unrelated = Kerberos.explain_error(oracle_error(942)).to_string();
+        assert_eq!(unrelated, oracle_error(942).to_string());
+        match Kerberos.explain_error(OciError::Authentication("no cache".to_string())) {
+            OciError::Authentication(ref message) => assert_eq!(message, "no cache"),
+            other => panic!("Expected the error unchanged, found {:?}", other),
+        }
+    }
+
+    #[test]
+    fn connection_builder_defaults_to_external_credentials() {
+        use crate::auth::{Credentials, StaticPassword};
+        use crate::connection::ConnectionBuilder;
+
+        let builder = ConnectionBuilder::new(CONNECTION);
+        match builder.credentials() {
+            Ok(Credentials::External) => (),
+            other => panic!("Expected external credentials, found {:?}", other),
+        }
+        let builder = builder.auth(StaticPassword::new(USER, PASSWORD));
+        match builder.credentials() {
+            Ok(ref credentials) => assert_eq!(credentials.user_name(), Some(USER)),
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    #[test]
+    fn redact_connect_string_hides_passwords() {
+        use crate::secret::redact_connect_string;
+
+        assert_eq!(
+            redact_connect_string("scott/tiger@localhost:1521/xe"),
+            "scott/********@localhost:1521/xe"
+        );
+        assert_eq!(
+            redact_connect_string("scott/\"ti@ger\"@//db.example.com/orcl?connect_timeout=5"),
+            "scott/********@//db.example.com/orcl?connect_timeout=5"
+        );
+        assert_eq!(
+            redact_connect_string("localhost:1521/xe?password=tiger&retry_count=3"),
+            "localhost:1521/xe?password=********&retry_count=3"
+        );
+        assert_eq!(
+            redact_connect_string("scott/tiger@db:1521/orcl?wallet_token=abc"),
+            "scott/********@db:1521/orcl?wallet_token=********"
+        );
+        assert_eq!(
+            redact_connect_string("(DESCRIPTION=(ADDRESS=(HOST=db)(PORT=1521))(PWD=tiger))"),
+            "(DESCRIPTION=(ADDRESS=(HOST=db)(PORT=1521))(PWD=********))"
+        );
+        assert_eq!(
+            redact_connect_string("localhost:1521/xe"),
+            "localhost:1521/xe"
+        );
+        assert_eq!(redact_connect_string("scott@db/xe"), "scott@db/xe");
+    }
+
+    #[test]
+    fn retry_delay_rounds_up_to_whole_seconds() {
+        use crate::connect_descriptor::ConnectDescriptor;
+        use std::time::Duration;
+
+        let delay = |retry_delay| {
+            ConnectDescriptor::new("db", "xe")
+                .retry_delay(retry_delay)
+                .to_string()
+        };
+        assert_eq!(delay(Duration::from_millis(500)), "db/xe?retry_delay=1");
+        assert_eq!(delay(Duration::from_secs(3)), "db/xe?retry_delay=3");
+        assert_eq!(delay(Duration::from_millis(3001)), "db/xe?retry_delay=4");
+        assert_eq!(delay(Duration::from_secs(0)), "db/xe?retry_delay=0");
+    }
+
+    #[test]
+    fn timeouts_round_up_to_the_smallest_unit() {
+        use crate::connect_descriptor::format_timeout;
+        use std::time::Duration;
+
+        assert_eq!(format_timeout(Duration::from_secs(0)), "0");
+        assert_eq!(format_timeout(Duration::from_micros(1)), "1ms");
+        assert_eq!(format_timeout(Duration::from_millis(1)), "1ms");
+        assert_eq!(format_timeout(Duration::from_millis(999)), "999ms");
+        assert_eq!(format_timeout(Duration::from_secs(1)), "1");
+        assert_eq!(format_timeout(Duration::from_micros(1_000_001)), "1001ms");
+        assert_eq!(format_timeout(Duration::from_millis(2500)), "2500ms");
+    }
+
+    #[test]
+    fn large_scns_bind_without_wrapping() {
+        use crate::scn::Scn;
+
+        match Scn::new(42).to_sql_value() {
+            SqlValue::Integer(i) => assert_eq!(i, 42),
+            other => panic!("Expected an integer, found {:?}", other),
+        }
+        let largest = Scn::new(u64::MAX).to_sql_value();
+        match largest {
+            SqlValue::VarChar(ref s) => assert_eq!(s, "18446744073709551615"),
+            ref other => panic!("Expected text, found {:?}", other),
+        }
+        assert_eq!(largest.value::<Scn>(), Some(Scn::new(u64::MAX)));
+    }
+
+    #[test]
+    fn create_prepared_statement() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Books";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(s) => s,
+            Err(err) => panic!("Failed to prepare drop Books: {}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Books(BookID int, Name varchar(200))";
+        let mut stmt = match conn.create_prepared_statement(sql_create) {
+            Ok(s) => s,
+            Err(err) => panic!("Failed to create a statement: {}", err),
+        };
+        if let Err(err) = stmt.execute() {
+            panic!("Failed to execute: {}", err)
+        }
+        if let Err(err) = stmt.commit() {
+            panic!("Failed to commit: {}", err)
+        }
+    }
+
+    #[test]
+    fn bind() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Fruit";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(s) => s,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Fruit(FruitId integer, Name varchar(20))";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Fruit(FruitId, Name) VALUES(:id, :name)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        let name = String::from("Apple");
+        let id: i64 = 22;
+        if let Err(err) = insert.bind(&[&id, &name]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        let name = String::from("Pear");
+        let id: i64 = 23;
+        if let Err(err) = insert.bind(&[&id, &name]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.bind(&[&24, &"Banana".to_string()]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.commit() {
+            panic!("{}", err)
+        }
+    }
+
+    #[test]
+    fn bind_null_and_option() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Berries";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Berries(BerryId integer, Name varchar(20), Weight float)";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Berries(BerryId, Name, Weight) VALUES(:id, :name, :weight)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        let no_weight: Option<f64> = None;
+        if let Err(err) = insert.bind(&[&1, &Some("Gooseberry"), &no_weight]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.bind(&[&2, &SqlValue::Null(None), &Some(0.5)]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+
+        let sql_select = "SELECT Name, Weight FROM Berries ORDER BY BerryId";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        let name: Option<String> = results[0][0].value();
+        let weight: Option<f64> = results[0][1].value();
+        assert_eq!(name, Some("Gooseberry".to_string()));
+        assert_eq!(weight, None);
+        let name: Option<String> = results[1][0].value();
+        let weight: Option<f64> = results[1][1].value();
+        assert_eq!(name, None);
+        assert_eq!(weight, Some(0.5));
+    }
+
+    #[test]
+    fn execute_batch_inserts_all_rows() {
+        use crate::oci_error::UsageError;
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Herbs";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Herbs(HerbId integer, Name varchar(20))";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Herbs(HerbId, Name) VALUES(:id, :name)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        let unnamed: Option<&str> = None;
+        let rows: [&[&dyn ToSqlValue]; 3] = [&[&1, &"Basil"], &[&2, &unnamed], &[&3, &"Rosemary"]];
+        if let Err(err) = insert.execute_batch(&rows) {
+            panic!("{}", err)
+        }
+        assert!(insert
+            .execute_batch(&[&[&4, &"Sage"], &[&5, &5.0]])
+            .is_err());
+        match insert.execute_batch(&[&[&6, &"Dill"], &[&7]]) {
+            Err(OciError::Usage(UsageError::Bind(_))) => (),
+            other => panic!("Expected a bind usage error, found {:?}", other),
+        }
+
+        let sql_select = "SELECT HerbId, Name FROM Herbs ORDER BY HerbId";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        match select.execute_batch(&[&[]]) {
+            Err(OciError::Usage(UsageError::Unsupported(_))) => (),
+            other => panic!("Expected an unsupported usage error, found {:?}", other),
+        }
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        let names: Vec<Option<String>> = results.iter().map(|row| row[1].value()).collect();
+        assert_eq!(
+            names,
+            vec![
+                Some("Basil".to_string()),
+                None,
+                Some("Rosemary".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn bind_dynamic_values() {
+        use crate::dynamic::DynValue;
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_query = "SELECT :id + 1, :name, :active FROM dual";
+        let mut select = match conn.create_prepared_statement(sql_query) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        let id = DynValue::from_text("41");
+        let name = DynValue::from_text("Fido");
+        let active = DynValue::from_text("true");
+        if let Err(err) = select.bind(&[&id, &name, &active]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        let row = &results[0];
+        assert_eq!(row[0].value::<i64>(), Some(42));
+        assert_eq!(row[1].value::<String>(), Some("Fido".to_string()));
+        assert_eq!(row[2].value::<String>(), Some("1".to_string()));
+    }
+
+    #[test]
+    fn safe_fetch_reads_lobs_in_full() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Manuscripts";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Manuscripts(Title varchar2(10 char), \
+                          Body clob, \
+                          Scan blob)";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let body = "Ça commence. ".repeat(1000);
+        let scan: Vec<u8> = (0..5000).map(|i| (i % 256) as u8).collect();
+        let sql_insert = "INSERT INTO Manuscripts(Title, Body, Scan) VALUES(:1, :2, :3)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.bind(&[&"Café Noël", &body, &&scan[..]]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.commit() {
+            panic!("{}", err)
+        }
+
+        let mut select = match conn.create_prepared_statement("SELECT * FROM Manuscripts") {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        select.set_safe_fetch(true);
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        {
+            let results = match select.result_set() {
+                Ok(results) => results,
+                Err(err) => panic!("{}", err),
+            };
+            assert_eq!(
+                results[0][0].value::<String>(),
+                Some("Café Noël".to_string())
+            );
+            assert_eq!(results[0][1].value::<String>(), Some(body.clone()));
+            match results[0][2] {
+                SqlValue::Blob(ref fetched) => assert_eq!(*fetched, scan),
+                ref other => panic!("Expected a blob, found: {:?}", other),
+            }
+        }
+        assert_eq!(
+            select.locator_columns(),
+            vec!["BODY".to_string(), "SCAN".to_string()]
+        );
+
+        let mut capped = match conn.create_prepared_statement("SELECT * FROM Manuscripts") {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        capped.set_column_cap("body", 100);
+        if let Err(err) = capped.execute() {
+            panic!("{}", err)
+        }
+        assert!(capped.result_set().is_err());
+
+        // the body has 13000 characters but takes 14000 bytes, and the cap counts bytes
+        capped.set_column_cap("body", 13500);
+        if let Err(err) = capped.execute() {
+            panic!("{}", err)
+        }
+        assert!(capped.result_set().is_err());
+        capped.set_column_cap("body", 14000);
+        if let Err(err) = capped.execute() {
+            panic!("{}", err)
+        }
+        match capped.result_set() {
+            Ok(results) => assert_eq!(results[0][1].value::<String>(), Some(body)),
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    #[test]
+    fn invalid_handle_errors_carry_diagnostics() {
+        use crate::diagnostics::CallLog;
+        use crate::oci_bindings::HandleType;
+        use crate::oci_error::get_error;
+        use std::ptr;
+
+        let call_log = CallLog::new();
+        call_log.watch(ptr::null_mut());
+        call_log.record_sql("SELECT 1 FROM dual");
+        call_log.record_call("OCIStmtExecute", HandleType::Statement, ptr::null_mut(), -2);
+        let record = match get_error(ptr::null_mut(), HandleType::Error, "Reading errors") {
+            OciError::Oracle(record) => record,
+            err => panic!("{}", err),
+        };
+        let diagnostics = match record.diagnostics() {
+            Some(diagnostics) => diagnostics,
+            None => panic!("Expected diagnostics for an invalid handle"),
+        };
+        assert_eq!(diagnostics.handle(), ("Error", 0));
+        assert_eq!(diagnostics.last_sql(), None);
+        let last_call = &diagnostics.recent_calls()[diagnostics.recent_calls().len() - 1];
+        assert_eq!(last_call.function(), "OCIStmtExecute");
+        assert_eq!(last_call.return_code(), -2);
+        assert!(record.to_string().contains("Recent OCI calls:"));
+        assert!(!record.to_string().contains("SELECT 1 FROM dual"));
+
+        call_log.set_record_sql(true);
+        call_log.record_sql("SELECT 2 FROM dual");
+        let record = match get_error(ptr::null_mut(), HandleType::Error, "Reading errors") {
+            OciError::Oracle(record) => record,
+            err => panic!("{}", err),
+        };
+        let diagnostics = match record.diagnostics() {
+            Some(diagnostics) => diagnostics,
+            None => panic!("Expected diagnostics for an invalid handle"),
+        };
+        assert_eq!(diagnostics.last_sql(), Some("SELECT 2 FROM dual"));
+        assert!(record.to_string().contains("Last SQL: SELECT 2 FROM dual"));
+    }
+
+    #[test]
+    fn diagnostics_only_see_calls_on_their_own_connection() {
+        use crate::diagnostics::Diagnostics;
+        use crate::oci_bindings::HandleType;
+
+        let first = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let second = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        if let Err(err) = first.ping() {
+            panic!("{}", err)
+        }
+        let snapshot = Diagnostics::capture(second.error_as_mut_void(), HandleType::Error);
+        assert!(snapshot.server_version().is_some());
+        assert!(snapshot
+            .recent_calls()
+            .iter()
+            .all(|call| call.function() != "OCIPing"));
+        assert_eq!(
+            snapshot.recent_calls().last().map(|call| call.function()),
+            Some("OCISessionBegin")
+        );
+    }
+
+    #[test]
+    fn registered_codec_fetches_new_type() {
+        use crate::types::{ColumnType, TypeCodec};
+
+        struct IntervalAsText;
+
+        impl TypeCodec for IntervalAsText {
+            fn fetch_as(&self, _column: &ColumnType) -> (u16, usize) {
+                (1, 32)
+            }
+
+            fn decode(&self, _type_code: u16, data: &[u8]) -> Result<SqlValue, OciError> {
+                let text = String::from_utf8_lossy(data);
+                Ok(SqlValue::VarChar(text.trim_end_matches('\0').to_string()))
+            }
+        }
+
+        let mut conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_query = "SELECT NUMTODSINTERVAL(90, 'MINUTE') FROM dual";
+        {
+            let mut select = match conn.create_prepared_statement(sql_query) {
+                Ok(stmt) => stmt,
+                Err(err) => panic!("{}", err),
+            };
+            if let Err(err) = select.execute() {
+                panic!("{}", err)
+            }
+            assert!(select.result_set().is_err());
+        }
+        // SQLT_INTERVAL_DS
+        conn.register_codec(190, IntervalAsText);
+        let mut select = match conn.create_prepared_statement(sql_query) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        let interval: String = match results[0][0].value() {
+            Some(interval) => interval,
+            None => panic!("Expected the interval as text"),
+        };
+        assert!(interval.starts_with("+00 01:30:00"));
+    }
+
+    #[test]
+    fn column_codec_fetches_matching_names() {
+        use crate::types::{ColumnType, TypeCodec};
+
+        struct AsText;
+
+        impl TypeCodec for AsText {
+            fn fetch_as(&self, _column: &ColumnType) -> (u16, usize) {
+                (1, 32)
+            }
+
+            fn decode(&self, _type_code: u16, data: &[u8]) -> Result<SqlValue, OciError> {
+                let text = String::from_utf8_lossy(data);
+                Ok(SqlValue::VarChar(text.trim_end_matches('\0').to_string()))
+            }
+        }
+
+        let mut conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        conn.register_column_codec("*_interval", AsText);
+        conn.register_column_codec("code*", AsText);
+        let sql_query = "SELECT NUMTODSINTERVAL(90, 'MINUTE') AS Break_Interval, \
+                         7 AS CodeNumber, 7 AS Seven FROM dual";
+        let mut select = match conn.create_prepared_statement(sql_query) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        let interval: String = match results[0][0].value() {
+            Some(interval) => interval,
+            None => panic!("Expected the interval as text"),
+        };
+        assert!(interval.starts_with("+00 01:30:00"));
+        match results[0][1] {
+            SqlValue::VarChar(ref code) => assert_eq!(code, "7"),
+            ref other => panic!("Expected CodeNumber as text, found {:?}", other),
+        }
+        match results[0][2] {
+            SqlValue::Integer(seven) => assert_eq!(seven, 7),
+            ref other => panic!("Expected Seven as an integer, found {:?}", other),
+        }
+    }
+
+    #[test]
+    fn describe_query_without_running_it() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Shells";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Shells(ShellId number(5) not null, \
+                          Name varchar2(20), \
+                          Weight number(6,2), \
+                          Found date)";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_query = "SELECT ShellId, Name, Weight, Found FROM Shells WHERE Name = :name";
+        let mut select = match conn.create_prepared_statement(sql_query) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        let columns = match select.describe() {
+            Ok(columns) => columns,
+            Err(err) => panic!("{}", err),
+        };
+        let names: Vec<&str> = columns.iter().map(|column| column.name()).collect();
+        assert_eq!(names, vec!["SHELLID", "NAME", "WEIGHT", "FOUND"]);
+        assert_eq!(columns[0].data_type(), "NUMBER");
+        assert_eq!(columns[0].precision(), Some(5));
+        assert!(!columns[0].nullable());
+        assert_eq!(columns[1].data_type(), "VARCHAR2");
+        assert_eq!(columns[1].data_length(), 20);
+        assert!(columns[1].nullable());
+        assert_eq!(columns[2].scale(), Some(2));
+        assert_eq!(columns[3].data_type(), "DATE");
+    }
+
+    #[test]
+    fn selects_all_columns_spots_star_queries() {
+        use crate::statement::selects_all_columns;
+
+        assert!(selects_all_columns("SELECT * FROM Dogs"));
+        assert!(selects_all_columns("select d.* from Dogs d"));
+        assert!(selects_all_columns("SELECT DISTINCT * FROM Dogs"));
+        assert!(selects_all_columns("SELECT Name, d.* FROM Dogs d"));
+        assert!(selects_all_columns("SELECT* FROM Dogs"));
+        assert!(selects_all_columns("SELECT\n  *\nFROM Dogs"));
+        assert!(!selects_all_columns("SELECT COUNT(*) FROM Dogs"));
+        assert!(!selects_all_columns("SELECT Name, Age FROM Dogs"));
+        assert!(!selects_all_columns("SELECT 2 * Age FROM Dogs"));
+        assert!(!selects_all_columns("INSERT INTO Dogs(Name) VALUES('Rex')"));
+    }
+
+    #[test]
+    fn rows_share_their_column_names() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_select = "SELECT level AS Step FROM dual CONNECT BY level <= 3";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let rows = match select.lazy_result_set() {
+            Ok(rows) => match rows.collect::<Result<Vec<Row>, OciError>>() {
+                Ok(rows) => rows,
+                Err(err) => panic!("{}", err),
+            },
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].column_names(), &["STEP".to_string()]);
+        let names = rows[0].column_names().as_ptr();
+        assert!(rows.iter().all(|row| row.column_names().as_ptr() == names));
+    }
+
+    #[test]
+    fn lazy_result_set_fetches_in_batches() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Pebbles";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Pebbles(PebbleId integer, Colour varchar(20))";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Pebbles(PebbleId, Colour) VALUES(:id, :colour)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        for id in 0..250i64 {
+            let colour = if id % 3 == 0 {
+                None
+            } else {
+                Some(format!("Grey {}", id))
+            };
+            if let Err(err) = insert.bind(&[&id, &colour]) {
+                panic!("{}", err)
+            }
+            if let Err(err) = insert.execute() {
+                panic!("{}", err)
+            }
+        }
+        if let Err(err) = insert.commit() {
+            panic!("{}", err)
+        }
+        let sql_select = "SELECT PebbleId, Colour FROM Pebbles ORDER BY PebbleId";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        select.set_fetch_array_size(7);
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let rows = match select.lazy_result_set() {
+            Ok(rows) => rows,
+            Err(err) => panic!("{}", err),
+        };
+        let mut expected_id = 0i64;
+        for row in rows {
+            let row = match row {
+                Ok(row) => row,
+                Err(err) => panic!("{}", err),
+            };
+            let id: i64 = match row[0].value() {
+                Some(id) => id,
+                None => panic!("PebbleId should not be null"),
+            };
+            assert_eq!(id, expected_id);
+            let colour: Option<String> = row[1].value();
+            if id % 3 == 0 {
+                assert_eq!(colour, None);
+            } else {
+                assert_eq!(colour, Some(format!("Grey {}", id)));
+            }
+            expected_id += 1;
+        }
+        assert_eq!(expected_id, 250);
+    }
+
+    #[test]
+    fn repeated_queries_reuse_column_definitions() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Acorns";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Acorns(AcornId integer, Tree varchar(20))";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Acorns(AcornId, Tree) VALUES(:id, :tree)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        for &(id, tree) in &[(1i64, "Oak"), (2, "Sessile oak"), (3, "Cork oak")] {
+            if let Err(err) = insert.bind(&[&id, &tree]) {
+                panic!("{}", err)
+            }
+            if let Err(err) = insert.execute() {
+                panic!("{}", err)
+            }
+        }
+        if let Err(err) = insert.commit() {
+            panic!("{}", err)
+        }
+        let sql_select = "SELECT Tree FROM Acorns WHERE AcornId >= :id ORDER BY AcornId";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        for &(from_id, ref expected) in &[
+            (1i64, vec!["Oak", "Sessile oak", "Cork oak"]),
+            (3, vec!["Cork oak"]),
+            (2, vec!["Sessile oak", "Cork oak"]),
+        ] {
+            if let Err(err) = select.bind(&[&from_id]) {
+                panic!("{}", err)
+            }
+            if let Err(err) = select.execute() {
+                panic!("{}", err)
+            }
+            let trees: Vec<String> = match select.result_set() {
+                Ok(rows) => rows
+                    .iter()
+                    .filter_map(|row| row[0].value::<String>())
+                    .collect(),
+                Err(err) => panic!("{}", err),
+            };
+            assert_eq!(&trees, expected);
+        }
+    }
+
+    #[test]
+    fn execute_with_binds_and_runs() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Conkers";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Conkers(ConkerId integer, Owner varchar(20))";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Conkers(ConkerId, Owner) VALUES(:id, :owner)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.execute_with(&[&1, &"Tom"]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute_named_with(&[("owner", &"Ann"), ("id", &2)]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.commit() {
+            panic!("{}", err)
+        }
+        let sql_select = "SELECT Owner FROM Conkers WHERE ConkerId = :id";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute_with(&[&2]) {
+            panic!("{}", err)
+        }
+        let owner: Option<String> = match select.result_set() {
+            Ok(rows) => rows[0][0].value(),
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(owner, Some("Ann".to_string()));
+    }
+
+    #[test]
+    fn cancel_long_running_statement() {
+        use std::thread;
+        use std::time::{Duration, Instant};
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let mut sleep = match conn.create_prepared_statement("BEGIN DBMS_SESSION.SLEEP(30); END;") {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        let cancel = match sleep.cancel_handle() {
+            Ok(cancel) => cancel,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = cancel.cancel() {
+            panic!("{}", err)
+        }
+        let started = Instant::now();
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                thread::sleep(Duration::from_secs(1));
+                if let Err(err) = cancel.cancel() {
+                    panic!("{}", err)
+                }
+            });
+            match sleep.execute() {
+                Err(OciError::Cancelled(_)) => (),
+                Err(err) => panic!("{}", err),
+                Ok(()) => panic!("Statement should have been cancelled"),
+            }
+        });
+        assert!(started.elapsed() < Duration::from_secs(30));
+        if let Err(err) = conn.ping() {
+            panic!("{}", err)
+        }
+    }
+
+    #[test]
+    fn returned_lengths_are_limited_to_the_element() {
+        use crate::statement::returned_bytes;
+
+        let buffer = b"ab\0\0cdef";
+        assert_eq!(returned_bytes(buffer, 0, 4, 2), b"ab");
+        assert_eq!(returned_bytes(buffer, 1, 4, 4), b"cdef");
+        assert_eq!(returned_bytes(buffer, 0, 4, 6), b"ab\0\0");
+        assert_eq!(returned_bytes(buffer, 1, 4, 9), b"cdef");
+        assert_eq!(returned_bytes(buffer, 1, 4, 0), b"");
+    }
+
+    #[test]
+    fn late_cancel_does_not_interrupt_the_next_call() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let mut quick = match conn.create_prepared_statement("BEGIN NULL; END;") {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        let cancel = match quick.cancel_handle() {
+            Ok(cancel) => cancel,
+            Err(err) => panic!("{}", err),
+        };
+        // a cancel that finds the statement still marked as in a call that has already finished
+        quick.set_in_call(true);
+        if let Err(err) = cancel.cancel() {
+            panic!("{}", err)
+        }
+        quick.set_in_call(false);
+        if let Err(err) = quick.execute() {
+            panic!("{}", err)
+        }
+        if let Err(err) = quick.execute() {
+            panic!("{}", err)
+        }
+    }
+
+    #[test]
+    fn execute_with_timeout_cancels_slow_statement() {
+        use std::time::Duration;
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let mut sleep = match conn.create_prepared_statement("BEGIN DBMS_SESSION.SLEEP(30); END;") {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        match sleep.execute_with_timeout(Duration::from_secs(1)) {
+            Err(OciError::TimedOut(timeout)) => assert_eq!(timeout, Duration::from_secs(1)),
+            Err(err) => panic!("{}", err),
+            Ok(()) => panic!("Statement should have timed out"),
+        }
+        let mut quick = match conn.create_prepared_statement("BEGIN NULL; END;") {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = quick.execute_with_timeout(Duration::from_secs(10)) {
+            panic!("{}", err)
+        }
+    }
+
+    #[test]
+    fn batch_errors_report_failed_rows() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Marbles";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Marbles(MarbleId integer primary key, Colour varchar(20))";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Marbles(MarbleId, Colour) VALUES(:id, :colour)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        let failed = match insert.execute_batch_with_errors(&[
+            &[&1, &"Red"],
+            &[&2, &"Blue"],
+            &[&1, &"Green"],
+            &[&3, &"Amber"],
+            &[&2, &"Cloudy"],
+        ]) {
+            Ok(failed) => failed,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.commit() {
+            panic!("{}", err)
+        }
+        let rows: Vec<usize> = failed.iter().map(|batch_error| batch_error.row()).collect();
+        assert_eq!(rows, vec![2, 4]);
+        for batch_error in &failed {
+            let unique_constraint: i32 = 1;
+            assert_eq!(batch_error.error().error_records()[0].0, unique_constraint);
+        }
+        let sql_select = "SELECT Colour FROM Marbles ORDER BY MarbleId";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let colours: Vec<String> = match select.result_set() {
+            Ok(rows) => rows.iter().filter_map(|row| row[0].value()).collect(),
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(colours, vec!["Red", "Blue", "Amber"]);
+    }
+
+    #[test]
+    fn refetch_inserted_row_by_rowid() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Kites";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Kites(KiteId integer, Shape varchar(20))";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Kites(KiteId, Shape) VALUES(:id, :shape)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.execute_with(&[&1, &"Diamond"]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute_with(&[&2, &"Box"]) {
+            panic!("{}", err)
+        }
+        let rowid = match insert.last_rowid() {
+            Ok(rowid) => rowid,
+            Err(err) => panic!("{}", err),
+        };
+        let sql_select = "SELECT Shape FROM Kites WHERE ROWID = CHARTOROWID(:rowid)";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute_with(&[&rowid]) {
+            panic!("{}", err)
+        }
+        let shape: Option<String> = match select.result_set() {
+            Ok(rows) => rows[0][0].value(),
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(shape, Some("Box".to_string()));
+    }
+
+    #[test]
+    fn prepare_new_sql_on_same_statement() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Buckets";
+        let mut stmt = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        stmt.execute().ok();
+        if let Err(err) = stmt.set_prefetch(10) {
+            panic!("{}", err)
+        }
+        let sql_create = "CREATE TABLE Buckets(BucketId integer, Colour varchar(20))";
+        if let Err(err) = stmt.prepare(sql_create) {
+            panic!("{}", err)
+        }
+        if let Err(err) = stmt.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Buckets(BucketId, Colour) VALUES(:id, :colour)";
+        if let Err(err) = stmt.prepare(sql_insert) {
+            panic!("{}", err)
+        }
+        if let Err(err) = stmt.execute_with(&[&1, &"Red"]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = stmt.execute_with(&[&2, &"Yellow"]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = stmt.execute_with(&[&3, &"Green"]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = stmt.prepare("SELECT Colour FROM Buckets ORDER BY BucketId") {
+            panic!("{}", err)
+        }
+        if let Err(err) = stmt.execute() {
+            panic!("{}", err)
+        }
+        let colours: Vec<String> = match stmt.result_set() {
+            Ok(rows) => rows.iter().filter_map(|row| row[0].value()).collect(),
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(colours, vec!["Red", "Yellow", "Green"]);
+    }
+
+    #[test]
+    fn execute_and_commit_survives_rollback() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Spades";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Spades(SpadeId integer, Handle varchar(20))";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Spades(SpadeId, Handle) VALUES(:id, :handle)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.bind(&[&1, &"Ash"]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute_and_commit() {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute_with(&[&2, &"Oak"]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = conn.rollback() {
+            panic!("{}", err)
+        }
+        let sql_select = "SELECT Handle FROM Spades";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let handles: Vec<String> = match select.result_set() {
+            Ok(rows) => rows.iter().filter_map(|row| row[0].value()).collect(),
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(handles, vec!["Ash"]);
+    }
+
+    #[test]
+    fn parse_errors_show_where_sql_went_wrong() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql = "SELECT 1,\n       NoSuchColumn\nFROM dual";
+        let mut select = match conn.create_prepared_statement(sql) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        let record = match select.execute() {
+            Err(OciError::Oracle(record)) => record,
+            Err(err) => panic!("{}", err),
+            Ok(()) => panic!("Statement should not have parsed"),
+        };
+        assert_eq!(record.parse_error_offset(), Some(17));
+        let text = format!("{}", record);
+        assert!(text.contains("line 2, column 8:\n       NoSuchColumn\n       ^"));
+    }
+
+    #[test]
+    fn column_count_of_empty_result_set() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql = "SELECT 1, 'two', SYSDATE FROM dual WHERE 1 = 0";
+        let mut select = match conn.create_prepared_statement(sql) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        match select.column_count() {
+            Ok(count) => assert_eq!(count, 3),
+            Err(err) => panic!("{}", err),
+        }
+        match select.result_set() {
+            Ok(rows) => assert!(rows.is_empty()),
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    #[test]
+    fn query_map_converts_rows_lazily() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Gas_Giants";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Gas_Giants(Name varchar(20), Moons integer)";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Gas_Giants(Name, Moons) VALUES(:name, :moons)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.execute_batch(&[&[&"Jupiter", &95], &[&"Saturn", &146]]) {
+            panic!("{}", err)
+        }
+        let sql_query = "SELECT Name, Moons FROM Gas_Giants ORDER BY Name";
+        let mut select = match conn.create_prepared_statement(sql_query) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let names: Vec<String> = match select.query_map(|row| row.try_get("Name")) {
+            Ok(names) => names.map(|name| name.expect("Not a string")).collect(),
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(names, vec!["Jupiter", "Saturn"]);
+
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let planets: Vec<Planet> = match select.query_map_into::<Planet>() {
+            Ok(planets) => planets
+                .map(|planet| planet.expect("Not a planet"))
+                .collect(),
+            Err(err) => panic!("{}", err),
+        };
+        let jupiter = Planet {
+            name: "Jupiter".to_string(),
+            moons: 95,
+        };
+        assert_eq!(planets[0], jupiter);
+        assert_eq!(planets.len(), 2);
+    }
+
+    #[test]
+    fn execute_many_loads_rows_in_chunks() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Sandbags";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Sandbags(SandbagId integer, Label varchar(20))";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Sandbags(SandbagId, Label) VALUES(:id, :label)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        let sandbags = (0..2500i64).map(|id| (id, format!("Bag {}", id)));
+        let inserted = match insert.execute_many(sandbags) {
+            Ok(inserted) => inserted,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(inserted, 2500);
+        if let Err(err) = insert.commit() {
+            panic!("{}", err)
+        }
+        let sql_count = "SELECT COUNT(*), MAX(Label) FROM Sandbags";
+        let mut count = match conn.create_prepared_statement(sql_count) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = count.execute() {
+            panic!("{}", err)
+        }
+        let results = match count.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        let total: i64 = results[0][0].value().expect("Not an i64");
+        let label: String = results[0][1].value().expect("Not a string");
+        assert_eq!(total, 2500);
+        assert_eq!(label, "Bag 999");
+    }
+
+    #[test]
+    fn statement_type_reports_kind_of_sql() {
+        use crate::statement::StatementType;
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let cases = [
+            ("SELECT 1 FROM dual", StatementType::Select),
+            ("INSERT INTO Lanterns VALUES(1)", StatementType::Insert),
+            ("UPDATE Lanterns SET LanternId = 2", StatementType::Update),
+            ("DELETE FROM Lanterns", StatementType::Delete),
+            (
+                "CREATE TABLE Lanterns(LanternId integer)",
+                StatementType::Create,
+            ),
+            ("DROP TABLE Lanterns", StatementType::Drop),
+            ("BEGIN NULL; END;", StatementType::Begin),
+            (
+                "MERGE INTO Lanterns l USING dual ON (1 = 0) \
+                 WHEN NOT MATCHED THEN INSERT VALUES(1)",
+                StatementType::Merge,
+            ),
+        ];
+        for (sql, expected) in cases.iter() {
+            let stmt = match conn.create_prepared_statement(sql) {
+                Ok(stmt) => stmt,
+                Err(err) => panic!("{}", err),
+            };
+            match stmt.statement_type() {
+                Ok(statement_type) => assert_eq!(statement_type, *expected),
+                Err(err) => panic!("{}", err),
+            }
+        }
+    }
+
+    #[test]
+    fn clear_binds_and_count_placeholders() {
+        use crate::oci_error::UsageError;
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Lanterns";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Lanterns(LanternId integer, Colour varchar(20))";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Lanterns(LanternId, Colour) VALUES(:id, :colour)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        match insert.placeholder_count() {
+            Ok(count) => assert_eq!(count, 2),
+            Err(err) => panic!("{}", err),
+        }
+        match insert.bind(&[&1]) {
+            Err(OciError::Usage(UsageError::BindCountMismatch(mismatch))) => {
+                assert_eq!(mismatch.placeholders(), 2);
+                assert_eq!(mismatch.bound(), 1);
+            }
+            Err(err) => panic!("{}", err),
+            Ok(()) => panic!("Binding too few values should fail"),
+        }
+        if let Err(err) = insert.bind(&[&1, &"Red"]) {
+            panic!("{}", err)
+        }
+        assert_eq!(insert.bound_count(), 2);
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        insert.clear_binds();
+        assert_eq!(insert.bound_count(), 0);
+        match insert.execute() {
+            Err(OciError::Usage(UsageError::BindCountMismatch(mismatch))) => {
+                assert_eq!(mismatch.bound(), 0)
+            }
+            Err(err) => panic!("{}", err),
+            Ok(()) => panic!("Executing without binds should fail"),
+        }
+        if let Err(err) = insert.bind(&[&2, &"Green"]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+    }
+
+    #[test]
+    fn bind_placeholders_are_listed_by_position() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql = "SELECT :colour, :size, :colour FROM dual";
+        let select = match conn.create_prepared_statement(sql) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        let placeholders = match select.bind_placeholders() {
+            Ok(placeholders) => placeholders,
+            Err(err) => panic!("{}", err),
+        };
+        let names: Vec<&str> = placeholders.iter().map(|p| p.name()).collect();
+        assert_eq!(names, vec!["COLOUR", "SIZE", "COLOUR"]);
+        assert_eq!(placeholders[1].position(), 2);
+        assert!(!placeholders[0].is_duplicate());
+        assert!(placeholders[2].is_duplicate());
+
+        let no_binds = match conn.create_prepared_statement("SELECT 1 FROM dual") {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        match no_binds.bind_placeholders() {
+            Ok(placeholders) => assert!(placeholders.is_empty()),
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    #[test]
+    fn bind_typed_null_in_merge() {
+        use crate::types::SqlType;
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Hedgehogs";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Hedgehogs(HedgehogId integer, Spines integer)";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_merge = "MERGE INTO Hedgehogs h
+                         USING (SELECT :id AS HedgehogId, :spines AS Spines FROM dual) s
+                         ON (h.HedgehogId = s.HedgehogId)
+                         WHEN MATCHED THEN UPDATE SET h.Spines = s.Spines
+                         WHEN NOT MATCHED THEN INSERT (HedgehogId, Spines)
+                         VALUES (s.HedgehogId, s.Spines)";
+        let mut merge = match conn.create_prepared_statement(sql_merge) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = merge.bind(&[&1, &SqlValue::Null(None)]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = merge.bind_null(2, SqlType::Integer) {
+            panic!("{}", err)
+        }
+        if let Err(err) = merge.execute() {
+            panic!("{}", err)
+        }
+        let sql_select = "SELECT COUNT(*) FROM Hedgehogs WHERE Spines IS NULL";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        let count: i64 = results[0][0].value().expect("Not an i64");
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn call_procedure_with_out_params() {
+        use crate::plsql::Param;
+        use crate::types::SqlType;
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_create = "CREATE OR REPLACE PROCEDURE Greet_Badger(
+                              name IN VARCHAR2,
+                              greeting OUT VARCHAR2,
+                              visits IN OUT NUMBER) AS
+                          BEGIN
+                              greeting := 'Hello ' || name;
+                              visits := visits + 1;
+                          END;";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let mut params = [
+            Param::In(&"Brock"),
+            Param::Out(SqlType::VarChar),
+            Param::InOut(SqlValue::Integer(41)),
+        ];
+        let outputs = match conn.call("Greet_Badger", &mut params) {
+            Ok(outputs) => outputs,
+            Err(err) => panic!("{}", err),
+        };
+        let greeting: String = outputs[0].value().expect("Not a string");
+        assert_eq!(greeting, "Hello Brock");
+        let visits: i64 = outputs[1].value().expect("Not an i64");
+        assert_eq!(visits, 42);
+        match params[2] {
+            Param::InOut(ref value) => assert_eq!(value.value::<i64>(), Some(42)),
+            _ => panic!("Parameter changed kind"),
+        }
+        assert!(conn.call("Greet_Badger; DROP TABLE Dogs", &mut []).is_err());
+    }
+
+    #[test]
+    fn block_builder_returns_named_outputs() {
+        use crate::plsql::{BlockBuilder, Param};
+        use crate::types::SqlType;
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_create = "CREATE OR REPLACE FUNCTION Otter_Name(id IN NUMBER) RETURN VARCHAR2 AS
+                          BEGIN
+                              RETURN 'Otter ' || id;
+                          END;";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let mut block = BlockBuilder::new()
+            .declare("doubled NUMBER")
+            .statement("doubled := :fish * 2")
+            .statement(":total := doubled + :total")
+            .call_function("name", "Otter_Name", SqlType::VarChar, &[":id"])
+            .bind("fish", Param::In(&5))
+            .bind(":total", Param::InOut(SqlValue::Integer(1)))
+            .bind("id", Param::In(&7));
+        let outputs = match block.execute(&conn) {
+            Ok(outputs) => outputs,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(outputs.value::<i64>("total"), Some(11));
+        assert_eq!(outputs.value::<String>("name"), Some("Otter 7".to_string()));
+        assert!(outputs.get("fish").is_none());
+
+        let outputs = match block.execute(&conn) {
+            Ok(outputs) => outputs,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(outputs.value::<i64>("total"), Some(21));
+
+        let mut unbound = BlockBuilder::new().statement(":missing := 1");
+        assert!(unbound.execute(&conn).is_err());
+    }
+
+    #[test]
+    fn rows_fetched_by_last_batch() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Buttons";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Buttons(ButtonId integer)";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Buttons(ButtonId) VALUES(:id)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.execute_many((0..10i64).map(|id| (id,))) {
+            panic!("{}", err)
+        }
+        let sql_select = "SELECT ButtonId FROM Buttons";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        select.set_fetch_array_size(7);
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let rows = match select.lazy_result_set() {
+            Ok(rows) => rows,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(rows.count(), 10);
+        match select.rows_fetched() {
+            Ok(rows_fetched) => assert_eq!(rows_fetched, 3),
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    #[test]
+    fn errors_carry_the_sql_of_the_statement() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql = "SELECT 1 / 0 FROM dual";
+        let mut select = match conn.create_prepared_statement(sql) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(select.sql(), sql);
+        let record = match select
+            .execute()
+            .and_then(|_| select.result_set().map(|_| ()))
+        {
+            Err(OciError::Oracle(record)) => record,
+            Err(err) => panic!("{}", err),
+            Ok(()) => panic!("Dividing by zero should fail"),
+        };
+        assert_eq!(record.sql(), Some(sql));
+        assert!(format!("{}", record).contains("SQL: SELECT 1 / 0 FROM dual"));
+    }
+
+    #[test]
+    fn statement_options_scrollable_cursor() {
+        use crate::statement::StatementOptions;
+        use std::time::Duration;
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Dominoes";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Dominoes(DominoId integer)";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Dominoes(DominoId) VALUES(:id)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.execute_many((1..=20i64).map(|id| (id,))) {
+            panic!("{}", err)
+        }
+        let options = StatementOptions::new()
+            .prefetch_rows(5)
+            .fetch_array_size(4)
+            .scrollable(true)
+            .call_timeout(Duration::from_secs(30));
+        let sql_select = "SELECT DominoId FROM Dominoes ORDER BY DominoId";
+        let mut select = match conn.create_prepared_statement_with(sql_select, &options) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let row = match select.fetch_row_at(15) {
+            Ok(Some(row)) => row,
+            Ok(None) => panic!("There should be a 15th row"),
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(row[0].value::<i64>(), Some(15));
+        let row = match select.fetch_row_at(2) {
+            Ok(Some(row)) => row,
+            Ok(None) => panic!("There should be a 2nd row"),
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(row[0].value::<i64>(), Some(2));
+        match select.fetch_row_at(21) {
+            Ok(row) => assert!(row.is_none()),
+            Err(err) => panic!("{}", err),
+        }
+
+        let mut forward_only = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = forward_only.execute() {
+            panic!("{}", err)
+        }
+        assert!(forward_only.fetch_row_at(1).is_err());
+    }
+
+    #[test]
+    fn check_syntax_without_executing() {
+        use crate::statement::StatementType;
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let check = match conn.check_syntax("SELECT 1 AS One, :name AS Name FROM dual") {
+            Ok(check) => check,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(check.statement_type(), StatementType::Select);
+        assert_eq!(check.columns().len(), 2);
+        assert_eq!(check.columns()[0].name(), "ONE");
+        assert_eq!(check.placeholders()[0].name(), "NAME");
+
+        match conn.check_syntax("SELECT 1 FROM dual WHERE") {
+            Err(OciError::Oracle(record)) => assert!(record.parse_error_offset().is_some()),
+            Err(err) => panic!("{}", err),
+            Ok(_) => panic!("The SQL should not have parsed"),
+        }
+        assert!(conn.check_syntax("DROP TABLE Nothing_To_Drop").is_err());
+    }
+
+    #[test]
+    fn execute_ignoring_missing_table() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Puddles";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = drop.execute_ignoring(&[942]) {
+            panic!("{}", err)
+        }
+        match drop.execute_ignoring(&[942]) {
+            Ok(ignored) => assert!(ignored),
+            Err(err) => panic!("{}", err),
+        }
+        let sql_create = "CREATE TABLE Puddles(PuddleId integer)";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        match create.execute_ignoring(&[955]) {
+            Ok(ignored) => assert!(!ignored),
+            Err(err) => panic!("{}", err),
+        }
+        assert!(create.execute_ignoring(&[942]).is_err());
+    }
+
+    #[test]
+    fn bind_duplicated_placeholder_once() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql = "SELECT :word || '-' || :word || '-' || :other FROM dual";
+        let mut select = match conn.create_prepared_statement(sql) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        assert!(select.bind(&[&"echo", &"!"]).is_err());
+        select.set_bind_duplicates_once(true);
+        if let Err(err) = select.bind(&[&"echo", &"!"]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(
+            results[0][0].value::<String>(),
+            Some("echo-echo-!".to_string())
+        );
+    }
+
+    #[test]
+    fn owned_statement_outlives_borrow() {
+        use crate::statement::OwnedStatement;
+
+        fn prepare(conn: Connection) -> OwnedStatement {
+            match OwnedStatement::new(conn, "SELECT :1 * 2 FROM dual") {
+                Ok(stmt) => stmt,
+                Err(err) => panic!("{}", err),
+            }
+        }
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let mut select = prepare(conn);
+        let doubled = select.with(|stmt| {
+            stmt.bind(&[&21])?;
+            stmt.execute()?;
+            let results = stmt.result_set()?;
+            Ok::<Option<i64>, OciError>(results[0][0].value())
+        });
+        match doubled {
+            Ok(value) => assert_eq!(value, Some(42)),
+            Err(err) => panic!("{}", err),
+        }
+        let conn = select.into_connection();
+        if let Err(err) = conn.ping() {
+            panic!("{}", err)
+        }
+    }
+
+    #[test]
+    fn owned_statement_moves_to_another_thread() {
+        use crate::statement::OwnedStatement;
+        use std::thread;
+
+        fn assert_send<T: Send>() {}
+        assert_send::<OwnedStatement>();
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let mut select = match OwnedStatement::new(conn, "SELECT :1 * 2 FROM dual") {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        let worker = thread::spawn(move || {
+            let doubled = select.with(|stmt| {
+                stmt.bind(&[&4])?;
+                stmt.execute()?;
+                let results = stmt.result_set()?;
+                Ok::<Option<i64>, OciError>(results[0][0].value())
+            });
+            (doubled, select)
+        });
+        let (doubled, select) = match worker.join() {
+            Ok(result) => result,
+            Err(_) => panic!("The worker thread panicked"),
+        };
+        match doubled {
+            Ok(value) => assert_eq!(value, Some(8)),
+            Err(err) => panic!("{}", err),
+        }
+        if let Err(err) = select.connection().ping() {
+            panic!("{}", err)
+        }
+        let worker = thread::spawn(move || select.into_connection());
+        let conn = match worker.join() {
+            Ok(conn) => conn,
+            Err(_) => panic!("The worker thread panicked"),
+        };
+        if let Err(err) = conn.ping() {
+            panic!("{}", err)
+        }
+    }
+
+    #[test]
+    fn statement_cache_key() {
+        use crate::statement::StatementOptions;
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql = "SELECT 'kite' FROM dual";
+        let options = StatementOptions::new().cache_key("kites.select");
+        for _ in 0..2 {
+            let mut select = match conn.create_prepared_statement_with(sql, &options) {
+                Ok(stmt) => stmt,
+                Err(err) => panic!("{}", err),
+            };
+            assert_eq!(select.cache_key(), "kites.select");
+            if let Err(err) = select.execute() {
+                panic!("{}", err)
+            }
+            let results = match select.result_set() {
+                Ok(results) => results,
+                Err(err) => panic!("{}", err),
+            };
+            assert_eq!(results[0][0].value::<String>(), Some("kite".to_string()));
+        }
+        let options = StatementOptions::new().cache(false);
+        let mut select = match conn.create_prepared_statement_with(sql, &options) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(select.cache_key(), sql);
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+    }
+
+    #[test]
+    fn paginator_pages_through_query() {
+        use crate::paginate::{PageSyntax, Paginator};
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql = "SELECT LEVEL AS Step FROM dual WHERE LEVEL > :low CONNECT BY LEVEL <= 8 \
+                   ORDER BY Step";
+        for syntax in &[PageSyntax::OffsetFetch, PageSyntax::RowNum] {
+            let mut steps = match Paginator::with_syntax(&conn, sql, 3, *syntax) {
+                Ok(paginator) => paginator,
+                Err(err) => panic!("{}", err),
+            };
+            steps.bind(&[&1]);
+            let second = match steps.page(1) {
+                Ok(rows) => rows,
+                Err(err) => panic!("{}", err),
+            };
+            assert_eq!(second.len(), 3);
+            assert_eq!(second[0].columns().len(), 1);
+            assert_eq!(second[0][0].value::<i64>(), Some(5));
+            steps.bind(&[&1]);
+            let pages: Vec<Vec<i64>> = steps
+                .map(|page| match page {
+                    Ok(rows) => rows.iter().filter_map(|row| row[0].value()).collect(),
+                    Err(err) => panic!("{}", err),
+                })
+                .collect();
+            assert_eq!(pages, vec![vec![2, 3, 4], vec![5, 6, 7], vec![8]]);
+        }
+        assert!(Paginator::new(&conn, sql, 0).is_err());
+    }
+
+    #[test]
+    fn statement_queue_flushes_together() {
+        use crate::pipeline::StatementQueue;
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Pinecones";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Pinecones(PineconeId integer PRIMARY KEY, Name varchar(20))";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let insert = "INSERT INTO Pinecones(PineconeId, Name) VALUES(:id, :name)";
+        let mut queue = StatementQueue::new(&conn);
+        queue.push(insert, &[&1, &"Oak's"]);
+        queue.push(insert, &[&2, &"Holm"]);
+        queue.push(
+            "UPDATE Pinecones SET Name = Name || '!' WHERE PineconeId = :id",
+            &[&2],
+        );
+        assert_eq!(queue.len(), 3);
+        match queue.flush() {
+            Ok(flushed) => assert_eq!(flushed, 3),
+            Err(err) => panic!("{}", err),
+        }
+        assert!(queue.is_empty());
+        queue.push(insert, &[&3, &"Cork"]);
+        queue.push(insert, &[&1, &"Duplicate"]);
+        assert!(queue.flush().is_err());
+        assert_eq!(queue.len(), 2);
+        queue.clear();
+        let sql_select = "SELECT Name FROM Pinecones ORDER BY PineconeId";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        let names: Vec<String> = results.iter().filter_map(|row| row[0].value()).collect();
+        assert_eq!(names, vec!["Oak's".to_string(), "Holm!".to_string()]);
+    }
+
+    #[test]
+    fn prefetch_policies() {
+        use crate::statement::Prefetch;
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql = "SELECT LEVEL, RPAD('x', 100, 'x') FROM dual CONNECT BY LEVEL <= 250";
+        let mut select = match conn.create_prepared_statement(sql) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        for policy in &[
+            Prefetch::Rows(20),
+            Prefetch::Memory(64 * 1024),
+            Prefetch::Auto,
+        ] {
+            if let Err(err) = select.set_prefetch(*policy) {
+                panic!("{}", err)
+            }
+            if let Err(err) = select.execute() {
+                panic!("{}", err)
+            }
+            let rows = match select.lazy_result_set() {
+                Ok(rows) => rows.count(),
+                Err(err) => panic!("{}", err),
+            };
+            assert_eq!(rows, 250);
+        }
+        assert_eq!(Prefetch::from(-1), Prefetch::Rows(0));
+    }
+
+    #[test]
+    fn fetch_char_semantics_column() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Coins";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Coins(CoinId integer, Symbol varchar2(5 CHAR))";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Coins(CoinId, Symbol) VALUES(:id, :symbol)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.bind(&[&1, &"€€€€€"]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        let sql_select = "SELECT Symbol FROM Coins";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(results[0][0].value::<String>(), Some("€€€€€".to_string()));
+    }
+
+    #[test]
+    fn truncated_fetch_is_an_error() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql = "SELECT RPAD('x', 50, 'x') AS Padded FROM dual";
+        let mut select = match conn.create_prepared_statement(sql) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        select.set_column_cap("padded", 10);
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        match select.result_set() {
+            Err(OciError::FetchTruncated(truncated)) => {
+                assert_eq!(truncated.column(), "PADDED");
+                assert_eq!(truncated.buffer_size(), 10);
+                assert_eq!(truncated.length(), Some(50));
+            }
+            Err(err) => panic!("{}", err),
+            Ok(_) => panic!("Fetching a truncated value should fail"),
+        }
+    }
+
+    #[test]
+    fn warnings_from_compilation_errors() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql = "CREATE OR REPLACE PROCEDURE Broken_Kettle AS BEGIN no_such_thing; END;";
+        let mut create = match conn.create_prepared_statement(sql) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let warnings = create.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code(), 24344);
+        let mut drop = match conn.create_prepared_statement("DROP PROCEDURE Broken_Kettle") {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = drop.execute() {
+            panic!("{}", err)
+        }
+        assert!(drop.warnings().is_empty());
+    }
+
+    #[test]
+    fn attributes_round_trip_and_unknown_ones_are_rejected() {
+        use crate::attribute::{AttributeHandle, AttributeKind, AttributeValue};
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        const OCI_ATTR_STMTCACHESIZE: u32 = 176;
+        const NOT_AN_ATTRIBUTE: u32 = 65_000;
+        // SAFETY: OCI_ATTR_STMTCACHESIZE is a ub4 attribute of the service context.
+        let cache_size = unsafe {
+            if let Err(err) = conn.set_attribute(
+                AttributeHandle::Service,
+                OCI_ATTR_STMTCACHESIZE,
+                &AttributeValue::UInt(37),
+            ) {
+                panic!("{}", err)
+            }
+            conn.get_attribute(
+                AttributeHandle::Service,
+                OCI_ATTR_STMTCACHESIZE,
+                AttributeKind::UInt,
+            )
+        };
+        match cache_size {
+            Ok(cache_size) => assert_eq!(cache_size, AttributeValue::UInt(37)),
+            Err(err) => panic!("{}", err),
+        }
+
+        // SAFETY: OCI checks the attribute number before it reads or writes the value.
+        let (set, get) = unsafe {
+            (
+                conn.set_attribute(
+                    AttributeHandle::Service,
+                    NOT_AN_ATTRIBUTE,
+                    &AttributeValue::UInt(1),
+                ),
+                conn.get_attribute(
+                    AttributeHandle::Service,
+                    NOT_AN_ATTRIBUTE,
+                    AttributeKind::UInt,
+                ),
+            )
+        };
+        match set {
+            Err(OciError::Oracle(_)) => (),
+            other => panic!("Expected an Oracle error, found {:?}", other),
+        }
+        match get {
+            Err(OciError::Oracle(_)) => (),
+            other => panic!("Expected an Oracle error, found {:?}", other),
+        }
+    }
+
+    #[test]
+    fn nonblocking_execute_and_fetch() {
+        use std::task::Poll;
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        if let Err(err) = conn.set_nonblocking(true) {
+            panic!("{}", err)
+        }
+        assert_eq!(conn.is_nonblocking().ok(), Some(true));
+        let sql = "SELECT LEVEL FROM dual CONNECT BY LEVEL <= 300";
+        let mut select = match conn.create_prepared_statement(sql) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        loop {
+            match select.poll_execute() {
+                Ok(Poll::Ready(())) => break,
+                Ok(Poll::Pending) => (),
+                Err(err) => panic!("{}", err),
+            }
+        }
+        let mut rows = match select.lazy_result_set() {
+            Ok(rows) => rows,
+            Err(err) => panic!("{}", err),
+        };
+        let mut total = 0;
+        loop {
+            match rows.poll_next() {
+                Poll::Ready(Some(Ok(row))) => total += row[0].value::<i64>().unwrap_or(0),
+                Poll::Ready(Some(Err(err))) => panic!("{}", err),
+                Poll::Ready(None) => break,
+                Poll::Pending => (),
+            }
+        }
+        assert_eq!(total, 300 * 301 / 2);
+        if let Err(err) = conn.set_nonblocking(false) {
+            panic!("{}", err)
+        }
+        assert_eq!(conn.is_nonblocking().ok(), Some(false));
+    }
+
+    #[test]
+    fn nonblocking_mode_waits_for_other_calls() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        if let Err(err) = conn.set_nonblocking(true) {
+            panic!("{}", err)
+        }
+        if let Err(err) = conn.ping() {
+            panic!("{}", err)
+        }
+        let sql = "SELECT LEVEL AS Lvl FROM dual CONNECT BY LEVEL <= 3";
+        match conn.check_syntax(sql) {
+            Ok(check) => assert_eq!(check.columns().len(), 1),
+            Err(err) => panic!("{}", err),
+        }
+        let mut select = match conn.create_prepared_statement(sql) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        match select.describe() {
+            Ok(columns) => assert_eq!(columns[0].name(), "LVL"),
+            Err(err) => panic!("{}", err),
+        }
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        match select.result_set() {
+            Ok(rows) => assert_eq!(rows.len(), 3),
+            Err(err) => panic!("{}", err),
+        }
+        if let Err(err) = conn.commit() {
+            panic!("{}", err)
+        }
+    }
+
+    #[test]
+    fn blob_round_trip() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Photos";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Photos(PhotoId integer, Image blob)";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let image: Vec<u8> = (0..100_000u32).map(|i| (i % 251) as u8).collect();
+        let sql_insert = "INSERT INTO Photos(PhotoId, Image) VALUES(:id, :image)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.bind(&[&1, &image]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        let sql_select = "SELECT Image FROM Photos WHERE PhotoId = 1";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(results[0][0].value::<Vec<u8>>(), Some(image));
+    }
+
+    #[test]
+    fn nclob_round_trip() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Phrasebooks";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Phrasebooks(BookId integer, Text nclob)";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let text = "Bore da, Guten Morgen, Доброе утро, おはようございます, 早上好".repeat(10);
+        let sql_insert = "INSERT INTO Phrasebooks(BookId, Text) VALUES(:id, TO_NCLOB(:text))";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.bind(&[&1, &text]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        let sql_select = "SELECT Text FROM Phrasebooks WHERE BookId = 1";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        let columns = match select.describe() {
+            Ok(columns) => columns,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(columns[0].data_type(), "NCLOB");
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(results[0][0].value::<String>(), Some(text));
+    }
+
+    #[test]
+    fn bfile_names_are_fetched() {
+        use crate::bfile::BFile;
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Documents";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Documents(DocumentId integer, Scan bfile)";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Documents(DocumentId, Scan) \
+                          VALUES(1, BFILENAME('OCI_RS_MISSING', 'scan.pdf'))";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        let sql_select = "SELECT Scan FROM Documents WHERE DocumentId = 1";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        let scan: BFile = match results[0][0].value() {
+            Some(scan) => scan,
+            None => panic!("The BFILE was not fetched"),
+        };
+        assert_eq!(scan, BFile::new("OCI_RS_MISSING", "scan.pdf"));
+        // the directory doesn't exist, so the file can't be opened
+        assert!(scan.open(&conn).is_err());
+    }
+
+    #[test]
+    fn raw_and_long_raw_round_trip() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Hashes";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Hashes(HashId integer, Digest raw(16), Content long raw)";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let digest: Vec<u8> = (0..16).collect();
+        let content: Vec<u8> = (0..10_000u32).map(|i| (i % 256) as u8).collect();
+        let sql_insert =
+            "INSERT INTO Hashes(HashId, Digest, Content) VALUES(:id, :digest, :content)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.bind(&[&1, &digest, &content]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        let sql_select = "SELECT Digest, Content FROM Hashes WHERE HashId = 1";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(results[0][0].value::<Vec<u8>>(), Some(digest));
+        assert_eq!(results[0][1].value::<Vec<u8>>(), Some(content));
+    }
+
+    #[test]
+    fn bind_bytes_as_raw_and_long_raw() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Thumbnails";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Thumbnails(Checksum raw(32), Image blob)";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let checksum: Vec<u8> = (0..32).collect();
+        let image: Vec<u8> = (0..20_000u32).map(|i| (i % 7) as u8).collect();
+        let sql_insert = "INSERT INTO Thumbnails(Checksum, Image) VALUES(:checksum, :image)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.bind(&[&checksum.as_slice(), &image.as_slice()]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        // a LONG RAW can't be compared, so the checksum has to have been bound as a RAW
+        let sql_select = "SELECT Image FROM Thumbnails WHERE Checksum = :checksum";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.bind(&[&checksum]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0][0].value::<Vec<u8>>(), Some(image));
+    }
+
+    #[test]
+    #[cfg(feature = "decimal")]
+    fn decimal_round_trip() {
+        use rust_decimal::Decimal;
+        use std::str::FromStr;
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Invoices";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Invoices(InvoiceId integer, Amount number(38, 10))";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        // more significant digits than an f64 holds
+        let amount = match Decimal::from_str("-12345678901234567.0123456789") {
+            Ok(amount) => amount,
+            Err(err) => panic!("{}", err),
+        };
+        let sql_insert = "INSERT INTO Invoices(InvoiceId, Amount) VALUES(:id, :amount)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.bind(&[&1, &amount]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        let sql_select = "SELECT Amount FROM Invoices WHERE InvoiceId = 1";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(results[0][0].value::<Decimal>(), Some(amount));
+    }
+
+    #[test]
+    #[cfg(feature = "bigdecimal")]
+    fn bigdecimal_round_trip() {
+        use bigdecimal::BigDecimal;
+        use std::str::FromStr;
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Measurements";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Measurements(Counter number(38), Reading number(38, 30))";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        // both are beyond what an i64 or a rust_decimal::Decimal can hold
+        let counter = match BigDecimal::from_str("12345678901234567890123456789012345678") {
+            Ok(counter) => counter,
+            Err(err) => panic!("{}", err),
+        };
+        let reading = match BigDecimal::from_str("-1234567.000000000000000000000000000001") {
+            Ok(reading) => reading,
+            Err(err) => panic!("{}", err),
+        };
+        let sql_insert = "INSERT INTO Measurements(Counter, Reading) VALUES(:counter, :reading)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.bind(&[&counter, &reading]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        let sql_select = "SELECT Counter, Reading FROM Measurements";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(results[0][0].value::<BigDecimal>(), Some(counter));
+        assert_eq!(results[0][1].value::<BigDecimal>(), Some(reading));
+        assert_eq!(results[0][0].value::<i64>(), None);
+    }
+
+    #[test]
+    fn number_type_decided_by_value() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Readings";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Readings(ReadingId integer, Value number)";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT ALL \
+                          INTO Readings VALUES(1, 42) \
+                          INTO Readings VALUES(2, 2.5) \
+                          INTO Readings VALUES(3, 123456789012345678901234567890) \
+                          SELECT * FROM dual";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        let sql_select = "SELECT Value FROM Readings ORDER BY ReadingId";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(results[0][0].value::<i64>(), Some(42));
+        assert_eq!(results[0][0].value::<f64>(), Some(42.0));
+        assert_eq!(results[1][0].value::<f64>(), Some(2.5));
+        assert_eq!(results[1][0].value::<i64>(), None);
+        assert_eq!(
+            results[2][0].value::<String>(),
+            Some("123456789012345678901234567890".to_string())
+        );
+    }
+
+    #[test]
+    fn small_numeric_types() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Gauges";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Gauges(Tiny number, Small number, Medium number, \
+                          Unsigned number, Reading number)";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Gauges(Tiny, Small, Medium, Unsigned, Reading) \
+                          VALUES(:tiny, :small, :medium, :unsigned, :reading)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.bind(&[&-8i8, &300i16, &-70_000i32, &4_000_000_000u32, &1.5f32]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        let sql_select = "SELECT Tiny, Small, Medium, Unsigned, Reading FROM Gauges";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        let row = &results[0];
+        assert_eq!(row[0].value::<i8>(), Some(-8));
+        assert_eq!(row[1].value::<i16>(), Some(300));
+        assert_eq!(row[2].value::<i32>(), Some(-70_000));
+        assert_eq!(row[3].value::<u32>(), Some(4_000_000_000));
+        assert_eq!(row[4].value::<f32>(), Some(1.5));
+        // out of range values don't convert
+        assert_eq!(row[1].value::<i8>(), None);
+        assert_eq!(row[2].value::<u32>(), None);
+        assert_eq!(row[3].value::<i32>(), None);
+    }
+
+    #[test]
+    fn u64_and_checked_conversions() {
+        use std::convert::TryFrom;
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Odometers";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Odometers(Small number, Distance number)";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Odometers(Small, Distance) VALUES(:small, :distance)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.bind(&[&42u64, &u64::MAX]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        let sql_select = "SELECT Small, Distance FROM Odometers";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        let row = &results[0];
+        assert_eq!(row[0].value::<u64>(), Some(42));
+        assert_eq!(row[1].value::<u64>(), Some(u64::MAX));
+        match row[1].try_value::<u64>() {
+            Ok(distance) => assert_eq!(distance, u64::MAX),
+            Err(err) => panic!("{}", err),
+        }
+        match row[1].try_value::<i64>() {
+            Err(err) => {
+                assert_eq!(err.sql_type(), "NUMBER");
+                assert_eq!(err.target(), "i64");
+            }
+            Ok(distance) => panic!("{} should not fit in an i64", distance),
+        }
+        assert!(row[0].try_value::<i8>().is_ok());
+        assert!(SqlValue::Integer(-1).try_value::<u64>().is_err());
+        assert_eq!(u32::try_from(&row[0]).ok(), Some(42));
+        match String::try_from(&SqlValue::Null(None)) {
+            Err(err) => assert_eq!(err.sql_type(), "NULL"),
+            Ok(text) => panic!("A null converted to {}", text),
+        }
+        // a conversion error converts to an OciError
+        let as_oci_error = || -> Result<i8, OciError> { Ok(row[1].try_value::<i8>()?) };
+        assert!(as_oci_error().is_err());
+    }
+
+    #[test]
+    fn i128_round_trip() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Ledgers";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Ledgers(LedgerId NUMBER(38), Balance NUMBER(38))";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let big: i128 = 99_999_999_999_999_999_999_999_999_999_999_999_999;
+        let sql_insert = "INSERT INTO Ledgers(LedgerId, Balance) VALUES(:id, :balance)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.bind(&[&big, &-7i128]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        let sql_select = "SELECT LedgerId, Balance FROM Ledgers WHERE LedgerId = :id";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.bind(&[&big]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(results.len(), 1);
+        let row = &results[0];
+        assert_eq!(row[0].value::<i128>(), Some(big));
+        assert_eq!(row[1].value::<i128>(), Some(-7));
+        assert_eq!(row[0].value::<i64>(), None);
+    }
+
+    #[test]
+    fn bool_flags() {
+        use crate::types::BoolEncoding;
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Subscriptions";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Subscriptions(Active NUMBER(1), Renews CHAR(1))";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Subscriptions(Active, Renews) VALUES(:active, :renews)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.bind(&[&true, &BoolEncoding::YesNo.encode(false)]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        let sql_select = "SELECT Active, Renews FROM Subscriptions";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        let row = &results[0];
+        assert_eq!(row[0].value::<bool>(), Some(true));
+        assert_eq!(row[1].value::<bool>(), Some(false));
+        assert_eq!(BoolEncoding::YesNo.decode(&row[0]), None);
+        assert_eq!(BoolEncoding::Number.decode(&SqlValue::Integer(2)), None);
+    }
+
+    #[test]
+    #[cfg(feature = "time")]
+    fn time_crate_round_trip() {
+        use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Appointments";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Appointments(Booked DATE, Starts TIMESTAMP, \
+                          Reminder TIMESTAMP WITH TIME ZONE)";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let booked = match Date::from_calendar_date(2019, Month::March, 14) {
+            Ok(date) => date,
+            Err(err) => panic!("{}", err),
+        };
+        let starts = match Time::from_hms_nano(9, 30, 15, 250_000_000) {
+            Ok(time) => PrimitiveDateTime::new(booked, time),
+            Err(err) => panic!("{}", err),
+        };
+        let reminder: OffsetDateTime = match UtcOffset::from_hms(-5, -30, 0) {
+            Ok(offset) => starts.assume_offset(offset),
+            Err(err) => panic!("{}", err),
+        };
+        let sql_insert = "INSERT INTO Appointments(Booked, Starts, Reminder) \
+                          VALUES(:booked, :starts, :reminder)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.bind(&[&booked, &starts, &reminder]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        let sql_select = "SELECT Booked, Starts, Reminder FROM Appointments";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        let row = &results[0];
+        assert_eq!(row[0].value::<Date>(), Some(booked));
+        assert_eq!(row[1].value::<PrimitiveDateTime>(), Some(starts));
+        let fetched_reminder: OffsetDateTime = match row[2].value() {
+            Some(reminder) => reminder,
+            None => panic!("The reminder did not convert"),
+        };
+        assert_eq!(fetched_reminder, reminder);
+        assert_eq!(fetched_reminder.offset(), reminder.offset());
+    }
+
+    #[test]
+    fn timestamp_with_time_zone_region() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Departures";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create =
+            "CREATE TABLE Departures(Flight VARCHAR(10), Leaves TIMESTAMP WITH TIME ZONE)";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Departures(Flight, Leaves) \
+                          VALUES('BA123', TIMESTAMP '2019-07-01 12:00:00 Europe/London')";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        let sql_select = "SELECT Leaves FROM Departures";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        match results[0][0] {
+            SqlValue::TimestampTzRegion(ref leaves, ref region, _) => {
+                assert_eq!(region, "Europe/London");
+                assert_eq!(leaves.offset().local_minus_utc(), 3600);
+                assert_eq!(leaves.hour(), 12);
+            }
+            ref other => panic!("Expected a timestamp in a region, got {:?}", other),
+        }
+        let leaves: DateTime<FixedOffset> = match results[0][0].value() {
+            Some(leaves) => leaves,
+            None => panic!("The timestamp did not convert"),
+        };
+        assert_eq!(leaves.with_timezone(&Utc).hour(), 11);
+        #[cfg(feature = "chrono-tz")]
+        {
+            let in_region: DateTime<chrono_tz::Tz> = match results[0][0].value() {
+                Some(leaves) => leaves,
+                None => panic!("The timestamp did not convert"),
+            };
+            assert_eq!(in_region.timezone(), chrono_tz::Europe::London);
+        }
+    }
+
+    #[test]
+    fn update_selected_row_by_rowid() {
+        use crate::types::RowId;
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Kennels";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Kennels(KennelId INTEGER, Occupant VARCHAR(20))";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Kennels(KennelId, Occupant) VALUES(:id, :occupant)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        for (id, occupant) in &[(1, "Gelert"), (2, "Cafall")] {
+            if let Err(err) = insert.bind(&[id, occupant]) {
+                panic!("{}", err)
+            }
+            if let Err(err) = insert.execute() {
+                panic!("{}", err)
+            }
+        }
+        let sql_select = "SELECT ROWID, Occupant FROM Kennels WHERE KennelId = 2 FOR UPDATE";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        let rowid: RowId = match results[0][0].value() {
+            Some(rowid) => rowid,
+            None => panic!("The ROWID did not convert"),
+        };
+        assert_eq!(rowid.as_str().len(), 18);
+        let sql_update = "UPDATE Kennels SET Occupant = 'Mabon' WHERE ROWID = :rowid";
+        let mut update = match conn.create_prepared_statement(sql_update) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = update.bind(&[&rowid]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = update.execute() {
+            panic!("{}", err)
+        }
+        if let Err(err) = conn.commit() {
+            panic!("{}", err)
+        }
+        let sql_check = "SELECT KennelId, Occupant FROM Kennels ORDER BY KennelId";
+        let mut check = match conn.create_prepared_statement(sql_check) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = check.execute() {
+            panic!("{}", err)
+        }
+        let results = match check.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(results[0][1].value::<String>(), Some("Gelert".to_string()));
+        assert_eq!(results[1][1].value::<String>(), Some("Mabon".to_string()));
+    }
+
+    #[test]
+    fn nchar_and_nvarchar2_round_trip() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Signposts";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create =
+            "CREATE TABLE Signposts(SignpostId INTEGER, Code NCHAR(4), Place NVARCHAR2(20))";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let places = ["Llanelli", "Москва", "東京", "Ελλάδα"];
+        let sql_insert = "INSERT INTO Signposts(SignpostId, Code, Place) \
+                          VALUES(:id, TO_NCHAR(:code), TO_NCHAR(:place))";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        for (id, place) in places.iter().enumerate() {
+            let code: String = place.chars().take(2).collect();
+            if let Err(err) = insert.bind(&[&(id as i64), &code, place]) {
+                panic!("{}", err)
+            }
+            if let Err(err) = insert.execute() {
+                panic!("{}", err)
+            }
+        }
+        let sql_select = "SELECT Code, Place FROM Signposts ORDER BY SignpostId";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(results.len(), places.len());
+        for (row, place) in results.iter().zip(places.iter()) {
+            let code: String = place.chars().take(2).collect();
+            // NCHAR is blank padded to its length in characters
+            assert_eq!(row[0].value::<String>(), Some(format!("{}  ", code)));
+            assert_eq!(row[1].value::<String>(), Some(place.to_string()));
+        }
+    }
+
+    #[test]
+    fn xmltype_fetched_as_text() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Pedigrees";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Pedigrees(DogId INTEGER, Lineage XMLTYPE)";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let xml = "<dog><name>Gelert</name><sire>Cafall</sire></dog>";
+        let sql_insert = "INSERT INTO Pedigrees(DogId, Lineage) VALUES(1, XMLTYPE(:xml))";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.bind(&[&xml]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        let sql_select = "SELECT Lineage FROM Pedigrees";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        let lineage: String = match results[0][0].value() {
+            Some(lineage) => lineage,
+            None => panic!("The XMLType did not convert"),
+        };
+        // Oracle may add whitespace when it serialises the document
+        let compact: String = lineage.split_whitespace().collect();
+        assert_eq!(compact, xml);
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn json_column_round_trip() {
+        use serde_json::{json, Value};
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Breeds";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Breeds(BreedId INTEGER, Standard JSON)";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let standard = json!({
+            "name": "Welsh Corgi",
+            "height_cm": [25, 30],
+            "colours": ["red", "sable", "fawn"],
+            "herding": true
+        });
+        let sql_insert = "INSERT INTO Breeds(BreedId, Standard) VALUES(1, :standard)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.bind(&[&standard]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        let sql_select = "SELECT Standard FROM Breeds";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(results[0][0].value::<Value>(), Some(standard));
+        let text: String = match results[0][0].value() {
+            Some(text) => text,
+            None => panic!("The JSON did not convert to text"),
+        };
+        assert!(text.contains("Welsh Corgi"));
+    }
+
+    #[test]
+    fn varray_and_nested_table_round_trip() {
+        use crate::collection::{Collection, ElementType};
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        for sql_drop in &[
+            "DROP TABLE Shows",
+            "DROP TYPE ShowScores",
+            "DROP TYPE ShowJudges",
+        ] {
+            let mut drop = match conn.create_prepared_statement(sql_drop) {
+                Ok(stmt) => stmt,
+                Err(err) => panic!("{}", err),
+            };
+            drop.execute().ok();
+        }
+        for sql_create in &[
+            "CREATE TYPE ShowScores AS VARRAY(10) OF NUMBER",
+            "CREATE TYPE ShowJudges AS TABLE OF VARCHAR2(20)",
+            "CREATE TABLE Shows(ShowId INTEGER, Scores ShowScores, Judges ShowJudges) \
+             NESTED TABLE Judges STORE AS ShowJudgesTable",
+        ] {
+            let mut create = match conn.create_prepared_statement(sql_create) {
+                Ok(stmt) => stmt,
+                Err(err) => panic!("{}", err),
+            };
+            if let Err(err) = create.execute() {
+                panic!("{}", err)
+            }
+        }
+        let scores = Collection::from_values("ShowScores", ElementType::Number, &[9.5, 8.0, 7.25]);
+        let judges = Collection::new(
+            "ShowJudges",
+            ElementType::VarChar,
+            vec![
+                "Gwen".to_sql_value(),
+                SqlValue::Null(None),
+                "Rhodri".to_sql_value(),
+            ],
+        );
+        let sql_insert = "INSERT INTO Shows(ShowId, Scores, Judges) VALUES(:id, :scores, :judges)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.bind(&[&1, &scores, &judges]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.bind(&[&2, &SqlValue::Null(None), &SqlValue::Null(None)]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+
+        let sql_select = "SELECT Scores, Judges FROM Shows ORDER BY ShowId";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(results.len(), 2);
+        let fetched_scores: Collection = match results[0][0].value() {
+            Some(scores) => scores,
+            None => panic!("Scores weren't fetched as a collection"),
+        };
+        assert!(fetched_scores.type_name().ends_with(".SHOWSCORES"));
+        assert_eq!(fetched_scores.element_type(), ElementType::Number);
+        assert_eq!(fetched_scores.values::<f64>(), Some(vec![9.5, 8.0, 7.25]));
+        let fetched_judges: Collection = match results[0][1].value() {
+            Some(judges) => judges,
+            None => panic!("Judges weren't fetched as a collection"),
+        };
+        assert_eq!(fetched_judges.element_type(), ElementType::VarChar);
+        assert_eq!(fetched_judges.len(), 3);
+        assert_eq!(
+            fetched_judges.elements()[0].value::<String>(),
+            Some("Gwen".to_string())
+        );
+        assert!(fetched_judges.elements()[1].value::<String>().is_none());
+        assert!(results[1][0].value::<Collection>().is_none());
+        assert!(results[1][1].value::<Collection>().is_none());
+
+        // a collection can be queried as a table, as PL/SQL array parameters are
+        let sql_sum = "SELECT SUM(COLUMN_VALUE) FROM TABLE(:scores)";
+        let mut sum = match conn.create_prepared_statement(sql_sum) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = sum.bind(&[&fetched_scores]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = sum.execute() {
+            panic!("{}", err)
+        }
+        let total: f64 = match sum.result_set() {
+            Ok(results) => results[0][0].value().unwrap_or_default(),
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(total, 24.75);
+    }
+
+    #[test]
+    #[cfg(feature = "uuid")]
+    fn uuid_raw16_round_trip() {
+        use uuid::Uuid;
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Microchips";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create =
+            "CREATE TABLE Microchips(ChipId RAW(16) PRIMARY KEY, DogName VARCHAR2(20))";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let chip_id = match Uuid::parse_str("6f1c2a9e-3b4d-4e5f-8a7b-9c0d1e2f3a4b") {
+            Ok(id) => id,
+            Err(err) => panic!("{}", err),
+        };
+        let sql_insert = "INSERT INTO Microchips(ChipId, DogName) VALUES(:id, :name)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.bind(&[&chip_id, &"Meg"]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        let sql_select =
+            "SELECT ChipId, RAWTOHEX(ChipId), DogName FROM Microchips WHERE ChipId = :id";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.bind(&[&chip_id]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0][0].value::<Uuid>(), Some(chip_id));
+        assert_eq!(results[0][1].value::<Uuid>(), Some(chip_id));
+        assert_eq!(results[0][2].value::<String>(), Some("Meg".to_string()));
+    }
+
+    #[test]
+    fn sql_type_of_fetched_values() {
+        use crate::types::ValueType;
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Rosettes";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Rosettes(Place INTEGER, Colour VARCHAR2(10), \
+                          Class CHAR(2), Awarded DATE, Judge VARCHAR2(20))";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Rosettes(Place, Colour, Class, Awarded, Judge) \
+                          VALUES(12, 'Red', 'A1', SYSDATE, NULL)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        let sql_select = "SELECT Place, Place / 8, Colour, Class, Awarded, Judge FROM Rosettes";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        let types: Vec<ValueType> = results[0].columns().iter().map(|v| v.sql_type()).collect();
+        assert_eq!(
+            types[0],
+            ValueType::Number {
+                precision: Some(2),
+                scale: Some(0)
+            }
+        );
+        assert_eq!(types[2], ValueType::VarChar);
+        assert_eq!(types[3], ValueType::Char);
+        assert_eq!(types[4], ValueType::Date);
+        assert_eq!(types[5], ValueType::Null);
+        assert_eq!(types[0].to_string(), "NUMBER(2, 0)");
+        match types[1] {
+            ValueType::Number { .. } => (),
+            other => panic!("12 / 8 is a {}", other),
+        }
+    }
+
+    #[test]
+    fn numbers_as_exact_text() {
+        let mut conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        {
+            let sql_drop = "DROP TABLE Payouts";
+            let mut drop = match conn.create_prepared_statement(sql_drop) {
+                Ok(stmt) => stmt,
+                Err(err) => panic!("{}", err),
+            };
+            drop.execute().ok();
+            let sql_create = "CREATE TABLE Payouts(Ticket NUMBER(38), Stake NUMBER(10, 2), \
+                              Odds NUMBER)";
+            let mut create = match conn.create_prepared_statement(sql_create) {
+                Ok(stmt) => stmt,
+                Err(err) => panic!("{}", err),
+            };
+            if let Err(err) = create.execute() {
+                panic!("{}", err)
+            }
+            let sql_insert = "INSERT INTO Payouts(Ticket, Stake, Odds) \
+                              VALUES(12345678901234567890123456789012345678, 1.5, 0.25)";
+            let mut insert = match conn.create_prepared_statement(sql_insert) {
+                Ok(stmt) => stmt,
+                Err(err) => panic!("{}", err),
+            };
+            if let Err(err) = insert.execute() {
+                panic!("{}", err)
+            }
+            let sql_insert = "INSERT INTO Payouts(Ticket, Stake, Odds) VALUES(1, -0.5, 3)";
+            let mut insert = match conn.create_prepared_statement(sql_insert) {
+                Ok(stmt) => stmt,
+                Err(err) => panic!("{}", err),
+            };
+            if let Err(err) = insert.execute() {
+                panic!("{}", err)
+            }
+            if let Err(err) = conn.commit() {
+                panic!("{}", err)
+            }
+        }
+        conn.set_numbers_as_text(true);
+        let sql_select = "SELECT Ticket, Stake, Odds FROM Payouts ORDER BY Stake DESC";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        let text = |row: usize, column: usize| results[row][column].value::<String>();
+        assert_eq!(
+            text(0, 0),
+            Some("12345678901234567890123456789012345678".to_string())
+        );
+        assert_eq!(text(0, 1), Some("1.50".to_string()));
+        assert_eq!(text(0, 2), Some("0.25".to_string()));
+        assert_eq!(text(1, 0), Some("1".to_string()));
+        assert_eq!(text(1, 1), Some("-0.50".to_string()));
+        assert_eq!(text(1, 2), Some("3".to_string()));
+
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        select.set_numbers_as_text(false);
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(results[1][2].value::<i64>(), Some(3));
+    }
+
+    #[test]
+    #[cfg(feature = "derive")]
+    fn derived_from_row_and_to_params() {
+        use crate::types::ToParams;
+
+        #[derive(FromRow, ToParams)]
+        struct Greyhound {
+            #[oci(rename = "DogId")]
+            id: i64,
+            name: String,
+            trainer: Option<String>,
+            #[oci(skip)]
+            wins: u32,
+        }
+
+        #[derive(FromRow)]
+        struct IdAndName(i64, String);
+
+        #[derive(FromRow)]
+        struct NameAndId(#[oci(position = 1)] String, #[oci(position = 0)] i64);
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Greyhounds";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Greyhounds(DogId INTEGER, Name VARCHAR2(20), \
+                          Trainer VARCHAR2(20))";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Greyhounds(DogId, Name, Trainer) \
+                          VALUES(:DogId, :name, :trainer)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        let dogs = [
+            Greyhound {
+                id: 1,
+                name: "Mick the Miller".to_string(),
+                trainer: Some("Sidney Orton".to_string()),
+                wins: 36,
+            },
+            Greyhound {
+                id: 2,
+                name: "Ballyregan Bob".to_string(),
+                trainer: None,
+                wins: 32,
+            },
+        ];
+        assert_eq!(dogs[0].to_params().len(), 3);
+        for dog in &dogs {
+            if let Err(err) = insert.bind_params(dog) {
+                panic!("{}", err)
+            }
+            if let Err(err) = insert.execute() {
+                panic!("{}", err)
+            }
+        }
+        let sql_select = "SELECT Trainer, Name, DogId FROM Greyhounds ORDER BY DogId";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let greyhounds: Vec<Greyhound> = match select.typed_result_set() {
+            Ok(greyhounds) => greyhounds,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(greyhounds.len(), 2);
+        assert_eq!(greyhounds[0].id, 1);
+        assert_eq!(greyhounds[0].name, "Mick the Miller");
+        assert_eq!(greyhounds[0].trainer, Some("Sidney Orton".to_string()));
+        assert_eq!(greyhounds[0].wins, 0);
+        assert_eq!(greyhounds[1].trainer, None);
+
+        let sql_select = "SELECT DogId, Name FROM Greyhounds ORDER BY DogId";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let ids: Vec<IdAndName> = match select.typed_result_set() {
+            Ok(ids) => ids,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(ids[1].0, 2);
+        assert_eq!(ids[1].1, "Ballyregan Bob");
+        let names: Vec<NameAndId> = match select.typed_result_set() {
+            Ok(names) => names,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(names[1].0, "Ballyregan Bob");
+        assert_eq!(names[1].1, 2);
+    }
+
+    #[test]
+    fn bind_borrowed_and_boxed_text() {
+        use std::borrow::Cow;
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Nicknames";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Nicknames(Name VARCHAR2(20), Short VARCHAR2(20), \
+                          Boxed VARCHAR2(20), Initial CHAR(1), Alias VARCHAR2(20))";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Nicknames(Name, Short, Boxed, Initial, Alias) \
+                          VALUES(:name, :short, :boxed, :initial, :alias)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        let name = "Bartholomew".to_string();
+        let short: Cow<str> = Cow::Borrowed("Bart");
+        let boxed: Box<str> = "Barty".into();
+        let alias: Option<String> = None;
+        if let Err(err) = insert.bind(&[&&name, &short, &boxed, &'B', &&alias]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        let sql_select = "SELECT Name, Short, Boxed, Initial, Alias FROM Nicknames";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        let row = &results[0];
+        assert_eq!(row[0].value::<String>(), Some(name));
+        assert_eq!(row[1].value::<String>(), Some("Bart".to_string()));
+        assert_eq!(row[2].value::<String>(), Some("Barty".to_string()));
+        assert_eq!(row[3].value::<String>(), Some("B".to_string()));
+        match row[4] {
+            SqlValue::Null(..) => (),
+            ref other => panic!("Alias should be null, found {:?}", other),
+        }
+    }
+
+    #[test]
+    fn text_trimming_is_configurable() {
+        use crate::types::TextTrim;
+
+        let mut conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_select = "SELECT Slogan, Code FROM Placards";
+        {
+            let sql_drop = "DROP TABLE Placards";
+            let mut drop = match conn.create_prepared_statement(sql_drop) {
+                Ok(stmt) => stmt,
+                Err(err) => panic!("{}", err),
+            };
+            drop.execute().ok();
+            let sql_create = "CREATE TABLE Placards(Slogan VARCHAR2(20), Code CHAR(6))";
+            let mut create = match conn.create_prepared_statement(sql_create) {
+                Ok(stmt) => stmt,
+                Err(err) => panic!("{}", err),
+            };
+            if let Err(err) = create.execute() {
+                panic!("{}", err)
+            }
+            let sql_insert = "INSERT INTO Placards(Slogan, Code) VALUES('  Vote ', 'AB')";
+            let mut insert = match conn.create_prepared_statement(sql_insert) {
+                Ok(stmt) => stmt,
+                Err(err) => panic!("{}", err),
+            };
+            if let Err(err) = insert.execute() {
+                panic!("{}", err)
+            }
+            if let Err(err) = conn.commit() {
+                panic!("{}", err)
+            }
+
+            let mut select = match conn.create_prepared_statement(sql_select) {
+                Ok(stmt) => stmt,
+                Err(err) => panic!("{}", err),
+            };
+            if let Err(err) = select.execute() {
+                panic!("{}", err)
+            }
+            let results = match select.result_set() {
+                Ok(results) => results,
+                Err(err) => panic!("{}", err),
+            };
+            assert_eq!(results[0][0].value::<String>(), Some("Vote".to_string()));
+            assert_eq!(results[0][1].value::<String>(), Some("AB    ".to_string()));
+        }
+
+        conn.set_varchar_trim(TextTrim::Preserve);
+        conn.set_char_trim(TextTrim::TrimEnd);
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(results[0][0].value::<String>(), Some("  Vote ".to_string()));
+        assert_eq!(results[0][1].value::<String>(), Some("AB".to_string()));
+
+        select.set_varchar_trim(TextTrim::TrimEnd);
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(results[0][0].value::<String>(), Some("  Vote".to_string()));
+    }
+
+    #[test]
+    fn binary_floats_keep_nan_and_infinities() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Soundings";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Soundings(SoundingId INTEGER, Single BINARY_FLOAT, \
+                          Twice BINARY_DOUBLE, Depth NUMBER)";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Soundings(SoundingId, Single, Twice) \
+                          VALUES(:id, :single, :twice)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.bind(&[&1, &f64::NAN, &f64::NAN]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        let (two, three, four) = (2, 3, 4);
+        let (infinity, minus_infinity, finite) = (f64::INFINITY, f64::NEG_INFINITY, 1.5);
+        let rows: [&[&dyn ToSqlValue]; 3] = [
+            &[&two, &infinity, &infinity],
+            &[&three, &minus_infinity, &minus_infinity],
+            &[&four, &finite, &finite],
+        ];
+        if let Err(err) = insert.execute_batch(&rows) {
+            panic!("{}", err)
+        }
+
+        let sql_select = "SELECT Single, Twice FROM Soundings ORDER BY SoundingId";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        let float = |row: usize, column: usize| match results[row][column].value::<f64>() {
+            Some(f) => f,
+            None => panic!("Expected a float, found {:?}", results[row][column]),
+        };
+        for column in 0..2 {
+            assert!(float(0, column).is_nan());
+            assert_eq!(float(1, column), f64::INFINITY);
+            assert_eq!(float(2, column), f64::NEG_INFINITY);
+            assert_eq!(float(3, column), 1.5);
+        }
+        assert_eq!(results[0][1].value::<i64>(), None);
+        assert_eq!(results[1][1].value::<String>(), Some("inf".to_string()));
+
+        let sql_update = "UPDATE Soundings SET Depth = :depth";
+        let mut update = match conn.create_prepared_statement(sql_update) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = update.bind(&[&f64::INFINITY]) {
+            panic!("{}", err)
+        }
+        assert!(update.execute().is_err());
+    }
+
+    #[test]
+    fn checked_numeric_conversions_say_why() {
+        use crate::oci_error::NumericErrorKind;
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_select = "SELECT 12345678901234567890123, 2.5, 9007199254740993, \
+                          -42, NULL, SYSDATE FROM dual";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        let row = &results[0];
+        let kind = |result: Result<i64, _>| match result {
+            Ok(i) => panic!("Expected an error, found {}", i),
+            Err(err) => crate::oci_error::NumericError::kind(&err),
+        };
+        assert_eq!(kind(row[0].as_i64_checked()), NumericErrorKind::Overflow);
+        assert_eq!(row[0].as_i128_checked(), Ok(12_345_678_901_234_567_890_123));
+        assert_eq!(
+            kind(row[1].as_i64_checked()),
+            NumericErrorKind::PrecisionLoss
+        );
+        assert_eq!(row[1].as_f64_checked(), Ok(2.5));
+        assert_eq!(row[2].as_i64_checked(), Ok(9_007_199_254_740_993));
+        match row[2].as_f64_checked() {
+            Err(ref err) if err.kind() == NumericErrorKind::PrecisionLoss => (),
+            other => panic!("Expected a loss of precision, found {:?}", other),
+        }
+        assert_eq!(row[2].as_f64_lossy(), Ok(9_007_199_254_740_992.0));
+        match row[3].as_u64_checked() {
+            Err(ref err) if err.kind() == NumericErrorKind::Overflow => (),
+            other => panic!("Expected -42 to be out of range, found {:?}", other),
+        }
+        assert_eq!(kind(row[4].as_i64_checked()), NumericErrorKind::NotANumber);
+        assert_eq!(kind(row[5].as_i64_checked()), NumericErrorKind::NotANumber);
+        let err: OciError = match row[0].as_i64_checked() {
+            Ok(i) => panic!("Expected an error, found {}", i),
+            Err(err) => err.into(),
+        };
+        assert!(err.to_string().contains("out of range for i64"));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn dates_across_the_whole_oracle_range() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Almanac";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Almanac(Id INTEGER, Day DATE)";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("Couldn't execute create Almanac: {}", err)
+        }
+
+        // chrono counts 1 BC as year 0, Oracle goes from 1 BC to AD 1.
+        let days = [
+            (Utc.ymd(-4711, 1, 1), "-4712-01-01"),
+            (Utc.ymd(-99, 12, 31), "-0100-12-31"),
+            (Utc.ymd(0, 12, 31), "-0001-12-31"),
+            (Utc.ymd(1, 1, 1), " 0001-01-01"),
+            (Utc.ymd(99, 12, 31), " 0099-12-31"),
+            (Utc.ymd(100, 1, 1), " 0100-01-01"),
+            (Utc.ymd(9999, 12, 31), " 9999-12-31"),
+        ];
+        let sql_insert = "INSERT INTO Almanac(Id, Day) VALUES(:id, :day)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        for (id, &(day, _)) in days.iter().enumerate() {
+            if let Err(err) = insert.bind(&[&(id as i64), &day]) {
+                panic!("{}", err)
+            }
+            if let Err(err) = insert.execute() {
+                panic!("Couldn't insert {} into Almanac: {}", day, err)
+            }
+        }
+
+        let sql_select = "SELECT Day, TO_CHAR(Day, 'SYYYY-MM-DD') FROM Almanac ORDER BY Id";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(results.len(), days.len());
+        for (row, &(day, text)) in results.iter().zip(days.iter()) {
+            let fetched: Date<Utc> = row[0].value().unwrap();
+            let oracle_text: String = row[1].value().unwrap();
+            assert_eq!(fetched, day);
+            assert_eq!(oracle_text, text);
+        }
+    }
+
+    #[test]
+    fn date_year_bytes_at_the_edges() {
+        use crate::types::{convert_year_from_raw, convert_year_to_raw};
+
+        // (chrono's year, Oracle's century and year bytes)
+        let edges = [
+            (-4711, [53, 88]),
+            (-4700, [53, 99]),
+            (-99, [99, 100]),
+            (-1, [100, 98]),
+            (0, [100, 99]),
+            (1, [100, 101]),
+            (99, [100, 199]),
+            (100, [101, 100]),
+            (2000, [120, 100]),
+            (9999, [199, 199]),
+        ];
+        for &(year, raw) in &edges {
+            assert_eq!(convert_year_to_raw(year), raw, "writing {}", year);
+            assert_eq!(
+                convert_year_from_raw(raw[0], raw[1]),
+                year,
+                "reading {:?}",
+                raw
+            );
+        }
+        assert_eq!(convert_year_to_raw(-4712), [0, 0]);
+        assert_eq!(convert_year_to_raw(10000), [0, 0]);
+        assert_eq!(convert_year_to_raw(30000), [0, 0]);
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn date_year_bytes_follow_oracle(oracle_year in -4712i32..=9999) {
+            proptest::prop_assume!(oracle_year != 0);
+            use crate::types::{convert_year_from_raw, convert_year_to_raw};
+
+            let (century, year) = (oracle_year.abs() / 100, oracle_year.abs() % 100);
+            let raw = if oracle_year < 0 {
+                [(100 - century) as u8, (100 - year) as u8]
+            } else {
+                [(100 + century) as u8, (100 + year) as u8]
+            };
+            let chrono_year = if oracle_year < 0 { oracle_year + 1 } else { oracle_year };
+            proptest::prop_assert_eq!(convert_year_to_raw(chrono_year), raw);
+            proptest::prop_assert_eq!(convert_year_from_raw(raw[0], raw[1]), chrono_year);
+        }
+
+        #[test]
+        #[allow(deprecated)]
+        fn dates_round_trip_through_oracle_bytes(
+            year in -4711i32..=9999,
+            ordinal in 1u32..=365,
+            seconds in 0u32..86_400,
+        ) {
+            use crate::types::create_date_value_from_raw;
+
+            let date = Utc.yo(year, ordinal);
+            let raw = date.to_sql_value().to_oci_bytes();
+            let fetched: Option<Date<Utc>> = create_date_value_from_raw(&raw).value();
+            proptest::prop_assert_eq!(fetched, Some(date));
+
+            let timestamp = date.and_hms(seconds / 3600, seconds / 60 % 60, seconds % 60);
+            let raw = timestamp.to_sql_value().to_oci_bytes();
+            proptest::prop_assert_eq!(
+                crate::types::create_datetime_from_raw(&raw),
+                timestamp
+            );
+        }
+    }
+
+    #[test]
+    fn nulls_carry_their_column_type() {
+        use crate::types::{SqlType, ValueType};
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Vacancies";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Vacancies(Id INTEGER, Salary NUMBER(8, 2), \
+                          Rating FLOAT, Title VARCHAR2(30), Grade CHAR(2), Opens DATE, \
+                          Posted TIMESTAMP, Notes CLOB, Logo BLOB)";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Vacancies(Id) VALUES(1)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+
+        let sql_select = "SELECT Salary, Rating, Title, Grade, Opens, Posted, Notes, Logo, \
+                          Salary / 3 FROM Vacancies";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        let row = &results[0];
+        assert!(row
+            .columns()
+            .iter()
+            .all(|v| v.sql_type() == ValueType::Null));
+        let types: Vec<Option<ValueType>> = row.columns().iter().map(|v| v.null_type()).collect();
+        assert_eq!(
+            types,
+            vec![
+                Some(ValueType::Number {
+                    precision: Some(8),
+                    scale: Some(2)
+                }),
+                Some(ValueType::Number {
+                    precision: Some(126),
+                    scale: None
+                }),
+                Some(ValueType::VarChar),
+                Some(ValueType::Char),
+                Some(ValueType::Date),
+                Some(ValueType::Timestamp),
+                Some(ValueType::VarChar),
+                Some(ValueType::Blob),
+                Some(ValueType::Number {
+                    precision: None,
+                    scale: None
+                }),
+            ]
+        );
+        assert_eq!(row[0].value::<f64>(), None);
+
+        let mut block = match conn.create_prepared_statement("BEGIN :1 := NULL; END;") {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = block.bind(&[&SqlValue::Null(None)]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = block.bind_out(1, SqlType::Date) {
+            panic!("{}", err)
+        }
+        if let Err(err) = block.execute() {
+            panic!("{}", err)
+        }
+        match block.out_value(1) {
+            Ok(value) => assert_eq!(value.null_type(), Some(ValueType::Date)),
+            Err(err) => panic!("{}", err),
+        }
+        assert_eq!(SqlValue::Null(None).null_type(), None);
+        assert_eq!(None::<i64>.to_sql_value().null_type(), None);
+    }
+
+    #[test]
+    fn try_get_says_which_column_failed() {
+        use crate::oci_error::ColumnConversionError;
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_select = "SELECT 'Biscuit' AS Treat, CAST(NULL AS DATE) AS Fed, 7 AS Count \
+                          FROM dual";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        let row = &results[0];
+        let column_error = |result: Result<i64, OciError>| match result {
+            Err(OciError::Conversion(err)) => err
+                .downcast_ref::<ColumnConversionError>()
+                .expect("Not a column conversion error")
+                .clone(),
+            Err(err) => panic!("{}", err),
+            Ok(value) => panic!("Expected a conversion error, found {}", value),
+        };
+
+        match row.try_get::<String>("treat") {
+            Ok(treat) => assert_eq!(treat, "Biscuit"),
+            Err(err) => panic!("{}", err),
+        }
+        match row.try_get::<i64>(2) {
+            Ok(count) => assert_eq!(count, 7),
+            Err(err) => panic!("{}", err),
+        }
+        let name = String::from("Count");
+        match row.try_get::<i64>(&name) {
+            Ok(count) => assert_eq!(count, 7),
+            Err(err) => panic!("{}", err),
+        }
+
+        let err = column_error(row.try_get("Treat"));
+        assert_eq!(err.column(), "TREAT");
+        assert_eq!(err.position(), 0);
+        assert_eq!(err.sql_type(), "CHAR");
+        assert_eq!(err.target(), "i64");
+        assert_eq!(
+            err.to_string(),
+            "Column TREAT at position 0 can't be converted from CHAR to i64, \
+             found: Char(\"Biscuit\")"
+        );
+        let err = column_error(row.try_get(1));
+        assert_eq!(err.column(), "FED");
+        assert_eq!(err.sql_type(), "DATE");
+
+        match row.try_get::<i64>("Walked") {
+            Err(err) => assert!(err.to_string().contains("No column named Walked")),
+            Ok(value) => panic!("Expected no column, found {}", value),
+        }
+        match row.try_get::<i64>(3) {
+            Err(err) => assert!(err.to_string().contains("the row has 3 columns")),
+            Ok(value) => panic!("Expected no column, found {}", value),
+        }
+    }
+
+    #[test]
+    fn rows_into_tuples() {
+        use crate::oci_error::{ColumnConversionError, UsageError};
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_select = "SELECT 1, 'Rex', 2.5, CAST(NULL AS VARCHAR2(10)) FROM dual";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        let row = &results[0];
+        match row.into_tuple::<(i64, String, f64, Option<String>)>() {
+            Ok(tuple) => assert_eq!(tuple, (1, "Rex".to_string(), 2.5, None)),
+            Err(err) => panic!("{}", err),
+        }
+        match row.into_tuple::<(i64, String)>() {
+            Err(OciError::Usage(UsageError::Column(description))) => assert_eq!(
+                description,
+                "The row has 4 columns, which can't be converted to a tuple of 2"
+            ),
+            Err(err) => panic!("{}", err),
+            Ok(tuple) => panic!("Expected too many columns, found {:?}", tuple),
+        }
+        match row.into_tuple::<(i64, i64, f64, Option<String>)>() {
+            Err(OciError::Conversion(err)) => {
+                let err = err
+                    .downcast_ref::<ColumnConversionError>()
+                    .expect("Not a column conversion error");
+                assert_eq!(err.position(), 1);
+                assert_eq!(err.target(), "i64");
+            }
+            Err(err) => panic!("{}", err),
+            Ok(tuple) => panic!("Expected a conversion error, found {:?}", tuple),
+        }
+
+        let sql_dogs = "SELECT LEVEL, 'Dog ' || LEVEL FROM dual CONNECT BY LEVEL <= 3";
+        let mut dogs = match conn.create_prepared_statement(sql_dogs) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = dogs.execute() {
+            panic!("{}", err)
+        }
+        match dogs.typed_result_set::<(i64, String)>() {
+            Ok(dogs) => assert_eq!(
+                dogs,
+                vec![
+                    (1, "Dog 1".to_string()),
+                    (2, "Dog 2".to_string()),
+                    (3, "Dog 3".to_string()),
+                ]
+            ),
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    #[test]
+    fn bind_named_in_any_order() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Nuts";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Nuts(NutId integer, Name varchar(20))";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Nuts(NutId, Name) VALUES(:id, :name)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.bind_named(&[("name", &"Almond"), (":ID", &3)]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+
+        let sql_select = "SELECT Name FROM Nuts WHERE NutId = :id";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.bind_named(&[("id", &3)]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        let name: String = results[0][0].value().unwrap();
+        assert_eq!(name, "Almond");
+    }
+
+    #[test]
+    fn bind_map_matches_names_case_insensitively() {
+        use std::collections::HashMap;
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Vegetables";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Vegetables(VegetableId integer, Name varchar(20))";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Vegetables(VegetableId, Name) VALUES(:Id, :name)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        let mut params = HashMap::new();
+        params.insert("NAME".to_string(), SqlValue::VarChar("Leek".to_string()));
+        params.insert(":id".to_string(), SqlValue::Integer(7));
+        if let Err(err) = insert.bind_map(&params) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+
+        params.insert("colour".to_string(), SqlValue::VarChar("Green".to_string()));
+        assert!(insert.bind_map(&params).is_err());
+
+        let sql_select = "SELECT Name FROM Vegetables WHERE VegetableId = :id";
+        let mut select = match conn.create_prepared_statement(sql_select) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        let mut params = HashMap::new();
+        params.insert("ID".to_string(), 7);
+        if let Err(err) = select.bind_map(&params) {
+            panic!("{}", err)
+        }
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let results = match select.result_set() {
+            Ok(results) => results,
+            Err(err) => panic!("{}", err),
+        };
+        let name: String = results[0][0].value().unwrap();
+        assert_eq!(name, "Leek");
+    }
+
+    #[test]
+    fn query() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Cars";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(s) => s,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Cars(CarId integer, Name varchar(20))";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Cars(CarId, Name) VALUES('12', 'BMW')";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        let sql_query = "SELECT * FROM Cars";
+        let mut select = match conn.create_prepared_statement(sql_query) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let result_set = match select.result_set() {
+            Ok(res) => res,
+            Err(err) => panic!("{}", err),
+        };
+        if result_set.is_empty() {
+            panic!("Should not have an empty result")
+        }
+        let row = &result_set[0];
+        let car_id: i64 = row[0].value().expect("Not an i64");
+        assert_eq!(car_id, 12);
+        let car_name: String = row[1].value().expect("Not a string");
+        assert_eq!(car_name, "BMW")
+    }
+
+    #[test]
+    fn multi_row_query() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Flowers";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Flowers(FlowerId integer, Name varchar(20))";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Flowers(FlowerId, Name) VALUES(:id, :name)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = insert.bind(&[&1, &"Rose".to_string()]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.bind(&[&2, &"Tulip".to_string()]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = insert.execute() {
+            panic!("{}", err)
+        }
+        let sql_query = "SELECT * FROM Flowers";
+        let mut select = match conn.create_prepared_statement(sql_query) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let result_set = match select.result_set() {
+            Ok(res) => res,
+            Err(err) => panic!("{}", err),
+        };
+        if result_set.is_empty() {
+            panic!("Should not have an empty result")
+        }
+        let pairs = [(1, "Rose"), (2, "Tulip")];
+        for (index, pair) in pairs.iter().enumerate() {
+            let row = &result_set[index];
+            let flower_id: i64 = row[0].value().expect("Not an i64");
+            let flower_name: String = row[1].value().expect("Not a string");
+            assert_eq!(flower_id, pair.0);
+            assert_eq!(flower_name, pair.1);
+        }
+    }
+
+    #[test]
+    fn result_set_size_is_tracked_and_warned_about() {
+        use std::sync::Mutex;
+
+        static WARNINGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+        struct CaptureWarnings;
+
+        impl log::Log for CaptureWarnings {
+            fn enabled(&self, metadata: &log::Metadata) -> bool {
+                metadata.level() <= log::Level::Warn
+            }
+
+            fn log(&self, record: &log::Record) {
+                if self.enabled(record.metadata()) {
+                    WARNINGS.lock().unwrap().push(record.args().to_string());
+                }
+            }
+
+            fn flush(&self) {}
+        }
+
+        static LOGGER: CaptureWarnings = CaptureWarnings;
+        if log::set_logger(&LOGGER).is_ok() {
+            log::set_max_level(log::LevelFilter::Warn);
+        }
+
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Shrubs";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Shrubs(ShrubId integer, Name varchar(20))";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Shrubs(ShrubId, Name) VALUES(:id, :name)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        for (id, name) in [(1, "Box"), (2, "Holly"), (3, "Laurel")].iter() {
+            if let Err(err) = insert.bind(&[id, name]) {
+                panic!("{}", err)
+            }
+            if let Err(err) = insert.execute() {
+                panic!("{}", err)
+            }
+        }
+
+        let sql_query = "SELECT ShrubId, Name FROM Shrubs WHERE ShrubId <= :max_id";
+        let mut select = match conn.create_prepared_statement(sql_query) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(select.result_set_size(), 0);
+        assert_eq!(select.result_set_high_water(), 0);
+        select.set_result_set_size_warning(1);
+
+        if let Err(err) = select.bind(&[&3]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        match select.result_set() {
+            Ok(rows) => assert_eq!(rows.len(), 3),
+            Err(err) => panic!("{}", err),
+        }
+        let full_size = select.result_set_size();
+        assert!(full_size > 0);
+        assert_eq!(select.result_set_high_water(), full_size);
+        assert!(WARNINGS.lock().unwrap().iter().any(|warning| warning
+            .starts_with("Result set has grown past 1 bytes after 1 rows")
+            && warning.ends_with(sql_query)));
+
+        if let Err(err) = select.bind(&[&1]) {
+            panic!("{}", err)
+        }
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        match select.result_set() {
+            Ok(rows) => assert_eq!(rows.len(), 1),
+            Err(err) => panic!("{}", err),
+        }
+        let smaller_size = select.result_set_size();
+        assert!(smaller_size > 0 && smaller_size < full_size);
+        assert_eq!(select.result_set_high_water(), full_size);
+    }
+
+    #[test]
+    fn multi_row_query_with_prefetch() {
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let sql_drop = "DROP TABLE Words";
+        let mut drop = match conn.create_prepared_statement(sql_drop) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        drop.execute().ok();
+        let sql_create = "CREATE TABLE Words(Word varchar(100))";
+        let mut create = match conn.create_prepared_statement(sql_create) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+        if let Err(err) = create.execute() {
+            panic!("{}", err)
+        }
+        let sql_insert = "INSERT INTO Words(Word) VALUES(:word)";
+        let mut insert = match conn.create_prepared_statement(sql_insert) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
 
-    #[test]
-    #[allow(unused_variables)]
-    fn create_connection_with_bad_connection_string() {
-        let error = match Connection::new(BAD_CONNECTION, USER, PASSWORD) {
-            Ok(conn) => panic!("Should not have been able to create a connection, test is wrong."),
-            Err(err) => err,
-        };
-        let code = match error {
-            OciError::Oracle(ref error_record) => &error_record.error_records()[0].0,
-            OciError::Conversion(_) => {
-                panic!("Should not have found a conversion error, test is wrong.")
+        let words = ["Podgy", "Rumpy", "Wiggle", "Ponder", "Spice", "Constable"];
+
+        for word in words.iter() {
+            if let Err(err) = insert.bind(&[word]) {
+                panic!("{}", err)
+            }
+            if let Err(err) = insert.execute() {
+                panic!("{}", err)
             }
+        }
+
+        let sql_query = "SELECT * FROM Words";
+        let mut select = match conn.create_prepared_statement(sql_query) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
         };
-        let tns_listener_error: i32 = 12514;
-        assert_eq!(&tns_listener_error, code)
+
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+
+        if let Err(err) = select.set_prefetch(5) {
+            panic!("{}", err)
+        }
+
+        let result_set = match select.result_set() {
+            Ok(res) => res,
+            Err(err) => panic!("{}", err),
+        };
+
+        if result_set.is_empty() {
+            panic!("Should not have an empty result")
+        }
+
+        for row in result_set {
+            let word: String = row[0].value().unwrap();
+            assert!(words.contains(&word.as_ref()));
+        }
     }
 
     #[test]
-    fn create_prepared_statement() {
+    fn switching_prefetch_policy_lifts_memory_limit() {
+        use crate::statement::Prefetch;
+
         let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
             Ok(conn) => conn,
             Err(err) => panic!("Failed to create a connection: {}", err),
         };
-        let sql_drop = "DROP TABLE Books";
-        let mut drop = match conn.create_prepared_statement(sql_drop) {
-            Ok(s) => s,
-            Err(err) => panic!("Failed to prepare drop Books: {}", err),
-        };
-        drop.execute().ok();
-        let sql_create = "CREATE TABLE Books(BookID int, Name varchar(200))";
-        let mut stmt = match conn.create_prepared_statement(sql_create) {
-            Ok(s) => s,
-            Err(err) => panic!("Failed to create a statement: {}", err),
+        let sql_query = "SELECT LEVEL FROM dual CONNECT BY LEVEL <= 50";
+        let mut select = match conn.create_prepared_statement(sql_query) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
         };
-        if let Err(err) = stmt.execute() {
-            panic!("Failed to execute: {}", err)
+        assert_eq!(select.prefetch_memory(), None);
+
+        if let Err(err) = select.set_prefetch(Prefetch::Memory(64)) {
+            panic!("{}", err)
         }
-        if let Err(err) = stmt.commit() {
-            panic!("Failed to commit: {}", err)
+        assert_eq!(select.prefetch_memory(), Some(64));
+        if let Err(err) = select.set_prefetch(Prefetch::Rows(20)) {
+            panic!("{}", err)
+        }
+        assert_eq!(select.prefetch_memory(), None);
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        match select.result_set() {
+            Ok(rows) => assert_eq!(rows.len(), 50),
+            Err(err) => panic!("{}", err),
+        }
+
+        if let Err(err) = select.set_prefetch(Prefetch::Memory(64)) {
+            panic!("{}", err)
+        }
+        if let Err(err) = select.set_prefetch(Prefetch::Auto) {
+            panic!("{}", err)
+        }
+        assert_eq!(select.prefetch_memory(), None);
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        match select.result_set() {
+            Ok(rows) => assert_eq!(rows.len(), 50),
+            Err(err) => panic!("{}", err),
         }
     }
 
     #[test]
-    fn bind() {
+    fn multi_row_query_with_adaptive_prefetch() {
         let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
             Ok(conn) => conn,
             Err(err) => panic!("Failed to create a connection: {}", err),
         };
-        let sql_drop = "DROP TABLE Fruit";
+        let sql_drop = "DROP TABLE Nouns";
         let mut drop = match conn.create_prepared_statement(sql_drop) {
-            Ok(s) => s,
+            Ok(stmt) => stmt,
             Err(err) => panic!("{}", err),
         };
         drop.execute().ok();
-        let sql_create = "CREATE TABLE Fruit(FruitId integer, Name varchar(20))";
+        let sql_create = "CREATE TABLE Nouns(NounId integer, Noun varchar(100))";
         let mut create = match conn.create_prepared_statement(sql_create) {
             Ok(stmt) => stmt,
             Err(err) => panic!("{}", err),
@@ -592,51 +6297,76 @@ mod tests {
         if let Err(err) = create.execute() {
             panic!("{}", err)
         }
-        let sql_insert = "INSERT INTO Fruit(FruitId, Name) VALUES(:id, :name)";
+        let sql_insert = "INSERT INTO Nouns(NounId, Noun) VALUES(:id, :noun)";
         let mut insert = match conn.create_prepared_statement(sql_insert) {
             Ok(stmt) => stmt,
             Err(err) => panic!("{}", err),
         };
-        let name = String::from("Apple");
-        let id: i64 = 22;
-        if let Err(err) = insert.bind(&[&id, &name]) {
-            panic!("{}", err)
-        }
-        if let Err(err) = insert.execute() {
-            panic!("{}", err)
-        }
-        let name = String::from("Pear");
-        let id: i64 = 23;
-        if let Err(err) = insert.bind(&[&id, &name]) {
-            panic!("{}", err)
+
+        for id in 0..100 {
+            if let Err(err) = insert.bind(&[&id, &"Teapot"]) {
+                panic!("{}", err)
+            }
+            if let Err(err) = insert.execute() {
+                panic!("{}", err)
+            }
         }
-        if let Err(err) = insert.execute() {
+
+        let sql_query = "SELECT * FROM Nouns ORDER BY NounId";
+        let mut select = match conn.create_prepared_statement(sql_query) {
+            Ok(stmt) => stmt,
+            Err(err) => panic!("{}", err),
+        };
+
+        if let Err(err) = select.set_adaptive_prefetch(1024 * 1024) {
             panic!("{}", err)
         }
-        if let Err(err) = insert.bind(&[&24, &"Banana".to_string()]) {
+
+        if let Err(err) = select.execute() {
             panic!("{}", err)
         }
-        if let Err(err) = insert.execute() {
-            panic!("{}", err)
+
+        let result_set = match select.result_set() {
+            Ok(res) => res,
+            Err(err) => panic!("{}", err),
+        };
+        assert_eq!(result_set.len(), 100);
+        for (expected_id, row) in (0..100).zip(result_set.iter()) {
+            let id: i64 = row[0].value().expect("Not an i64");
+            let noun: String = row[1].value().expect("Not a String");
+            assert_eq!(id, expected_id);
+            assert_eq!(noun, "Teapot");
         }
-        if let Err(err) = insert.commit() {
-            panic!("{}", err)
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Planet {
+        name: String,
+        moons: i64,
+    }
+
+    impl FromRow for Planet {
+        fn from_row(row: &Row) -> Result<Planet, OciError> {
+            Ok(Planet {
+                name: row.try_get("Name")?,
+                moons: row.try_get("Moons")?,
+            })
         }
     }
 
     #[test]
-    fn query() {
+    fn typed_result_set_maps_columns_by_name() {
         let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
             Ok(conn) => conn,
             Err(err) => panic!("Failed to create a connection: {}", err),
         };
-        let sql_drop = "DROP TABLE Cars";
+        let sql_drop = "DROP TABLE Planets";
         let mut drop = match conn.create_prepared_statement(sql_drop) {
-            Ok(s) => s,
+            Ok(stmt) => stmt,
             Err(err) => panic!("{}", err),
         };
         drop.execute().ok();
-        let sql_create = "CREATE TABLE Cars(CarId integer, Name varchar(20))";
+        let sql_create = "CREATE TABLE Planets(Moons integer, Name varchar(20))";
         let mut create = match conn.create_prepared_statement(sql_create) {
             Ok(stmt) => stmt,
             Err(err) => panic!("{}", err),
@@ -644,49 +6374,54 @@ mod tests {
         if let Err(err) = create.execute() {
             panic!("{}", err)
         }
-        let sql_insert = "INSERT INTO Cars(CarId, Name) VALUES('12', 'BMW')";
+        let sql_insert = "INSERT INTO Planets(Moons, Name) VALUES(:moons, :name)";
         let mut insert = match conn.create_prepared_statement(sql_insert) {
             Ok(stmt) => stmt,
             Err(err) => panic!("{}", err),
         };
+        if let Err(err) = insert.bind(&[&2, &"Mars"]) {
+            panic!("{}", err)
+        }
         if let Err(err) = insert.execute() {
             panic!("{}", err)
         }
-        let sql_query = "SELECT * FROM Cars";
+
+        let sql_query = "SELECT * FROM Planets";
         let mut select = match conn.create_prepared_statement(sql_query) {
             Ok(stmt) => stmt,
             Err(err) => panic!("{}", err),
         };
+        select.warn_on_select_star(true);
         if let Err(err) = select.execute() {
             panic!("{}", err)
         }
-        let result_set = match select.result_set() {
-            Ok(res) => res,
+        let planets: Vec<Planet> = match select.typed_result_set() {
+            Ok(planets) => planets,
             Err(err) => panic!("{}", err),
         };
-        if result_set.is_empty() {
-            panic!("Should not have an empty result")
-        }
-        let row = &result_set[0];
-        let car_id: i64 = row[0].value().expect("Not an i64");
-        assert_eq!(car_id, 12);
-        let car_name: String = row[1].value().expect("Not a string");
-        assert_eq!(car_name, "BMW")
+        let mars = Planet {
+            name: "Mars".to_string(),
+            moons: 2,
+        };
+        assert_eq!(planets, vec![mars]);
     }
 
     #[test]
-    fn multi_row_query() {
+    fn versioned_rows_carry_their_scn() {
+        use crate::scn::{changed_since_query, Scn, Versioned};
+
         let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
             Ok(conn) => conn,
             Err(err) => panic!("Failed to create a connection: {}", err),
         };
-        let sql_drop = "DROP TABLE Flowers";
+        let sql_drop = "DROP TABLE Dwarf_Planets";
         let mut drop = match conn.create_prepared_statement(sql_drop) {
             Ok(stmt) => stmt,
             Err(err) => panic!("{}", err),
         };
         drop.execute().ok();
-        let sql_create = "CREATE TABLE Flowers(FlowerId integer, Name varchar(20))";
+        let sql_create =
+            "CREATE TABLE Dwarf_Planets(Moons integer, Name varchar(20)) ROWDEPENDENCIES";
         let mut create = match conn.create_prepared_statement(sql_create) {
             Ok(stmt) => stmt,
             Err(err) => panic!("{}", err),
@@ -694,61 +6429,71 @@ mod tests {
         if let Err(err) = create.execute() {
             panic!("{}", err)
         }
-        let sql_insert = "INSERT INTO Flowers(FlowerId, Name) VALUES(:id, :name)";
+        let sql_insert = "INSERT INTO Dwarf_Planets(Moons, Name) VALUES(:moons, :name)";
         let mut insert = match conn.create_prepared_statement(sql_insert) {
             Ok(stmt) => stmt,
             Err(err) => panic!("{}", err),
         };
-        if let Err(err) = insert.bind(&[&1, &"Rose".to_string()]) {
+        if let Err(err) = insert.bind(&[&5, &"Pluto"]) {
             panic!("{}", err)
         }
         if let Err(err) = insert.execute() {
             panic!("{}", err)
         }
-        if let Err(err) = insert.bind(&[&2, &"Tulip".to_string()]) {
-            panic!("{}", err)
-        }
-        if let Err(err) = insert.execute() {
+        if let Err(err) = insert.commit() {
             panic!("{}", err)
         }
-        let sql_query = "SELECT * FROM Flowers";
-        let mut select = match conn.create_prepared_statement(sql_query) {
+
+        let sql_query = match changed_since_query("Dwarf_Planets", &["Moons", "Name"]) {
+            Ok(sql_query) => sql_query,
+            Err(err) => panic!("{}", err),
+        };
+        let mut select = match conn.create_prepared_statement(&sql_query) {
             Ok(stmt) => stmt,
             Err(err) => panic!("{}", err),
         };
+        if let Err(err) = select.bind(&[&Scn::new(0)]) {
+            panic!("{}", err)
+        }
         if let Err(err) = select.execute() {
             panic!("{}", err)
         }
-        let result_set = match select.result_set() {
-            Ok(res) => res,
+        let planets: Vec<Versioned<Planet>> = match select.typed_result_set() {
+            Ok(planets) => planets,
             Err(err) => panic!("{}", err),
         };
-        if result_set.is_empty() {
-            panic!("Should not have an empty result")
+        assert_eq!(planets.len(), 1);
+        assert_eq!(planets[0].value().name, "Pluto");
+        assert!(planets[0].scn() > Scn::new(0));
+
+        if let Err(err) = select.bind(&[&planets[0].scn()]) {
+            panic!("{}", err)
         }
-        let pairs = [(1, "Rose"), (2, "Tulip")];
-        for (index, pair) in pairs.iter().enumerate() {
-            let row = &result_set[index];
-            let flower_id: i64 = row[0].value().expect("Not an i64");
-            let flower_name: String = row[1].value().expect("Not a string");
-            assert_eq!(flower_id, pair.0);
-            assert_eq!(flower_name, pair.1);
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
         }
+        let planets: Vec<Versioned<Planet>> = match select.typed_result_set() {
+            Ok(planets) => planets,
+            Err(err) => panic!("{}", err),
+        };
+        assert!(planets.is_empty());
     }
 
     #[test]
-    fn multi_row_query_with_prefetch() {
+    fn export_resumes_from_checkpoint() {
+        use crate::export::{Export, MemoryCheckpointStore};
+
         let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
             Ok(conn) => conn,
             Err(err) => panic!("Failed to create a connection: {}", err),
         };
-        let sql_drop = "DROP TABLE Words";
+        let sql_drop = "DROP TABLE Parcels";
         let mut drop = match conn.create_prepared_statement(sql_drop) {
             Ok(stmt) => stmt,
             Err(err) => panic!("{}", err),
         };
         drop.execute().ok();
-        let sql_create = "CREATE TABLE Words(Word varchar(100))";
+        let sql_create = "CREATE TABLE Parcels(ParcelId integer PRIMARY KEY, Label varchar(20))";
         let mut create = match conn.create_prepared_statement(sql_create) {
             Ok(stmt) => stmt,
             Err(err) => panic!("{}", err),
@@ -756,16 +6501,13 @@ mod tests {
         if let Err(err) = create.execute() {
             panic!("{}", err)
         }
-        let sql_insert = "INSERT INTO Words(Word) VALUES(:word)";
+        let sql_insert = "INSERT INTO Parcels(ParcelId, Label) VALUES(:id, :label)";
         let mut insert = match conn.create_prepared_statement(sql_insert) {
             Ok(stmt) => stmt,
             Err(err) => panic!("{}", err),
         };
-
-        let words = ["Podgy", "Rumpy", "Wiggle", "Ponder", "Spice", "Constable"];
-
-        for word in words.iter() {
-            if let Err(err) = insert.bind(&[word]) {
+        for id in 1..=10 {
+            if let Err(err) = insert.bind(&[&id, &"Parcel"]) {
                 panic!("{}", err)
             }
             if let Err(err) = insert.execute() {
@@ -773,33 +6515,89 @@ mod tests {
             }
         }
 
-        let sql_query = "SELECT * FROM Words";
-        let mut select = match conn.create_prepared_statement(sql_query) {
-            Ok(stmt) => stmt,
+        let export = Export::new("Parcels", "ParcelId").checkpoint_every(3);
+        let mut store = MemoryCheckpointStore::new();
+        let mut exported: Vec<i64> = Vec::new();
+        let interrupted = export.run(&conn, &mut store, |row| {
+            let id: i64 = row.try_get("ParcelId")?;
+            if id == 8 {
+                return Err(OciError::Conversion("Network went down".into()));
+            }
+            exported.push(id);
+            Ok(())
+        });
+        assert!(interrupted.is_err());
+        match store.checkpoint() {
+            Some(checkpoint) => assert_eq!(checkpoint.rows(), 6),
+            None => panic!("No checkpoint saved"),
+        }
+
+        exported.truncate(6);
+        let rows = match export.run(&conn, &mut store, |row| {
+            exported.push(row.try_get("ParcelId")?);
+            Ok(())
+        }) {
+            Ok(rows) => rows,
             Err(err) => panic!("{}", err),
         };
+        assert_eq!(rows, 10);
+        assert_eq!(exported, (1..=10).collect::<Vec<i64>>());
+    }
 
-        if let Err(err) = select.execute() {
-            panic!("{}", err)
-        }
+    #[test]
+    fn describe_several_tables_at_once() {
+        use crate::describe::describe_tables;
 
-        if let Err(err) = select.set_prefetch(5) {
-            panic!("{}", err)
+        let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
+            Ok(conn) => conn,
+            Err(err) => panic!("Failed to create a connection: {}", err),
+        };
+        let tables = [
+            (
+                "Kettles",
+                "CREATE TABLE Kettles(KettleId integer NOT NULL, Name varchar(20))",
+            ),
+            ("Mugs", "CREATE TABLE Mugs(MugId integer, Volume float)"),
+        ];
+        for &(table, sql_create) in &tables {
+            let sql_drop = format!("DROP TABLE {}", table);
+            let mut drop = match conn.create_prepared_statement(&sql_drop) {
+                Ok(stmt) => stmt,
+                Err(err) => panic!("{}", err),
+            };
+            drop.execute().ok();
+            let mut create = match conn.create_prepared_statement(sql_create) {
+                Ok(stmt) => stmt,
+                Err(err) => panic!("{}", err),
+            };
+            if let Err(err) = create.execute() {
+                panic!("{}", err)
+            }
         }
 
-        let result_set = match select.result_set() {
-            Ok(res) => res,
+        let descriptions = match describe_tables(&conn, &["Mugs", "Missing", "Kettles"]) {
+            Ok(descriptions) => descriptions,
             Err(err) => panic!("{}", err),
         };
+        assert_eq!(descriptions.len(), 2);
+        assert_eq!(descriptions[0].name(), "MUGS");
+        assert_eq!(descriptions[1].name(), "KETTLES");
+        let kettle_columns = descriptions[1].columns();
+        assert_eq!(kettle_columns.len(), 2);
+        assert_eq!(kettle_columns[0].name(), "KETTLEID");
+        assert!(!kettle_columns[0].nullable());
+        assert_eq!(kettle_columns[1].data_type(), "VARCHAR2");
 
-        if result_set.is_empty() {
-            panic!("Should not have an empty result")
-        }
-
-        for row in result_set {
-            let word: String = row[0].value().unwrap();
-            assert!(words.contains(&word.as_ref()));
-        }
+        let qualified = format!("{}.Mugs", USER);
+        let names = [
+            "Kettles", "Mugs", "KETTLES", "\"MUGS\"", &qualified, "Kettles",
+        ];
+        let descriptions = match describe_tables(&conn, &names) {
+            Ok(descriptions) => descriptions,
+            Err(err) => panic!("{}", err),
+        };
+        let described: Vec<&str> = descriptions.iter().map(|table| table.name()).collect();
+        assert_eq!(described, ["KETTLES", "MUGS"]);
     }
 
     #[test]
@@ -847,8 +6645,12 @@ mod tests {
         if let Err(err) = select.execute() {
             panic!("{}", err)
         }
+        let rows = match select.lazy_result_set() {
+            Ok(rows) => rows,
+            Err(err) => panic!("{}", err),
+        };
         let mut result_set = Vec::new();
-        for row_result in select.lazy_result_set() {
+        for row_result in rows {
             match row_result {
                 Ok(row) => result_set.push(row),
                 Err(err) => panic!("{}", err),
@@ -868,8 +6670,9 @@ mod tests {
     }
 
     #[test]
-    #[should_panic]
     fn lazy_multi_row_query_repeat_call() {
+        use crate::oci_error::UsageError;
+
         let conn = match Connection::new(CONNECTION, USER, PASSWORD) {
             Ok(conn) => conn,
             Err(err) => panic!("Failed to create a connection: {}", err),
@@ -913,8 +6716,12 @@ mod tests {
         if let Err(err) = select.execute() {
             panic!("{}", err)
         }
+        let rows = match select.lazy_result_set() {
+            Ok(rows) => rows,
+            Err(err) => panic!("{}", err),
+        };
         let mut result_set = Vec::new();
-        for row_result in select.lazy_result_set() {
+        for row_result in rows {
             match row_result {
                 Ok(row) => result_set.push(row),
                 Err(err) => panic!("{}", err),
@@ -932,13 +6739,32 @@ mod tests {
             assert_eq!(bird_name, pair.1);
         }
 
+        match select.lazy_result_set() {
+            Err(OciError::Usage(UsageError::ResultSetConsumed(_))) => (),
+            Err(err) => panic!("{}", err),
+            Ok(_) => panic!("The result set should already have been consumed"),
+        }
+        match select.result_set() {
+            Err(OciError::Usage(UsageError::ResultSetConsumed(_))) => (),
+            Err(err) => panic!("{}", err),
+            Ok(_) => panic!("The result set should already have been consumed"),
+        }
+
+        if let Err(err) = select.execute() {
+            panic!("{}", err)
+        }
+        let rows = match select.lazy_result_set() {
+            Ok(rows) => rows,
+            Err(err) => panic!("{}", err),
+        };
         let mut repeat_result_set = Vec::new();
-        for row_result in select.lazy_result_set() {
+        for row_result in rows {
             match row_result {
                 Ok(row) => repeat_result_set.push(row),
                 Err(err) => panic!("{}", err),
             }
         }
+        assert_eq!(repeat_result_set.len(), 2);
     }
 
     #[test]
@@ -1302,5 +7128,4 @@ mod tests {
             panic!("Couldn't execute insert into big_objects: {}", err)
         }
     }
-
 }
@@ -1,4 +1,4 @@
-use libc::{c_int, c_uchar, c_uint, c_ushort, c_void, size_t};
+use libc::{c_int, c_schar, c_uchar, c_uint, c_ushort, c_void, size_t};
 
 #[repr(C)]
 pub struct OCIEnv {
@@ -40,14 +40,70 @@ pub struct OCIParam {
 pub struct OCIDefine {
     _private: [u8; 0],
 }
+#[repr(C)]
+pub struct OCILobLocator {
+    _private: [u8; 0],
+}
+#[repr(C)]
+pub struct OCIRowid {
+    _private: [u8; 0],
+}
+#[repr(C)]
+pub struct OCIDateTime {
+    _private: [u8; 0],
+}
+#[repr(C)]
+pub struct OCIType {
+    _private: [u8; 0],
+}
+#[repr(C)]
+pub struct OCITypeElem {
+    _private: [u8; 0],
+}
+#[repr(C)]
+pub struct OCIColl {
+    _private: [u8; 0],
+}
+#[repr(C)]
+pub struct OCIString {
+    _private: [u8; 0],
+}
+
+/// A date as it is held in an object or collection, rather than in Oracle's seven byte format.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct OCIDate {
+    pub year: i16,
+    pub month: c_uchar,
+    pub day: c_uchar,
+    pub hour: c_uchar,
+    pub minute: c_uchar,
+    pub second: c_uchar,
+}
 
 const OCI_DEFAULT: c_uint = 0;
 const OCI_THREADED: c_uint = 1;
-
-#[derive(Debug)]
+const OCI_MIGRATE: c_uint = 0x01;
+const OCI_STMT_CACHE: c_uint = 0x40;
+const OCI_DESCRIBE_ONLY: c_uint = 0x10;
+const OCI_BATCH_ERRORS: c_uint = 0x80;
+const OCI_COMMIT_ON_SUCCESS: c_uint = 0x20;
+const OCI_STMT_SCROLLABLE_READONLY: c_uint = 0x08;
+const OCI_PARSE_ONLY: c_uint = 0x100;
+const OCI_STRLS_CACHE_DELETE: c_uint = 0x10;
+
+#[derive(Debug, Clone, Copy)]
 pub enum EnvironmentMode {
     Default,
     Threaded,
+    Migrate,
+    StatementCache,
+    DescribeOnly,
+    BatchErrors,
+    CommitOnSuccess,
+    ScrollableReadOnly,
+    ParseOnly,
+    CacheDelete,
 }
 
 impl From<EnvironmentMode> for c_uint {
@@ -55,6 +111,14 @@ impl From<EnvironmentMode> for c_uint {
         match mode {
             EnvironmentMode::Default => OCI_DEFAULT,
             EnvironmentMode::Threaded => OCI_THREADED,
+            EnvironmentMode::Migrate => OCI_MIGRATE,
+            EnvironmentMode::StatementCache => OCI_STMT_CACHE,
+            EnvironmentMode::DescribeOnly => OCI_DESCRIBE_ONLY,
+            EnvironmentMode::BatchErrors => OCI_BATCH_ERRORS,
+            EnvironmentMode::CommitOnSuccess => OCI_COMMIT_ON_SUCCESS,
+            EnvironmentMode::ScrollableReadOnly => OCI_STMT_SCROLLABLE_READONLY,
+            EnvironmentMode::ParseOnly => OCI_PARSE_ONLY,
+            EnvironmentMode::CacheDelete => OCI_STRLS_CACHE_DELETE,
         }
     }
 }
@@ -64,6 +128,7 @@ const OCI_SUCCESS_WITH_INFO: c_int = 1;
 const OCI_ERROR: c_int = -1;
 const OCI_NO_DATA: c_int = 100;
 const OCI_INVALID_HANDLE: c_int = -2;
+const OCI_STILL_EXECUTING: c_int = -3123;
 
 #[derive(Debug)]
 pub enum ReturnCode {
@@ -72,6 +137,8 @@ pub enum ReturnCode {
     Error,
     NoData,
     InvalidHandle,
+    StillExecuting,
+    Unknown(c_int),
 }
 
 impl From<c_int> for ReturnCode {
@@ -82,10 +149,8 @@ impl From<c_int> for ReturnCode {
             OCI_NO_DATA => ReturnCode::NoData,
             OCI_INVALID_HANDLE => ReturnCode::InvalidHandle,
             OCI_ERROR => ReturnCode::Error,
-            _ => panic!(format!(
-                "Found an unknown return code: {}, this should not happen.",
-                number
-            )),
+            OCI_STILL_EXECUTING => ReturnCode::StillExecuting,
+            _ => ReturnCode::Unknown(number),
         }
     }
 }
@@ -157,33 +222,78 @@ impl<'hnd> From<HandleType> for &'hnd str {
 
 const OCI_ATTR_DATA_SIZE: c_uint = 1;
 const OCI_ATTR_DATA_TYPE: c_uint = 2;
+const OCI_ATTR_NAME: c_uint = 4;
 const OCI_ATTR_PRECISION: c_uint = 5;
 const OCI_ATTR_SCALE: c_uint = 6;
+const OCI_ATTR_TYPE_NAME: c_uint = 8;
+const OCI_ATTR_SCHEMA_NAME: c_uint = 9;
 const OCI_ATTR_SERVER: c_uint = 6;
 const OCI_ATTR_SESSION: c_uint = 7;
 const OCI_ATTR_PREFETCH_ROWS: c_uint = 11;
+const OCI_ATTR_PREFETCH_MEMORY: c_uint = 13;
 const OCI_ATTR_PARAM_COUNT: c_uint = 18;
 const OCI_ATTR_USERNAME: c_uint = 22;
 const OCI_ATTR_PASSWORD: c_uint = 23;
 const OCI_ATTR_STMT: c_uint = 24;
+const OCI_ATTR_CHARSET_ID: c_uint = 31;
+const OCI_ATTR_CHARSET_FORM: c_uint = 32;
 const OCI_ATTR_LOBEMPTY: c_uint = 45;
+const OCI_ATTR_MIGSESSION: c_uint = 86;
 const OCI_ATTR_PARAM: c_uint = 124;
+const OCI_ATTR_STMTCACHESIZE: c_uint = 176;
+const OCI_ATTR_CHAR_USED: c_uint = 285;
+const OCI_ATTR_CHAR_SIZE: c_uint = 286;
+const OCI_ATTR_MODULE: c_uint = 366;
+const OCI_ATTR_ACTION: c_uint = 367;
+const OCI_ATTR_TOKEN: c_uint = 515;
+const OCI_ATTR_IAM_PRIVKEY: c_uint = 516;
+const OCI_ATTR_CALL_TIMEOUT: c_uint = 531;
+const OCI_ATTR_IS_NULL: c_uint = 7;
+const OCI_ATTR_ROWS_FETCHED: c_uint = 197;
+const OCI_ATTR_NUM_DML_ERRORS: c_uint = 73;
+const OCI_ATTR_DML_ROW_OFFSET: c_uint = 74;
+const OCI_ATTR_ROWID: c_uint = 19;
+const OCI_ATTR_PARSE_ERROR_OFFSET: c_uint = 129;
+const OCI_ATTR_BIND_COUNT: c_uint = 190;
 
 #[derive(Debug)]
 pub enum AttributeType {
     DataSize,
     DataType,
+    Name,
     Precision,
     Scale,
+    TypeName,
+    SchemaName,
     Server,
     Session,
     PrefetchRows,
+    PrefetchMemory,
     ParameterCount,
     UserName,
     Password,
     Statement,
+    CharsetId,
+    CharsetForm,
     LobEmpty,
+    MigrationSession,
     Parameter,
+    StatementCacheSize,
+    CharUsed,
+    CharSize,
+    Module,
+    Action,
+    Token,
+    IamPrivateKey,
+    CallTimeout,
+    IsNull,
+    RowsFetched,
+    NumDmlErrors,
+    DmlRowOffset,
+    Rowid,
+    ParseErrorOffset,
+    BindCount,
+    Other(c_uint),
 }
 
 impl From<AttributeType> for c_uint {
@@ -191,32 +301,58 @@ impl From<AttributeType> for c_uint {
         match attribute_type {
             AttributeType::DataSize => OCI_ATTR_DATA_SIZE,
             AttributeType::DataType => OCI_ATTR_DATA_TYPE,
+            AttributeType::Name => OCI_ATTR_NAME,
             AttributeType::Precision => OCI_ATTR_PRECISION,
             AttributeType::Scale => OCI_ATTR_SCALE,
+            AttributeType::TypeName => OCI_ATTR_TYPE_NAME,
+            AttributeType::SchemaName => OCI_ATTR_SCHEMA_NAME,
             AttributeType::Server => OCI_ATTR_SERVER,
             AttributeType::Session => OCI_ATTR_SESSION,
             AttributeType::PrefetchRows => OCI_ATTR_PREFETCH_ROWS,
+            AttributeType::PrefetchMemory => OCI_ATTR_PREFETCH_MEMORY,
             AttributeType::ParameterCount => OCI_ATTR_PARAM_COUNT,
             AttributeType::UserName => OCI_ATTR_USERNAME,
             AttributeType::Password => OCI_ATTR_PASSWORD,
             AttributeType::Statement => OCI_ATTR_STMT,
+            AttributeType::CharsetId => OCI_ATTR_CHARSET_ID,
+            AttributeType::CharsetForm => OCI_ATTR_CHARSET_FORM,
             AttributeType::LobEmpty => OCI_ATTR_LOBEMPTY,
+            AttributeType::MigrationSession => OCI_ATTR_MIGSESSION,
             AttributeType::Parameter => OCI_ATTR_PARAM,
+            AttributeType::StatementCacheSize => OCI_ATTR_STMTCACHESIZE,
+            AttributeType::CharUsed => OCI_ATTR_CHAR_USED,
+            AttributeType::CharSize => OCI_ATTR_CHAR_SIZE,
+            AttributeType::Module => OCI_ATTR_MODULE,
+            AttributeType::Action => OCI_ATTR_ACTION,
+            AttributeType::Token => OCI_ATTR_TOKEN,
+            AttributeType::IamPrivateKey => OCI_ATTR_IAM_PRIVKEY,
+            AttributeType::CallTimeout => OCI_ATTR_CALL_TIMEOUT,
+            AttributeType::IsNull => OCI_ATTR_IS_NULL,
+            AttributeType::RowsFetched => OCI_ATTR_ROWS_FETCHED,
+            AttributeType::NumDmlErrors => OCI_ATTR_NUM_DML_ERRORS,
+            AttributeType::DmlRowOffset => OCI_ATTR_DML_ROW_OFFSET,
+            AttributeType::Rowid => OCI_ATTR_ROWID,
+            AttributeType::ParseErrorOffset => OCI_ATTR_PARSE_ERROR_OFFSET,
+            AttributeType::BindCount => OCI_ATTR_BIND_COUNT,
+            AttributeType::Other(attribute) => attribute,
         }
     }
 }
 
 const OCI_CRED_RDBMS: c_uint = 1;
+const OCI_CRED_EXT: c_uint = 2;
 
 #[derive(Debug)]
 pub enum CredentialsType {
     Rdbms,
+    External,
 }
 
 impl From<CredentialsType> for c_uint {
     fn from(credentials_type: CredentialsType) -> Self {
         match credentials_type {
             CredentialsType::Rdbms => OCI_CRED_RDBMS,
+            CredentialsType::External => OCI_CRED_EXT,
         }
     }
 }
@@ -236,17 +372,30 @@ impl From<SyntaxType> for c_uint {
     }
 }
 
-const SQLT_CHR: c_ushort = 1;
-const SQLT_NUM: c_ushort = 2;
-const SQLT_INT: c_ushort = 3;
-const SQLT_FLT: c_ushort = 4;
-const SQLT_DAT: c_ushort = 12;
-const SQLT_LBI: c_ushort = 24;
-const SQLT_AFC: c_ushort = 96;
-const SQLT_TIMESTAMP: c_ushort = 187;
-const SQLT_TIMESTAMP_INTERNAL: c_ushort = 180;
-const SQLT_TIMESTAMP_TZ: c_ushort = 188;
-const SQLT_TIMESTAMP_TZ_INTERNAL: c_ushort = 181;
+pub(crate) const SQLT_CHR: c_ushort = 1;
+pub(crate) const SQLT_NUM: c_ushort = 2;
+pub(crate) const SQLT_INT: c_ushort = 3;
+pub(crate) const SQLT_FLT: c_ushort = 4;
+pub(crate) const SQLT_VNU: c_ushort = 6;
+pub(crate) const SQLT_LNG: c_ushort = 8;
+pub(crate) const SQLT_DAT: c_ushort = 12;
+pub(crate) const SQLT_BDOUBLE: c_ushort = 22;
+pub(crate) const SQLT_BIN: c_ushort = 23;
+pub(crate) const SQLT_LBI: c_ushort = 24;
+pub(crate) const SQLT_AFC: c_ushort = 96;
+pub(crate) const SQLT_IBFLOAT: c_ushort = 100;
+pub(crate) const SQLT_IBDOUBLE: c_ushort = 101;
+pub(crate) const SQLT_RDD: c_ushort = 104;
+pub(crate) const SQLT_NTY: c_ushort = 108;
+pub(crate) const SQLT_CLOB: c_ushort = 112;
+pub(crate) const SQLT_BLOB: c_ushort = 113;
+pub(crate) const SQLT_BFILE: c_ushort = 114;
+pub(crate) const SQLT_JSON: c_ushort = 119;
+pub(crate) const SQLT_TIMESTAMP: c_ushort = 187;
+pub(crate) const SQLT_TIMESTAMP_INTERNAL: c_ushort = 180;
+pub(crate) const SQLT_TIMESTAMP_TZ: c_ushort = 188;
+pub(crate) const SQLT_TIMESTAMP_TZ_INTERNAL: c_ushort = 181;
+pub(crate) const SQLT_UROWID: c_ushort = 208;
 
 #[derive(Debug)]
 pub enum OciDataType {
@@ -254,11 +403,17 @@ pub enum OciDataType {
     SqlInt,
     SqlNum,
     SqlFloat,
+    SqlBinaryDouble,
     SqlDate,
     SqlChar,
     SqlBlob,
+    SqlRaw,
     SqlTimestamp,
     SqlTimestampTz,
+    SqlClob,
+    SqlBlobLocator,
+    SqlBFile,
+    SqlNamedType,
 }
 impl OciDataType {
     /// The number of bytes needed to respresent the data type.
@@ -266,12 +421,20 @@ impl OciDataType {
     pub fn size(&self) -> c_ushort {
         match *self {
             OciDataType::SqlVarChar => 4000,
-            OciDataType::SqlInt | OciDataType::SqlNum | OciDataType::SqlFloat => 8,
+            OciDataType::SqlInt
+            | OciDataType::SqlNum
+            | OciDataType::SqlFloat
+            | OciDataType::SqlBinaryDouble => 8,
             OciDataType::SqlDate => 7,
             OciDataType::SqlChar => 2000,
             OciDataType::SqlBlob => 0,
+            OciDataType::SqlRaw => 2000,
             OciDataType::SqlTimestamp => 11,
             OciDataType::SqlTimestampTz => 13,
+            OciDataType::SqlClob | OciDataType::SqlBlobLocator | OciDataType::SqlBFile => {
+                std::mem::size_of::<*mut OCILobLocator>() as c_ushort
+            }
+            OciDataType::SqlNamedType => std::mem::size_of::<*mut c_void>() as c_ushort,
         }
     }
 }
@@ -283,11 +446,17 @@ impl From<OciDataType> for c_ushort {
             OciDataType::SqlInt => SQLT_INT,
             OciDataType::SqlNum => SQLT_NUM,
             OciDataType::SqlFloat => SQLT_FLT,
+            OciDataType::SqlBinaryDouble => SQLT_BDOUBLE,
             OciDataType::SqlDate => SQLT_DAT,
             OciDataType::SqlBlob => SQLT_LBI,
+            OciDataType::SqlRaw => SQLT_BIN,
             OciDataType::SqlChar => SQLT_AFC,
             OciDataType::SqlTimestamp => SQLT_TIMESTAMP_INTERNAL,
             OciDataType::SqlTimestampTz => SQLT_TIMESTAMP_TZ_INTERNAL,
+            OciDataType::SqlClob => SQLT_CLOB,
+            OciDataType::SqlBlobLocator => SQLT_BLOB,
+            OciDataType::SqlBFile => SQLT_BFILE,
+            OciDataType::SqlNamedType => SQLT_NTY,
         }
     }
 }
@@ -299,11 +468,17 @@ impl<'a> From<&'a OciDataType> for c_ushort {
             OciDataType::SqlInt => SQLT_INT,
             OciDataType::SqlNum => SQLT_NUM,
             OciDataType::SqlFloat => SQLT_FLT,
+            OciDataType::SqlBinaryDouble => SQLT_BDOUBLE,
             OciDataType::SqlDate => SQLT_DAT,
             OciDataType::SqlBlob => SQLT_LBI,
+            OciDataType::SqlRaw => SQLT_BIN,
             OciDataType::SqlChar => SQLT_AFC,
             OciDataType::SqlTimestamp => SQLT_TIMESTAMP_INTERNAL,
             OciDataType::SqlTimestampTz => SQLT_TIMESTAMP_TZ_INTERNAL,
+            OciDataType::SqlClob => SQLT_CLOB,
+            OciDataType::SqlBlobLocator => SQLT_BLOB,
+            OciDataType::SqlBFile => SQLT_BFILE,
+            OciDataType::SqlNamedType => SQLT_NTY,
         }
     }
 }
@@ -315,10 +490,17 @@ impl From<c_ushort> for OciDataType {
             SQLT_INT => OciDataType::SqlInt,
             SQLT_NUM => OciDataType::SqlNum,
             SQLT_FLT => OciDataType::SqlFloat,
+            SQLT_BDOUBLE => OciDataType::SqlBinaryDouble,
             SQLT_DAT => OciDataType::SqlDate,
+            SQLT_BIN => OciDataType::SqlRaw,
+            SQLT_LBI => OciDataType::SqlBlob,
             SQLT_AFC => OciDataType::SqlChar,
             SQLT_TIMESTAMP => OciDataType::SqlTimestamp,
             SQLT_TIMESTAMP_TZ => OciDataType::SqlTimestampTz,
+            SQLT_CLOB => OciDataType::SqlClob,
+            SQLT_BLOB => OciDataType::SqlBlobLocator,
+            SQLT_BFILE => OciDataType::SqlBFile,
+            SQLT_NTY => OciDataType::SqlNamedType,
             _ => panic!(format!(
                 "Found an unknown OciDataType code, {}, this should not happen.",
                 number
@@ -337,19 +519,34 @@ const OCI_STMT_DROP: c_uint = 6;
 const OCI_STMT_ALTER: c_uint = 7;
 const OCI_STMT_BEGIN: c_uint = 8;
 const OCI_STMT_DECLARE: c_uint = 9;
+const OCI_STMT_MERGE: c_uint = 16;
 
-#[derive(Debug)]
+/// The kind of SQL statement that has been prepared.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StatementType {
+    /// A statement that is none of the others, e.g. `GRANT`.
     Unknown,
+    /// A `SELECT` query.
     Select,
+    /// An `UPDATE`.
     Update,
+    /// A `DELETE`.
     Delete,
+    /// An `INSERT`.
     Insert,
+    /// A `CREATE`.
     Create,
+    /// A `DROP`.
     Drop,
+    /// An `ALTER`.
     Alter,
+    /// A PL/SQL block starting with `BEGIN`.
     Begin,
+    /// A PL/SQL block starting with `DECLARE`.
     Declare,
+    /// A `MERGE`.
+    Merge,
 }
 
 impl From<StatementType> for c_uint {
@@ -365,6 +562,7 @@ impl From<StatementType> for c_uint {
             StatementType::Alter => OCI_STMT_ALTER,
             StatementType::Begin => OCI_STMT_BEGIN,
             StatementType::Declare => OCI_STMT_DECLARE,
+            StatementType::Merge => OCI_STMT_MERGE,
         }
     }
 }
@@ -382,6 +580,7 @@ impl From<c_uint> for StatementType {
             OCI_STMT_ALTER => StatementType::Alter,
             OCI_STMT_BEGIN => StatementType::Begin,
             OCI_STMT_DECLARE => StatementType::Declare,
+            OCI_STMT_MERGE => StatementType::Merge,
             _ => panic!(format!(
                 "Found an unknown statement type: {}, this should not happen.",
                 number
@@ -391,30 +590,41 @@ impl From<c_uint> for StatementType {
 }
 
 const OCI_DTYPE_LOB: c_uint = 50;
+const OCI_DTYPE_FILE: c_uint = 56;
 const OCI_DTYPE_PARAM: c_uint = 53;
+const OCI_DTYPE_ROWID: c_uint = 4;
+const OCI_DTYPE_TIMESTAMP_TZ: c_uint = 70;
 
 #[derive(Debug)]
 pub enum DescriptorType {
     Lob,
+    File,
     Parameter,
+    Rowid,
+    TimestampTz,
 }
 
 impl From<DescriptorType> for c_uint {
     fn from(descriptor_type: DescriptorType) -> Self {
         match descriptor_type {
             DescriptorType::Lob => OCI_DTYPE_LOB,
+            DescriptorType::File => OCI_DTYPE_FILE,
             DescriptorType::Parameter => OCI_DTYPE_PARAM,
+            DescriptorType::Rowid => OCI_DTYPE_ROWID,
+            DescriptorType::TimestampTz => OCI_DTYPE_TIMESTAMP_TZ,
         }
     }
 }
 
 const OCI_FETCH_NEXT: c_ushort = 2;
 const OCI_FETCH_FIRST: c_ushort = 4;
+const OCI_FETCH_ABSOLUTE: c_ushort = 32;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum FetchType {
     Next,
     First,
+    Absolute,
 }
 
 impl From<FetchType> for c_ushort {
@@ -422,6 +632,7 @@ impl From<FetchType> for c_ushort {
         match fetch_type {
             FetchType::Next => OCI_FETCH_NEXT,
             FetchType::First => OCI_FETCH_FIRST,
+            FetchType::Absolute => OCI_FETCH_ABSOLUTE,
         }
     }
 }
@@ -727,6 +938,16 @@ extern "C" {
     ///
     pub fn OCITransCommit(svchp: *mut OCISvcCtx, errhp: *mut OCIError, flags: c_uint) -> c_int;
 
+    /// Rolls back the current transaction.
+    /// See [Oracle docs](https://docs.oracle.com/cd/E11882_01/appdev.112/e10646/
+    /// oci17msc006.htm#LNOCI13116) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCITransRollback(svchp: *mut OCISvcCtx, errhp: *mut OCIError, flags: c_uint) -> c_int;
+
     /// Creates an association between a program variable and a placeholder in a SQL statement
     /// or PL/SQL block.
     /// See [Oracle docs](http://docs.oracle.com/database/122/LNOCI/
@@ -752,6 +973,32 @@ extern "C" {
         mode: c_uint,
     ) -> c_int;
 
+    /// Creates an association between a program variable and a named placeholder in a SQL
+    /// statement or PL/SQL block.
+    /// See [Oracle docs](http://docs.oracle.com/database/122/LNOCI/
+    /// bind-define-describe-functions.htm#LNOCI17140) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCIBindByName(
+        stmtp: *mut OCIStmt,
+        bindpp: &*mut OCIBind,
+        errhp: *mut OCIError,
+        placeholder: *const c_uchar,
+        placeh_len: c_int,
+        valuep: *mut c_void,
+        value_sz: c_int,
+        dty: c_ushort,
+        indp: *mut c_void,
+        alenp: *mut c_ushort,
+        rcodep: *mut c_ushort,
+        maxarr_len: c_uint,
+        curelep: *mut c_uint,
+        mode: c_uint,
+    ) -> c_int;
+
     /// Returns a descriptor of a parameter specified by position in the describe handle or
     /// statement handle.
     /// See [Oracle docs](http://docs.oracle.com/database/122/LNOCI/
@@ -810,6 +1057,22 @@ extern "C" {
         mode: c_uint,
     ) -> c_int;
 
+    /// Allocates storage to hold descriptors or LOB locators.
+    /// See [Oracle docs](http://docs.oracle.com/database/122/LNOCI/
+    /// handle-and-descriptor-functions.htm#LNOCI17132) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCIDescriptorAlloc(
+        parenth: *const c_void,
+        descpp: *mut *mut c_void,
+        desc_type: c_uint,
+        xtramem_sz: size_t,
+        usrmempp: *mut *mut c_void,
+    ) -> c_int;
+
     /// Deallocates a previously allocated descriptor.
     /// See [Oracle docs](http://docs.oracle.com/database/122/LNOCI/
     /// handle-and-descriptor-functions.htm#LNOCI17134) for more info.
@@ -820,4 +1083,610 @@ extern "C" {
     ///
     pub fn OCIDescriptorFree(descp: *mut c_void, desc_type: c_uint) -> c_int;
 
+    /// Gets the length of a LOB, in characters for a CLOB and in bytes for a BLOB.
+    /// See [Oracle docs](http://docs.oracle.com/database/122/LNOCI/
+    /// lob-functions.htm#LNOCI17837) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCILobGetLength2(
+        svchp: *mut OCISvcCtx,
+        errhp: *mut OCIError,
+        locp: *mut OCILobLocator,
+        lenp: *mut u64,
+    ) -> c_int;
+
+    /// Reads a portion of a LOB into a buffer.
+    /// See [Oracle docs](http://docs.oracle.com/database/122/LNOCI/
+    /// lob-functions.htm#LNOCI17848) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCILobRead2(
+        svchp: *mut OCISvcCtx,
+        errhp: *mut OCIError,
+        locp: *mut OCILobLocator,
+        byte_amtp: *mut u64,
+        char_amtp: *mut u64,
+        offset: u64,
+        bufp: *mut c_void,
+        bufl: u64,
+        piece: c_uchar,
+        ctxp: *mut c_void,
+        cbfp: *mut c_void,
+        csid: c_ushort,
+        csfrm: c_uchar,
+    ) -> c_int;
+
+    /// Sets the directory alias and file name in a BFILE locator.
+    /// See [Oracle docs](http://docs.oracle.com/database/122/LNOCI/
+    /// lob-functions.htm#LNOCI17833) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCILobFileSetName(
+        envhp: *mut OCIEnv,
+        errhp: *mut OCIError,
+        filepp: *mut *mut OCILobLocator,
+        dir_alias: *const c_uchar,
+        d_length: c_ushort,
+        filename: *const c_uchar,
+        f_length: c_ushort,
+    ) -> c_int;
+
+    /// Gets the directory alias and file name from a BFILE locator.
+    /// See [Oracle docs](http://docs.oracle.com/database/122/LNOCI/
+    /// lob-functions.htm#LNOCI17829) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCILobFileGetName(
+        envhp: *mut OCIEnv,
+        errhp: *mut OCIError,
+        filep: *const OCILobLocator,
+        dir_alias: *mut c_uchar,
+        d_length: *mut c_ushort,
+        filename: *mut c_uchar,
+        f_length: *mut c_ushort,
+    ) -> c_int;
+
+    /// Opens the file a BFILE locator points to, which has to be done before it is read.
+    /// See [Oracle docs](http://docs.oracle.com/database/122/LNOCI/
+    /// lob-functions.htm#LNOCI17830) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCILobFileOpen(
+        svchp: *mut OCISvcCtx,
+        errhp: *mut OCIError,
+        filep: *mut OCILobLocator,
+        mode: c_uchar,
+    ) -> c_int;
+
+    /// Closes a file opened with `OCILobFileOpen`.
+    /// See [Oracle docs](http://docs.oracle.com/database/122/LNOCI/
+    /// lob-functions.htm#LNOCI17824) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCILobFileClose(
+        svchp: *mut OCISvcCtx,
+        errhp: *mut OCIError,
+        filep: *mut OCILobLocator,
+    ) -> c_int;
+
+    /// Checks whether the file a BFILE locator points to exists on the server.
+    /// See [Oracle docs](http://docs.oracle.com/database/122/LNOCI/
+    /// lob-functions.htm#LNOCI17827) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCILobFileExists(
+        svchp: *mut OCISvcCtx,
+        errhp: *mut OCIError,
+        filep: *mut OCILobLocator,
+        flag: *mut c_int,
+    ) -> c_int;
+
+    /// Checks whether an Oracle number is an integer.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// oci-number-functions.htm#LNOCI17440) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCINumberIsInt(err: *mut OCIError, number: *const c_void, result: *mut c_int) -> c_int;
+
+    /// Converts an Oracle number to an integer, failing if it doesn't fit.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// oci-number-functions.htm#LNOCI17460) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCINumberToInt(
+        err: *mut OCIError,
+        number: *const c_void,
+        rsl_length: c_uint,
+        rsl_flag: c_uint,
+        rsl: *mut c_void,
+    ) -> c_int;
+
+    /// Converts an Oracle number to a floating point number.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// oci-number-functions.htm#LNOCI17461) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCINumberToReal(
+        err: *mut OCIError,
+        number: *const c_void,
+        rsl_length: c_uint,
+        rsl: *mut c_void,
+    ) -> c_int;
+
+    /// Converts an Oracle number to text, using a number format model.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// oci-number-functions.htm#LNOCI17463) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCINumberToText(
+        err: *mut OCIError,
+        number: *const c_void,
+        fmt: *const c_uchar,
+        fmt_length: c_uint,
+        nls_params: *const c_uchar,
+        nls_p_length: c_uint,
+        buf_size: *mut c_uint,
+        buf: *mut c_uchar,
+    ) -> c_int;
+
+    /// Sets a datetime descriptor from a date or timestamp in Oracle's own byte format.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// oci-date-datetime-and-interval-functions.htm#LNOCI17392) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCIDateTimeFromArray(
+        hndl: *mut c_void,
+        err: *mut OCIError,
+        inarray: *const c_uchar,
+        len: c_uint,
+        type_: c_uchar,
+        datetime: *mut OCIDateTime,
+        reftz: *const c_void,
+        fsprec: c_uchar,
+    ) -> c_int;
+
+    /// Gets the name of the time zone region of a datetime, or its offset as text when it
+    /// has no region.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// oci-date-datetime-and-interval-functions.htm#LNOCI17399) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCIDateTimeGetTimeZoneName(
+        hndl: *mut c_void,
+        err: *mut OCIError,
+        datetime: *const OCIDateTime,
+        buf: *mut c_uchar,
+        buflen: *mut c_uint,
+    ) -> c_int;
+
+    /// Gets the offset from UTC of a datetime, for a region the offset in effect at that time.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// oci-date-datetime-and-interval-functions.htm#LNOCI17400) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCIDateTimeGetTimeZoneOffset(
+        hndl: *mut c_void,
+        err: *mut OCIError,
+        datetime: *const OCIDateTime,
+        hour: *mut c_schar,
+        min: *mut c_schar,
+    ) -> c_int;
+
+    /// Makes a round trip call to the server to confirm that the connection and the server are
+    /// active.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// miscellaneous-functions.htm#LNOCI17271) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCIPing(svchp: *mut OCISvcCtx, errhp: *mut OCIError, mode: c_uint) -> c_int;
+
+    /// Aborts the call currently running on a server or service context handle, which then
+    /// returns ORA-01013.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// miscellaneous-functions.htm#LNOCI17268) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCIBreak(hndlp: *mut c_void, errhp: *mut OCIError) -> c_int;
+
+    /// Resets the protocol of a server or service context handle after a call was aborted with
+    /// `OCIBreak`.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// miscellaneous-functions.htm#LNOCI17276) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCIReset(hndlp: *mut c_void, errhp: *mut OCIError) -> c_int;
+
+    /// Gets the names of the bind placeholders of a prepared statement. The names are returned as
+    /// pointers into memory owned by the statement along with their lengths.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// bind-define-describe-functions.htm#LNOCI17151) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCIStmtGetBindInfo(
+        stmtp: *mut OCIStmt,
+        errhp: *mut OCIError,
+        size: c_uint,
+        startloc: c_uint,
+        found: *mut c_int,
+        bvnp: *mut *mut c_uchar,
+        bvnl: *mut c_uchar,
+        invp: *mut *mut c_uchar,
+        inpl: *mut c_uchar,
+        dupl: *mut c_uchar,
+        hndl: *mut *mut OCIBind,
+    ) -> c_int;
+
+    /// Converts a ROWID descriptor into its text form. On input `outbflp` holds the size of the
+    /// buffer and on output the length of the text.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// miscellaneous-functions.htm#LNOCI17277) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCIRowidToChar(
+        rowid_desc: *mut OCIRowid,
+        outbfp: *mut c_uchar,
+        outbflp: *mut c_ushort,
+        errhp: *mut OCIError,
+    ) -> c_int;
+
+    /// Returns the version of the OCI client library in use.
+    /// See [Oracle docs](http://docs.oracle.com/database/122/LNOCI/
+    /// miscellaneous-functions.htm#LNOCI17274) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCIClientVersion(
+        major_version: *mut c_int,
+        minor_version: *mut c_int,
+        update_num: *mut c_int,
+        patch_num: *mut c_int,
+        port_update_num: *mut c_int,
+    );
+
+    /// Returns the version of the database server as a banner.
+    /// See [Oracle docs](http://docs.oracle.com/database/122/LNOCI/
+    /// miscellaneous-functions.htm#LNOCI17293) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCIServerVersion(
+        hndlp: *mut c_void,
+        errhp: *mut OCIError,
+        bufp: *mut c_uchar,
+        bufsz: c_uint,
+        hndltype: c_uchar,
+    ) -> c_int;
+
+    /// Gets numeric information about the character set of an environment, such as the most
+    /// bytes a character can take up.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// oci-globalization-support-functions.htm#LNOCI17391) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCINlsNumericInfoGet(
+        envhp: *mut c_void,
+        errhp: *mut OCIError,
+        val: *mut c_int,
+        item: c_ushort,
+    ) -> c_int;
+
+    /// Gets the type descriptor of a named type, such as a collection type.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// oci-type-information-accessor-functions.htm#LNOCI17500) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCITypeByName(
+        env: *mut OCIEnv,
+        err: *mut OCIError,
+        svc: *const OCISvcCtx,
+        schema_name: *const c_uchar,
+        s_length: c_uint,
+        type_name: *const c_uchar,
+        t_length: c_uint,
+        version_name: *const c_uchar,
+        v_length: c_uint,
+        pin_duration: c_ushort,
+        get_option: c_int,
+        tdo: *mut *mut OCIType,
+    ) -> c_int;
+
+    /// Gets the element of a collection type, which describes the type of its elements.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// oci-type-information-accessor-functions.htm#LNOCI17487) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCITypeCollElem(
+        env: *mut OCIEnv,
+        err: *mut OCIError,
+        tdo: *const OCIType,
+        element: *mut *mut OCITypeElem,
+    ) -> c_int;
+
+    /// Gets the type code of an element of a type.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// oci-type-information-accessor-functions.htm#LNOCI17496) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCITypeElemTypeCode(
+        env: *mut OCIEnv,
+        err: *mut OCIError,
+        elem: *const OCITypeElem,
+    ) -> c_ushort;
+
+    /// Associates a define with the type of the object or collection fetched into it.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// bind-define-describe-functions.htm#LNOCI17141) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCIDefineObject(
+        defnp: *mut OCIDefine,
+        errhp: *mut OCIError,
+        type_: *const OCIType,
+        pgvpp: *mut *mut c_void,
+        pvszsp: *mut c_uint,
+        indpp: *mut *mut c_void,
+        indszp: *mut c_uint,
+    ) -> c_int;
+
+    /// Associates a bind with the object or collection bound to it.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// bind-define-describe-functions.htm#LNOCI17129) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCIBindObject(
+        bindp: *mut OCIBind,
+        errhp: *mut OCIError,
+        type_: *const OCIType,
+        pgvpp: *mut *mut c_void,
+        pvszsp: *mut c_uint,
+        indpp: *mut *mut c_void,
+        indszp: *mut c_uint,
+    ) -> c_int;
+
+    /// Creates an instance of an object or collection type.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// oci-navigational-and-type-functions.htm#LNOCI17213) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCIObjectNew(
+        env: *mut OCIEnv,
+        err: *mut OCIError,
+        svc: *const OCISvcCtx,
+        typecode: c_ushort,
+        tdo: *mut OCIType,
+        table: *mut c_void,
+        duration: c_ushort,
+        value: c_int,
+        instance: *mut *mut c_void,
+    ) -> c_int;
+
+    /// Frees an instance of an object or collection type.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// oci-navigational-and-type-functions.htm#LNOCI17209) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCIObjectFree(
+        env: *mut OCIEnv,
+        err: *mut OCIError,
+        instance: *mut c_void,
+        flags: c_ushort,
+    ) -> c_int;
+
+    /// Gets the number of elements in a collection.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// oci-collection-and-iterator-functions.htm#LNOCI17310) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCICollSize(
+        env: *mut OCIEnv,
+        err: *mut OCIError,
+        coll: *const OCIColl,
+        size: *mut c_int,
+    ) -> c_int;
+
+    /// Gets a pointer to the element of a collection at an index, and to its null indicator.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// oci-collection-and-iterator-functions.htm#LNOCI17306) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCICollGetElem(
+        env: *mut OCIEnv,
+        err: *mut OCIError,
+        coll: *const OCIColl,
+        index: c_int,
+        exists: *mut c_int,
+        elem: *mut *mut c_void,
+        elemind: *mut *mut c_void,
+    ) -> c_int;
+
+    /// Appends a copy of an element to the end of a collection.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// oci-collection-and-iterator-functions.htm#LNOCI17302) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCICollAppend(
+        env: *mut OCIEnv,
+        err: *mut OCIError,
+        elem: *const c_void,
+        elemind: *const c_void,
+        coll: *mut OCIColl,
+    ) -> c_int;
+
+    /// Sets a string in an object or collection to a copy of some text, allocating the string
+    /// if it is null.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// oci-string-functions.htm#LNOCI17496) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCIStringAssignText(
+        env: *mut OCIEnv,
+        err: *mut OCIError,
+        rhs: *const c_uchar,
+        rhs_len: c_uint,
+        lhs: *mut *mut OCIString,
+    ) -> c_int;
+
+    /// Resizes a string in an object or collection, a size of zero frees it.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// oci-string-functions.htm#LNOCI17498) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCIStringResize(
+        env: *mut OCIEnv,
+        err: *mut OCIError,
+        new_size: c_uint,
+        str: *mut *mut OCIString,
+    ) -> c_int;
+
+    /// Gets a pointer to the text of a string in an object or collection.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// oci-string-functions.htm#LNOCI17497) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCIStringPtr(env: *mut OCIEnv, vs: *const OCIString) -> *mut c_uchar;
+
+    /// Gets the size in bytes of a string in an object or collection.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// oci-string-functions.htm#LNOCI17499) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCIStringSize(env: *mut OCIEnv, vs: *const OCIString) -> c_uint;
+
+    /// Converts an integer to an Oracle number.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// oci-number-functions.htm#LNOCI17449) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCINumberFromInt(
+        err: *mut OCIError,
+        inum: *const c_void,
+        inum_length: c_uint,
+        inum_s_flag: c_uint,
+        number: *mut c_void,
+    ) -> c_int;
+
+    /// Converts a floating point number to an Oracle number.
+    /// See [Oracle docs](https://docs.oracle.com/database/122/LNOCI/
+    /// oci-number-functions.htm#LNOCI17450) for more info.
+    ///
+    /// # Safety
+    ///
+    /// Unsafe C
+    ///
+    pub fn OCINumberFromReal(
+        err: *mut OCIError,
+        rnum: *const c_void,
+        rnum_length: c_uint,
+        number: *mut c_void,
+    ) -> c_int;
+
 }
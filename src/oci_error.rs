@@ -1,15 +1,21 @@
+use crate::diagnostics::Diagnostics;
 use crate::oci_bindings::{HandleType, OCIErrorGet, ReturnCode};
+use crate::types::SqlValue;
 use libc::{c_int, c_uchar, c_uint, c_void};
 use std::error;
 use std::error::Error;
 use std::fmt;
 use std::ptr;
+use std::time::Duration;
 
 const MAX_ERROR_MESSAGE_SIZE: usize = 3024;
 
 /// The various errors that might result when interacting with the OCI library.
 ///
+/// More variants may be added over time, so a `match` on it needs a wildcard arm.
+///
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum OciError {
     /// Contains the Oracle error details.
     /// Everything that comes back from the database will be retuned in this variant.
@@ -17,6 +23,33 @@ pub enum OciError {
     /// Picks up any errors that might come during conversion, such as a `Utf8Error`.
     /// It will not represent any Oracle errors.
     Conversion(Box<Error + Send + Sync>),
+    /// The call was aborted by a [`CancelHandle`][1], Oracle reports this as ORA-01013.
+    ///
+    /// [1]: ../statement/struct.CancelHandle.html
+    Cancelled(ErrorRecord),
+    /// The deadline set with [`Connection.with_deadline`][1] has passed. It holds the error
+    /// from Oracle, ORA-03156, when a call was interrupted by the deadline, or `None` when the
+    /// deadline had already passed and the call was not made.
+    ///
+    /// [1]: ../connection/struct.Connection.html#method.with_deadline
+    DeadlineExceeded(Option<ErrorRecord>),
+    /// A statement run with [`Statement.execute_with_timeout`][1] took longer than the timeout
+    /// it holds and was cancelled.
+    ///
+    /// [1]: ../statement/struct.Statement.html#method.execute_with_timeout
+    TimedOut(Duration),
+    /// The crate's API was used in a way that can't work, such as binding the wrong number of
+    /// values. These are mistakes in the calling code rather than problems with the database.
+    Usage(UsageError),
+    /// A fetched value didn't fit the buffer of its column and was cut short, Oracle reports
+    /// this as ORA-01406. See [`FetchTruncated`][1].
+    ///
+    /// [1]: struct.FetchTruncated.html
+    FetchTruncated(FetchTruncated),
+    /// The client isn't set up for the chosen way of authenticating, such as a Kerberos ticket
+    /// cache that doesn't exist. It holds what was found to be wrong and no call to the database
+    /// was made.
+    Authentication(String),
 }
 
 impl fmt::Display for OciError {
@@ -24,6 +57,19 @@ impl fmt::Display for OciError {
         match *self {
             OciError::Oracle(ref err) => write!(f, "{}", err),
             OciError::Conversion(ref err) => write!(f, "{}", err),
+            OciError::Cancelled(ref err) => write!(f, "{}", err),
+            OciError::DeadlineExceeded(Some(ref err)) => write!(f, "{}", err),
+            OciError::DeadlineExceeded(None) => {
+                write!(f, "The deadline for the database call has passed")
+            }
+            OciError::TimedOut(timeout) => write!(
+                f,
+                "The statement was cancelled after running for longer than {:?}",
+                timeout
+            ),
+            OciError::Usage(ref err) => write!(f, "{}", err),
+            OciError::FetchTruncated(ref err) => write!(f, "{}", err),
+            OciError::Authentication(ref err) => write!(f, "{}", err),
         }
     }
 }
@@ -33,6 +79,12 @@ impl error::Error for OciError {
         match *self {
             OciError::Oracle(_) => "Oracle error",
             OciError::Conversion(_) => "Cannot convert from OCI to Rust type",
+            OciError::Cancelled(_) => "Oracle call cancelled",
+            OciError::DeadlineExceeded(_) => "Deadline for Oracle call exceeded",
+            OciError::TimedOut(_) => "Oracle statement timed out",
+            OciError::Usage(_) => "Incorrect use of the API",
+            OciError::FetchTruncated(_) => "Fetched value truncated",
+            OciError::Authentication(_) => "Authentication not set up",
         }
     }
 
@@ -40,8 +92,454 @@ impl error::Error for OciError {
         match *self {
             OciError::Oracle(_) => None,
             OciError::Conversion(ref err) => Some(err.as_ref()),
+            OciError::Cancelled(_) => None,
+            OciError::DeadlineExceeded(_) => None,
+            OciError::TimedOut(_) => None,
+            OciError::Usage(ref err) => Some(err),
+            OciError::FetchTruncated(ref err) => Some(err),
+            OciError::Authentication(_) => None,
+        }
+    }
+}
+
+/// The ways the crate's API can be used incorrectly, returned inside `OciError::Usage`.
+///
+/// The variants that hold a `String` hold a description of what was wrong.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum UsageError {
+    /// See [`ResultSetConsumed`][1].
+    ///
+    /// [1]: struct.ResultSetConsumed.html
+    ResultSetConsumed(ResultSetConsumed),
+    /// See [`BindCountMismatch`][1].
+    ///
+    /// [1]: struct.BindCountMismatch.html
+    BindCountMismatch(BindCountMismatch),
+    /// The values bound don't fit the statement, e.g. a placeholder that isn't in the SQL, one
+    /// that was given no value, or rows of a batch with different numbers of values.
+    Bind(String),
+    /// A column that isn't in the row was asked for, or the row has a different number of
+    /// columns to the type it is converted into.
+    Column(String),
+    /// A name that is put into SQL, such as that of a constraint or a procedure, isn't a valid
+    /// identifier.
+    InvalidName(String),
+    /// The statement can't do what was asked of it, e.g. execute a `SELECT` as a batch or
+    /// fetch a row by position without a scrollable cursor, or an argument is out of range.
+    Unsupported(String),
+}
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            UsageError::ResultSetConsumed(ref err) => write!(f, "{}", err),
+            UsageError::BindCountMismatch(ref err) => write!(f, "{}", err),
+            UsageError::Bind(ref description)
+            | UsageError::Column(ref description)
+            | UsageError::InvalidName(ref description)
+            | UsageError::Unsupported(ref description) => write!(f, "{}", description),
+        }
+    }
+}
+
+impl From<UsageError> for OciError {
+    fn from(err: UsageError) -> Self {
+        OciError::Usage(err)
+    }
+}
+
+impl error::Error for UsageError {}
+
+/// Returned, inside `OciError::Usage`, when the rows of a result set are asked for after
+/// they have already been handed out by [`Statement.lazy_result_set`][1], or fetched by
+/// `.result_set` in the case of `.lazy_result_set`. Executing the statement again gives a new
+/// result set.
+///
+/// [1]: ../statement/struct.Statement.html#method.lazy_result_set
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResultSetConsumed;
+
+impl fmt::Display for ResultSetConsumed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "The result set has already been fetched, execute the statement again for new results"
+        )
+    }
+}
+
+impl error::Error for ResultSetConsumed {}
+
+impl From<ResultSetConsumed> for OciError {
+    fn from(err: ResultSetConsumed) -> Self {
+        OciError::Usage(UsageError::ResultSetConsumed(err))
+    }
+}
+
+/// Returned, inside `OciError::Usage`, when the number of values bound to a statement
+/// doesn't match the number of placeholders in its SQL.
+///
+/// It is returned by [`Statement.bind`][1] when given the wrong number of parameters, and when a
+/// statement with placeholders is executed with nothing bound, e.g. after
+/// [`Statement.clear_binds`][2].
+///
+/// [1]: ../statement/struct.Statement.html#method.bind
+/// [2]: ../statement/struct.Statement.html#method.clear_binds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BindCountMismatch {
+    placeholders: usize,
+    bound: usize,
+}
+impl BindCountMismatch {
+    pub(crate) fn new(placeholders: usize, bound: usize) -> BindCountMismatch {
+        BindCountMismatch {
+            placeholders,
+            bound,
         }
     }
+
+    /// Returns the number of placeholders in the SQL.
+    pub fn placeholders(&self) -> usize {
+        self.placeholders
+    }
+
+    /// Returns the number of values that were bound.
+    pub fn bound(&self) -> usize {
+        self.bound
+    }
+}
+
+impl fmt::Display for BindCountMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "The SQL has {} placeholders but {} values were bound",
+            self.placeholders, self.bound
+        )
+    }
+}
+
+impl error::Error for BindCountMismatch {}
+
+impl From<BindCountMismatch> for OciError {
+    fn from(err: BindCountMismatch) -> Self {
+        OciError::Usage(UsageError::BindCountMismatch(err))
+    }
+}
+
+/// Returned, inside `OciError::FetchTruncated`, when a value fetched from a text or raw column
+/// is too long for the buffer the column is fetched into.
+///
+/// Oracle cuts the value short to fit, so rather than return part of it the fetch fails with
+/// this error. It can happen when a column is limited with [`Statement.set_column_cap`][1], or
+/// for text that grows when it is converted to the client character set, which is prevented
+/// by [`Statement.set_safe_fetch`][2].
+///
+/// [1]: ../statement/struct.Statement.html#method.set_column_cap
+/// [2]: ../statement/struct.Statement.html#method.set_safe_fetch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchTruncated {
+    column: String,
+    buffer_size: usize,
+    length: Option<usize>,
+}
+impl FetchTruncated {
+    pub(crate) fn new(column: &str, buffer_size: usize, length: Option<usize>) -> FetchTruncated {
+        FetchTruncated {
+            column: column.to_string(),
+            buffer_size,
+            length,
+        }
+    }
+
+    /// Returns the name of the column.
+    pub fn column(&self) -> &str {
+        &self.column
+    }
+
+    /// Returns the size, in bytes, of the buffer the column was fetched into.
+    pub fn buffer_size(&self) -> usize {
+        self.buffer_size
+    }
+
+    /// Returns the length, in bytes, of the whole value, if Oracle reported it. Oracle can only
+    /// report lengths up to 32,767 bytes.
+    pub fn length(&self) -> Option<usize> {
+        self.length
+    }
+}
+
+impl fmt::Display for FetchTruncated {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "A value of column {} was truncated to fit its {} byte buffer",
+            self.column, self.buffer_size
+        )?;
+        if let Some(length) = self.length {
+            write!(f, ", it is {} bytes long", length)?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for FetchTruncated {}
+
+impl From<FetchTruncated> for OciError {
+    fn from(err: FetchTruncated) -> Self {
+        OciError::FetchTruncated(err)
+    }
+}
+
+/// Returned by [`SqlValue.try_value`][1] when a value can't be converted to the type asked for,
+/// such as a `NUMBER` that doesn't fit in it. It converts into an `OciError::Conversion`, so
+/// `?` can be used with it in a function that returns an `OciError`.
+///
+/// [1]: ../types/enum.SqlValue.html#method.try_value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversionError {
+    sql_type: &'static str,
+    value: String,
+    target: &'static str,
+}
+impl ConversionError {
+    pub(crate) fn new(value: &SqlValue, target: &'static str) -> ConversionError {
+        ConversionError {
+            sql_type: value.sql_type_name(),
+            value: format!("{:?}", value),
+            target,
+        }
+    }
+
+    /// Returns the SQL type of the value, such as `NUMBER` or `VARCHAR2`.
+    pub fn sql_type(&self) -> &str {
+        self.sql_type
+    }
+
+    /// Returns the value that couldn't be converted, as it is debug formatted.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Returns the name of the Rust type the value couldn't be converted to.
+    pub fn target(&self) -> &str {
+        self.target
+    }
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} can't be converted to {}",
+            self.sql_type, self.value, self.target
+        )
+    }
+}
+
+impl error::Error for ConversionError {}
+
+impl From<ConversionError> for OciError {
+    fn from(err: ConversionError) -> Self {
+        OciError::Conversion(Box::new(err))
+    }
+}
+
+/// Returned by [`Row.try_get`][1] when a column's value can't be converted to the type asked
+/// for. Unlike a `None` from `SqlValue.value` it says which column failed, the SQL type it holds
+/// and the Rust type asked for. It converts into an `OciError::Conversion`.
+///
+/// [1]: ../row/struct.Row.html#method.try_get
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnConversionError {
+    column: String,
+    position: usize,
+    sql_type: String,
+    value: String,
+    target: &'static str,
+}
+impl ColumnConversionError {
+    pub(crate) fn new(
+        column: &str,
+        position: usize,
+        value: &SqlValue,
+        target: &'static str,
+    ) -> ColumnConversionError {
+        let sql_type = match value.null_type() {
+            Some(null_type) => null_type.to_string(),
+            None => value.sql_type_name().to_string(),
+        };
+        ColumnConversionError {
+            column: column.to_string(),
+            position,
+            sql_type,
+            value: format!("{:?}", value),
+            target,
+        }
+    }
+
+    /// Returns the name of the column.
+    pub fn column(&self) -> &str {
+        &self.column
+    }
+
+    /// Returns the position of the column in the row, counting from zero.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the SQL type of the value, such as `NUMBER` or `VARCHAR2`. A null gives the type
+    /// of its column when that is known, otherwise `NULL`.
+    pub fn sql_type(&self) -> &str {
+        &self.sql_type
+    }
+
+    /// Returns the value that couldn't be converted, as it is debug formatted.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Returns the name of the Rust type the value couldn't be converted to.
+    pub fn target(&self) -> &str {
+        self.target
+    }
+}
+
+impl fmt::Display for ColumnConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Column {} at position {} can't be converted from {} to {}, found: {}",
+            self.column, self.position, self.sql_type, self.target, self.value
+        )
+    }
+}
+
+impl error::Error for ColumnConversionError {}
+
+impl From<ColumnConversionError> for OciError {
+    fn from(err: ColumnConversionError) -> Self {
+        OciError::Conversion(Box::new(err))
+    }
+}
+
+/// Why a value failed one of the checked numeric conversions of [`SqlValue`][1], such as
+/// `.as_i64_checked`.
+///
+/// [1]: ../types/enum.SqlValue.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumericErrorKind {
+    /// The number is outside the range of the type asked for.
+    Overflow,
+    /// The number can't be held exactly by the type asked for, such as a fraction asked for as
+    /// an integer or a number with more digits than an `f64` keeps.
+    PrecisionLoss,
+    /// The value isn't a number, such as a null, a date or a NaN asked for as an integer.
+    NotANumber,
+}
+
+/// Returned by the checked numeric conversions of [`SqlValue`][1], such as `.as_i64_checked`,
+/// saying whether the value overflowed, would lose precision or isn't a number. It converts into
+/// an `OciError::Conversion`, so `?` can be used with it in a function that returns an
+/// `OciError`.
+///
+/// [1]: ../types/enum.SqlValue.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumericError {
+    kind: NumericErrorKind,
+    value: String,
+    target: &'static str,
+}
+impl NumericError {
+    pub(crate) fn new(
+        kind: NumericErrorKind,
+        value: &SqlValue,
+        target: &'static str,
+    ) -> NumericError {
+        NumericError {
+            kind,
+            value: format!("{:?}", value),
+            target,
+        }
+    }
+
+    /// Returns why the value couldn't be converted.
+    pub fn kind(&self) -> NumericErrorKind {
+        self.kind
+    }
+
+    /// Returns the value that couldn't be converted, as it is debug formatted.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Returns the name of the Rust type the value couldn't be converted to.
+    pub fn target(&self) -> &str {
+        self.target
+    }
+}
+
+impl fmt::Display for NumericError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            NumericErrorKind::Overflow => {
+                write!(f, "{} is out of range for {}", self.value, self.target)
+            }
+            NumericErrorKind::PrecisionLoss => write!(
+                f,
+                "{} can't be held by {} without losing precision",
+                self.value, self.target
+            ),
+            NumericErrorKind::NotANumber => {
+                write!(
+                    f,
+                    "{} isn't a number, so can't be a {}",
+                    self.value, self.target
+                )
+            }
+        }
+    }
+}
+
+impl error::Error for NumericError {}
+
+impl From<NumericError> for OciError {
+    fn from(err: NumericError) -> Self {
+        OciError::Conversion(Box::new(err))
+    }
+}
+
+/// A row of a batch that failed, as returned by
+/// [`Statement.execute_batch_with_errors`][1].
+///
+/// [1]: ../statement/struct.Statement.html#method.execute_batch_with_errors
+#[derive(Debug)]
+pub struct BatchError {
+    row: usize,
+    error: ErrorRecord,
+}
+impl BatchError {
+    pub(crate) fn new(row: usize, error: ErrorRecord) -> BatchError {
+        BatchError { row, error }
+    }
+
+    /// Returns the position of the row in the batch, counting from zero.
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    /// Returns the Oracle error the row failed with.
+    pub fn error(&self) -> &ErrorRecord {
+        &self.error
+    }
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Row {} of the batch failed: {}", self.row, self.error)
+    }
 }
 
 /// Used to capture the errors details from OCI errors. Typically
@@ -51,14 +549,20 @@ impl error::Error for OciError {
 pub struct ErrorRecord {
     description: String,
     records: Vec<(i32, String)>,
+    parse_error: Option<(usize, String)>,
+    sql: Option<String>,
+    diagnostics: Option<Box<Diagnostics>>,
 }
 impl ErrorRecord {
     /// Create a new ErrorRecord. The description is used to help show what action
     /// caused the error.
-    fn new(description: &str) -> ErrorRecord {
+    pub(crate) fn new(description: &str) -> ErrorRecord {
         ErrorRecord {
             records: Vec::new(),
             description: description.to_string(),
+            parse_error: None,
+            sql: None,
+            diagnostics: None,
         }
     }
 
@@ -67,8 +571,71 @@ impl ErrorRecord {
         &self.records
     }
 
+    /// Get the diagnostic snapshot, which is only taken when the OCI library failed in an
+    /// unexpected way, such as being given an invalid handle.
+    pub fn diagnostics(&self) -> Option<&Diagnostics> {
+        self.diagnostics
+            .as_ref()
+            .map(|diagnostics| diagnostics.as_ref())
+    }
+
+    /// Get the offset, in characters from the start of the SQL, at which Oracle failed to parse
+    /// the statement. This is only known for errors found while parsing, such as a syntax error
+    /// or an unknown column, that aren't at the very start of the SQL.
+    pub fn parse_error_offset(&self) -> Option<usize> {
+        self.parse_error.as_ref().map(|parse_error| parse_error.0)
+    }
+
+    /// Records where the SQL failed to parse, with the line of SQL marked at that point so that
+    /// it is shown with the error.
+    pub(crate) fn set_parse_error(&mut self, offset: usize, sql: &str) {
+        let before: Vec<char> = sql.chars().take(offset).collect();
+        let line_start = before
+            .iter()
+            .rposition(|&c| c == '\n')
+            .map_or(0, |index| index + 1);
+        let line_number = before.iter().filter(|&&c| c == '\n').count() + 1;
+        let line = sql.lines().nth(line_number - 1).unwrap_or("");
+        let caret: String = before[line_start..]
+            .iter()
+            .map(|&c| if c == '\t' { '\t' } else { ' ' })
+            .chain(Some('^'))
+            .collect();
+        let snippet = format!(
+            "line {}, column {}:\n{}\n{}",
+            line_number,
+            before.len() - line_start + 1,
+            line,
+            caret
+        );
+        self.parse_error = Some((offset, snippet));
+    }
+
+    /// Get the SQL of the statement that failed, if the error came from executing a statement or
+    /// fetching its rows.
+    pub fn sql(&self) -> Option<&str> {
+        self.sql.as_deref()
+    }
+
+    /// Records the SQL of the statement that failed, so that it is shown with the error.
+    pub(crate) fn set_sql(&mut self, sql: &str) {
+        self.sql = Some(sql.to_string());
+    }
+
+    /// Returns true if any of the errors has the given Oracle error code.
+    pub(crate) fn has_code(&self, code: i32) -> bool {
+        self.records.iter().any(|record| record.0 == code)
+    }
+
+    /// Adds a hint to the description, to help explain what might have caused the error.
+    pub(crate) fn add_hint(&mut self, hint: &str) {
+        self.description.push_str(" (");
+        self.description.push_str(hint);
+        self.description.push(')');
+    }
+
     /// Add a new error code and description to the ErrorRecord
-    fn add_error(&mut self, code: i32, description: String) {
+    pub(crate) fn add_error(&mut self, code: i32, description: String) {
         self.records.push((code, description))
     }
 }
@@ -92,13 +659,72 @@ impl fmt::Display for ErrorRecord {
                 .as_ref(),
             )
         }
+        if let Some(ref sql) = self.sql {
+            text.push_str(format!("\nSQL: {}", sql).as_ref())
+        }
+        if let Some((offset, ref snippet)) = self.parse_error {
+            text.push_str(format!("\nParse error at offset {}, {}", offset, snippet).as_ref())
+        }
+        if let Some(ref diagnostics) = self.diagnostics {
+            text.push_str(format!("\nDiagnostics:\n{}", diagnostics).as_ref())
+        }
         write!(f, "{}", text)
     }
 }
 
+/// A warning Oracle gave with an otherwise successful call, i.e. one that returned
+/// `OCI_SUCCESS_WITH_INFO`.
+///
+/// Warnings are collected by [`Statement.warnings`][1], e.g. ORA-24344 when a PL/SQL unit is
+/// created with compilation errors.
+///
+/// [1]: ../statement/struct.Statement.html#method.warnings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    code: i32,
+    message: String,
+}
+impl Warning {
+    /// Returns the Oracle code of the warning, e.g. 24344 for ORA-24344.
+    pub fn code(&self) -> i32 {
+        self.code
+    }
+
+    /// Returns the text of the warning, as Oracle gives it, e.g.
+    /// `ORA-24344: success with compilation error`.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Fetches the warnings registered against the handle provided, after a call returned
+/// `OCI_SUCCESS_WITH_INFO`.
+pub(crate) fn get_warnings(handle: *mut c_void, handle_type: HandleType) -> Vec<Warning> {
+    match get_error(handle, handle_type, "Reading warnings") {
+        OciError::Oracle(record) => record
+            .records
+            .into_iter()
+            .map(|(code, message)| Warning {
+                code,
+                message: message.trim_end().to_string(),
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
 /// Fetches the error records registered against the handle provided. If it is called
 /// out of sequence then the errors returned might be caused by a different function.
 /// Often the caller will need to cast their handle to *mut `c_void` to make it work.
+///
+/// If the errors can't be read, or there are none to explain why the call failed, then a
+/// diagnostic snapshot is attached as well.
 pub(crate) fn get_error(
     handle: *mut c_void,
     handle_type: HandleType,
@@ -107,6 +733,7 @@ pub(crate) fn get_error(
     let mut record_nmb: c_uint = 1;
     let sql_state: *mut c_uchar = ptr::null_mut();
     let mut error_record = ErrorRecord::new(description);
+    let mut fatal = false;
 
     loop {
         let mut error_code: c_int = 0;
@@ -125,22 +752,45 @@ pub(crate) fn get_error(
         };
         match error_result.into() {
             ReturnCode::NoData => break,
-            ReturnCode::Success => {
-                let first_null_byte_index = error_message.iter().position(|&x| x == 0).unwrap();
+            // with info means the message was cut short to fit the buffer
+            ReturnCode::Success | ReturnCode::SuccessWithInfo => {
+                let first_null_byte_index = error_message
+                    .iter()
+                    .position(|&x| x == 0)
+                    .unwrap_or(MAX_ERROR_MESSAGE_SIZE);
                 let oracle_error_text =
                     String::from_utf8_lossy(&error_message[0..first_null_byte_index]).into_owned();
 
                 error_record.add_error(error_code, oracle_error_text)
             }
             ReturnCode::Error => {
-                error_record.add_error(error_code, "Call to OCIErrorGet failed".to_string())
+                error_record.add_error(error_code, "Call to OCIErrorGet failed".to_string());
+                fatal = true;
+                break;
             }
             ReturnCode::InvalidHandle => {
-                error_record.add_error(error_code, "Invalid handle used to get errors".to_string())
+                error_record.add_error(error_code, "Invalid handle used to get errors".to_string());
+                fatal = true;
+                break;
+            }
+            ReturnCode::StillExecuting => {
+                error_record.add_error(error_code, "OCIErrorGet is still executing".to_string());
+                fatal = true;
+                break;
+            }
+            ReturnCode::Unknown(code) => {
+                error_record.add_error(
+                    error_code,
+                    format!("OCIErrorGet returned an unknown code: {}", code),
+                );
+                fatal = true;
+                break;
             }
-            ReturnCode::SuccessWithInfo => unreachable!(), // I think...
         }
         record_nmb += 1;
     }
+    if fatal || error_record.records.is_empty() {
+        error_record.diagnostics = Some(Box::new(Diagnostics::capture(handle, handle_type)));
+    }
     OciError::Oracle(error_record)
 }
@@ -0,0 +1,215 @@
+use crate::connection::Connection;
+use crate::oci_error::{OciError, UsageError};
+use crate::row::Row;
+use crate::statement::Statement;
+use crate::types::{SqlValue, ToSqlValue};
+use std::sync::Arc;
+
+/// The name given to the row number column added by `PageSyntax::RowNum`.
+const ROW_NUMBER_COLUMN: &str = "OCI_RS_ROW_NUMBER";
+
+/// How a [`Paginator`][1] limits a query to a page of rows.
+///
+/// [1]: struct.Paginator.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageSyntax {
+    /// `OFFSET ... ROWS FETCH NEXT ... ROWS ONLY`, available from Oracle 12c.
+    OffsetFetch,
+    /// A filter on `ROWNUM`, for Oracle 11g and earlier.
+    RowNum,
+}
+
+/// Runs a query a page of rows at a time.
+///
+/// The query is wrapped so that only the rows of the page asked for are returned, with the
+/// position and size of the page bound as parameters. The wrapped query is prepared once, so
+/// moving between pages doesn't parse it again. The query should have an `ORDER BY` that
+/// orders the rows uniquely, otherwise Oracle is free to return rows in a different order each
+/// time and rows can be skipped or repeated across pages.
+///
+/// Pages are numbered from zero and fetched with `.page`, or in order by using the
+/// `Paginator` as an iterator, which stops after the first page that isn't full.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use oci_rs::connection::Connection;
+/// use oci_rs::paginate::Paginator;
+///
+/// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+///
+/// let sql = "SELECT DogId, Name FROM Dogs WHERE Name LIKE :pattern ORDER BY DogId";
+/// let mut dogs = Paginator::new(&conn, sql, 50).unwrap();
+/// dogs.bind(&[&"P%"]);
+///
+/// // Jump straight to the third page
+/// let third_page = dogs.page(2).unwrap();
+///
+/// // Or go through them all
+/// for page in dogs {
+///     for row in page.unwrap() {
+///         let name: String = row[1].value().unwrap();
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Paginator<'conn> {
+    statement: Statement<'conn>,
+    syntax: PageSyntax,
+    page_size: u32,
+    params: Vec<SqlValue>,
+    next_page: u32,
+    finished: bool,
+}
+impl<'conn> Paginator<'conn> {
+    /// Creates a new `Paginator` that fetches pages of `page_size` rows using
+    /// `PageSyntax::OffsetFetch`.
+    ///
+    /// # Errors
+    ///
+    /// An `OciError::Usage` holding `UsageError::Unsupported` is returned if the page size is
+    /// zero. Any error in the underlying calls to the OCI library will be returned.
+    ///
+    pub fn new(
+        connection: &'conn Connection,
+        sql: &str,
+        page_size: u32,
+    ) -> Result<Paginator<'conn>, OciError> {
+        Paginator::with_syntax(connection, sql, page_size, PageSyntax::OffsetFetch)
+    }
+
+    /// Creates a new `Paginator` that limits the query to a page with the given syntax.
+    ///
+    /// `PageSyntax::RowNum` adds a row number column to the query, which is removed again from
+    /// the rows returned.
+    ///
+    /// # Errors
+    ///
+    /// An `OciError::Usage` holding `UsageError::Unsupported` is returned if the page size is
+    /// zero. Any error in the underlying calls to the OCI library will be returned.
+    ///
+    pub fn with_syntax(
+        connection: &'conn Connection,
+        sql: &str,
+        page_size: u32,
+        syntax: PageSyntax,
+    ) -> Result<Paginator<'conn>, OciError> {
+        if page_size == 0 {
+            return Err(UsageError::Unsupported(
+                "The page size of a Paginator must be at least one".to_string(),
+            )
+            .into());
+        }
+        let statement = connection.create_prepared_statement(&page_sql(sql, syntax))?;
+        Ok(Paginator {
+            statement,
+            syntax,
+            page_size,
+            params: Vec::new(),
+            next_page: 0,
+            finished: false,
+        })
+    }
+
+    /// Sets the parameters for the bind placeholders of the query, by position.
+    ///
+    /// They are used for every page fetched from now on and iteration starts again from the
+    /// first page.
+    ///
+    pub fn bind(&mut self, params: &[&dyn ToSqlValue]) {
+        self.params = params.iter().map(|param| param.to_sql_value()).collect();
+        self.next_page = 0;
+        self.finished = false;
+    }
+
+    /// Returns the number of rows in a full page.
+    ///
+    pub fn page_size(&self) -> u32 {
+        self.page_size
+    }
+
+    /// Fetches the page with the given number, counting from zero.
+    ///
+    /// A page past the end of the results is empty. Iteration carries on from the page after
+    /// this one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BindCountMismatch`][1], inside `OciError::Usage`, if the parameters set
+    /// with `.bind` don't match the placeholders in the query. Any error in the underlying calls
+    /// to the OCI library will be returned.
+    ///
+    /// [1]: ../oci_error/struct.BindCountMismatch.html
+    pub fn page(&mut self, number: u32) -> Result<Vec<Row>, OciError> {
+        let size = i64::from(self.page_size);
+        let skipped = i64::from(number) * size;
+        let limits = match self.syntax {
+            PageSyntax::OffsetFetch => [skipped, size],
+            PageSyntax::RowNum => [skipped + size, skipped],
+        };
+        let mut params: Vec<&dyn ToSqlValue> = self
+            .params
+            .iter()
+            .map(|param| param as &dyn ToSqlValue)
+            .collect();
+        params.push(&limits[0]);
+        params.push(&limits[1]);
+        self.statement.bind(&params)?;
+        self.statement.execute()?;
+        let mut rows = self
+            .statement
+            .lazy_result_set()?
+            .collect::<Result<Vec<Row>, OciError>>()?;
+        if self.syntax == PageSyntax::RowNum {
+            if let Some(first) = rows.first() {
+                let names = first.column_names();
+                let column_names: Arc<[String]> = names[..names.len() - 1].into();
+                for row in &mut rows {
+                    row.remove_last_column(&column_names);
+                }
+            }
+        }
+        self.finished = rows.len() < self.page_size as usize;
+        self.next_page = number.saturating_add(1);
+        Ok(rows)
+    }
+}
+
+impl<'conn> Iterator for Paginator<'conn> {
+    type Item = Result<Vec<Row>, OciError>;
+
+    /// Fetches the next page, stopping after the first one that isn't full, or when a page
+    /// can't be fetched.
+    ///
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let number = self.next_page;
+        match self.page(number) {
+            Ok(ref rows) if rows.is_empty() => None,
+            Ok(rows) => Some(Ok(rows)),
+            Err(err) => {
+                self.finished = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// Wraps the query so that it returns one page, with placeholders for where the page starts
+/// and ends.
+///
+fn page_sql(sql: &str, syntax: PageSyntax) -> String {
+    match syntax {
+        PageSyntax::OffsetFetch => format!(
+            "SELECT * FROM ({}) OFFSET :oci_rs_offset ROWS FETCH NEXT :oci_rs_rows ROWS ONLY",
+            sql
+        ),
+        PageSyntax::RowNum => format!(
+            "SELECT * FROM (SELECT oci_rs_page.*, ROWNUM AS {} FROM ({}) oci_rs_page \
+             WHERE ROWNUM <= :oci_rs_last) WHERE {} > :oci_rs_skipped",
+            ROW_NUMBER_COLUMN, sql, ROW_NUMBER_COLUMN
+        ),
+    }
+}
@@ -0,0 +1,150 @@
+use crate::connection::Connection;
+use crate::oci_error::OciError;
+use crate::types::{SqlValue, ToSqlValue};
+
+/// Queues DML statements and sends them to the database together.
+///
+/// Each statement run on its own costs a network round trip, which adds up for bursts of
+/// small inserts, updates and deletes, e.g. to different tables, that can't be batched with
+/// `Statement.execute_batch`. A `StatementQueue` holds the statements and their parameters
+/// until `.flush` is called, which runs them all in one anonymous PL/SQL block, and so in one
+/// round trip. Oracle 23ai can pipeline statements in the OCI library itself, but that isn't
+/// available in the OCI versions this crate supports.
+///
+/// The statements are run in the order they were queued. As with any PL/SQL block, if one of
+/// them fails then the changes made by the others in the block are undone and the error is
+/// returned, so a flush either makes all of its changes or none. Nothing is committed.
+///
+/// Only statements that don't return rows can be queued, i.e. DML and PL/SQL blocks. Their
+/// bind placeholders are set by position, as with `Statement.bind`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use oci_rs::connection::Connection;
+/// use oci_rs::pipeline::StatementQueue;
+///
+/// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+///
+/// let mut queue = StatementQueue::new(&conn);
+/// queue.push("INSERT INTO Dogs (DogId, Name) VALUES (:id, :name)", &[&7, &"Rex"]);
+/// queue.push("UPDATE Kennels SET Dogs = Dogs + 1 WHERE KennelId = :id", &[&3]);
+/// queue.push("DELETE FROM Waiting_List WHERE Name = :name", &[&"Rex"]);
+/// queue.flush().unwrap();
+/// conn.commit().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct StatementQueue<'conn> {
+    connection: &'conn Connection,
+    statements: Vec<QueuedStatement>,
+}
+impl<'conn> StatementQueue<'conn> {
+    /// Creates a new, empty `StatementQueue`.
+    ///
+    pub fn new(connection: &'conn Connection) -> StatementQueue<'conn> {
+        StatementQueue {
+            connection,
+            statements: Vec::new(),
+        }
+    }
+
+    /// Adds a statement and the parameters for its bind placeholders to the queue.
+    ///
+    /// Nothing is sent to the database until the queue is flushed, so mistakes in the SQL are
+    /// only reported then.
+    ///
+    pub fn push(&mut self, sql: &str, params: &[&dyn ToSqlValue]) {
+        let sql = sql.trim().to_string();
+        let params = params.iter().map(|param| param.to_sql_value()).collect();
+        self.statements.push(QueuedStatement { sql, params });
+    }
+
+    /// Returns the number of statements waiting to be flushed.
+    ///
+    pub fn len(&self) -> usize {
+        self.statements.len()
+    }
+
+    /// Returns `true` if no statements are waiting to be flushed.
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.statements.is_empty()
+    }
+
+    /// Removes all the statements from the queue without running them.
+    ///
+    pub fn clear(&mut self) {
+        self.statements.clear();
+    }
+
+    /// Runs all the queued statements in a single round trip and empties the queue.
+    ///
+    /// Returns the number of statements run.
+    ///
+    /// # Errors
+    ///
+    /// If a statement fails then none of the changes made by the queued statements are kept and
+    /// the error is returned. The Oracle error stack names the line of the block that failed,
+    /// which is the position of the statement in the queue, counting from one. The statements
+    /// are left in the queue so that they can be flushed again or cleared.
+    ///
+    /// Any other error in the underlying calls to the OCI library will be returned.
+    ///
+    pub fn flush(&mut self) -> Result<usize, OciError> {
+        if self.statements.is_empty() {
+            return Ok(0);
+        }
+        let mut stmt = self
+            .connection
+            .create_prepared_statement(&queue_block(&self.statements))?;
+        let params: Vec<&dyn ToSqlValue> = self
+            .statements
+            .iter()
+            .flat_map(|statement| statement.params.iter())
+            .map(|param| param as &dyn ToSqlValue)
+            .collect();
+        stmt.bind(&params)?;
+        stmt.execute()?;
+        let flushed = self.statements.len();
+        self.statements.clear();
+        Ok(flushed)
+    }
+}
+
+#[derive(Debug)]
+struct QueuedStatement {
+    sql: String,
+    params: Vec<SqlValue>,
+}
+
+/// Writes the anonymous block that runs the queued statements.
+///
+/// Each statement is run with `EXECUTE IMMEDIATE`, so that its own placeholders are filled by
+/// position from the `USING` clause. The placeholders of the block are numbered across all of
+/// the statements and each statement goes on its own line, so that the line numbers in errors
+/// match the positions of the statements in the queue.
+///
+fn queue_block(statements: &[QueuedStatement]) -> String {
+    let mut block = String::from("BEGIN ");
+    let mut position = 0;
+    for statement in statements {
+        block.push_str("EXECUTE IMMEDIATE '");
+        block.push_str(&statement.sql.replace('\'', "''"));
+        block.push('\'');
+        if !statement.params.is_empty() {
+            let placeholders: Vec<String> = statement
+                .params
+                .iter()
+                .map(|_| {
+                    position += 1;
+                    format!(":{}", position)
+                })
+                .collect();
+            block.push_str(" USING ");
+            block.push_str(&placeholders.join(", "));
+        }
+        block.push_str(";\n");
+    }
+    block.push_str("END;");
+    block
+}
@@ -0,0 +1,358 @@
+use crate::connection::Connection;
+use crate::describe::quote_name;
+use crate::oci_error::{OciError, UsageError};
+use crate::statement::Statement;
+use crate::types::{FromSqlValue, SqlType, SqlValue, ToSqlValue};
+use std::collections::HashMap;
+
+/// A parameter of a stored procedure called with [`Connection.call`][1], or a bind variable of
+/// a block built with [`BlockBuilder`][2].
+///
+/// [1]: ../connection/struct.Connection.html#method.call
+/// [2]: struct.BlockBuilder.html
+pub enum Param<'a> {
+    /// An `IN` parameter, passed the value.
+    In(&'a dyn ToSqlValue),
+    /// An `OUT` parameter of the given type.
+    Out(SqlType),
+    /// An `IN OUT` parameter, passed the value and left holding the value it is set to by the
+    /// PL/SQL. The output has the same type as the value, or is text if it is null.
+    InOut(SqlValue),
+}
+
+/// Builds and executes an anonymous PL/SQL block, `DECLARE ... BEGIN ... END;`, with named
+/// bind variables for its inputs and outputs.
+///
+/// Each bind variable is a [`Param`][1] given a name, which is used as a placeholder, e.g.
+/// `:total`, in the statements of the block. The values of the `OUT` and `IN OUT` bind variables
+/// are returned by `.execute` as [`BlockOutputs`][2]. A function's return value is bound as an
+/// output by `.call_function`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use oci_rs::connection::Connection;
+/// use oci_rs::plsql::{BlockBuilder, Param};
+/// use oci_rs::types::SqlType;
+///
+/// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+///
+/// let mut block = BlockBuilder::new()
+///     .declare("dogs NUMBER")
+///     .statement("SELECT COUNT(*) INTO dogs FROM Dogs WHERE Name LIKE :pattern")
+///     .statement(":dogs := dogs")
+///     .call_function("name", "kennel.dogs.name_of", SqlType::VarChar, &[":id"])
+///     .bind("pattern", Param::In(&"P%"))
+///     .bind("dogs", Param::Out(SqlType::Integer))
+///     .bind("id", Param::In(&1));
+/// let outputs = block.execute(&conn).unwrap();
+/// let dogs: i64 = outputs.value("dogs").unwrap();
+/// let name: String = outputs.value("name").unwrap();
+/// ```
+///
+/// [1]: enum.Param.html
+/// [2]: struct.BlockOutputs.html
+pub struct BlockBuilder<'a> {
+    declarations: Vec<String>,
+    body: Vec<BodyItem>,
+    binds: Vec<(String, Param<'a>)>,
+}
+impl<'a> BlockBuilder<'a> {
+    /// Creates a new `BlockBuilder` for an empty block.
+    ///
+    pub fn new() -> BlockBuilder<'a> {
+        BlockBuilder {
+            declarations: Vec::new(),
+            body: Vec::new(),
+            binds: Vec::new(),
+        }
+    }
+
+    /// Adds a declaration to the `DECLARE` section, e.g. `total NUMBER := 0`.
+    ///
+    pub fn declare(mut self, declaration: &str) -> BlockBuilder<'a> {
+        self.declarations.push(terminate(declaration));
+        self
+    }
+
+    /// Adds a statement to the body of the block. The trailing semicolon is optional.
+    ///
+    pub fn statement(mut self, statement: &str) -> BlockBuilder<'a> {
+        self.body.push(BodyItem::Statement(terminate(statement)));
+        self
+    }
+
+    /// Adds a call to a function, optionally qualified with its schema and package, whose return
+    /// value is bound as an output with the given name and type. The arguments are PL/SQL
+    /// expressions, such as placeholders, e.g. `:id`, or local variables.
+    ///
+    pub fn call_function(
+        mut self,
+        result: &str,
+        function: &str,
+        return_type: SqlType,
+        args: &[&str],
+    ) -> BlockBuilder<'a> {
+        self.body.push(BodyItem::Function {
+            result: bind_name(result),
+            function: function.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        });
+        self.bind(result, Param::Out(return_type))
+    }
+
+    /// Binds a parameter to the placeholder with the given name, with or without the leading
+    /// colon, replacing any parameter already bound to it.
+    ///
+    pub fn bind(mut self, name: &str, param: Param<'a>) -> BlockBuilder<'a> {
+        let name = bind_name(name);
+        self.binds.retain(|(bound, _)| *bound != name);
+        self.binds.push((name, param));
+        self
+    }
+
+    /// Returns the PL/SQL of the block.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name of a function isn't a valid dotted name.
+    ///
+    pub fn sql(&self) -> Result<String, OciError> {
+        let mut sql = String::new();
+        if !self.declarations.is_empty() {
+            sql.push_str("DECLARE\n");
+            for declaration in &self.declarations {
+                sql.push_str(&format!("    {}\n", declaration));
+            }
+        }
+        sql.push_str("BEGIN\n");
+        if self.body.is_empty() {
+            sql.push_str("    NULL;\n");
+        }
+        for item in &self.body {
+            match *item {
+                BodyItem::Statement(ref statement) => sql.push_str(&format!("    {}\n", statement)),
+                BodyItem::Function {
+                    ref result,
+                    ref function,
+                    ref args,
+                } => sql.push_str(&format!(
+                    "    :{} := {}({});\n",
+                    result,
+                    quote_procedure_name(function)?,
+                    args.join(", ")
+                )),
+            }
+        }
+        sql.push_str("END;");
+        Ok(sql)
+    }
+
+    /// Executes the block and returns the values of its `OUT` and `IN OUT` bind variables.
+    ///
+    /// Afterwards each [`Param::InOut`][1] holds the value the block set it to, so the block can
+    /// be executed again carrying on from there.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a placeholder in the block has no parameter bound to it, or a
+    /// parameter is bound to a name that isn't a placeholder in the block.
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned.
+    ///
+    /// [1]: enum.Param.html#variant.InOut
+    pub fn execute(&mut self, connection: &Connection) -> Result<BlockOutputs, OciError> {
+        let mut stmt = connection.create_prepared_statement(&self.sql()?)?;
+        let mut positions = Vec::with_capacity(self.binds.len());
+        for placeholder in stmt.bind_placeholders()? {
+            match self
+                .binds
+                .iter()
+                .position(|(name, _)| *name == placeholder.name())
+            {
+                Some(index) => positions.push((placeholder.position(), index)),
+                None => {
+                    return Err(UsageError::Bind(format!(
+                        "Nothing is bound to :{}",
+                        placeholder.name()
+                    ))
+                    .into())
+                }
+            }
+        }
+        for (index, (name, _)) in self.binds.iter().enumerate() {
+            if !positions.iter().any(|&(_, bound)| bound == index) {
+                return Err(UsageError::Bind(format!(
+                    ":{} isn't a placeholder in the block",
+                    name
+                ))
+                .into());
+            }
+        }
+        let inputs: Vec<SqlValue> = positions
+            .iter()
+            .map(|&(_, index)| input_value(&self.binds[index].1))
+            .collect();
+        let inputs: Vec<&dyn ToSqlValue> = inputs
+            .iter()
+            .map(|value| value as &dyn ToSqlValue)
+            .collect();
+        stmt.bind(&inputs)?;
+        for &(position, index) in &positions {
+            bind_output(&mut stmt, position, &self.binds[index].1)?;
+        }
+        stmt.execute()?;
+        let mut outputs = HashMap::new();
+        for &(position, index) in &positions {
+            let (ref name, ref mut param) = self.binds[index];
+            if let Some(value) = read_output(&stmt, position, param)? {
+                outputs.insert(name.clone(), value);
+            }
+        }
+        Ok(BlockOutputs { outputs })
+    }
+}
+
+impl<'a> Default for BlockBuilder<'a> {
+    fn default() -> Self {
+        BlockBuilder::new()
+    }
+}
+
+/// The values of the `OUT` and `IN OUT` bind variables of a block run by
+/// [`BlockBuilder.execute`][1], looked up by name with or without the leading colon.
+///
+/// [1]: struct.BlockBuilder.html#method.execute
+#[derive(Debug, Clone)]
+pub struct BlockOutputs {
+    outputs: HashMap<String, SqlValue>,
+}
+impl BlockOutputs {
+    /// Returns the value of an output, or `None` if there is no output with the name.
+    ///
+    pub fn get(&self, name: &str) -> Option<&SqlValue> {
+        self.outputs.get(&bind_name(name))
+    }
+
+    /// Returns the value of an output converted to a Rust type, or `None` if there is no output
+    /// with the name or it is null or can't be converted.
+    ///
+    pub fn value<T: FromSqlValue>(&self, name: &str) -> Option<T> {
+        self.get(name).and_then(|value| value.value())
+    }
+}
+
+/// A part of the body of a block.
+///
+enum BodyItem {
+    Statement(String),
+    Function {
+        result: String,
+        function: String,
+        args: Vec<String>,
+    },
+}
+
+/// Normalises the name of a bind variable to the form Oracle reports placeholders in, without
+/// the colon and in upper case unless it is quoted.
+///
+fn bind_name(name: &str) -> String {
+    let name = name.trim().trim_start_matches(':');
+    if name.starts_with('"') {
+        name.trim_matches('"').to_string()
+    } else {
+        name.to_uppercase()
+    }
+}
+
+/// Adds a semicolon to the end of a declaration or statement if it hasn't got one.
+///
+fn terminate(statement: &str) -> String {
+    let statement = statement.trim();
+    if statement.ends_with(';') {
+        statement.to_string()
+    } else {
+        format!("{};", statement)
+    }
+}
+
+/// The value a parameter is bound with by `.bind`, before any output is bound over it.
+///
+fn input_value(param: &Param) -> SqlValue {
+    match *param {
+        Param::In(value) => value.to_sql_value(),
+        Param::Out(_) => SqlValue::Null(None),
+        Param::InOut(ref value) => value.clone(),
+    }
+}
+
+/// Binds the `OUT` and `IN OUT` parameters as outputs.
+///
+fn bind_output(stmt: &mut Statement, position: usize, param: &Param) -> Result<(), OciError> {
+    match *param {
+        Param::In(_) => Ok(()),
+        Param::Out(data_type) => stmt.bind_out(position, data_type),
+        Param::InOut(ref value) => stmt.bind_in_out(position, value),
+    }
+}
+
+/// Reads the output of an `OUT` or `IN OUT` parameter once the statement has been executed,
+/// leaving an `IN OUT` parameter holding it.
+///
+fn read_output(
+    stmt: &Statement,
+    position: usize,
+    param: &mut Param,
+) -> Result<Option<SqlValue>, OciError> {
+    match *param {
+        Param::In(_) => Ok(None),
+        Param::Out(_) => Ok(Some(stmt.out_value(position)?)),
+        Param::InOut(ref mut value) => {
+            *value = stmt.out_value(position)?;
+            Ok(Some(value.clone()))
+        }
+    }
+}
+
+/// Calls a stored procedure, binding the parameters by position, and returns the values of the
+/// `OUT` and `IN OUT` parameters in order.
+///
+pub(crate) fn call_procedure(
+    connection: &Connection,
+    procedure: &str,
+    params: &mut [Param],
+) -> Result<Vec<SqlValue>, OciError> {
+    let placeholders: Vec<String> = (1..=params.len())
+        .map(|position| format!(":{}", position))
+        .collect();
+    let sql = format!(
+        "BEGIN {}({}); END;",
+        quote_procedure_name(procedure)?,
+        placeholders.join(", ")
+    );
+    let mut stmt = connection.create_prepared_statement(&sql)?;
+    let inputs: Vec<SqlValue> = params.iter().map(input_value).collect();
+    let inputs: Vec<&dyn ToSqlValue> = inputs
+        .iter()
+        .map(|value| value as &dyn ToSqlValue)
+        .collect();
+    stmt.bind(&inputs)?;
+    for (index, param) in params.iter().enumerate() {
+        bind_output(&mut stmt, index + 1, param)?;
+    }
+    stmt.execute()?;
+    let mut outputs = Vec::new();
+    for (index, param) in params.iter_mut().enumerate() {
+        if let Some(value) = read_output(&stmt, index + 1, param)? {
+            outputs.push(value);
+        }
+    }
+    Ok(outputs)
+}
+
+/// Quotes a possibly schema and package qualified procedure name, so that it can be put into
+/// PL/SQL safely. Names that aren't valid identifiers are rejected, see `quote_name`.
+///
+fn quote_procedure_name(name: &str) -> Result<String, OciError> {
+    quote_name(name, 3, "procedure name")
+}
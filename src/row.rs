@@ -1,15 +1,24 @@
-use crate::types::SqlValue;
+use crate::oci_error::{ColumnConversionError, OciError, UsageError};
+use crate::types::{FromSqlValue, SqlValue};
+use std::any;
 use std::ops::Index;
+use std::sync::Arc;
 
 /// Represents a row of data returned from a SQL query.
 ///
+/// The column names are shared by all the rows fetched by a query, rather than copied into each.
+///
 #[derive(Debug)]
 pub struct Row {
     columns: Vec<SqlValue>,
+    column_names: Arc<[String]>,
 }
 impl Row {
-    pub(crate) fn new(columns: Vec<SqlValue>) -> Row {
-        Row { columns }
+    pub(crate) fn new(columns: Vec<SqlValue>, column_names: Arc<[String]>) -> Row {
+        Row {
+            columns,
+            column_names,
+        }
     }
 
     /// Returns the columns in the row.
@@ -17,6 +26,143 @@ impl Row {
     pub fn columns(&self) -> &[SqlValue] {
         &self.columns
     }
+
+    /// Returns an approximation of the number of bytes the values in the row take up.
+    ///
+    pub fn approximate_size(&self) -> usize {
+        self.columns.iter().map(SqlValue::approximate_size).sum()
+    }
+
+    /// Returns the names of the columns in the row, as described by the database.
+    ///
+    /// Oracle reports unquoted identifiers in upper case, so `SELECT Name FROM Dogs` has a
+    /// column called `NAME`.
+    ///
+    pub fn column_names(&self) -> &[String] {
+        &self.column_names
+    }
+
+    /// Returns the column with the given name.
+    ///
+    /// The name is matched against the names described by the database, ignoring case. This
+    /// lets values be picked out by name rather than position so that code is not broken by a
+    /// change in the order of the columns, for example after a table is rebuilt and a
+    /// `SELECT *` query returns its columns in a new order.
+    ///
+    pub fn get(&self, name: &str) -> Option<&SqlValue> {
+        self.column_names
+            .iter()
+            .position(|column_name| column_name.eq_ignore_ascii_case(name))
+            .map(|index| &self.columns[index])
+    }
+
+    /// Returns the value of a column, picked out by its name or by its position counting from
+    /// zero, converted to a Rust type.
+    ///
+    /// A name is matched as it is by `.get`, ignoring case. Where `SqlValue.value` gives `None`
+    /// for a value that can't be converted, this says which column it was, the SQL type it holds
+    /// and the Rust type asked for.
+    ///
+    /// # Errors
+    ///
+    /// An `OciError::Usage` holding `UsageError::Column` is returned if there is no such column,
+    /// and an `OciError::Conversion` holding a [`ColumnConversionError`][1] if the value can't be
+    /// converted to `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    /// use oci_rs::oci_error::{ColumnConversionError, OciError};
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    /// let mut select = conn
+    ///     .create_prepared_statement("SELECT DogId, Name FROM Dogs")
+    ///     .unwrap();
+    /// select.execute().unwrap();
+    /// let results = select.result_set().unwrap();
+    /// let row = &results[0];
+    ///
+    /// let id: i64 = row.try_get("DogId").unwrap();
+    /// let name: String = row.try_get(1).unwrap();
+    ///
+    /// if let Err(OciError::Conversion(err)) = row.try_get::<i64>("Name") {
+    ///     let err = err.downcast_ref::<ColumnConversionError>().unwrap();
+    ///     assert_eq!(err.column(), "NAME");
+    ///     assert_eq!(err.sql_type(), "VARCHAR2");
+    ///     assert_eq!(err.target(), "i64");
+    /// }
+    /// ```
+    ///
+    /// [1]: ../oci_error/struct.ColumnConversionError.html
+    pub fn try_get<T: FromSqlValue>(&self, index: impl ColumnIndex) -> Result<T, OciError> {
+        let position = index.position_in(self)?;
+        let column = &self.columns[position];
+        column.value().ok_or_else(|| {
+            ColumnConversionError::new(
+                &self.column_names[position],
+                position,
+                column,
+                any::type_name::<T>(),
+            )
+            .into()
+        })
+    }
+
+    /// Returns the value of the column at the given position, counting from zero, converted to
+    /// a Rust type. This is the same as `.try_get` with a position.
+    ///
+    /// # Errors
+    ///
+    /// An `OciError::Usage` is returned if the row has no column at that position and an
+    /// `OciError::Conversion` if the value can't be converted to `T`.
+    ///
+    pub fn try_get_at<T: FromSqlValue>(&self, index: usize) -> Result<T, OciError> {
+        self.try_get(index)
+    }
+
+    /// Converts the row into a tuple, with one element for each column in order.
+    ///
+    /// Tuples of up to twelve elements implement `FromRow`, so this takes any `FromRow` type,
+    /// and a whole result set of tuples can be had from `Statement.typed_result_set`.
+    ///
+    /// # Errors
+    ///
+    /// An `OciError::Usage` holding `UsageError::Column` is returned if the row doesn't have as
+    /// many columns as the tuple has elements, and an `OciError::Conversion` holding a
+    /// `ColumnConversionError` for the first column that can't be converted to its element's
+    /// type.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    /// let mut select = conn
+    ///     .create_prepared_statement("SELECT DogId, Name, Height FROM Dogs")
+    ///     .unwrap();
+    /// select.execute().unwrap();
+    ///
+    /// for row in select.result_set().unwrap() {
+    ///     let (id, name, height): (i64, String, f64) = row.into_tuple().unwrap();
+    ///     println!("{} {} is {}cm tall", id, name, height);
+    /// }
+    /// ```
+    ///
+    // by reference, as the rows of `.result_set` are borrowed from the statement
+    #[allow(clippy::wrong_self_convention)]
+    pub fn into_tuple<T: FromRow>(&self) -> Result<T, OciError> {
+        T::from_row(self)
+    }
+
+    /// Removes the last column, which is one added to the query by the crate. The names of the
+    /// remaining columns are passed in so that the rows can go on sharing them.
+    ///
+    pub(crate) fn remove_last_column(&mut self, column_names: &Arc<[String]>) {
+        self.columns.pop();
+        self.column_names = Arc::clone(column_names);
+    }
 }
 impl Index<usize> for Row {
     type Output = SqlValue;
@@ -25,3 +171,158 @@ impl Index<usize> for Row {
         &self.columns[index]
     }
 }
+
+/// Picks out a column of a [`Row`][1] for `Row.try_get`, by its name or by its position counting
+/// from zero.
+///
+/// It is implemented for `usize` positions and `str` and `String` names, as well as references
+/// to them.
+///
+/// [1]: struct.Row.html
+pub trait ColumnIndex {
+    /// Returns the position of the column in the row.
+    ///
+    /// # Errors
+    ///
+    /// An `OciError::Usage` holding `UsageError::Column` is returned if the row has no such
+    /// column.
+    ///
+    fn position_in(&self, row: &Row) -> Result<usize, OciError>;
+}
+
+impl ColumnIndex for usize {
+    fn position_in(&self, row: &Row) -> Result<usize, OciError> {
+        if *self < row.columns.len() {
+            Ok(*self)
+        } else {
+            Err(UsageError::Column(format!(
+                "No column at position {}, the row has {} columns",
+                self,
+                row.columns.len()
+            ))
+            .into())
+        }
+    }
+}
+
+impl ColumnIndex for str {
+    fn position_in(&self, row: &Row) -> Result<usize, OciError> {
+        row.column_names
+            .iter()
+            .position(|column_name| column_name.eq_ignore_ascii_case(self))
+            .ok_or_else(|| {
+                UsageError::Column(format!("No column named {} in the result set", self)).into()
+            })
+    }
+}
+
+impl ColumnIndex for String {
+    fn position_in(&self, row: &Row) -> Result<usize, OciError> {
+        self.as_str().position_in(row)
+    }
+}
+
+impl<T: ColumnIndex + ?Sized> ColumnIndex for &T {
+    fn position_in(&self, row: &Row) -> Result<usize, OciError> {
+        (**self).position_in(row)
+    }
+}
+
+/// Converts a `Row` into a Rust type, typically a struct with one field per column.
+///
+/// Implementations are used by [`Statement.typed_result_set`][1]. Looking columns up with
+/// `Row::try_get` maps them to fields by name, which keeps working if the order of the
+/// columns in the query changes. `Row::try_get_at` maps them by position.
+///
+/// With the `derive` feature the implementation can be generated with `#[derive(FromRow)]`.
+/// Each field of a struct with named fields is read from the column of the same name, ignoring
+/// case, and each field of a tuple struct from the column at its position. A field's
+/// `#[oci(...)]` attribute changes this:
+///
+/// - `#[oci(rename = "DogId")]` reads the field from the named column.
+/// - `#[oci(position = 0)]` reads the field from the column at the position, counting from
+///   zero.
+/// - `#[oci(skip)]` leaves the column out and sets the field to its `Default` value.
+///
+/// A field that is an `Option` is `None` when the column is `NULL`.
+///
+/// Tuples of up to twelve elements implement it as well, taking the columns in order, so
+/// `Statement.typed_result_set::<(i64, String)>` needs no type of its own. The row must have as
+/// many columns as the tuple has elements.
+///
+/// # Examples
+///
+/// ```rust
+/// use oci_rs::oci_error::OciError;
+/// use oci_rs::row::{FromRow, Row};
+///
+/// struct Dog {
+///     id: i64,
+///     name: String,
+/// }
+///
+/// impl FromRow for Dog {
+///     fn from_row(row: &Row) -> Result<Dog, OciError> {
+///         Ok(Dog {
+///             id: row.try_get("DogId")?,
+///             name: row.try_get("Name")?,
+///         })
+///     }
+/// }
+/// ```
+///
+/// The same type written with the derive macro:
+///
+/// ```rust,ignore
+/// use oci_rs::row::FromRow;
+///
+/// #[derive(FromRow)]
+/// struct Dog {
+///     #[oci(rename = "DogId")]
+///     id: i64,
+///     name: String,
+///     owner: Option<String>,
+/// }
+/// ```
+///
+/// [1]: ../statement/struct.Statement.html#method.typed_result_set
+pub trait FromRow: Sized {
+    /// Builds the type from the row.
+    ///
+    fn from_row(row: &Row) -> Result<Self, OciError>;
+}
+
+#[cfg(feature = "derive")]
+pub use oci_rs_derive::FromRow;
+
+macro_rules! tuple_from_row {
+    ($($name:ident: $index:tt),+) => {
+        impl<$($name: FromSqlValue),+> FromRow for ($($name,)+) {
+            fn from_row(row: &Row) -> Result<Self, OciError> {
+                let elements = [$($index),+].len();
+                if row.columns.len() != elements {
+                    return Err(UsageError::Column(format!(
+                        "The row has {} columns, which can't be converted to a tuple of {}",
+                        row.columns.len(),
+                        elements
+                    ))
+                    .into());
+                }
+                Ok(($(row.try_get::<$name>($index)?,)+))
+            }
+        }
+    };
+}
+
+tuple_from_row!(A: 0);
+tuple_from_row!(A: 0, B: 1);
+tuple_from_row!(A: 0, B: 1, C: 2);
+tuple_from_row!(A: 0, B: 1, C: 2, D: 3);
+tuple_from_row!(A: 0, B: 1, C: 2, D: 3, E: 4);
+tuple_from_row!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
+tuple_from_row!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6);
+tuple_from_row!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7);
+tuple_from_row!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8);
+tuple_from_row!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8, J: 9);
+tuple_from_row!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8, J: 9, K: 10);
+tuple_from_row!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8, J: 9, K: 10, L: 11);
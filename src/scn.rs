@@ -0,0 +1,239 @@
+use crate::connection::Connection;
+use crate::describe::quote_name;
+use crate::oci_error::OciError;
+use crate::row::{FromRow, Row};
+use crate::types::{FromSqlValue, SqlValue, ToSqlValue};
+use chrono::{DateTime, Utc};
+use std::fmt;
+
+/// The name of the pseudocolumn that holds the SCN of the last change to a row.
+pub const ORA_ROWSCN: &str = "ORA_ROWSCN";
+
+/// A system change number, Oracle's logical clock that orders every committed change.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Scn(u64);
+impl Scn {
+    /// Creates a new `Scn`.
+    ///
+    pub fn new(scn: u64) -> Scn {
+        Scn(scn)
+    }
+
+    /// Returns the number.
+    ///
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for Scn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// An SCN too big for an `i64` is bound as text, as a `u64` is, rather than wrapping round to a
+/// negative number.
+///
+impl ToSqlValue for Scn {
+    fn to_sql_value(&self) -> SqlValue {
+        self.0.to_sql_value()
+    }
+}
+
+impl FromSqlValue for Scn {
+    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
+        match *sql_value {
+            SqlValue::Integer(i) if i >= 0 => Some(Scn(i as u64)),
+            SqlValue::VarChar(ref s) => s.parse().ok().map(Scn),
+            _ => None,
+        }
+    }
+}
+
+/// A row converted to a Rust type together with the SCN of its last change.
+///
+/// The query must select `ORA_ROWSCN` as well as the columns that `T` needs, which is what
+/// [`changed_since_query`][1] does. Keeping the SCN with the value allows optimistic
+/// concurrency: an update can include `AND ORA_ROWSCN = :scn` and will change nothing if the
+/// row was changed by someone else in the meantime.
+///
+/// Unless the table was created with `ROWDEPENDENCIES` Oracle only records the SCN for each
+/// block, so a row can appear to have changed when another row in the same block did.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use oci_rs::connection::Connection;
+/// use oci_rs::oci_error::OciError;
+/// use oci_rs::row::{FromRow, Row};
+/// use oci_rs::scn::Versioned;
+///
+/// struct Dog {
+///     name: String,
+/// }
+///
+/// impl FromRow for Dog {
+///     fn from_row(row: &Row) -> Result<Dog, OciError> {
+///         Ok(Dog {
+///             name: row.try_get("Name")?,
+///         })
+///     }
+/// }
+///
+/// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+/// let mut select = conn
+///     .create_prepared_statement("SELECT ORA_ROWSCN, Name FROM Dogs")
+///     .unwrap();
+/// select.execute().unwrap();
+/// let dogs: Vec<Versioned<Dog>> = select.typed_result_set().unwrap();
+/// for dog in &dogs {
+///     println!("{} last changed at {}", dog.value().name, dog.scn());
+/// }
+/// ```
+///
+/// [1]: fn.changed_since_query.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct Versioned<T> {
+    value: T,
+    scn: Scn,
+}
+impl<T> Versioned<T> {
+    /// Returns the value.
+    ///
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Returns the SCN of the last change to the row.
+    ///
+    pub fn scn(&self) -> Scn {
+        self.scn
+    }
+
+    /// Returns the value, dropping the SCN.
+    ///
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T: FromRow> FromRow for Versioned<T> {
+    fn from_row(row: &Row) -> Result<Self, OciError> {
+        Ok(Versioned {
+            value: T::from_row(row)?,
+            scn: row.try_get(ORA_ROWSCN)?,
+        })
+    }
+}
+
+/// Builds a query for the rows of a table that changed after an SCN, for incremental
+/// extraction.
+///
+/// The query selects `ORA_ROWSCN` followed by the columns, in SCN order, and has a single bind
+/// variable, `:scn`. Keeping the highest SCN seen and passing it to the next run picks up only
+/// the rows changed since. As with [`Versioned`][1] the SCN may be per block rather than per
+/// row, so some unchanged rows can be returned again, but no changed row is missed.
+///
+/// The table name may be qualified by a schema. It and the column names are quoted, so names
+/// that aren't already in double quotes are upper-cased, as Oracle would.
+///
+/// # Errors
+///
+/// An `OciError::Usage` holding `UsageError::InvalidName` is returned if the table or a column
+/// name is not a valid identifier.
+///
+/// # Examples
+///
+/// ```rust
+/// use oci_rs::scn::changed_since_query;
+///
+/// assert_eq!(
+///     changed_since_query("Dogs", &["DogId", "Name"]).unwrap(),
+///     "SELECT ORA_ROWSCN, \"DOGID\", \"NAME\" FROM \"DOGS\" WHERE ORA_ROWSCN > :scn \
+///      ORDER BY ORA_ROWSCN"
+/// );
+/// assert!(changed_since_query("Dogs; DROP TABLE Dogs", &["DogId"]).is_err());
+/// ```
+///
+/// [1]: struct.Versioned.html
+pub fn changed_since_query(table: &str, columns: &[&str]) -> Result<String, OciError> {
+    let mut select_list = vec![ORA_ROWSCN.to_string()];
+    for column in columns {
+        select_list.push(quote_name(column, 1, "column name")?);
+    }
+    Ok(format!(
+        "SELECT {} FROM {} WHERE {} > :scn ORDER BY {}",
+        select_list.join(", "),
+        quote_name(table, 2, "table name")?,
+        ORA_ROWSCN,
+        ORA_ROWSCN
+    ))
+}
+
+/// Returns the current SCN of the database.
+///
+/// The user needs to be able to execute `DBMS_FLASHBACK`.
+///
+/// # Errors
+///
+/// Any error in the underlying calls to the OCI library will be returned.
+///
+pub fn current_scn(conn: &Connection) -> Result<Scn, OciError> {
+    query_scn(
+        conn,
+        "SELECT DBMS_FLASHBACK.GET_SYSTEM_CHANGE_NUMBER FROM dual",
+        &[],
+    )
+}
+
+/// Returns the approximate time at which an SCN was current.
+///
+/// Oracle only keeps the mapping for a limited time, so an old SCN results in an error. The
+/// time is the database server's, as for all `TIMESTAMP` values.
+///
+/// # Errors
+///
+/// Any error in the underlying calls to the OCI library will be returned.
+///
+pub fn scn_to_timestamp(conn: &Connection, scn: Scn) -> Result<DateTime<Utc>, OciError> {
+    let mut select = conn.create_prepared_statement("SELECT SCN_TO_TIMESTAMP(:scn) FROM dual")?;
+    select.bind(&[&scn])?;
+    select.execute()?;
+    let row = first_row(select.result_set()?)?;
+    row[0].value().ok_or_else(|| {
+        OciError::Conversion(format!("Expected a timestamp, found: {:?}", row[0]).into())
+    })
+}
+
+/// Returns the approximate SCN that was current at a time.
+///
+/// # Errors
+///
+/// Any error in the underlying calls to the OCI library will be returned.
+///
+pub fn timestamp_to_scn(conn: &Connection, timestamp: &DateTime<Utc>) -> Result<Scn, OciError> {
+    query_scn(
+        conn,
+        "SELECT TIMESTAMP_TO_SCN(:timestamp) FROM dual",
+        &[timestamp],
+    )
+}
+
+fn query_scn(conn: &Connection, sql: &str, params: &[&dyn ToSqlValue]) -> Result<Scn, OciError> {
+    let mut select = conn.create_prepared_statement(sql)?;
+    if !params.is_empty() {
+        select.bind(params)?;
+    }
+    select.execute()?;
+    let row = first_row(select.result_set()?)?;
+    row[0]
+        .value()
+        .ok_or_else(|| OciError::Conversion(format!("Expected an SCN, found: {:?}", row[0]).into()))
+}
+
+fn first_row(rows: &[Row]) -> Result<&Row, OciError> {
+    rows.first()
+        .ok_or_else(|| OciError::Conversion("The query returned no rows".into()))
+}
@@ -0,0 +1,101 @@
+use std::fmt;
+
+const REDACTED: &str = "********";
+
+/// A string holding a credential such as a password or token.
+///
+/// It behaves like a normal `String` except that its `Debug` output is redacted, so that
+/// credentials can't leak by accident when a struct holding one is logged via `{:?}`. The actual
+/// value is only available through an explicit call to `.expose_secret`.
+///
+/// # Examples
+///
+/// ```rust
+/// use oci_rs::secret::SecretString;
+///
+/// let password = SecretString::from("tiger");
+///
+/// assert_eq!(format!("{:?}", password), "SecretString(********)");
+/// assert_eq!(password.expose_secret(), "tiger");
+/// ```
+///
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretString {
+    secret: String,
+}
+impl SecretString {
+    /// Creates a new `SecretString`.
+    ///
+    pub fn new(secret: String) -> SecretString {
+        SecretString { secret }
+    }
+
+    /// Returns the secret value.
+    ///
+    pub fn expose_secret(&self) -> &str {
+        &self.secret
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(secret: String) -> Self {
+        SecretString::new(secret)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(secret: &str) -> Self {
+        SecretString::new(secret.to_string())
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SecretString({})", REDACTED)
+    }
+}
+
+/// Replaces the password of a `user/password@host` connect string, and the value of any
+/// `key=value` pair whose key looks like it holds a credential, e.g. `password` or `token`.
+///
+pub(crate) fn redact_connect_string(connect_string: &str) -> String {
+    let mut redacted = String::with_capacity(connect_string.len());
+    let mut rest = connect_string;
+    // the credentials come before the last `@` ahead of any Easy Connect parameters, a quoted
+    // password can itself hold an `@`
+    let head = &rest[..rest.find('?').unwrap_or(rest.len())];
+    if let Some(at) = head.rfind('@') {
+        let credentials = &head[..at];
+        if let Some(slash) = credentials.find('/') {
+            if !credentials.contains(['(', '=']) {
+                redacted.push_str(&credentials[..=slash]);
+                redacted.push_str(REDACTED);
+                rest = &rest[at..];
+            }
+        }
+    }
+    while let Some(equals) = rest.find('=') {
+        let key = &rest[..equals];
+        let key_start = key
+            .rfind(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .map_or(0, |index| index + 1);
+        let value = &rest[equals + 1..];
+        let value_end = value.find(['&', ')', ';']).unwrap_or(value.len());
+        redacted.push_str(&rest[..=equals]);
+        if is_secret_key(&key[key_start..]) {
+            redacted.push_str(REDACTED);
+        } else {
+            redacted.push_str(&value[..value_end]);
+        }
+        rest = &value[value_end..];
+    }
+    redacted.push_str(rest);
+    redacted
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_lowercase();
+    ["password", "passwd", "pwd", "token", "secret"]
+        .iter()
+        .any(|secret| key.contains(secret))
+}
@@ -1,20 +1,83 @@
-use crate::common::set_handle_attribute;
-use crate::connection::Connection;
+use crate::bfile::BFile;
+use crate::collection::{CollectionObject, CollectionType};
+use crate::common::{set_handle_attribute, wait_for_call, STILL_EXECUTING_WAIT};
+use crate::connection::{create_error_handle, Connection};
+use crate::describe::{type_name, ColumnDescription};
+use crate::diagnostics::CallLog;
+pub use crate::oci_bindings::StatementType;
 use crate::oci_bindings::{
     AttributeType, DescriptorType, EnvironmentMode, FetchType, HandleType, OCIAttrGet, OCIBind,
-    OCIBindByPos, OCIDefine, OCIDefineByPos, OCIDescriptorFree, OCIError, OCIParam, OCIParamGet,
-    OCISnapshot, OCIStmt, OCIStmtExecute, OCIStmtFetch2, OCIStmtPrepare2, OCIStmtRelease,
-    OCITransCommit, OciDataType, ReturnCode, StatementType, SyntaxType,
+    OCIBindByName, OCIBindByPos, OCIBreak, OCIDefine, OCIDefineByPos, OCIDescriptorAlloc,
+    OCIDescriptorFree, OCIError, OCIHandleFree, OCILobGetLength2, OCILobLocator, OCILobRead2,
+    OCIParam, OCIParamGet, OCIReset, OCIRowid, OCIRowidToChar, OCISnapshot, OCIStmt,
+    OCIStmtExecute, OCIStmtFetch2, OCIStmtGetBindInfo, OCIStmtPrepare2, OCIStmtRelease, OCISvcCtx,
+    OciDataType, ReturnCode, SyntaxType, SQLT_AFC, SQLT_CHR, SQLT_JSON, SQLT_NTY, SQLT_NUM,
 };
-use crate::oci_error::{get_error, OciError};
-use crate::row::Row;
-use crate::types::{SqlValue, ToSqlValue};
-use libc::{c_int, c_schar, c_short, c_uint, c_ushort, c_void};
+use crate::oci_error::{
+    get_error, get_warnings, BatchError, BindCountMismatch, ErrorRecord, FetchTruncated, OciError,
+    ResultSetConsumed, UsageError, Warning,
+};
+use crate::row::{FromRow, Row};
+use crate::types::{
+    ColumnType, DecodeContext, NumberTextCodec, SqlType, SqlValue, TextCodec, TextTrim, ToParams,
+    ToSqlParams, ToSqlValue, TypeCodec, ValueType, XmlTypeCodec, DEFAULT_CHAR_TRIM,
+    DEFAULT_VARCHAR_TRIM,
+};
+use libc::{c_int, c_schar, c_short, c_uchar, c_uint, c_ushort, c_void};
+use log::{debug, error, warn};
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
 use std::ptr;
+use std::slice;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::Poll;
+use std::thread;
+use std::time::Duration;
+
+/// ORA-04068: existing state of packages has been discarded
+const PACKAGE_STATE_DISCARDED: i32 = 4068;
+/// ORA-04061: existing state of package has been invalidated
+const PACKAGE_STATE_INVALIDATED: i32 = 4061;
+const DEFAULT_PACKAGE_STATE_RETRIES: u32 = 1;
+/// ORA-01013: user requested cancel of current operation
+const USER_CANCELLED: i32 = 1013;
+/// ORA-01406: fetched column value was truncated, the column level return code of a fetch
+const FETCH_TRUNCATED: c_ushort = 1406;
+/// Big enough for the text of any ROWID, including the universal ROWIDs of index organised
+/// tables.
+const ROWID_BUFFER_SIZE: usize = 4000;
+/// The most bytes a character takes up in any Oracle character set, i.e. AL32UTF8.
+const MAX_BYTES_PER_CHAR: c_int = 4;
+/// SQLCS_IMPLICIT, the database character set.
+const SQLCS_IMPLICIT: c_uchar = 1;
+/// SQLCS_NCHAR, the national character set, used by `NCHAR`, `NVARCHAR2` and `NCLOB`.
+const SQLCS_NCHAR: c_uchar = 2;
+/// The character set ID of AL32UTF8, which text read from a LOB or fetched from a national
+/// character set column is converted to.
+const AL32UTF8_ID: c_ushort = 873;
+/// OCI_ONE_PIECE, read a LOB in a single call.
+const OCI_ONE_PIECE: c_uchar = 0;
+const DEFAULT_FETCH_ARRAY_SIZE: u32 = 100;
+/// The number of parameter sets sent in each batch by `.execute_many`.
+const EXECUTE_MANY_CHUNK_SIZE: usize = 1000;
+/// The most memory, in bytes, given over to the buffers that rows are fetched into.
+const FETCH_BUFFER_LIMIT: usize = 1024 * 1024;
+/// The memory, in bytes, that `Prefetch::Auto` aims to fill with prefetched rows.
+const AUTO_PREFETCH_BYTES: usize = 512 * 1024;
+/// The most rows prefetched by `Prefetch::Auto`, or when the memory limit decides.
+const MAX_PREFETCH_ROWS: u32 = 10_000;
 
 #[derive(Debug)]
 enum ResultState {
+    /// Fetched in full by `.result_set` and held on to.
     Fetched,
+    /// Handed out, or being handed out, by `.lazy_result_set`.
+    Streamed,
     NotFetched,
 }
 
@@ -29,8 +92,8 @@ enum ResultState {
 /// state
 /// of the object. The underlying OCI objects are stateful and re-use of an OCI statement for new
 /// binding parameters or diferent results is more efficient than allocating resources for a new
-/// statement. At the moment changing the SQL requires a new `Statement` but it might prove useful
-/// in future to allow this to be also changed without new allocation in the OCI library.
+/// statement. The SQL can also be changed with `.prepare`, which keeps the settings of the
+/// `Statement` and its buffers.
 ///
 /// See the [module level documentation][2] for an overview plus examples.
 ///
@@ -40,24 +103,177 @@ enum ResultState {
 pub struct Statement<'conn> {
     connection: &'conn Connection,
     statement: *mut OCIStmt,
+    sql: String,
     bindings: Vec<*mut OCIBind>,
     values: Vec<SqlValue>,
+    indicators: Vec<c_short>,
+    array_binds: Vec<ArrayBind>,
+    position_binds: Vec<PositionBind>,
+    collection_binds: Vec<CollectionObject>,
     result_set: Vec<Row>,
     result_state: ResultState,
+    prefetch_rows: Option<u32>,
+    prefetch_target: Option<u32>,
+    prefetch_memory: Option<u32>,
+    scrollable: bool,
+    call_timeout: Option<Duration>,
+    result_set_bytes: usize,
+    result_set_high_water: usize,
+    result_set_warning: Option<usize>,
+    select_star_check: bool,
+    safe_fetch: bool,
+    numbers_as_text: bool,
+    varchar_trim: TextTrim,
+    char_trim: TextTrim,
+    fetch_array_size: u32,
+    column_caps: Vec<(String, u32)>,
+    locator_columns: RefCell<Vec<String>>,
+    columns: RefCell<Option<Vec<Column>>>,
+    call_state: Arc<CallState>,
+    package_state_retries: u32,
+    bind_duplicates_once: bool,
+    prefetch_auto: bool,
+    warnings: RefCell<Vec<Warning>>,
+    cache_key: Option<String>,
+    cached: bool,
 }
 impl<'conn> Statement<'conn> {
     /// Creates a new `Statement`.
     ///
     pub(crate) fn new(connection: &'conn Connection, sql: &str) -> Result<Self, OciError> {
-        let statement = prepare_statement(connection, sql)?;
-        Ok(Statement {
+        Statement::with_options(connection, sql, &StatementOptions::default())
+    }
+
+    /// Creates a new `Statement` with the options applied.
+    ///
+    pub(crate) fn with_options(
+        connection: &'conn Connection,
+        sql: &str,
+        options: &StatementOptions,
+    ) -> Result<Self, OciError> {
+        let key = options.cache_key.as_deref().unwrap_or(sql);
+        let statement = prepare_statement(connection, sql, key)?;
+        let mut statement = Statement {
             connection,
             statement,
+            sql: sql.to_string(),
             bindings: Vec::new(),
             values: Vec::new(),
+            indicators: Vec::new(),
+            array_binds: Vec::new(),
+            position_binds: Vec::new(),
+            collection_binds: Vec::new(),
             result_set: Vec::new(),
             result_state: ResultState::NotFetched,
-        })
+            prefetch_rows: None,
+            prefetch_target: None,
+            prefetch_memory: None,
+            scrollable: false,
+            call_timeout: None,
+            result_set_bytes: 0,
+            result_set_high_water: 0,
+            result_set_warning: None,
+            select_star_check: false,
+            safe_fetch: false,
+            numbers_as_text: connection.numbers_as_text(),
+            varchar_trim: connection.text_trim().0,
+            char_trim: connection.text_trim().1,
+            fetch_array_size: DEFAULT_FETCH_ARRAY_SIZE,
+            column_caps: Vec::new(),
+            locator_columns: RefCell::new(Vec::new()),
+            columns: RefCell::new(None),
+            call_state: Arc::new(CallState::default()),
+            package_state_retries: DEFAULT_PACKAGE_STATE_RETRIES,
+            bind_duplicates_once: false,
+            prefetch_auto: false,
+            warnings: RefCell::new(Vec::new()),
+            cache_key: options.cache_key.clone(),
+            cached: !options.skip_cache,
+        };
+        statement.apply_options(options)?;
+        Ok(statement)
+    }
+
+    /// Prepares new SQL in place of the current SQL.
+    ///
+    /// The old statement is released back to the statement cache and the new one is prepared
+    /// on the same `Statement`, so the settings made on it, such as prefetching, the fetch array
+    /// size and safe fetching, are kept and its buffers are reused. Any parameters bound and
+    /// any result set fetched are cleared. A cache key set with `StatementOptions.cache_key`
+    /// only applies to the old SQL, the new SQL is cached under itself.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned. If the new SQL
+    /// can't be prepared then the `Statement` is left with the old SQL.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// let mut stmt = conn
+    ///     .create_prepared_statement("SELECT Name FROM Dogs")
+    ///     .unwrap();
+    /// stmt.set_prefetch(100).unwrap();
+    /// stmt.execute().unwrap();
+    ///
+    /// stmt.prepare("SELECT Name FROM Cats").unwrap();
+    /// stmt.execute().unwrap();
+    /// ```
+    ///
+    pub fn prepare(&mut self, sql: &str) -> Result<(), OciError> {
+        let statement = prepare_statement(self.connection, sql, sql)?;
+        self.forget_columns();
+        let old_statement = mem::replace(&mut self.statement, statement);
+        let old_sql = mem::replace(&mut self.sql, sql.to_string());
+        let old_key = self.cache_key.take().unwrap_or(old_sql);
+        self.bindings.clear();
+        self.values.clear();
+        self.indicators.clear();
+        self.array_binds.clear();
+        self.position_binds.clear();
+        self.collection_binds.clear();
+        self.result_set.clear();
+        self.results_not_fetched();
+        self.locator_columns.get_mut().clear();
+        self.warnings.get_mut().clear();
+        release_statement(old_statement, self.connection, &old_key, self.cached)?;
+        if let Some(rows) = self.prefetch_rows {
+            set_prefetch_rows(self.statement, self.connection.error(), rows)?;
+        }
+        if let Some(target_bytes) = self.prefetch_target.or(self.prefetch_memory) {
+            set_prefetch_memory(self.statement, self.connection.error(), target_bytes)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the key the statement is found by in the statement cache, either the key set with
+    /// `StatementOptions.cache_key` or the SQL itself.
+    ///
+    pub fn cache_key(&self) -> &str {
+        self.cache_key.as_deref().unwrap_or(&self.sql)
+    }
+
+    /// Applies the options the statement was created with.
+    ///
+    fn apply_options(&mut self, options: &StatementOptions) -> Result<(), OciError> {
+        if let Some(rows) = options.prefetch_rows {
+            set_prefetch_rows(self.statement, self.connection.error(), rows)?;
+            self.prefetch_rows = Some(rows);
+        }
+        if let Some(bytes) = options.prefetch_memory {
+            set_prefetch_memory(self.statement, self.connection.error(), bytes)?;
+            self.prefetch_memory = Some(bytes);
+        }
+        if let Some(rows) = options.fetch_array_size {
+            self.set_fetch_array_size(rows);
+        }
+        self.scrollable = options.scrollable;
+        self.call_timeout = options.call_timeout;
+        Ok(())
     }
 
     /// Sets the parameters that will be used in a SQL statement with bind variables.
@@ -66,6 +282,10 @@ impl<'conn> Statement<'conn> {
     ///
     /// # Errors
     ///
+    /// Returns [`BindCountMismatch`][1], inside `OciError::Usage`, if the number of
+    /// parameters isn't the same as the number of placeholders in the SQL, or of distinct
+    /// placeholders if `.set_bind_duplicates_once` is turned on.
+    ///
     /// Any error in the underlying calls to the OCI library will be returned.
     ///
     /// # Examples
@@ -109,6 +329,7 @@ impl<'conn> Statement<'conn> {
     ///
     /// let correct_results = vec!["Poodle".to_string(), "Bulldog".to_string()];
     /// let results: Vec<String> = select.lazy_result_set()
+    ///                                  .unwrap()
     ///                                  .map(|row_result| row_result.unwrap())
     ///                                  .map(|row| row[0].value::<String>().unwrap())
     ///                                  .collect();
@@ -116,25 +337,40 @@ impl<'conn> Statement<'conn> {
     /// assert_eq!(results, correct_results);
     /// ```
     /// For large scale inserts to the database this is a bit inefficient as many calls to bind
-    /// the parameters are needed. OCI does support batch processing and/or arrays of bind
-    /// parameters, however this is not yet available through this crate.
+    /// the parameters are needed, and each execution is a round trip to the database. Use
+    /// `.execute_batch` instead to send many rows at once.
     ///
+    /// [1]: ../oci_error/struct.BindCountMismatch.html
     pub fn bind(&mut self, params: &[&ToSqlValue]) -> Result<(), OciError> {
+        if self.bind_duplicates_once {
+            return self.bind_once_per_name(params);
+        }
+        let placeholders = self.placeholder_count()?;
+        if params.len() != placeholders {
+            return Err(BindCountMismatch::new(placeholders, params.len()).into());
+        }
+
         // clear out previous bind parameters
         self.values.clear();
+        self.indicators.clear();
+        self.array_binds.clear();
+        self.position_binds.clear();
+        self.collection_binds.clear();
 
         // ensure that the vec is large enough to hold all the parameters
         // otherwise the vec will re-size, re-allocate and the addresses will change
         self.values.reserve(params.len());
+        self.indicators.reserve(params.len());
 
         for (index, param) in params.iter().enumerate() {
             let sql_value = param.to_sql_value();
+            self.indicators.push(sql_value.indicator());
             self.values.push(sql_value);
             let binding: *mut OCIBind = ptr::null_mut();
             self.bindings.push(binding);
             let position = (index + 1) as c_uint;
-            let null_mut_ptr = ptr::null_mut();
-            let indp = null_mut_ptr;
+            let null_mut_ptr: *mut c_void = ptr::null_mut();
+            let indp = &mut self.indicators[index] as *mut c_short as *mut c_void;
             let alenp = null_mut_ptr as *mut c_ushort;
             let rcodep = null_mut_ptr as *mut c_ushort;
             let curelep = null_mut_ptr as *mut c_uint;
@@ -167,238 +403,2623 @@ impl<'conn> Statement<'conn> {
                     ));
                 }
             }
+            self.bind_collection(index)?;
         }
         Ok(())
     }
 
-    /// Executes the SQL statement.
+    /// Sets the parameters that will be used in a SQL statement with bind variables, matching
+    /// them to the placeholders by name.
+    ///
+    /// The parameters can be given in any order and a placeholder that appears more than once in
+    /// the SQL only needs one value. Names are not case sensitive and the leading colon is
+    /// optional.
     ///
     /// # Errors
     ///
-    /// Any error in the underlying calls to the OCI library will be returned.
+    /// Any error in the underlying calls to the OCI library will be returned, including when a
+    /// name doesn't match a placeholder in the statement.
     ///
-    pub fn execute(&mut self) -> Result<(), OciError> {
-        let stmt_type = get_statement_type(self.statement, self.connection.error())?;
-        let iters = match stmt_type {
-            StatementType::Select => 0 as c_uint,
-            _ => 1 as c_uint,
-        };
-        let rowoff = 0 as c_uint;
-        let snap_in: *const OCISnapshot = ptr::null();
-        let snap_out: *mut OCISnapshot = ptr::null_mut();
-        let execute_result = unsafe {
-            OCIStmtExecute(
-                self.connection.service(),
-                self.statement,
-                self.connection.error(),
-                iters,
-                rowoff,
-                snap_in,
-                snap_out,
-                EnvironmentMode::Default.into(),
-            )
-        };
-        match execute_result.into() {
-            ReturnCode::Success => {
-                self.results_not_fetched();
-                Ok(())
-            }
-            _ => Err(get_error(
-                self.connection.error_as_mut_void(),
-                HandleType::Error,
-                "Executing statement",
-            )),
-        }
-    }
-
-    /// Returns the results of a `SELECT` statement.
+    /// # Examples
     ///
-    /// After the execution of a `SELECT` statement a result set will be available from the
-    /// database. This will contain none or many `Row`s of data depending on the query. There are
-    /// two options for seeing the results, the first is to call this method to retrieve all the
-    /// rows in one go, the second is to iterate through them row by row.
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
     ///
-    /// Should you go for the first option then the rows will be fetched once this method is
-    /// called. They will not be fetched eagerly as part of the `.execute` call, although this is
-    /// not apparent to the caller. Once the results are retrieved from the database then they will
-    /// be held until either the `Statement` goes out of scope or `.execute` is called again. This
-    /// way, repeated calls to `.result_set` will be the same. If there are no data then an empty
-    /// `Vec<Row>` will be returned.
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
     ///
-    /// # Errors
+    /// let sql_insert = "INSERT INTO Dogs (DogId, Name)
+    ///                   VALUES (:id, :name)";
+    /// let mut insert = conn.create_prepared_statement(sql_insert).unwrap();
     ///
-    /// Any error in the underlying calls to the OCI library will be returned.
+    /// insert.bind_named(&[("name", &"Poodle"), ("id", &1)]).unwrap();
+    /// insert.execute().unwrap();
+    /// ```
     ///
-    pub fn result_set(&mut self) -> Result<&[Row], OciError> {
-        match self.result_state {
-            ResultState::Fetched => (),
-            ResultState::NotFetched => {
-                let rows: Result<Vec<Row>, _> = self.lazy_result_set().collect();
-                self.result_set = rows?
-            }
-        }
-        Ok(&self.result_set)
+    pub fn bind_named(&mut self, params: &[(&str, &dyn ToSqlValue)]) -> Result<(), OciError> {
+        let values = params
+            .iter()
+            .map(|&(name, value)| {
+                (
+                    name.trim_start_matches(':').to_string(),
+                    value.to_sql_value(),
+                )
+            })
+            .collect();
+        self.bind_by_name(values)
     }
 
-    /// Set the number of rows that will be prefetched from the database.
+    /// Sets the parameters of a SQL statement from a map of placeholder names to values.
     ///
-    /// The OCI library internally manages the number of rows that are pre-fetched from the
-    /// database. This can be tweaked. The OCI default is one row, so for each call to the
-    /// database two rows are retrieved, thus half the number of round trips needed.
+    /// This suits SQL and parameters that are loaded from somewhere else, e.g. a configuration
+    /// file, as the values don't have to be put in the order of the placeholders. Names are
+    /// matched to the placeholders without regard to case and can be given with or without the
+    /// leading colon. A placeholder that appears more than once in the SQL only needs one value.
     ///
     /// # Errors
     ///
-    /// Any error in the underlying calls to the OCI library will be returned.
+    /// An `OciError::Usage` holding `UsageError::Bind` is returned if a placeholder has no value
+    /// in the map, or if the map has a value that doesn't match any placeholder. Any error in the
+    /// underlying calls to the OCI library will be returned.
     ///
-    pub fn set_prefetch(&mut self, nmb_of_rows: i32) -> Result<(), OciError> {
-        let size: c_uint = 0;
-        let rows: c_uint = nmb_of_rows as c_uint;
-        let rows_ptr: *const c_uint = &rows;
-        set_handle_attribute(
-            self.statement as *mut c_void,
-            HandleType::Statement,
-            rows_ptr as *mut c_void,
-            size,
-            AttributeType::PrefetchRows,
-            self.connection.error(),
-            "Setting prefetch rows in statement handle",
-        )?;
-        Ok(())
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    /// use std::collections::HashMap;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// let mut params = HashMap::new();
+    /// params.insert("Name".to_string(), "Poodle".to_string());
+    /// params.insert("id".to_string(), "1".to_string());
+    ///
+    /// let mut insert = conn
+    ///     .create_prepared_statement("INSERT INTO Dogs (DogId, Name) VALUES (:id, :name)")
+    ///     .unwrap();
+    /// insert.bind_map(&params).unwrap();
+    /// insert.execute().unwrap();
+    /// ```
+    ///
+    pub fn bind_map<V: ToSqlValue>(&mut self, params: &HashMap<String, V>) -> Result<(), OciError> {
+        let placeholders = placeholder_names(&self.sql);
+        for name in params.keys() {
+            let name = name.trim_start_matches(':');
+            if !placeholders
+                .iter()
+                .any(|placeholder| placeholder.eq_ignore_ascii_case(name))
+            {
+                return Err(UsageError::Bind(format!(
+                    "There is no placeholder :{} in the statement",
+                    name
+                ))
+                .into());
+            }
+        }
+        let mut values = Vec::with_capacity(placeholders.len());
+        for placeholder in placeholders {
+            let value = params
+                .iter()
+                .find(|&(name, _)| {
+                    name.trim_start_matches(':')
+                        .eq_ignore_ascii_case(&placeholder)
+                })
+                .map(|(_, value)| value.to_sql_value())
+                .ok_or_else(|| {
+                    UsageError::Bind(format!(
+                        "No value was given for placeholder :{}",
+                        placeholder
+                    ))
+                })?;
+            values.push((placeholder, value));
+        }
+        self.bind_by_name(values)
     }
 
-    /// Returns the results of a `SELECT` statement row by row via the `RowIter` iterator.
-    ///
-    /// The `RowIter` returned can then be used to run through the rows of data in the result set.
-    /// This is a more attractive option if there are many rows or you want to process the results
-    /// in a pipeline.
+    /// Sets the parameters of a SQL statement from a type that implements [`ToParams`][1],
+    /// typically a struct with a field for each placeholder.
     ///
-    /// The same comments about pre-fetching configuration applies here as to `.result_set`.
+    /// The parameters are matched to the placeholders by name, as with `.bind_named`.
     ///
     /// # Errors
     ///
-    /// This method will not report errors directly however subsequent use of `RowIter` will return
-    /// any OCI errors encountered as each row is fetched.
+    /// Any error in the underlying calls to the OCI library will be returned, including when a
+    /// name doesn't match a placeholder in the statement.
     ///
     /// # Examples
     ///
-    /// ```rust
+    /// ```rust,no_run
     /// use oci_rs::connection::Connection;
+    /// use oci_rs::types::{SqlValue, ToParams, ToSqlValue};
+    ///
+    /// struct Dog {
+    ///     id: i64,
+    ///     name: String,
+    /// }
+    ///
+    /// impl ToParams for Dog {
+    ///     fn to_params(&self) -> Vec<(String, SqlValue)> {
+    ///         vec![
+    ///             ("id".to_string(), self.id.to_sql_value()),
+    ///             ("name".to_string(), self.name.to_sql_value()),
+    ///         ]
+    ///     }
+    /// }
     ///
     /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
     ///
-    /// # let mut drop = conn.create_prepared_statement("DROP TABLE Countries").unwrap();
-    /// # drop.execute().ok();
-    /// # let sql_create = "CREATE TABLE Countries (CountryId INTEGER,
-    /// #                                           Name VARCHAR(20))";
-    /// # let mut create = conn.create_prepared_statement(sql_create).unwrap();
-    /// # create.execute().unwrap();
-    /// # create.commit().unwrap();
+    /// let mut insert = conn
+    ///     .create_prepared_statement("INSERT INTO Dogs (DogId, Name) VALUES (:id, :name)")
+    ///     .unwrap();
+    /// let dog = Dog {
+    ///     id: 1,
+    ///     name: "Poodle".to_string(),
+    /// };
+    /// insert.bind_params(&dog).unwrap();
+    /// insert.execute().unwrap();
+    /// ```
     ///
-    /// // Insert some values using bind variables
-    /// let sql_insert = "INSERT INTO Countries (CountryId, Name)
-    ///                   VALUES (:id, :name)";
-    /// let mut insert = conn.create_prepared_statement(sql_insert).unwrap();
+    /// [1]: ../types/trait.ToParams.html
+    pub fn bind_params<P: ToParams + ?Sized>(&mut self, params: &P) -> Result<(), OciError> {
+        let values = params
+            .to_params()
+            .into_iter()
+            .map(|(name, value)| (name.trim_start_matches(':').to_string(), value))
+            .collect();
+        self.bind_by_name(values)
+    }
+
+    /// Lets `.bind` take a single value for a placeholder that appears more than once in the SQL.
     ///
-    /// let countries = vec!["Great Britain",
-    ///                      "Australia",
-    ///                      "Burma",
-    ///                      "Japan",
-    ///                      "Sudan",
-    ///                      "France",
-    ///                      "Germany",
-    ///                      "China"];
+    /// Normally each appearance of a placeholder in SQL is a separate position that needs its
+    /// own value, so `WHERE Owner = :name OR Walker = :name` takes the name twice. With this
+    /// turned on `.bind` takes one value for each distinct placeholder, in the order they first
+    /// appear, and sets every appearance from it, as happens with `.bind_named`. PL/SQL blocks
+    /// always work this way.
     ///
-    /// for (index, country) in countries.iter().enumerate(){
-    ///     let id = (index + 1) as i64;
-    ///     insert.bind(&[&id, country]).unwrap();
-    ///     insert.execute();
-    /// }
-    /// insert.commit();
+    /// # Examples
     ///
-    /// let sql_select = "SELECT Name FROM Countries";
-    /// let mut select = conn.create_prepared_statement(sql_select).unwrap();
-    /// select.execute().unwrap();
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
     ///
-    /// let results: Vec<String> = select.lazy_result_set()
-    ///                                  .map(|row_result| row_result.unwrap())
-    ///                                  .map(|row| row[0].value::<String>().unwrap())
-    ///                                  .filter(|country| country.contains("c") ||
-    ///                                                    country.contains("C"))
-    ///                                  .map(|country| country.to_uppercase())
-    ///                                  .collect();
-    /// assert_eq!(results.len(), 2);
-    /// assert!(results.contains(&"CHINA".to_string()));
-    /// assert!(results.contains(&"FRANCE".to_string()));
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// let mut select = conn
+    ///     .create_prepared_statement(
+    ///         "SELECT DogId FROM Dogs WHERE Owner = :name OR Walker = :name AND Born > :born",
+    ///     )
+    ///     .unwrap();
+    /// select.set_bind_duplicates_once(true);
+    /// select.bind(&[&"Alice", &2015]).unwrap();
+    /// select.execute().unwrap();
     /// ```
     ///
-    pub fn lazy_result_set(&mut self) -> RowIter {
-        match self.result_state {
-            ResultState::Fetched => panic!("Lazy fetch already completed."),
-            ResultState::NotFetched => {
-                self.results_fetched();
-                RowIter { statement: self }
+    pub fn set_bind_duplicates_once(&mut self, once: bool) {
+        self.bind_duplicates_once = once;
+    }
+
+    /// Binds one value to each distinct placeholder name, in the order they first appear.
+    ///
+    fn bind_once_per_name(&mut self, params: &[&dyn ToSqlValue]) -> Result<(), OciError> {
+        let names: Vec<String> = self
+            .bind_placeholders()?
+            .into_iter()
+            .filter(|placeholder| !placeholder.is_duplicate())
+            .map(|placeholder| placeholder.name().to_string())
+            .collect();
+        if params.len() != names.len() {
+            return Err(BindCountMismatch::new(names.len(), params.len()).into());
+        }
+        let values = names
+            .into_iter()
+            .zip(params.iter().map(|param| param.to_sql_value()))
+            .collect();
+        self.bind_by_name(values)
+    }
+
+    /// Binds each value to the placeholder with the given name.
+    ///
+    fn bind_by_name(&mut self, params: Vec<(String, SqlValue)>) -> Result<(), OciError> {
+        self.values.clear();
+        self.indicators.clear();
+        self.array_binds.clear();
+        self.position_binds.clear();
+        self.collection_binds.clear();
+        self.bindings.clear();
+
+        // as in `.bind` the vecs must not re-allocate once OCI has the addresses
+        self.values.reserve(params.len());
+        self.indicators.reserve(params.len());
+        self.bindings.reserve(params.len());
+
+        for (index, (name, sql_value)) in params.into_iter().enumerate() {
+            self.indicators.push(sql_value.indicator());
+            self.values.push(sql_value);
+            self.bindings.push(ptr::null_mut());
+            let placeholder = format!(":{}", name);
+            let null_mut_ptr: *mut c_void = ptr::null_mut();
+            let indp = &mut self.indicators[index] as *mut c_short as *mut c_void;
+            let alenp = null_mut_ptr as *mut c_ushort;
+            let rcodep = null_mut_ptr as *mut c_ushort;
+            let curelep = null_mut_ptr as *mut c_uint;
+            let maxarr_len: c_uint = 0;
+
+            let bind_result = unsafe {
+                OCIBindByName(
+                    self.statement,
+                    &self.bindings[index],
+                    self.connection.error(),
+                    placeholder.as_ptr(),
+                    placeholder.len() as c_int,
+                    self.values[index].as_oci_ptr(),
+                    self.values[index].size(),
+                    self.values[index].as_oci_data_type().into(),
+                    indp,
+                    alenp,
+                    rcodep,
+                    maxarr_len,
+                    curelep,
+                    EnvironmentMode::Default.into(),
+                )
+            };
+            match bind_result.into() {
+                ReturnCode::Success => (),
+                _ => {
+                    return Err(get_error(
+                        self.connection.error_as_mut_void(),
+                        HandleType::Error,
+                        &format!("Binding parameter {}", placeholder),
+                    ));
+                }
             }
+            self.bind_collection(index)?;
         }
+        Ok(())
     }
 
-    /// Commits the changes to the database.
+    /// A collection is bound as a named type and then needs its collection object bound too,
+    /// which is kept until the next bind so that OCI can read it when the statement is executed.
     ///
-    /// When a statement makes changes to the database Oracle implicitly starts a
-    /// transaction. If all is well and the session is closed normally this will cause an
-    /// implicit commit of the changes. If anything goes wrong and the sesssion is not closed or
-    /// the connection is broken, Oracle will roll back the changes. This method, therefore allows
-    /// you to commit changes when you want, rather than relying on a successfull disconnection.
+    fn bind_collection(&mut self, index: usize) -> Result<(), OciError> {
+        if let SqlValue::Collection(ref collection) = self.values[index] {
+            let mut object = CollectionObject::new(self.connection, collection)?;
+            object.bind(self.bindings[index])?;
+            self.collection_binds.push(object);
+        }
+        Ok(())
+    }
+
+    /// Executes the SQL statement.
+    ///
+    /// PL/SQL blocks that fail because the state of a package has been discarded are run again,
+    /// see `.set_package_state_retries`.
     ///
     /// # Errors
     ///
     /// Any error in the underlying calls to the OCI library will be returned.
     ///
-    pub fn commit(&self) -> Result<(), OciError> {
-        let commit_result = unsafe {
-            OCITransCommit(
-                self.connection.service(),
-                self.connection.error(),
-                EnvironmentMode::Default.into(),
-            )
-        };
-        match commit_result.into() {
-            ReturnCode::Success => Ok(()),
-            _ => Err(get_error(
-                self.connection.error_as_mut_void(),
-                HandleType::Error,
-                "Commiting statement",
-            )),
-        }
+    pub fn execute(&mut self) -> Result<(), OciError> {
+        self.execute_in_mode(EnvironmentMode::Default)
     }
 
-    /// Transition to fetched state.
+    /// Executes the SQL statement, treating a failure with any of the given Oracle error codes
+    /// as success. Returns true if an error was ignored.
     ///
-    fn results_fetched(&mut self) {
-        self.result_state = ResultState::Fetched
-    }
+    /// The codes are the numbers after `ORA-`, e.g. 942 for "table or view does not exist", which
+    /// saves matching on the error when dropping a table that might not be there.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned, unless it has one
+    /// of the given codes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// let mut drop = conn.create_prepared_statement("DROP TABLE Dogs").unwrap();
+    /// if drop.execute_ignoring(&[942]).unwrap() {
+    ///     println!("There was no table to drop");
+    /// }
+    /// ```
+    ///
+    pub fn execute_ignoring(&mut self, error_codes: &[i32]) -> Result<bool, OciError> {
+        match self.execute() {
+            Ok(()) => Ok(false),
+            Err(OciError::Oracle(ref record))
+                if error_codes.iter().any(|&code| record.has_code(code)) =>
+            {
+                debug!("Ignoring error executing {}: {}", self.sql, record);
+                Ok(true)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Executes the SQL statement and commits the transaction if it succeeds, in a single round
+    /// trip.
+    ///
+    /// This saves the separate call to `.commit` for a transaction made up of just this
+    /// statement. Like `.commit` it commits every change made in the session so far, not only
+    /// those of this statement. Nothing is committed if the statement fails.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// let mut update = conn
+    ///     .create_prepared_statement("UPDATE Dogs SET Name = :name WHERE DogId = :id")
+    ///     .unwrap();
+    /// update.bind(&[&"Labrador", &1]).unwrap();
+    /// update.execute_and_commit().unwrap();
+    /// ```
+    ///
+    pub fn execute_and_commit(&mut self) -> Result<(), OciError> {
+        self.execute_in_mode(EnvironmentMode::CommitOnSuccess)?;
+        self.connection.end_transaction("commit on success");
+        Ok(())
+    }
+
+    /// Executes the SQL statement with the given mode, retrying PL/SQL blocks when the state of a
+    /// package has been discarded.
+    ///
+    fn execute_in_mode(&mut self, mode: EnvironmentMode) -> Result<(), OciError> {
+        let (stmt_type, mode, iters) = self.execution(mode)?;
+        let retries = match stmt_type {
+            StatementType::Begin | StatementType::Declare => self.package_state_retries,
+            _ => 0,
+        };
+        let mut attempt = 0;
+        loop {
+            match self.execute_once(iters, mode) {
+                Ok(()) => {
+                    self.connection.statement_executed(&stmt_type, &self.sql);
+                    return Ok(());
+                }
+                Err(OciError::Oracle(ref record))
+                    if attempt < retries && is_package_state_discarded(record) =>
+                {
+                    attempt += 1;
+                    warn!(
+                        "Package state discarded, retrying ({} of {}): {}",
+                        attempt, retries, record
+                    );
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Works out how to execute the statement: its type, the mode to use and the number of
+    /// iterations, which is zero for a query as its rows are fetched afterwards.
+    ///
+    fn execution(
+        &self,
+        mode: EnvironmentMode,
+    ) -> Result<(StatementType, EnvironmentMode, c_uint), OciError> {
+        let stmt_type = get_statement_type(self.statement, self.connection.error())?;
+        let mode = match stmt_type {
+            StatementType::Select if self.scrollable => EnvironmentMode::ScrollableReadOnly,
+            _ => mode,
+        };
+        let iters = match stmt_type {
+            StatementType::Select => 0,
+            _ => 1,
+        };
+        Ok((stmt_type, mode, iters))
+    }
+
+    /// Executes the SQL statement without waiting for the database, on a connection in
+    /// non-blocking mode.
+    ///
+    /// Returns `Poll::Pending` while the database is still working on it, in which case it has
+    /// to be called again, and `Poll::Ready` once it has finished. The statement must not be
+    /// used for anything else in between. The rows of a query can then be fetched without
+    /// waiting with `RowIter.poll_next`. On a connection that isn't in non-blocking mode it
+    /// waits for the execution and is always ready. See `Connection.set_nonblocking`.
+    ///
+    /// Unlike `.execute` it doesn't retry PL/SQL after the state of a package was discarded.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned.
+    ///
+    pub fn poll_execute(&mut self) -> Result<Poll<()>, OciError> {
+        let (stmt_type, mode, iters) = self.execution(EnvironmentMode::Default)?;
+        let polled = self.execute_call(iters, mode)?;
+        if polled.is_ready() {
+            self.connection.statement_executed(&stmt_type, &self.sql);
+        }
+        Ok(polled)
+    }
+
+    /// Executes the SQL statement, cancelling it if it runs for longer than the timeout.
+    ///
+    /// A watchdog thread waits alongside the call and cancels it, as a [`CancelHandle`][1]
+    /// would, once the timeout has passed. The connection can be used again straight away. Only
+    /// the execution is covered, not fetching the rows of a query afterwards. For a limit on
+    /// every call made through the connection see `Connection.with_deadline`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `OciError::TimedOut` if the statement was cancelled because it ran for too long.
+    /// Any error in the underlying calls to the OCI library will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    /// use oci_rs::oci_error::OciError;
+    /// use std::time::Duration;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    /// let mut report = conn
+    ///     .create_prepared_statement("BEGIN build_monthly_report; END;")
+    ///     .unwrap();
+    /// match report.execute_with_timeout(Duration::from_secs(30)) {
+    ///     Ok(()) => println!("Report built"),
+    ///     Err(OciError::TimedOut(_)) => println!("Report took too long"),
+    ///     Err(err) => panic!("{}", err),
+    /// }
+    /// ```
+    ///
+    /// [1]: struct.CancelHandle.html
+    pub fn execute_with_timeout(&mut self, timeout: Duration) -> Result<(), OciError> {
+        let cancel = self.cancel_handle()?;
+        let timed_out = AtomicBool::new(false);
+        let (done, finished) = mpsc::channel::<()>();
+        let result = thread::scope(|scope| {
+            let cancel = &cancel;
+            let timed_out = &timed_out;
+            scope.spawn(move || {
+                if let Err(RecvTimeoutError::Timeout) = finished.recv_timeout(timeout) {
+                    timed_out.store(true, Ordering::SeqCst);
+                    if let Err(err) = cancel.cancel() {
+                        error!("Could not cancel statement after the timeout: {}", err)
+                    }
+                }
+            });
+            let result = self.execute();
+            drop(done);
+            result
+        });
+        match result {
+            Err(OciError::Cancelled(_)) if timed_out.load(Ordering::SeqCst) => {
+                Err(OciError::TimedOut(timeout))
+            }
+            result => result,
+        }
+    }
+
+    /// Binds the parameters and executes the SQL statement in one step.
+    ///
+    /// This is the same as calling `.bind` followed by `.execute`.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// let mut insert = conn
+    ///     .create_prepared_statement("INSERT INTO Dogs (DogId, Name) VALUES (:id, :name)")
+    ///     .unwrap();
+    /// insert.execute_with(&[&1, &"Poodle"]).unwrap();
+    /// insert.execute_with(&[&2, &"Bulldog"]).unwrap();
+    /// insert.commit().unwrap();
+    /// ```
+    ///
+    pub fn execute_with(&mut self, params: &[&dyn ToSqlValue]) -> Result<(), OciError> {
+        self.bind(params)?;
+        self.execute()
+    }
+
+    /// Binds the parameters by name and executes the SQL statement in one step.
+    ///
+    /// This is the same as calling `.bind_named` followed by `.execute`.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned, including when a
+    /// name doesn't match a placeholder in the statement.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// let mut update = conn
+    ///     .create_prepared_statement("UPDATE Dogs SET Name = :name WHERE DogId = :id")
+    ///     .unwrap();
+    /// update
+    ///     .execute_named_with(&[("id", &1), ("name", &"Labrador")])
+    ///     .unwrap();
+    /// update.commit().unwrap();
+    /// ```
+    ///
+    pub fn execute_named_with(
+        &mut self,
+        params: &[(&str, &dyn ToSqlValue)],
+    ) -> Result<(), OciError> {
+        self.bind_named(params)?;
+        self.execute()
+    }
+
+    /// Describes the columns a query returns, without running it.
+    ///
+    /// The statement is executed in describe only mode, so Oracle parses the query and works
+    /// out its select list but doesn't fetch anything or take any locks. This suits tools that
+    /// generate code or mappings from SQL. Bind variables needn't be bound first. The statement
+    /// can still be bound and executed as usual afterwards.
+    ///
+    /// The data types are named as in `ALL_TAB_COLUMNS`, e.g. `VARCHAR2`. Types the crate doesn't
+    /// know the name of are given as `TYPE` followed by their OCI type code.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned, including when the
+    /// SQL is not valid or is not a query.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    /// let mut select = conn
+    ///     .create_prepared_statement("SELECT DogId, Name FROM Dogs WHERE Name = :name")
+    ///     .unwrap();
+    /// for column in select.describe().unwrap() {
+    ///     println!("{} {}", column.name(), column.data_type());
+    /// }
+    /// ```
+    ///
+    pub fn describe(&mut self) -> Result<Vec<ColumnDescription>, OciError> {
+        let _call = self.start_call()?;
+        let snap_in: *const OCISnapshot = ptr::null();
+        let snap_out: *mut OCISnapshot = ptr::null_mut();
+        let describe_result = wait_for_call(|| unsafe {
+            OCIStmtExecute(
+                self.connection.service(),
+                self.statement,
+                self.connection.error(),
+                0,
+                0,
+                snap_in,
+                snap_out,
+                EnvironmentMode::DescribeOnly.into(),
+            )
+        });
+        self.connection.call_log().record_call(
+            "OCIStmtExecute",
+            HandleType::Statement,
+            self.statement as *mut c_void,
+            describe_result,
+        );
+        match describe_result.into() {
+            ReturnCode::Success => (),
+            _ => {
+                return Err(get_error(
+                    self.connection.error_as_mut_void(),
+                    HandleType::Error,
+                    "Describing statement",
+                ))
+            }
+        }
+        let error = self.connection.error();
+        let column_count = number_of_columns(self.statement, error)?;
+        (1..=column_count)
+            .map(|position| describe_column(self.statement, error, position))
+            .collect()
+    }
+
+    /// Executes the statement once for each row of parameters, sending them all to the database
+    /// in a single round trip.
+    ///
+    /// The values for each placeholder are bound as an array and the statement is executed with
+    /// that many iterations, which is far quicker than binding and executing each row in turn
+    /// when inserting, updating or deleting thousands of rows. Each row must have a value for
+    /// every placeholder, in order, and all the values for a placeholder must be of the same
+    /// type, although any of them can be null. Nothing is executed if there are no rows.
+    ///
+    /// # Errors
+    ///
+    /// An `OciError::Usage` is returned if the statement is a `SELECT`, holding
+    /// `UsageError::Unsupported`, or if the rows don't have the same number of values, holding
+    /// `UsageError::Bind`. An `OciError::Conversion` is returned if the values for a placeholder
+    /// have different types or a value is longer than 65535 bytes. Any error in the underlying
+    /// calls to the OCI library will be returned, in which case the rows before the one in error
+    /// have been processed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// let mut insert = conn
+    ///     .create_prepared_statement("INSERT INTO Dogs (DogId, Name) VALUES (:id, :name)")
+    ///     .unwrap();
+    /// insert
+    ///     .execute_batch(&[&[&1, &"Poodle"], &[&2, &"Bulldog"], &[&3, &"Beagle"]])
+    ///     .unwrap();
+    /// insert.commit().unwrap();
+    /// ```
+    ///
+    pub fn execute_batch(&mut self, rows: &[&[&dyn ToSqlValue]]) -> Result<(), OciError> {
+        let stmt_type = match self.bind_batch(rows)? {
+            Some(stmt_type) => stmt_type,
+            None => return Ok(()),
+        };
+        self.execute_once(rows.len() as c_uint, EnvironmentMode::Default)?;
+        self.connection.statement_executed(&stmt_type, &self.sql);
+        Ok(())
+    }
+
+    /// Executes a DML statement for every row of values in a single round trip, carrying on
+    /// past rows that fail.
+    ///
+    /// This works like `.execute_batch` except that a row that fails, say because it breaks a
+    /// constraint, doesn't stop the rest. The rows that failed are returned together with the
+    /// Oracle error for each, and all the others have been processed and are waiting to be
+    /// committed. An empty `Vec` means every row succeeded.
+    ///
+    /// # Errors
+    ///
+    /// An `OciError::Usage` or `OciError::Conversion` is returned for the same reasons as
+    /// `.execute_batch`. Any error
+    /// in the underlying calls to the OCI library that isn't down to a single row will be
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// let mut insert = conn
+    ///     .create_prepared_statement("INSERT INTO Dogs (DogId, Name) VALUES (:id, :name)")
+    ///     .unwrap();
+    /// let failed = insert
+    ///     .execute_batch_with_errors(&[&[&1, &"Poodle"], &[&1, &"Bulldog"], &[&3, &"Beagle"]])
+    ///     .unwrap();
+    /// for row in &failed {
+    ///     println!("{}", row);
+    /// }
+    /// insert.commit().unwrap();
+    /// ```
+    ///
+    pub fn execute_batch_with_errors(
+        &mut self,
+        rows: &[&[&dyn ToSqlValue]],
+    ) -> Result<Vec<BatchError>, OciError> {
+        let stmt_type = match self.bind_batch(rows)? {
+            Some(stmt_type) => stmt_type,
+            None => return Ok(Vec::new()),
+        };
+        self.execute_once(rows.len() as c_uint, EnvironmentMode::BatchErrors)?;
+        self.connection.statement_executed(&stmt_type, &self.sql);
+        self.batch_errors()
+    }
+
+    /// Executes a DML statement for every set of parameters an iterator yields, sending them to
+    /// the database in chunks.
+    ///
+    /// The parameters are gathered into chunks of 1000 sets, each of which is executed as a batch
+    /// with `.execute_batch`, so a bulk load from a stream or a file never holds more than one
+    /// chunk in memory. The sets can be tuples, e.g. `(i64, String)`, or anything else that
+    /// implements [`ToSqlParams`][1]. Returns the number of sets executed.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as `.execute_batch`. The chunks before the one in error have been
+    /// processed, as have the rows of that chunk before the one in error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    /// use std::fs::File;
+    /// use std::io::{BufRead, BufReader};
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// let mut insert = conn
+    ///     .create_prepared_statement("INSERT INTO Dogs (DogId, Name) VALUES (:id, :name)")
+    ///     .unwrap();
+    /// let lines = BufReader::new(File::open("dogs.txt").unwrap()).lines();
+    /// let dogs = lines
+    ///     .map(|line| line.unwrap())
+    ///     .enumerate()
+    ///     .map(|(index, name)| (index as i64 + 1, name));
+    /// let inserted = insert.execute_many(dogs).unwrap();
+    /// insert.commit().unwrap();
+    /// println!("Inserted {} dogs", inserted);
+    /// ```
+    ///
+    /// [1]: ../types/trait.ToSqlParams.html
+    pub fn execute_many<I>(&mut self, params: I) -> Result<usize, OciError>
+    where
+        I: IntoIterator,
+        I::Item: ToSqlParams,
+    {
+        let mut executed = 0;
+        let mut chunk = Vec::with_capacity(EXECUTE_MANY_CHUNK_SIZE);
+        for param_set in params {
+            chunk.push(param_set.to_sql_values());
+            if chunk.len() == EXECUTE_MANY_CHUNK_SIZE {
+                self.execute_chunk(&chunk)?;
+                executed += chunk.len();
+                chunk.clear();
+            }
+        }
+        if !chunk.is_empty() {
+            self.execute_chunk(&chunk)?;
+            executed += chunk.len();
+        }
+        Ok(executed)
+    }
+
+    /// Executes a chunk of parameter sets as a batch.
+    ///
+    fn execute_chunk(&mut self, chunk: &[Vec<SqlValue>]) -> Result<(), OciError> {
+        let values: Vec<Vec<&dyn ToSqlValue>> = chunk
+            .iter()
+            .map(|row| row.iter().map(|value| value as &dyn ToSqlValue).collect())
+            .collect();
+        let rows: Vec<&[&dyn ToSqlValue]> = values.iter().map(|row| row.as_slice()).collect();
+        self.execute_batch(&rows)
+    }
+
+    /// Binds each column of the rows of a batch as an array. Returns the type of the statement,
+    /// or `None` if there are no rows so nothing needs executing.
+    ///
+    fn bind_batch(
+        &mut self,
+        rows: &[&[&dyn ToSqlValue]],
+    ) -> Result<Option<StatementType>, OciError> {
+        let stmt_type = get_statement_type(self.statement, self.connection.error())?;
+        if let StatementType::Select = stmt_type {
+            return Err(UsageError::Unsupported(
+                "A SELECT statement can't be executed as a batch".to_string(),
+            )
+            .into());
+        }
+        if rows.is_empty() {
+            return Ok(None);
+        }
+        let columns = rows[0].len();
+        if rows.iter().any(|row| row.len() != columns) {
+            return Err(UsageError::Bind(
+                "Every row in a batch must have the same number of values".to_string(),
+            )
+            .into());
+        }
+
+        self.values.clear();
+        self.indicators.clear();
+        self.array_binds.clear();
+        self.position_binds.clear();
+        self.collection_binds.clear();
+        self.array_binds.reserve(columns);
+        self.bindings.clear();
+        self.bindings.reserve(columns);
+
+        for index in 0..columns {
+            let values: Vec<SqlValue> = rows.iter().map(|row| row[index].to_sql_value()).collect();
+            self.array_binds
+                .push(ArrayBind::new(&values).map_err(|err| {
+                    OciError::Conversion(format!("Parameter {}: {}", index + 1, err).into())
+                })?);
+            self.bindings.push(ptr::null_mut());
+            let array_bind = &mut self.array_binds[index];
+            let position = (index + 1) as c_uint;
+            let null_mut_ptr: *mut c_void = ptr::null_mut();
+            let rcodep = null_mut_ptr as *mut c_ushort;
+            let curelep = null_mut_ptr as *mut c_uint;
+            let maxarr_len: c_uint = 0;
+
+            let bind_result = unsafe {
+                OCIBindByPos(
+                    self.statement,
+                    &self.bindings[index],
+                    self.connection.error(),
+                    position,
+                    array_bind.data.as_mut_ptr() as *mut c_void,
+                    array_bind.element_size,
+                    array_bind.data_type,
+                    array_bind.indicators.as_mut_ptr() as *mut c_void,
+                    array_bind.lengths.as_mut_ptr(),
+                    rcodep,
+                    maxarr_len,
+                    curelep,
+                    EnvironmentMode::Default.into(),
+                )
+            };
+            match bind_result.into() {
+                ReturnCode::Success => (),
+                _ => {
+                    return Err(get_error(
+                        self.connection.error_as_mut_void(),
+                        HandleType::Error,
+                        "Binding parameter array",
+                    ));
+                }
+            }
+        }
+        Ok(Some(stmt_type))
+    }
+
+    /// Reads the rows that failed in the last batch executed with batch errors.
+    ///
+    fn batch_errors(&self) -> Result<Vec<BatchError>, OciError> {
+        let error = self.connection.error();
+        let mut error_count: c_uint = 0;
+        let attr_get_result = unsafe {
+            OCIAttrGet(
+                self.statement as *mut c_void,
+                HandleType::Statement.into(),
+                &mut error_count as *mut c_uint as *mut c_void,
+                ptr::null_mut(),
+                AttributeType::NumDmlErrors.into(),
+                error,
+            )
+        };
+        if let ReturnCode::Error = attr_get_result.into() {
+            return Err(get_error(
+                error as *mut c_void,
+                HandleType::Error,
+                "Getting number of batch errors",
+            ));
+        }
+        let mut errors = Vec::with_capacity(error_count as usize);
+        for index in 0..error_count {
+            let row_error = create_error_handle(self.connection.environment())?;
+            let batch_error = read_batch_error(error, row_error, index);
+            let free_result =
+                unsafe { OCIHandleFree(row_error as *mut c_void, HandleType::Error.into()) };
+            if let ReturnCode::Error = free_result.into() {
+                error!("Could not free the error handle for a batch error")
+            }
+            errors.push(batch_error?);
+        }
+        Ok(errors)
+    }
+
+    /// Binds a null of the given type to the placeholder at a position, starting from one.
+    ///
+    /// A `None` passed to `.bind` is sent as a `VARCHAR`, which Oracle converts to whatever type
+    /// the column has. Some statements, e.g. a `MERGE` using the placeholder in its `USING`
+    /// clause, need the null to have the right type instead. Bind the other values first, as
+    /// `.bind` replaces every placeholder, then the typed nulls.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned, including when
+    /// there is no placeholder at the position.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    /// use oci_rs::types::{SqlType, SqlValue};
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// let sql = "MERGE INTO Dogs d
+    ///            USING (SELECT :id AS DogId, :born AS Born FROM dual) s
+    ///            ON (d.DogId = s.DogId)
+    ///            WHEN MATCHED THEN UPDATE SET d.Born = s.Born
+    ///            WHEN NOT MATCHED THEN INSERT (DogId, Born) VALUES (s.DogId, s.Born)";
+    /// let mut merge = conn.create_prepared_statement(sql).unwrap();
+    /// merge.bind(&[&1, &SqlValue::Null(None)]).unwrap();
+    /// merge.bind_null(2, SqlType::Date).unwrap();
+    /// merge.execute().unwrap();
+    /// ```
+    ///
+    pub fn bind_null(&mut self, position: usize, data_type: SqlType) -> Result<(), OciError> {
+        self.bind_at_position(position, data_type, &SqlValue::Null(None))
+    }
+
+    /// Binds the placeholder at a position, starting from one, as an output of the given type.
+    ///
+    /// This is for the `OUT` parameters of PL/SQL, which are read with `.out_value` once the
+    /// statement has been executed. As with `.bind_null`, bind the inputs with `.bind` first,
+    /// passing anything for the outputs, and then the outputs. Text and binary outputs can be up
+    /// to 32767 bytes long.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned, including when
+    /// there is no placeholder at the position.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    /// use oci_rs::types::{SqlType, SqlValue};
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// let mut block = conn
+    ///     .create_prepared_statement("BEGIN SELECT COUNT(*) INTO :1 FROM Dogs; END;")
+    ///     .unwrap();
+    /// block.bind(&[&SqlValue::Null(None)]).unwrap();
+    /// block.bind_out(1, SqlType::Integer).unwrap();
+    /// block.execute().unwrap();
+    /// let dogs: i64 = block.out_value(1).unwrap().value().unwrap();
+    /// ```
+    ///
+    pub fn bind_out(&mut self, position: usize, data_type: SqlType) -> Result<(), OciError> {
+        self.bind_at_position(position, data_type, &SqlValue::Null(None))
+    }
+
+    /// Binds a value to the placeholder at a position, starting from one, as both an input and
+    /// an output.
+    ///
+    /// This is for the `IN OUT` parameters of PL/SQL. The output has the same type as the value
+    /// and is read with `.out_value` once the statement has been executed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is longer than 32767 bytes.
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned, including when
+    /// there is no placeholder at the position.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// let mut block = conn
+    ///     .create_prepared_statement("BEGIN :1 := :1 * 2; END;")
+    ///     .unwrap();
+    /// block.bind(&[&21]).unwrap();
+    /// block.bind_in_out(1, &21).unwrap();
+    /// block.execute().unwrap();
+    /// let doubled: i64 = block.out_value(1).unwrap().value().unwrap();
+    /// assert_eq!(doubled, 42);
+    /// ```
+    ///
+    pub fn bind_in_out(&mut self, position: usize, value: &dyn ToSqlValue) -> Result<(), OciError> {
+        let value = value.to_sql_value();
+        self.bind_at_position(position, SqlType::of(&value), &value)
+    }
+
+    /// Returns the value of an output bound with `.bind_out` or `.bind_in_out`, as it was after
+    /// the statement was last executed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if nothing was bound as an output at the position, or if the value can't
+    /// be converted.
+    ///
+    /// # Examples
+    ///
+    /// See `.bind_out`.
+    ///
+    pub fn out_value(&self, position: usize) -> Result<SqlValue, OciError> {
+        let position_bind = match self
+            .position_binds
+            .iter()
+            .find(|position_bind| position_bind.position == position)
+        {
+            Some(position_bind) => position_bind,
+            None => {
+                return Err(UsageError::Bind(format!(
+                    "Nothing is bound as an output at position {}",
+                    position
+                ))
+                .into())
+            }
+        };
+        if *position_bind.indicator == -1 {
+            return Ok(SqlValue::Null(Some(position_bind.data_type.value_type())));
+        }
+        // only the bytes OCI says it wrote are decoded, never the rest of the buffer
+        let buffer = &position_bind.buffer;
+        let value = returned_bytes(buffer, 0, buffer.len(), *position_bind.length as usize);
+        let context = DecodeContext::new(self.connection.environment(), self.connection.error());
+        position_bind.data_type.decode(&context, value)
+    }
+
+    /// Binds the placeholder at a position to a buffer of its own, which holds the value on the
+    /// way in and receives any value on the way out.
+    ///
+    fn bind_at_position(
+        &mut self,
+        position: usize,
+        data_type: SqlType,
+        value: &SqlValue,
+    ) -> Result<(), OciError> {
+        if let SqlValue::Collection(..) = *value {
+            return Err(UsageError::Bind(format!(
+                "Parameter {} is a collection, which can't be an output",
+                position
+            ))
+            .into());
+        }
+        let bytes = value.to_oci_bytes();
+        let buffer_size = data_type.buffer_size();
+        if bytes.len() > buffer_size {
+            return Err(UsageError::Bind(format!(
+                "Parameter {} is {} bytes long, more than the {} allowed",
+                position,
+                bytes.len(),
+                buffer_size
+            ))
+            .into());
+        }
+        let mut buffer = vec![0; buffer_size];
+        buffer[..bytes.len()].copy_from_slice(&bytes);
+        self.position_binds
+            .retain(|position_bind| position_bind.position != position);
+        self.position_binds.push(PositionBind {
+            position,
+            data_type,
+            buffer: buffer.into_boxed_slice(),
+            indicator: Box::new(value.indicator()),
+            length: Box::new(bytes.len() as c_ushort),
+        });
+        let position_bind = self.position_binds.last_mut().expect("Just pushed");
+        let binding: *mut OCIBind = ptr::null_mut();
+        let null_mut_ptr: *mut c_void = ptr::null_mut();
+        let bind_result = unsafe {
+            OCIBindByPos(
+                self.statement,
+                &binding,
+                self.connection.error(),
+                position as c_uint,
+                position_bind.buffer.as_mut_ptr() as *mut c_void,
+                position_bind.buffer.len() as c_int,
+                data_type.as_oci_data_type().into(),
+                &mut *position_bind.indicator as *mut c_short as *mut c_void,
+                &mut *position_bind.length as *mut c_ushort,
+                null_mut_ptr as *mut c_ushort,
+                0,
+                null_mut_ptr as *mut c_uint,
+                EnvironmentMode::Default.into(),
+            )
+        };
+        match bind_result.into() {
+            ReturnCode::Success => Ok(()),
+            _ => Err(get_error(
+                self.connection.error_as_mut_void(),
+                HandleType::Error,
+                "Binding parameter at position",
+            )),
+        }
+    }
+
+    /// Forgets any values bound to the statement.
+    ///
+    /// A long-lived statement can be cleared between uses so that values bound for one use
+    /// are never sent with the next by mistake. Executing a statement with placeholders once it
+    /// has been cleared is an error until all of them are bound again.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// let mut insert = conn
+    ///     .create_prepared_statement("INSERT INTO Dogs (DogId, Name) VALUES (:id, :name)")
+    ///     .unwrap();
+    /// insert.bind(&[&1, &"Poodle"]).unwrap();
+    /// insert.execute().unwrap();
+    ///
+    /// insert.clear_binds();
+    /// assert_eq!(insert.bound_count(), 0);
+    /// assert!(insert.execute().is_err());
+    /// ```
+    ///
+    pub fn clear_binds(&mut self) {
+        self.bindings.clear();
+        self.values.clear();
+        self.indicators.clear();
+        self.array_binds.clear();
+        self.position_binds.clear();
+        self.collection_binds.clear();
+    }
+
+    /// Returns the number of placeholders, such as `:id`, in the prepared SQL.
+    ///
+    /// For SQL each placeholder is counted every time it appears, whereas for PL/SQL a name
+    /// used more than once is counted once. Either way it is the number of parameters that
+    /// `.bind` expects.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// let insert = conn
+    ///     .create_prepared_statement("INSERT INTO Dogs (DogId, Name) VALUES (:id, :name)")
+    ///     .unwrap();
+    /// assert_eq!(insert.placeholder_count().unwrap(), 2);
+    /// ```
+    ///
+    pub fn placeholder_count(&self) -> Result<usize, OciError> {
+        let mut count: c_uint = 0;
+        let attr_get_result = unsafe {
+            OCIAttrGet(
+                self.statement as *mut c_void,
+                HandleType::Statement.into(),
+                &mut count as *mut c_uint as *mut c_void,
+                ptr::null_mut(),
+                AttributeType::BindCount.into(),
+                self.connection.error(),
+            )
+        };
+        match attr_get_result.into() {
+            ReturnCode::Success => Ok(count as usize),
+            _ => Err(get_error(
+                self.connection.error_as_mut_void(),
+                HandleType::Error,
+                "Getting bind count",
+            )),
+        }
+    }
+
+    /// Returns the bind placeholders of the prepared SQL in the order of their positions.
+    ///
+    /// This lets generic code match the fields of a struct to the placeholders of any statement
+    /// it is given. The names are as Oracle reports them: without the leading colon and in upper
+    /// case unless they were quoted. A name used more than once in SQL is listed each time it
+    /// appears, marked as a duplicate after the first.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// let insert = conn
+    ///     .create_prepared_statement("INSERT INTO Dogs (DogId, Name) VALUES (:id, :name)")
+    ///     .unwrap();
+    /// let names: Vec<String> = insert
+    ///     .bind_placeholders()
+    ///     .unwrap()
+    ///     .iter()
+    ///     .map(|placeholder| placeholder.name().to_string())
+    ///     .collect();
+    /// assert_eq!(names, vec!["ID", "NAME"]);
+    /// ```
+    ///
+    pub fn bind_placeholders(&self) -> Result<Vec<BindPlaceholder>, OciError> {
+        let count = self.placeholder_count()?;
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+        let mut found: c_int = 0;
+        let mut names: Vec<*mut c_uchar> = vec![ptr::null_mut(); count];
+        let mut name_lengths: Vec<c_uchar> = vec![0; count];
+        let mut indicator_names: Vec<*mut c_uchar> = vec![ptr::null_mut(); count];
+        let mut indicator_name_lengths: Vec<c_uchar> = vec![0; count];
+        let mut duplicates: Vec<c_uchar> = vec![0; count];
+        let mut handles: Vec<*mut OCIBind> = vec![ptr::null_mut(); count];
+        let bind_info_result = unsafe {
+            OCIStmtGetBindInfo(
+                self.statement,
+                self.connection.error(),
+                count as c_uint,
+                1,
+                &mut found,
+                names.as_mut_ptr(),
+                name_lengths.as_mut_ptr(),
+                indicator_names.as_mut_ptr(),
+                indicator_name_lengths.as_mut_ptr(),
+                duplicates.as_mut_ptr(),
+                handles.as_mut_ptr(),
+            )
+        };
+        match bind_info_result.into() {
+            ReturnCode::Success => (),
+            ReturnCode::NoData => return Ok(Vec::new()),
+            _ => {
+                return Err(get_error(
+                    self.connection.error_as_mut_void(),
+                    HandleType::Error,
+                    "Getting bind info",
+                ))
+            }
+        }
+        let found = (found.unsigned_abs() as usize).min(count);
+        let placeholders = (0..found)
+            .map(|index| {
+                let name =
+                    unsafe { slice::from_raw_parts(names[index], name_lengths[index] as usize) };
+                BindPlaceholder {
+                    name: String::from_utf8_lossy(name).into_owned(),
+                    position: index + 1,
+                    duplicate: duplicates[index] != 0,
+                }
+            })
+            .collect();
+        Ok(placeholders)
+    }
+
+    /// Returns the number of values currently bound to the statement.
+    ///
+    /// After `.execute_batch` this is the number of columns bound as arrays.
+    ///
+    pub fn bound_count(&self) -> usize {
+        if !self.array_binds.is_empty() {
+            return self.array_binds.len();
+        }
+        let nulls_beyond_values = self
+            .position_binds
+            .iter()
+            .filter(|position_bind| position_bind.position > self.values.len())
+            .count();
+        self.values.len() + nulls_beyond_values
+    }
+
+    /// Returns the kind of SQL statement that has been prepared.
+    ///
+    /// Generic code can use this to decide what to do with a statement it has been handed, such
+    /// as fetching the rows of a query or committing after DML.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    /// use oci_rs::statement::StatementType;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// let mut stmt = conn
+    ///     .create_prepared_statement("UPDATE Dogs SET Name = 'Rex'")
+    ///     .unwrap();
+    /// stmt.execute().unwrap();
+    /// match stmt.statement_type().unwrap() {
+    ///     StatementType::Select => println!("{} rows", stmt.result_set().unwrap().len()),
+    ///     StatementType::Insert | StatementType::Update | StatementType::Delete => {
+    ///         stmt.commit().unwrap()
+    ///     }
+    ///     _ => (),
+    /// }
+    /// ```
+    ///
+    pub fn statement_type(&self) -> Result<StatementType, OciError> {
+        get_statement_type(self.statement, self.connection.error())
+    }
+
+    /// Fetches the row at a position in the result set, starting from one, or `None` if there are
+    /// fewer rows.
+    ///
+    /// This needs a scrollable cursor, set with [`StatementOptions.scrollable`][1], and can be
+    /// mixed with iterating over the rows with `.lazy_result_set`, which carries on from the row
+    /// fetched.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the statement isn't scrollable.
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned.
+    ///
+    /// # Examples
+    ///
+    /// See [`StatementOptions`][2].
+    ///
+    /// [1]: struct.StatementOptions.html#method.scrollable
+    /// [2]: struct.StatementOptions.html
+    pub fn fetch_row_at(&mut self, position: u32) -> Result<Option<Row>, OciError> {
+        if !self.scrollable {
+            return Err(UsageError::Unsupported(
+                "Only a statement created with a scrollable cursor can fetch rows by position"
+                    .to_string(),
+            )
+            .into());
+        }
+        let _call = self.start_call()?;
+        if self.columns.borrow().is_none() {
+            let columns = define_columns(self).map_err(|err| attach_sql(err, &self.sql))?;
+            *self.columns.borrow_mut() = Some(columns);
+        }
+        let columns = self.columns.borrow();
+        let columns = match *columns {
+            Some(ref columns) => columns,
+            None => return Ok(None),
+        };
+        let in_call = InCall::start(&self.call_state, self.connection);
+        let fetch_result = fetch_rows(
+            self.statement,
+            self.connection,
+            &self.warnings,
+            1,
+            FetchType::Absolute,
+            position as c_int,
+        );
+        drop(in_call);
+        let (fetched, _) = fetch_result.map_err(|err| {
+            attach_sql(
+                check_cancelled(err, self.connection, &self.call_state),
+                &self.sql,
+            )
+        })?;
+        if fetched == 0 {
+            return Ok(None);
+        }
+        let sql_values = columns
+            .iter()
+            .map(|col| col.create_sql_value(0, self.connection))
+            .collect::<Result<Vec<_>, _>>()?;
+        let column_names = columns.iter().map(|col| col.name.clone()).collect();
+        Ok(Some(Row::new(sql_values, column_names)))
+    }
+
+    /// Returns the SQL the statement was prepared with.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// let mut select = conn
+    ///     .create_prepared_statement("SELECT Name FROM Dogs")
+    ///     .unwrap();
+    /// if let Err(err) = select.execute() {
+    ///     println!("{} failed: {}", select.sql(), err);
+    /// }
+    /// ```
+    ///
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    /// Returns the number of rows returned by the last call to fetch from the database.
+    ///
+    /// Rows are fetched in batches of up to the fetch array size, so while iterating over a
+    /// `.lazy_result_set` this shows how big the latest batch was, e.g. for progress reporting.
+    /// It is zero before anything has been fetched.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// let mut select = conn
+    ///     .create_prepared_statement("SELECT DogId, Name FROM Dogs")
+    ///     .unwrap();
+    /// select.set_fetch_array_size(50);
+    /// select.execute().unwrap();
+    /// let rows = select.lazy_result_set().unwrap();
+    /// for row in rows {
+    ///     let row = row.unwrap();
+    ///     // ...
+    /// }
+    /// println!("The last fetch returned {} rows", select.rows_fetched().unwrap());
+    /// ```
+    ///
+    pub fn rows_fetched(&self) -> Result<usize, OciError> {
+        get_rows_fetched(self.statement, self.connection.error())
+    }
+
+    /// Returns the number of columns in the result set of a query.
+    ///
+    /// This is known once the statement has been executed, or described with `.describe`, and
+    /// is zero for statements that aren't queries. It saves having to look at the first row,
+    /// which there might not be, to find out how wide the rows are.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// let mut select = conn
+    ///     .create_prepared_statement("SELECT DogId, Name FROM Dogs")
+    ///     .unwrap();
+    /// select.execute().unwrap();
+    /// assert_eq!(select.column_count().unwrap(), 2);
+    /// ```
+    ///
+    pub fn column_count(&self) -> Result<usize, OciError> {
+        let count = number_of_columns(self.statement, self.connection.error())?;
+        Ok(count as usize)
+    }
+
+    /// Returns the ROWID of the last row affected by the statement, after executing an `INSERT`,
+    /// `UPDATE` or `DELETE`.
+    ///
+    /// A row can be fetched again or locked by its ROWID without going through an index, which
+    /// is the quickest way to get back to a row that has just been inserted. For a statement
+    /// that affected many rows this is the ROWID of the last one.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned, e.g. when the
+    /// statement hasn't been executed or is not DML.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// let mut insert = conn
+    ///     .create_prepared_statement("INSERT INTO Dogs (DogId, Name) VALUES (:id, :name)")
+    ///     .unwrap();
+    /// insert.execute_with(&[&1, &"Poodle"]).unwrap();
+    /// let rowid = insert.last_rowid().unwrap();
+    ///
+    /// let mut select = conn
+    ///     .create_prepared_statement("SELECT Name FROM Dogs WHERE ROWID = CHARTOROWID(:rowid)")
+    ///     .unwrap();
+    /// select.execute_with(&[&rowid]).unwrap();
+    /// ```
+    ///
+    pub fn last_rowid(&self) -> Result<String, OciError> {
+        let mut rowid: *mut OCIRowid = ptr::null_mut();
+        let alloc_result = unsafe {
+            OCIDescriptorAlloc(
+                self.connection.environment() as *const c_void,
+                &mut rowid as *mut *mut OCIRowid as *mut *mut c_void,
+                DescriptorType::Rowid.into(),
+                0,
+                ptr::null_mut(),
+            )
+        };
+        if let ReturnCode::Error = alloc_result.into() {
+            return Err(get_error(
+                self.connection.error_as_mut_void(),
+                HandleType::Error,
+                "Allocating ROWID descriptor",
+            ));
+        }
+        let result = read_rowid(self.statement, self.connection.error(), rowid);
+        let free_result =
+            unsafe { OCIDescriptorFree(rowid as *mut c_void, DescriptorType::Rowid.into()) };
+        if let ReturnCode::Error = free_result.into() {
+            error!("Could not free the ROWID descriptor")
+        }
+        result
+    }
+
+    /// Sets how many times a PL/SQL block is retried when it fails because the state of a
+    /// package has been discarded, the default is once.
+    ///
+    /// Recompiling a package, e.g. during a deployment, discards its state in every session that
+    /// used it. The next call from those sessions fails with ORA-04068 or ORA-04061, but a second
+    /// call runs against the new version of the package. Note that any package variables will
+    /// have been reset. Setting the retries to zero turns this off.
+    ///
+    pub fn set_package_state_retries(&mut self, retries: u32) {
+        self.package_state_retries = retries;
+    }
+
+    /// Executes the statement once.
+    ///
+    fn execute_once(&mut self, iters: c_uint, mode: EnvironmentMode) -> Result<(), OciError> {
+        loop {
+            match self.execute_call(iters, mode)? {
+                Poll::Ready(()) => return Ok(()),
+                Poll::Pending => thread::sleep(STILL_EXECUTING_WAIT),
+            }
+        }
+    }
+
+    /// Makes a single call to execute the statement, which is still pending if the connection
+    /// is in non-blocking mode and the database hasn't finished.
+    ///
+    fn execute_call(&mut self, iters: c_uint, mode: EnvironmentMode) -> Result<Poll<()>, OciError> {
+        if self.bound_count() == 0 {
+            let placeholders = self.placeholder_count()?;
+            if placeholders > 0 {
+                return Err(BindCountMismatch::new(placeholders, 0).into());
+            }
+        }
+        let batch_errors = matches!(mode, EnvironmentMode::BatchErrors);
+        self.warnings.get_mut().clear();
+        let _call = self.start_call()?;
+        let rowoff = 0 as c_uint;
+        let snap_in: *const OCISnapshot = ptr::null();
+        let snap_out: *mut OCISnapshot = ptr::null_mut();
+        let in_call = InCall::start(&self.call_state, self.connection);
+        let execute_result = unsafe {
+            OCIStmtExecute(
+                self.connection.service(),
+                self.statement,
+                self.connection.error(),
+                iters,
+                rowoff,
+                snap_in,
+                snap_out,
+                mode.into(),
+            )
+        };
+        drop(in_call);
+        self.connection.call_log().record_call(
+            "OCIStmtExecute",
+            HandleType::Statement,
+            self.statement as *mut c_void,
+            execute_result,
+        );
+        match execute_result.into() {
+            ReturnCode::Success => {
+                self.results_not_fetched();
+                Ok(Poll::Ready(()))
+            }
+            // ORA-24381, some of the rows in the batch failed
+            ReturnCode::SuccessWithInfo if batch_errors => {
+                self.results_not_fetched();
+                Ok(Poll::Ready(()))
+            }
+            ReturnCode::SuccessWithInfo => {
+                record_warnings(&self.warnings, self.connection.error());
+                self.results_not_fetched();
+                Ok(Poll::Ready(()))
+            }
+            ReturnCode::StillExecuting => Ok(Poll::Pending),
+            _ => Err(self.execute_error("Executing statement")),
+        }
+    }
+
+    /// Reads the error from a failed execution, marking where the SQL failed to parse if that
+    /// was the problem.
+    ///
+    fn execute_error(&self, description: &str) -> OciError {
+        let offset = parse_error_offset(self.connection.error());
+        let mut err = get_error(
+            self.connection.error_as_mut_void(),
+            HandleType::Error,
+            description,
+        );
+        if let OciError::Oracle(ref mut record) = err {
+            if offset > 0 {
+                record.set_parse_error(usize::from(offset), &self.sql);
+            }
+        }
+        attach_sql(
+            check_cancelled(err, self.connection, &self.call_state),
+            &self.sql,
+        )
+    }
+
+    /// Has the database parse the statement without executing it, and gathers what is known
+    /// about it. See `Connection.check_syntax`.
+    ///
+    pub(crate) fn check_syntax(&mut self) -> Result<SyntaxCheck, OciError> {
+        let statement_type = self.statement_type()?;
+        if let StatementType::Create
+        | StatementType::Drop
+        | StatementType::Alter
+        | StatementType::Unknown = statement_type
+        {
+            return Err(UsageError::Unsupported(format!(
+                "{:?} statements can't be checked, Oracle runs them as soon as they are parsed",
+                statement_type
+            ))
+            .into());
+        }
+        self.warnings.get_mut().clear();
+        {
+            let _call = self.start_call()?;
+            let parse_result = wait_for_call(|| unsafe {
+                OCIStmtExecute(
+                    self.connection.service(),
+                    self.statement,
+                    self.connection.error(),
+                    0,
+                    0,
+                    ptr::null(),
+                    ptr::null_mut(),
+                    EnvironmentMode::ParseOnly.into(),
+                )
+            });
+            self.connection.call_log().record_call(
+                "OCIStmtExecute",
+                HandleType::Statement,
+                self.statement as *mut c_void,
+                parse_result,
+            );
+            match parse_result.into() {
+                ReturnCode::Success => (),
+                ReturnCode::SuccessWithInfo => {
+                    record_warnings(&self.warnings, self.connection.error())
+                }
+                _ => return Err(self.execute_error("Parsing statement")),
+            }
+        }
+        let columns = match statement_type {
+            StatementType::Select => self.describe()?,
+            _ => Vec::new(),
+        };
+        Ok(SyntaxCheck {
+            statement_type,
+            columns,
+            placeholders: self.bind_placeholders()?,
+        })
+    }
+
+    /// Applies the connection's deadline, and the statement's own call timeout if it has one,
+    /// before a call to the database.
+    ///
+    fn start_call(&self) -> Result<CallTimeoutGuard<'conn>, OciError> {
+        self.connection.apply_deadline()?;
+        match self.call_timeout {
+            Some(timeout) => {
+                self.connection.apply_call_timeout(timeout)?;
+                Ok(CallTimeoutGuard {
+                    connection: Some(self.connection),
+                })
+            }
+            None => Ok(CallTimeoutGuard { connection: None }),
+        }
+    }
+
+    /// Returns a handle that can cancel this statement from another thread, while it is being
+    /// executed or its rows are being fetched.
+    ///
+    /// Cancelling interrupts the call in progress, which then returns `OciError::Cancelled`, and
+    /// leaves the connection ready for the next statement. A cancel when the statement isn't
+    /// running does nothing. The handle can't outlive the connection.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    /// use oci_rs::oci_error::OciError;
+    /// use std::thread;
+    /// use std::time::Duration;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    /// let mut slow = conn
+    ///     .create_prepared_statement("BEGIN DBMS_SESSION.SLEEP(60); END;")
+    ///     .unwrap();
+    /// let cancel = slow.cancel_handle().unwrap();
+    ///
+    /// thread::scope(|scope| {
+    ///     scope.spawn(|| {
+    ///         thread::sleep(Duration::from_secs(1));
+    ///         cancel.cancel().unwrap();
+    ///     });
+    ///     match slow.execute() {
+    ///         Err(OciError::Cancelled(_)) => println!("Cancelled"),
+    ///         result => panic!("Expected a cancel: {:?}", result),
+    ///     }
+    /// });
+    /// ```
+    ///
+    pub fn cancel_handle(&self) -> Result<CancelHandle<'conn>, OciError> {
+        let error = create_error_handle(self.connection.environment())?;
+        Ok(CancelHandle {
+            service: self.connection.service(),
+            error,
+            call_state: Arc::clone(&self.call_state),
+            call_log: Arc::clone(self.connection.call_log()),
+            connection: PhantomData,
+        })
+    }
+
+    /// Marks the statement as in a call, or not, without making one, so that a cancel can be
+    /// made to arrive once a call has finished.
+    #[cfg(test)]
+    pub(crate) fn set_in_call(&self, in_call: bool) {
+        self.call_state.lock().in_call = in_call;
+    }
+
+    /// Returns the results of a `SELECT` statement.
+    ///
+    /// After the execution of a `SELECT` statement a result set will be available from the
+    /// database. This will contain none or many `Row`s of data depending on the query. There are
+    /// two options for seeing the results, the first is to call this method to retrieve all the
+    /// rows in one go, the second is to iterate through them row by row.
+    ///
+    /// Should you go for the first option then the rows will be fetched once this method is
+    /// called. They will not be fetched eagerly as part of the `.execute` call, although this is
+    /// not apparent to the caller. Once the results are retrieved from the database then they will
+    /// be held until either the `Statement` goes out of scope or `.execute` is called again. This
+    /// way, repeated calls to `.result_set` will be the same. If there are no data then an empty
+    /// `Vec<Row>` will be returned.
+    ///
+    /// As the whole result set is held in memory the approximate number of bytes it takes up is
+    /// tracked, see `.result_set_size` and `.set_result_set_size_warning`.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned.
+    ///
+    pub fn result_set(&mut self) -> Result<&[Row], OciError> {
+        match self.result_state {
+            ResultState::Fetched => (),
+            ResultState::Streamed => {
+                return Err(ResultSetConsumed.into());
+            }
+            ResultState::NotFetched => {
+                let warning = self
+                    .result_set_warning
+                    .map(|threshold| (threshold, self.sql.clone()));
+                let mut rows = Vec::new();
+                let mut bytes = 0;
+                let mut warned = false;
+                for row in self.lazy_result_set()? {
+                    let row = row?;
+                    bytes += row.approximate_size();
+                    rows.push(row);
+                    if let Some((threshold, ref sql)) = warning {
+                        if !warned && bytes > threshold {
+                            warned = true;
+                            warn!(
+                                "Result set has grown past {} bytes after {} rows: {}",
+                                threshold,
+                                rows.len(),
+                                sql
+                            );
+                        }
+                    }
+                }
+                self.results_fetched();
+                self.result_set = rows;
+                self.result_set_bytes = bytes;
+                self.result_set_high_water = self.result_set_high_water.max(bytes);
+            }
+        }
+        Ok(&self.result_set)
+    }
+
+    /// Returns the approximate number of bytes taken up by the rows last fetched by
+    /// `.result_set`.
+    ///
+    pub fn result_set_size(&self) -> usize {
+        self.result_set_bytes
+    }
+
+    /// Returns the approximate number of bytes taken up by the largest result set fetched by
+    /// `.result_set` over the life of the `Statement`.
+    ///
+    pub fn result_set_high_water(&self) -> usize {
+        self.result_set_high_water
+    }
+
+    /// Logs a warning when the rows fetched by `.result_set` take up more than roughly
+    /// `threshold` bytes.
+    ///
+    /// The warning is logged while the rows are still being fetched, so a query that returns far
+    /// more rows than expected shows up in the logs before it runs out of memory. Queries that
+    /// are meant to return many rows are better off with `.lazy_result_set`.
+    ///
+    pub fn set_result_set_size_warning(&mut self, threshold: usize) {
+        self.result_set_warning = Some(threshold);
+    }
+
+    /// Returns the results of a `SELECT` statement converted to a Rust type.
+    ///
+    /// Each `Row` in the result set is passed to `T::from_row`. Implementations of
+    /// [`FromRow`][1] that look columns up by name, rather than position, are not affected if the
+    /// order of the columns changes.
+    ///
+    /// If `.warn_on_select_star` has been turned on then a warning is logged when the query
+    /// uses `SELECT *`.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned, as will the first
+    /// error returned by `T::from_row`.
+    ///
+    /// [1]: ../row/trait.FromRow.html
+    pub fn typed_result_set<T: FromRow>(&mut self) -> Result<Vec<T>, OciError> {
+        self.check_select_star();
+        self.result_set()?.iter().map(T::from_row).collect()
+    }
+
+    /// Returns the results of a `SELECT` statement row by row, each converted by the closure.
+    ///
+    /// This is `.lazy_result_set` with the conversion of each `Row` built in, so a pipeline
+    /// doesn't have to unwrap the row before it can convert it. The iterator returns the error
+    /// if a row can't be fetched or the closure fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as `.lazy_result_set`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// let mut select = conn
+    ///     .create_prepared_statement("SELECT DogId, Name FROM Dogs")
+    ///     .unwrap();
+    /// select.execute().unwrap();
+    /// for dog in select
+    ///     .query_map(|row| Ok((row.try_get::<i64>("DogId")?, row.try_get::<String>("Name")?)))
+    ///     .unwrap()
+    /// {
+    ///     let (id, name) = dog.unwrap();
+    ///     println!("{} {}", id, name);
+    /// }
+    /// ```
+    ///
+    pub fn query_map<T, F>(&mut self, f: F) -> Result<MappedRows<'_, F>, OciError>
+    where
+        F: FnMut(&Row) -> Result<T, OciError>,
+    {
+        Ok(MappedRows {
+            rows: self.lazy_result_set()?,
+            f,
+        })
+    }
+
+    /// Returns the results of a `SELECT` statement row by row, each converted to a Rust type by
+    /// its [`FromRow`][1] implementation.
+    ///
+    /// This is the lazy counterpart of `.typed_result_set` and, like it, logs a warning for a
+    /// `SELECT *` query if `.warn_on_select_star` has been turned on.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as `.lazy_result_set`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    /// use oci_rs::oci_error::OciError;
+    /// use oci_rs::row::{FromRow, Row};
+    ///
+    /// struct Dog {
+    ///     id: i64,
+    ///     name: String,
+    /// }
+    ///
+    /// impl FromRow for Dog {
+    ///     fn from_row(row: &Row) -> Result<Dog, OciError> {
+    ///         Ok(Dog {
+    ///             id: row.try_get("DogId")?,
+    ///             name: row.try_get("Name")?,
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// let mut select = conn
+    ///     .create_prepared_statement("SELECT DogId, Name FROM Dogs")
+    ///     .unwrap();
+    /// select.execute().unwrap();
+    /// for dog in select.query_map_into::<Dog>().unwrap() {
+    ///     let dog = dog.unwrap();
+    ///     println!("{} {}", dog.id, dog.name);
+    /// }
+    /// ```
+    ///
+    /// [1]: ../row/trait.FromRow.html
+    pub fn query_map_into<T: FromRow>(&mut self) -> Result<MappedRows<'_, FromRowFn<T>>, OciError> {
+        self.check_select_star();
+        self.query_map(T::from_row as FromRowFn<T>)
+    }
+
+    /// Logs a warning if the query uses `SELECT *` and the check has been turned on.
+    ///
+    fn check_select_star(&self) {
+        if self.select_star_check && selects_all_columns(&self.sql) {
+            warn!(
+                "SELECT * used with a typed result set, the columns may change order: {}",
+                self.sql
+            );
+        }
+    }
+
+    /// Turns on a check that logs a warning when `.typed_result_set` is used with a `SELECT *`
+    /// query.
+    ///
+    /// The columns returned by `SELECT *` follow the table definition and so can change order or
+    /// gain new columns without the query changing. Code that maps columns by position will then
+    /// quietly read the wrong values. The check is off by default.
+    ///
+    pub fn warn_on_select_star(&mut self, enabled: bool) {
+        self.select_star_check = enabled;
+    }
+
+    /// Turns on sizing of columns so that no value is ever cut short, for queries such as
+    /// `SELECT *` where the columns are not known in advance.
+    ///
+    /// Text columns declared in characters, e.g. `VARCHAR2(20 CHAR)`, are always given room for
+    /// the widest encoding of each character in the client character set. Other text columns
+    /// are fetched into a buffer of the size the database describes, which can be too small
+    /// once the text is converted to the client's character set, e.g. for a single byte
+    /// database character set. With safe fetching the buffer allows for the widest possible
+    /// encoding of every character.
+    ///
+    /// `CLOB` and `BLOB` columns are always read in full through a LOB locator, whether or not
+    /// safe fetching is on, see `.locator_columns`. The size of any column can be limited with
+    /// `.set_column_cap`. Either way a value that doesn't fit is reported as a
+    /// [`FetchTruncated`][1] error and never silently truncated.
+    ///
+    /// [1]: ../oci_error/struct.FetchTruncated.html
+    pub fn set_safe_fetch(&mut self, enabled: bool) {
+        self.safe_fetch = enabled;
+        self.forget_columns();
+    }
+
+    /// Fetches `NUMBER` columns as their exact decimal text, a `SqlValue::VarChar`, rather than
+    /// as an integer, float or decimal.
+    ///
+    /// Every digit of the number is kept, up to the 38 digits Oracle holds, and a column declared
+    /// with decimal places is given that many, so 1.5 in a `NUMBER(10, 2)` column is fetched as
+    /// "1.50". This suits passing numbers through to another system unchanged. The default is
+    /// the connection's, see [`Connection.set_numbers_as_text`][1].
+    ///
+    /// [1]: ../connection/struct.Connection.html#method.set_numbers_as_text
+    pub fn set_numbers_as_text(&mut self, enabled: bool) {
+        self.numbers_as_text = enabled;
+        self.forget_columns();
+    }
+
+    /// Sets how text fetched from `VARCHAR2` columns is trimmed, see [`TextTrim`][1].
+    ///
+    /// The default is the connection's, see [`Connection.set_varchar_trim`][2].
+    ///
+    /// [1]: ../types/enum.TextTrim.html
+    /// [2]: ../connection/struct.Connection.html#method.set_varchar_trim
+    pub fn set_varchar_trim(&mut self, trim: TextTrim) {
+        self.varchar_trim = trim;
+        self.forget_columns();
+    }
+
+    /// Sets how text fetched from `CHAR` columns is trimmed, see [`TextTrim`][1].
+    ///
+    /// The default is the connection's, see [`Connection.set_char_trim`][2].
+    ///
+    /// [1]: ../types/enum.TextTrim.html
+    /// [2]: ../connection/struct.Connection.html#method.set_char_trim
+    pub fn set_char_trim(&mut self, trim: TextTrim) {
+        self.char_trim = trim;
+        self.forget_columns();
+    }
+
+    /// Limits the number of bytes fetched for a column, matched by name without regard to case.
+    ///
+    /// This bounds the memory a very wide column can take up: text columns get a buffer of at
+    /// most this size and `CLOB` and `BLOB` columns longer than this are not read. The cap is
+    /// in bytes of the text as fetched for every type. Oracle gives a `CLOB`'s length in
+    /// characters, so one with more characters than the cap is not read and one that has fewer
+    /// but turns out to take more bytes is read and then rejected, which needs a buffer of up
+    /// to the cap times the most bytes a character can take. In every case fetching the row
+    /// returns an error, which for a text column is a [`FetchTruncated`][1].
+    ///
+    /// [1]: ../oci_error/struct.FetchTruncated.html
+    pub fn set_column_cap(&mut self, column: &str, max_bytes: u32) {
+        let column = column.to_uppercase();
+        self.column_caps.retain(|cap| cap.0 != column);
+        self.column_caps.push((column, max_bytes));
+        self.forget_columns();
+    }
+
+    /// Returns the columns fetched so far that were read through a LOB locator rather than
+    /// directly into a buffer, i.e. the `CLOB` and `BLOB` columns. Each needs extra round trips
+    /// per row, which is worth knowing about for queries that return many rows.
+    ///
+    pub fn locator_columns(&self) -> Vec<String> {
+        self.locator_columns.borrow().clone()
+    }
+
+    /// Sets the number of rows fetched from the database in each round trip, by default 100.
+    ///
+    /// Rows are fetched in batches into buffers with room for this many rows, then handed out
+    /// one at a time by `.lazy_result_set` and `.result_set`. Larger batches mean fewer round
+    /// trips for big result sets, at the cost of memory. The buffers are kept to about 1 MB, so
+    /// fewer rows are fetched at a time when rows are very wide. A size of zero is taken as one.
+    ///
+    pub fn set_fetch_array_size(&mut self, rows: u32) {
+        self.fetch_array_size = rows.max(1);
+        self.forget_columns();
+    }
+
+    /// Returns the warnings Oracle gave since the statement was last executed, from executing it
+    /// and from fetching its rows.
+    ///
+    /// A warning doesn't stop the call from succeeding, e.g. creating a PL/SQL unit that has
+    /// compilation errors gives ORA-24344, so they are easily missed. They are also logged as
+    /// they arrive.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// let mut create = conn
+    ///     .create_prepared_statement("CREATE OR REPLACE PROCEDURE Broken AS BEGIN nonsense; END;")
+    ///     .unwrap();
+    /// create.execute().unwrap();
+    /// for warning in create.warnings() {
+    ///     println!("{}", warning);
+    /// }
+    /// ```
+    ///
+    pub fn warnings(&self) -> Vec<Warning> {
+        self.warnings.borrow().clone()
+    }
+
+    /// Drops the cached column definitions, so that they are made again with the current
+    /// settings before the next fetch.
+    ///
+    fn forget_columns(&mut self) {
+        *self.columns.get_mut() = None;
+    }
+
+    /// Set how many rows will be prefetched from the database.
+    ///
+    /// The OCI library internally manages the number of rows that are pre-fetched from the
+    /// database. This can be tweaked. The OCI default is one row, so for each call to the
+    /// database two rows are retrieved, thus half the number of round trips needed.
+    ///
+    /// The [`Prefetch`][1] policy fixes the number of rows, limits the memory used for them
+    /// or, with `Prefetch::Auto`, picks a number of rows to suit the width of the columns once
+    /// the query has been executed and its columns are known. A plain number of rows is taken
+    /// as `Prefetch::Rows`. Only the latest policy applies, so a memory limit set by an earlier
+    /// call is lifted by `Prefetch::Rows` or `Prefetch::Auto`.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    /// use oci_rs::statement::Prefetch;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// let mut select = conn.create_prepared_statement("SELECT * FROM Dogs").unwrap();
+    /// select.set_prefetch(100).unwrap();
+    /// select.set_prefetch(Prefetch::Memory(256 * 1024)).unwrap();
+    /// select.set_prefetch(Prefetch::Auto).unwrap();
+    /// select.execute().unwrap();
+    /// ```
+    ///
+    /// [1]: enum.Prefetch.html
+    pub fn set_prefetch<P: Into<Prefetch>>(&mut self, prefetch: P) -> Result<(), OciError> {
+        self.prefetch_target = None;
+        self.prefetch_auto = false;
+        match prefetch.into() {
+            Prefetch::Rows(rows) => {
+                self.clear_prefetch_memory()?;
+                set_prefetch_rows(self.statement, self.connection.error(), rows)?;
+                self.prefetch_rows = Some(rows);
+            }
+            Prefetch::Memory(bytes) => {
+                set_prefetch_memory(self.statement, self.connection.error(), bytes)?;
+                self.prefetch_memory = Some(bytes);
+                set_prefetch_rows(self.statement, self.connection.error(), MAX_PREFETCH_ROWS)?;
+                self.prefetch_rows = Some(MAX_PREFETCH_ROWS);
+            }
+            Prefetch::Auto => {
+                self.clear_prefetch_memory()?;
+                self.prefetch_auto = true;
+                let row_width = self
+                    .columns
+                    .get_mut()
+                    .as_ref()
+                    .map(|columns| row_width(columns));
+                if let Some(row_width) = row_width {
+                    self.tune_auto_prefetch(row_width)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the limit on the memory used for prefetched rows, if there is one.
+    ///
+    pub fn prefetch_memory(&self) -> Option<u32> {
+        self.prefetch_target.or(self.prefetch_memory)
+    }
+
+    /// Lifts the limit on the memory used for prefetched rows.
+    ///
+    fn clear_prefetch_memory(&mut self) -> Result<(), OciError> {
+        set_prefetch_memory(self.statement, self.connection.error(), 0)?;
+        self.prefetch_memory = None;
+        Ok(())
+    }
+
+    /// Sets the number of prefetched rows to fill about `AUTO_PREFETCH_BYTES` with rows of the
+    /// given width.
+    ///
+    fn tune_auto_prefetch(&self, row_width: usize) -> Result<(), OciError> {
+        let rows = (AUTO_PREFETCH_BYTES / row_width.max(1)).clamp(1, MAX_PREFETCH_ROWS as usize);
+        debug!(
+            "Auto prefetch: row width {} bytes, prefetching {} rows",
+            row_width, rows
+        );
+        set_prefetch_rows(self.statement, self.connection.error(), rows as c_uint)
+    }
+
+    /// Lets the number of prefetched rows be tuned automatically.
+    ///
+    /// Once the first row of a result set has been fetched its width is measured and the number
+    /// of rows to prefetch is adjusted so that each round trip to the database returns roughly
+    /// `target_bytes` of data, e.g. 1 MB. The memory used for prefetching is also capped at this
+    /// size. This gives good throughput without having to tune `.set_prefetch` per query.
+    ///
+    /// Calling `.set_prefetch` switches back to a fixed number of rows.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned.
+    ///
+    pub fn set_adaptive_prefetch(&mut self, target_bytes: u32) -> Result<(), OciError> {
+        set_prefetch_memory(self.statement, self.connection.error(), target_bytes)?;
+        self.prefetch_target = Some(target_bytes);
+        self.prefetch_auto = false;
+        Ok(())
+    }
+
+    /// Returns the results of a `SELECT` statement row by row via the `RowIter` iterator.
+    ///
+    /// The `RowIter` returned can then be used to run through the rows of data in the result set.
+    /// This is a more attractive option if there are many rows or you want to process the results
+    /// in a pipeline.
+    ///
+    /// The same comments about pre-fetching configuration applies here as to `.result_set`.
+    ///
+    /// The rows can only be handed out once for each execution. Execute the statement again to
+    /// iterate over a fresh result set.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ResultSetConsumed`][1], inside `OciError::Usage`, if the rows have already
+    /// been handed out since the statement was last executed, by this method or by
+    /// `.result_set`. Otherwise the `RowIter` will return any OCI errors encountered as each row
+    /// is fetched.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use oci_rs::connection::Connection;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    ///
+    /// # let mut drop = conn.create_prepared_statement("DROP TABLE Countries").unwrap();
+    /// # drop.execute().ok();
+    /// # let sql_create = "CREATE TABLE Countries (CountryId INTEGER,
+    /// #                                           Name VARCHAR(20))";
+    /// # let mut create = conn.create_prepared_statement(sql_create).unwrap();
+    /// # create.execute().unwrap();
+    /// # create.commit().unwrap();
+    ///
+    /// // Insert some values using bind variables
+    /// let sql_insert = "INSERT INTO Countries (CountryId, Name)
+    ///                   VALUES (:id, :name)";
+    /// let mut insert = conn.create_prepared_statement(sql_insert).unwrap();
+    ///
+    /// let countries = vec!["Great Britain",
+    ///                      "Australia",
+    ///                      "Burma",
+    ///                      "Japan",
+    ///                      "Sudan",
+    ///                      "France",
+    ///                      "Germany",
+    ///                      "China"];
+    ///
+    /// for (index, country) in countries.iter().enumerate(){
+    ///     let id = (index + 1) as i64;
+    ///     insert.bind(&[&id, country]).unwrap();
+    ///     insert.execute();
+    /// }
+    /// insert.commit();
+    ///
+    /// let sql_select = "SELECT Name FROM Countries";
+    /// let mut select = conn.create_prepared_statement(sql_select).unwrap();
+    /// select.execute().unwrap();
+    ///
+    /// let results: Vec<String> = select.lazy_result_set()
+    ///                                  .unwrap()
+    ///                                  .map(|row_result| row_result.unwrap())
+    ///                                  .map(|row| row[0].value::<String>().unwrap())
+    ///                                  .filter(|country| country.contains("c") ||
+    ///                                                    country.contains("C"))
+    ///                                  .map(|country| country.to_uppercase())
+    ///                                  .collect();
+    /// assert_eq!(results.len(), 2);
+    /// assert!(results.contains(&"CHINA".to_string()));
+    /// assert!(results.contains(&"FRANCE".to_string()));
+    /// ```
+    ///
+    /// [1]: ../oci_error/struct.ResultSetConsumed.html
+    pub fn lazy_result_set(&mut self) -> Result<RowIter, OciError> {
+        match self.result_state {
+            ResultState::Fetched | ResultState::Streamed => Err(ResultSetConsumed.into()),
+            ResultState::NotFetched => {
+                self.results_streamed();
+                Ok(RowIter::new(self))
+            }
+        }
+    }
+
+    /// Commits the changes to the database.
+    ///
+    /// When a statement makes changes to the database Oracle implicitly starts a
+    /// transaction. If all is well and the session is closed normally this will cause an
+    /// implicit commit of the changes. If anything goes wrong and the sesssion is not closed or
+    /// the connection is broken, Oracle will roll back the changes. This method, therefore allows
+    /// you to commit changes when you want, rather than relying on a successfull disconnection.
+    ///
+    /// This is the same as calling `.commit` on the `Connection`.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned.
+    ///
+    pub fn commit(&self) -> Result<(), OciError> {
+        self.connection.commit()
+    }
+
+    /// Transition to fetched state.
+    ///
+    fn results_fetched(&mut self) {
+        self.result_state = ResultState::Fetched
+    }
+
+    /// Transition to streamed state.
+    ///
+    fn results_streamed(&mut self) {
+        self.result_state = ResultState::Streamed
+    }
+
+    /// Transition to not-fetched state.
+    ///
+    fn results_not_fetched(&mut self) {
+        self.result_state = ResultState::NotFetched
+    }
+}
+
+impl<'conn> Drop for Statement<'conn> {
+    /// Frees any internal handles allocated by the OCI library.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resources can't be freed. This would be
+    /// a failure of the underlying OCI function.
+    ///
+    fn drop(&mut self) {
+        // the column parameter handles belong to the statement, so they go first
+        self.forget_columns();
+        let key = self.cache_key();
+        if let Err(err) = release_statement(self.statement, self.connection, key, self.cached) {
+            panic!(format!(
+                "Could not release the statement Statement: {}",
+                err
+            ))
+        }
+    }
+}
+
+/// How many rows the OCI library prefetches, set with [`Statement.set_prefetch`][1].
+///
+/// [1]: struct.Statement.html#method.set_prefetch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prefetch {
+    /// Prefetch a fixed number of rows.
+    Rows(u32),
+    /// Prefetch as many rows as fit into the given number of bytes, up to 10,000 rows.
+    Memory(u32),
+    /// Pick the number of rows from the width of the columns of the query, so that narrow rows
+    /// are prefetched in large numbers and wide ones in small numbers.
+    Auto,
+}
+
+impl From<i32> for Prefetch {
+    /// A number of rows, where a negative number turns prefetching off.
+    ///
+    fn from(rows: i32) -> Prefetch {
+        Prefetch::Rows(rows.max(0) as u32)
+    }
+}
+
+/// Options applied to a [`Statement`][1] when it is created with
+/// [`Connection.create_prepared_statement_with`][2].
+///
+/// Options that aren't set are left at their defaults. A set of options can be kept and used for
+/// many statements, e.g. all the reporting queries of an application.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use oci_rs::connection::Connection;
+/// use oci_rs::statement::StatementOptions;
+/// use std::time::Duration;
+///
+/// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+///
+/// let options = StatementOptions::new()
+///     .prefetch_rows(1000)
+///     .prefetch_memory(4 * 1024 * 1024)
+///     .fetch_array_size(1000)
+///     .scrollable(true)
+///     .call_timeout(Duration::from_secs(60));
+/// let mut select = conn
+///     .create_prepared_statement_with("SELECT Name FROM Dogs ORDER BY Name", &options)
+///     .unwrap();
+/// select.execute().unwrap();
+/// let last = select.fetch_row_at(10).unwrap();
+/// ```
+///
+/// [1]: struct.Statement.html
+/// [2]: ../connection/struct.Connection.html#method.create_prepared_statement_with
+#[derive(Debug, Clone, Default)]
+pub struct StatementOptions {
+    prefetch_rows: Option<u32>,
+    prefetch_memory: Option<u32>,
+    fetch_array_size: Option<u32>,
+    scrollable: bool,
+    call_timeout: Option<Duration>,
+    cache_key: Option<String>,
+    skip_cache: bool,
+}
+impl StatementOptions {
+    /// Creates a new `StatementOptions` with nothing set.
+    ///
+    pub fn new() -> StatementOptions {
+        StatementOptions::default()
+    }
 
-    /// Transition to not-fetched state.
+    /// Sets the number of rows the OCI library prefetches, as `Statement.set_prefetch` does.
     ///
-    fn results_not_fetched(&mut self) {
-        self.result_state = ResultState::NotFetched
+    pub fn prefetch_rows(mut self, rows: u32) -> StatementOptions {
+        self.prefetch_rows = Some(rows);
+        self
+    }
+
+    /// Caps the memory, in bytes, the OCI library uses for prefetched rows.
+    ///
+    pub fn prefetch_memory(mut self, bytes: u32) -> StatementOptions {
+        self.prefetch_memory = Some(bytes);
+        self
+    }
+
+    /// Sets the number of rows fetched in each round trip, as `Statement.set_fetch_array_size`
+    /// does.
+    ///
+    pub fn fetch_array_size(mut self, rows: u32) -> StatementOptions {
+        self.fetch_array_size = Some(rows);
+        self
+    }
+
+    /// Executes queries with a scrollable cursor, so that any row can be fetched with
+    /// `Statement.fetch_row_at` as well as iterating over them in order. Scrollable cursors
+    /// are read only and cost more on the server, so they are off by default.
+    ///
+    pub fn scrollable(mut self, scrollable: bool) -> StatementOptions {
+        self.scrollable = scrollable;
+        self
+    }
+
+    /// Limits how long each call to the database for the statement, executing it or fetching a
+    /// batch of rows, can take before it is interrupted with an error. A deadline set on the
+    /// connection with `Connection.with_deadline` still applies if it is sooner.
+    ///
+    pub fn call_timeout(mut self, timeout: Duration) -> StatementOptions {
+        self.call_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the key the statement is found by in the statement cache, instead of its SQL.
+    ///
+    /// A short tag is cheaper to look up than long SQL, and tagging related statements makes
+    /// them easy to spot when tuning the shared pool. If a statement with the key is already
+    /// cached then it is reused without checking its SQL, so each key must only ever be used
+    /// for the same SQL.
+    ///
+    pub fn cache_key(mut self, key: &str) -> StatementOptions {
+        self.cache_key = Some(key.to_string());
+        self
+    }
+
+    /// Sets whether the statement is kept in the statement cache when it is dropped, which is
+    /// the default. SQL that is only run once, e.g. generated reports, can be left out so that
+    /// it doesn't push statements that are reused out of the cache.
+    ///
+    pub fn cache(mut self, cache: bool) -> StatementOptions {
+        self.skip_cache = !cache;
+        self
     }
 }
 
-impl<'conn> Drop for Statement<'conn> {
-    /// Frees any internal handles allocated by the OCI library.
+/// A prepared statement that owns its connection.
+///
+/// A [`Statement`][1] borrows the `Connection` it was created from, which makes it awkward to
+/// keep in a struct or to return from a function. An `OwnedStatement` takes the `Connection`
+/// instead, so it has no lifetime and the connection stays open for as long as the statement
+/// needs it. The connection can still be used through `.connection` and is handed back by
+/// `.into_connection`.
+///
+/// The statement is used through `.with`, which lends it out for the length of a closure.
+/// Anything borrowed from the statement, e.g. a `RowIter` or a `CancelHandle`, can't escape the
+/// closure, while owned results such as rows or counts can be returned from it.
+///
+/// As the statement and its connection move together, an `OwnedStatement` can be sent to
+/// another thread, just like a `Connection`. This is why it takes the `Connection` itself
+/// rather than an `Arc<Connection>`: a `Connection` is `Send` but not `Sync`, so a statement
+/// sharing one through an `Arc` could be sent to another thread while a clone of the `Arc` was
+/// still used on this one.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use oci_rs::connection::Connection;
+/// use oci_rs::oci_error::OciError;
+/// use oci_rs::statement::OwnedStatement;
+/// use std::thread;
+///
+/// struct DogNames {
+///     select: OwnedStatement,
+/// }
+///
+/// impl DogNames {
+///     fn new(conn: Connection) -> Result<DogNames, OciError> {
+///         let select = OwnedStatement::new(conn, "SELECT Name FROM Dogs WHERE DogId = :id")?;
+///         Ok(DogNames { select })
+///     }
+///
+///     fn name(&mut self, id: i64) -> Result<Option<String>, OciError> {
+///         self.select.with(|select| {
+///             select.bind(&[&id])?;
+///             select.execute()?;
+///             match select.result_set()?.first() {
+///                 Some(row) => Ok(Some(row.try_get("NAME")?)),
+///                 None => Ok(None),
+///             }
+///         })
+///     }
+/// }
+///
+/// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+/// let mut names = DogNames::new(conn).unwrap();
+/// let worker = thread::spawn(move || names.name(1));
+/// let name = worker.join().unwrap().unwrap();
+/// ```
+///
+/// [1]: struct.Statement.html
+#[derive(Debug)]
+pub struct OwnedStatement {
+    // Declared before the connection so that the statement handle is released first.
+    statement: Statement<'static>,
+    connection: Box<Connection>,
+}
+
+// SAFETY: `Statement` isn't `Send` because it borrows a `Connection`, which isn't `Sync`, and
+// holds raw OCI handles. Here the only connection it borrows is the one boxed alongside it,
+// which nothing outside the `OwnedStatement` can reach except through `&self` or `&mut self`,
+// so the statement, its handles and its connection always move to another thread together and
+// are only ever used from one thread at a time. A `Connection` can itself be sent to another
+// thread, and the OCI environment is created in threaded mode, so handles can be used from a
+// different thread to the one that allocated them.
+unsafe impl Send for OwnedStatement {}
+
+impl OwnedStatement {
+    /// Creates a new `OwnedStatement` for the SQL on the connection.
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// Panics if the resources can't be freed. This would be
-    /// a failure of the underlying OCI function.
+    /// Any error in the underlying calls to the OCI library will be returned.
     ///
-    fn drop(&mut self) {
-        if let Err(err) = release_statement(self.statement, self.connection.error()) {
-            panic!(format!(
-                "Could not release the statement Statement: {}",
-                err
-            ))
-        }
+    pub fn new(connection: Connection, sql: &str) -> Result<OwnedStatement, OciError> {
+        OwnedStatement::with_options(connection, sql, &StatementOptions::default())
+    }
+
+    /// Creates a new `OwnedStatement` with options applied, as
+    /// `Connection.create_prepared_statement_with` does.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned.
+    ///
+    pub fn with_options(
+        connection: Connection,
+        sql: &str,
+        options: &StatementOptions,
+    ) -> Result<OwnedStatement, OciError> {
+        let connection = Box::new(connection);
+        // SAFETY: the statement borrows the connection for `'static`, which is a lie the
+        // `OwnedStatement` keeps from being seen. The connection is on the heap, so it stays at
+        // the same address when the box moves, and it is never moved out of the box or dropped
+        // until the statement has been: the statement field is declared first so it is dropped
+        // first, and `.into_connection` drops it before unboxing the connection. The statement
+        // is only lent out by `.with`, for a closure that has to work for any lifetime, so
+        // nothing borrowed from it can outlive the borrow of `self`.
+        let shared: &'static Connection = unsafe { &*(&*connection as *const Connection) };
+        let statement = Statement::with_options(shared, sql, options)?;
+        Ok(OwnedStatement {
+            statement,
+            connection,
+        })
+    }
+
+    /// Lends the statement to the closure and returns what it returns.
+    ///
+    pub fn with<F, R>(&mut self, f: F) -> R
+    where
+        F: for<'conn> FnOnce(&mut Statement<'conn>) -> R,
+    {
+        f(&mut self.statement)
+    }
+
+    /// Returns the connection the statement was created on.
+    ///
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    /// Releases the statement and hands back the connection.
+    ///
+    pub fn into_connection(self) -> Connection {
+        let OwnedStatement {
+            statement,
+            connection,
+        } = self;
+        drop(statement);
+        *connection
+    }
+
+    /// Returns the SQL of the statement.
+    ///
+    pub fn sql(&self) -> &str {
+        self.statement.sql()
     }
 }
 
@@ -410,35 +3031,578 @@ impl<'conn> Drop for Statement<'conn> {
 #[derive(Debug)]
 pub struct RowIter<'stmt> {
     statement: &'stmt Statement<'stmt>,
+    prefetch_tuned: bool,
+    rows: VecDeque<Row>,
+    finished: bool,
+}
+impl<'stmt> RowIter<'stmt> {
+    fn new(statement: &'stmt Statement<'stmt>) -> RowIter<'stmt> {
+        RowIter {
+            statement,
+            prefetch_tuned: false,
+            rows: VecDeque::new(),
+            finished: false,
+        }
+    }
+
+    /// Fetches the next batch of rows in a single round trip. The columns are described and
+    /// defined the first time the statement is fetched from and the definitions are reused after
+    /// that, for every batch and every execution.
+    ///
+    fn fetch_batch(&mut self) -> Result<(), OciError> {
+        self.poll_batch(true).map(|_| ())
+    }
+
+    /// Fetches the next batch of rows, or, unless told to wait, reports that the fetch is still
+    /// pending on a connection in non-blocking mode.
+    ///
+    fn poll_batch(&mut self, wait: bool) -> Result<Poll<()>, OciError> {
+        let statement = self.statement;
+        let _call = statement.start_call()?;
+        if statement.columns.borrow().is_none() {
+            let columns =
+                define_columns(statement).map_err(|err| attach_sql(err, &statement.sql))?;
+            *statement.columns.borrow_mut() = Some(columns);
+        }
+        let columns = statement.columns.borrow();
+        let columns = match *columns {
+            Some(ref columns) => columns,
+            None => return Ok(Poll::Ready(())),
+        };
+        let rows = columns.first().map_or(1, |column| column.rows());
+        let in_call = InCall::start(&statement.call_state, statement.connection);
+        let fetch_result = if wait {
+            fetch_rows(
+                statement.statement,
+                statement.connection,
+                &statement.warnings,
+                rows,
+                FetchType::Next,
+                0,
+            )
+            .map(Poll::Ready)
+        } else {
+            poll_fetch_rows(
+                statement.statement,
+                statement.connection,
+                &statement.warnings,
+                rows,
+                FetchType::Next,
+                0,
+            )
+        };
+        drop(in_call);
+        let (fetched, last) = match fetch_result.map_err(|err| {
+            attach_sql(
+                check_cancelled(err, statement.connection, &statement.call_state),
+                &statement.sql,
+            )
+        })? {
+            Poll::Ready(fetched) => fetched,
+            Poll::Pending => return Ok(Poll::Pending),
+        };
+        self.finished = last;
+        let column_names: Arc<[String]> = columns.iter().map(|col| col.name.clone()).collect();
+        for index in 0..fetched {
+            let sql_values = columns
+                .iter()
+                .map(|col| col.create_sql_value(index, statement.connection))
+                .collect::<Result<Vec<_>, _>>()?;
+            self.rows
+                .push_back(Row::new(sql_values, Arc::clone(&column_names)));
+        }
+        let row_width = self.rows.front().map(|row| row.approximate_size());
+        if let Some(row_width) = row_width {
+            self.tune_prefetch(row_width)?;
+        }
+        Ok(Poll::Ready(()))
+    }
+
+    /// Returns the next row without waiting for the database, on a connection in non-blocking
+    /// mode.
+    ///
+    /// Returns `Poll::Pending` while a batch of rows is still being fetched, in which case it
+    /// has to be called again, otherwise the next row, or `None` at the end, as `.next` does.
+    /// See `Connection.set_nonblocking`.
+    ///
+    pub fn poll_next(&mut self) -> Poll<Option<Result<Row, OciError>>> {
+        if self.rows.is_empty() && !self.finished {
+            match self.poll_batch(false) {
+                Ok(Poll::Ready(())) => (),
+                Ok(Poll::Pending) => return Poll::Pending,
+                Err(err) => {
+                    self.finished = true;
+                    return Poll::Ready(Some(Err(err)));
+                }
+            }
+        }
+        Poll::Ready(self.rows.pop_front().map(Ok))
+    }
+
+    /// Adjusts the number of prefetched rows based on the width of the first row, if adaptive
+    /// prefetching has been turned on.
+    ///
+    fn tune_prefetch(&mut self, row_width: usize) -> Result<(), OciError> {
+        if self.prefetch_tuned {
+            return Ok(());
+        }
+        self.prefetch_tuned = true;
+        if let Some(target_bytes) = self.statement.prefetch_target {
+            let rows = (target_bytes as usize / row_width.max(1)).max(1);
+            debug!(
+                "Adaptive prefetch: row width {} bytes, prefetching {} rows",
+                row_width, rows
+            );
+            set_prefetch_rows(
+                self.statement.statement,
+                self.statement.connection.error(),
+                rows as c_uint,
+            )?;
+        }
+        Ok(())
+    }
 }
 
 impl<'stmt> Iterator for RowIter<'stmt> {
     type Item = Result<Row, OciError>;
 
-    fn next(&mut self) -> Option<Result<Row, OciError>> {
-        match build_result_row(self.statement.statement, self.statement.connection.error()) {
-            Ok(option) => match option {
-                Some(row) => Some(Ok(row)),
-                None => None,
-            },
-            Err(err) => Some(Err(err)),
-        }
-    }
+    fn next(&mut self) -> Option<Result<Row, OciError>> {
+        if self.rows.is_empty() && !self.finished {
+            if let Err(err) = self.fetch_batch() {
+                self.finished = true;
+                return Some(Err(err));
+            }
+        }
+        self.rows.pop_front().map(Ok)
+    }
+}
+
+/// Converts a row with a `FromRow` implementation.
+type FromRowFn<T> = fn(&Row) -> Result<T, OciError>;
+
+/// An iterator over the rows of a result set, each converted by a closure.
+///
+/// See [`Statement.query_map`][1] for more info.
+///
+/// [1]: struct.Statement.html#method.query_map
+#[derive(Debug)]
+pub struct MappedRows<'stmt, F> {
+    rows: RowIter<'stmt>,
+    f: F,
+}
+
+impl<'stmt, T, F> Iterator for MappedRows<'stmt, F>
+where
+    F: FnMut(&Row) -> Result<T, OciError>,
+{
+    type Item = Result<T, OciError>;
+
+    fn next(&mut self) -> Option<Result<T, OciError>> {
+        let f = &mut self.f;
+        self.rows.next().map(|row| row.and_then(|row| f(&row)))
+    }
+}
+
+/// What the database found out about a statement checked with [`Connection.check_syntax`][1].
+///
+/// [1]: ../connection/struct.Connection.html#method.check_syntax
+#[derive(Debug, Clone)]
+pub struct SyntaxCheck {
+    statement_type: StatementType,
+    columns: Vec<ColumnDescription>,
+    placeholders: Vec<BindPlaceholder>,
+}
+
+impl SyntaxCheck {
+    /// Returns the kind of statement.
+    pub fn statement_type(&self) -> StatementType {
+        self.statement_type
+    }
+
+    /// Returns the columns a query would return, which is empty for other statements.
+    pub fn columns(&self) -> &[ColumnDescription] {
+        &self.columns
+    }
+
+    /// Returns the bind placeholders of the statement.
+    pub fn placeholders(&self) -> &[BindPlaceholder] {
+        &self.placeholders
+    }
+}
+
+/// A bind placeholder, such as `:id`, in the SQL of a prepared statement.
+///
+/// See [`Statement.bind_placeholders`][1] for more info.
+///
+/// [1]: struct.Statement.html#method.bind_placeholders
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BindPlaceholder {
+    name: String,
+    position: usize,
+    duplicate: bool,
+}
+
+impl BindPlaceholder {
+    /// Returns the name of the placeholder without the leading colon.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the position of the placeholder, starting from one.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns true if the name has already appeared at an earlier position.
+    pub fn is_duplicate(&self) -> bool {
+        self.duplicate
+    }
+}
+
+/// Cancels a running statement from another thread.
+///
+/// See [`Statement.cancel_handle`][1] for more info.
+///
+/// [1]: struct.Statement.html#method.cancel_handle
+#[derive(Debug)]
+pub struct CancelHandle<'conn> {
+    service: *mut OCISvcCtx,
+    error: *mut OCIError,
+    call_state: Arc<CallState>,
+    call_log: Arc<CallLog>,
+    connection: PhantomData<&'conn Connection>,
+}
+
+// The OCI environment is created in threaded mode and the handle has its own error handle, so
+// OCIBreak can be called on the service context while another thread is using it.
+unsafe impl<'conn> Send for CancelHandle<'conn> {}
+unsafe impl<'conn> Sync for CancelHandle<'conn> {}
+
+impl<'conn> CancelHandle<'conn> {
+    /// Interrupts the statement if it is being executed or fetched from, otherwise does nothing.
+    ///
+    /// The statement can't finish its call, or start another, while the break is being issued,
+    /// so the break is meant for the call in progress. If that call finishes before the break
+    /// reaches it, it returns as normal, so a statement that was cancelled isn't guaranteed to
+    /// return `OciError::Cancelled`. The break then can't land on the statement's next call,
+    /// as the connection is reset before that call is made.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying call to the OCI library will be returned.
+    ///
+    pub fn cancel(&self) -> Result<(), OciError> {
+        let mut status = self.call_state.lock();
+        if !status.in_call {
+            return Ok(());
+        }
+        let break_result = unsafe { OCIBreak(self.service as *mut c_void, self.error) };
+        self.call_log.record_call(
+            "OCIBreak",
+            HandleType::Service,
+            self.service as *mut c_void,
+            break_result,
+        );
+        match break_result.into() {
+            ReturnCode::Success => {
+                status.unreset_break = Some(status.generation);
+                Ok(())
+            }
+            _ => Err(get_error(
+                self.error as *mut c_void,
+                HandleType::Error,
+                "Cancelling statement",
+            )),
+        }
+    }
+}
+
+impl<'conn> Drop for CancelHandle<'conn> {
+    fn drop(&mut self) {
+        let free_result =
+            unsafe { OCIHandleFree(self.error as *mut c_void, HandleType::Error.into()) };
+        if let ReturnCode::Error = free_result.into() {
+            error!("Could not free the error handle in CancelHandle")
+        }
+    }
+}
+
+/// Whether a statement is in a call that can be cancelled, shared with its `CancelHandle`s.
+/// The lock is held while a break is issued, so the call the break is meant for can't end, nor
+/// the next one start, part way through.
+#[derive(Debug, Default)]
+struct CallState(Mutex<CallStatus>);
+
+#[derive(Debug, Default)]
+struct CallStatus {
+    /// Counts the calls made, so that a break is tied to the call it was issued during.
+    generation: u64,
+    in_call: bool,
+    /// The call that a break was issued during, if the connection hasn't been reset since.
+    unreset_break: Option<u64>,
+}
+
+impl CallState {
+    /// Locks the state. Nothing is left half done by a panic while it is locked, so a poisoned
+    /// lock is simply taken over.
+    fn lock(&self) -> MutexGuard<'_, CallStatus> {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Marks a statement as being in a call that can be cancelled, until it is dropped.
+struct InCall<'a>(&'a CallState);
+impl<'a> InCall<'a> {
+    /// Starts the next call. A break issued during an earlier call that finished before the
+    /// break reached it would interrupt this one instead, so the connection is reset first.
+    fn start(state: &'a CallState, connection: &Connection) -> InCall<'a> {
+        let mut status = state.lock();
+        if let Some(generation) = status.unreset_break.take() {
+            debug!(
+                "Resetting the connection for a break issued during call {}",
+                generation
+            );
+            reset_after_break(connection);
+        }
+        status.generation += 1;
+        status.in_call = true;
+        InCall(state)
+    }
+}
+
+impl<'a> Drop for InCall<'a> {
+    fn drop(&mut self) {
+        self.0.lock().in_call = false
+    }
+}
+
+/// Reads where the SQL of the statement that just failed stopped parsing, which is zero if it
+/// parsed or the offset can't be read.
+fn parse_error_offset(error: *mut OCIError) -> c_ushort {
+    let mut offset: c_ushort = 0;
+    let attr_get_result = unsafe {
+        OCIAttrGet(
+            error as *mut c_void,
+            HandleType::Error.into(),
+            &mut offset as *mut c_ushort as *mut c_void,
+            ptr::null_mut(),
+            AttributeType::ParseErrorOffset.into(),
+            error,
+        )
+    };
+    match attr_get_result.into() {
+        ReturnCode::Success => offset,
+        _ => 0,
+    }
+}
+
+/// Reads the ROWID of the last row affected by a statement into the descriptor and converts it to
+/// text.
+fn read_rowid(
+    statement: *mut OCIStmt,
+    error: *mut OCIError,
+    rowid: *mut OCIRowid,
+) -> Result<String, OciError> {
+    let attr_get_result = unsafe {
+        OCIAttrGet(
+            statement as *mut c_void,
+            HandleType::Statement.into(),
+            rowid as *mut c_void,
+            ptr::null_mut(),
+            AttributeType::Rowid.into(),
+            error,
+        )
+    };
+    if let ReturnCode::Error = attr_get_result.into() {
+        return Err(get_error(
+            error as *mut c_void,
+            HandleType::Error,
+            "Getting ROWID from statement",
+        ));
+    }
+    let mut buffer = vec![0u8; ROWID_BUFFER_SIZE];
+    let mut length = ROWID_BUFFER_SIZE as c_ushort;
+    let to_char_result = unsafe { OCIRowidToChar(rowid, buffer.as_mut_ptr(), &mut length, error) };
+    match to_char_result.into() {
+        ReturnCode::Success => {
+            buffer.truncate(length as usize);
+            String::from_utf8(buffer).map_err(|err| OciError::Conversion(Box::new(err)))
+        }
+        _ => Err(get_error(
+            error as *mut c_void,
+            HandleType::Error,
+            "Converting ROWID to text",
+        )),
+    }
+}
+
+/// Reads the row and error of one of the rows that failed in a batch, into the given error
+/// handle.
+fn read_batch_error(
+    error: *mut OCIError,
+    row_error: *mut OCIError,
+    index: c_uint,
+) -> Result<BatchError, OciError> {
+    let row_error_param = row_error as *mut OCIParam;
+    let param_result = unsafe {
+        OCIParamGet(
+            error as *const c_void,
+            HandleType::Error.into(),
+            error,
+            &row_error_param,
+            index,
+        )
+    };
+    if let ReturnCode::Error = param_result.into() {
+        return Err(get_error(
+            error as *mut c_void,
+            HandleType::Error,
+            "Getting batch error",
+        ));
+    }
+    let mut row: c_uint = 0;
+    let attr_get_result = unsafe {
+        OCIAttrGet(
+            row_error as *mut c_void,
+            HandleType::Error.into(),
+            &mut row as *mut c_uint as *mut c_void,
+            ptr::null_mut(),
+            AttributeType::DmlRowOffset.into(),
+            error,
+        )
+    };
+    if let ReturnCode::Error = attr_get_result.into() {
+        return Err(get_error(
+            error as *mut c_void,
+            HandleType::Error,
+            "Getting row of batch error",
+        ));
+    }
+    match get_error(
+        row_error as *mut c_void,
+        HandleType::Error,
+        &format!("Executing row {} of batch", row),
+    ) {
+        OciError::Oracle(record) => Ok(BatchError::new(row as usize, record)),
+        err => Err(err),
+    }
+}
+
+/// Turns the error from a call interrupted by a `CancelHandle` into `OciError::Cancelled`, and
+/// resets the connection so that it can be used again. An error from a call interrupted by the
+/// connection's deadline becomes `OciError::DeadlineExceeded`.
+fn check_cancelled(err: OciError, connection: &Connection, state: &CallState) -> OciError {
+    match err {
+        OciError::Oracle(record) if record.has_code(USER_CANCELLED) => {
+            let mut status = state.lock();
+            if status.unreset_break == Some(status.generation) {
+                status.unreset_break = None;
+            }
+            reset_after_break(connection);
+            OciError::Cancelled(record)
+        }
+        err => connection.check_deadline(err),
+    }
+}
+
+/// Resets the connection after a break, so that it can be used again.
+fn reset_after_break(connection: &Connection) {
+    let service = connection.service() as *mut c_void;
+    let reset_result = unsafe { OCIReset(service, connection.error()) };
+    connection
+        .call_log()
+        .record_call("OCIReset", HandleType::Service, service, reset_result);
+    if let ReturnCode::Error = reset_result.into() {
+        warn!("Could not reset the connection after cancelling a statement")
+    }
+}
+
+/// Records the SQL of the statement on an error from the database, so that whoever reports the
+/// error can say which statement failed.
+fn attach_sql(err: OciError, sql: &str) -> OciError {
+    match err {
+        OciError::Oracle(mut record) => {
+            record.set_sql(sql);
+            OciError::Oracle(record)
+        }
+        OciError::Cancelled(mut record) => {
+            record.set_sql(sql);
+            OciError::Cancelled(record)
+        }
+        OciError::DeadlineExceeded(Some(mut record)) => {
+            record.set_sql(sql);
+            OciError::DeadlineExceeded(Some(record))
+        }
+        err => err,
+    }
+}
+
+/// Set the number of rows to prefetch
+fn set_prefetch_rows(
+    statement: *mut OCIStmt,
+    error: *mut OCIError,
+    nmb_of_rows: c_uint,
+) -> Result<(), OciError> {
+    let size: c_uint = 0;
+    let rows_ptr: *const c_uint = &nmb_of_rows;
+    set_handle_attribute(
+        statement as *mut c_void,
+        HandleType::Statement,
+        rows_ptr as *mut c_void,
+        size,
+        AttributeType::PrefetchRows,
+        error,
+        "Setting prefetch rows in statement handle",
+    )
+}
+
+/// Set the maximum amount of memory used for prefetching
+fn set_prefetch_memory(
+    statement: *mut OCIStmt,
+    error: *mut OCIError,
+    nmb_of_bytes: c_uint,
+) -> Result<(), OciError> {
+    let size: c_uint = 0;
+    let bytes_ptr: *const c_uint = &nmb_of_bytes;
+    set_handle_attribute(
+        statement as *mut c_void,
+        HandleType::Statement,
+        bytes_ptr as *mut c_void,
+        size,
+        AttributeType::PrefetchMemory,
+        error,
+        "Setting prefetch memory in statement handle",
+    )
 }
 
 /// Release statement
-fn release_statement(statement: *mut OCIStmt, error: *mut OCIError) -> Result<(), OciError> {
-    let key_ptr = ptr::null();
-    let key_len = 0 as c_uint;
-    let release_result = unsafe {
-        OCIStmtRelease(
-            statement,
-            error,
-            key_ptr,
-            key_len,
-            EnvironmentMode::Default.into(),
-        )
+///
+/// The statement is tagged with the key so that it can be found in the statement cache, unless
+/// it isn't to be cached, in which case it is removed from the cache.
+///
+fn release_statement(
+    statement: *mut OCIStmt,
+    connection: &Connection,
+    key: &str,
+    cached: bool,
+) -> Result<(), OciError> {
+    let error = connection.error();
+    let mode = if cached {
+        EnvironmentMode::Default
+    } else {
+        EnvironmentMode::CacheDelete
     };
+    let key_ptr = key.as_ptr();
+    let key_len = key.len() as c_uint;
+    let release_result = unsafe { OCIStmtRelease(statement, error, key_ptr, key_len, mode.into()) };
+    connection.call_log().record_call(
+        "OCIStmtRelease",
+        HandleType::Statement,
+        statement as *mut c_void,
+        release_result,
+    );
 
     match release_result.into() {
         ReturnCode::Success => Ok(()),
@@ -451,12 +3615,21 @@ fn release_statement(statement: *mut OCIStmt, error: *mut OCIError) -> Result<()
 }
 
 /// Create statement handle and prepare sql
-fn prepare_statement(connection: &Connection, sql: &str) -> Result<*mut OCIStmt, OciError> {
+///
+/// The key, normally the SQL itself, is used to search the statement cache, if a statement has
+/// already been prepared and released with the same key then the cached cursor is re-used.
+///
+fn prepare_statement(
+    connection: &Connection,
+    sql: &str,
+    key: &str,
+) -> Result<*mut OCIStmt, OciError> {
+    connection.call_log().record_sql(sql);
     let statement: *mut OCIStmt = ptr::null_mut();
     let sql_ptr = sql.as_ptr();
     let sql_len = sql.len() as c_uint;
-    let key_ptr = ptr::null();
-    let key_len = 0 as c_uint;
+    let key_ptr = key.as_ptr();
+    let key_len = key.len() as c_uint;
     let prepare_result = unsafe {
         OCIStmtPrepare2(
             connection.service(),
@@ -470,6 +3643,12 @@ fn prepare_statement(connection: &Connection, sql: &str) -> Result<*mut OCIStmt,
             EnvironmentMode::Default.into(),
         )
     };
+    connection.call_log().record_call(
+        "OCIStmtPrepare2",
+        HandleType::Service,
+        connection.service() as *mut c_void,
+        prepare_result,
+    );
 
     match prepare_result.into() {
         ReturnCode::Success => Ok(statement),
@@ -514,103 +3693,649 @@ fn get_statement_type(
     }
 }
 
+/// Clears a statement's own call timeout when dropped, once the call and any error from it have
+/// been dealt with, so that the timeout doesn't apply to other statements on the connection.
+///
+struct CallTimeoutGuard<'conn> {
+    connection: Option<&'conn Connection>,
+}
+
+impl<'conn> Drop for CallTimeoutGuard<'conn> {
+    fn drop(&mut self) {
+        if let Some(connection) = self.connection {
+            connection.clear_call_timeout();
+        }
+    }
+}
+
+/// A value bound to one placeholder by position, used for typed nulls and outputs. The buffer,
+/// indicator and length are boxed so that their addresses, which OCI holds on to, stay the same
+/// as more placeholders are bound.
+///
+#[derive(Debug)]
+struct PositionBind {
+    position: usize,
+    data_type: SqlType,
+    buffer: Box<[u8]>,
+    indicator: Box<c_short>,
+    length: Box<c_ushort>,
+}
+
+/// The values for one placeholder of a batch, laid out as the arrays that OCI reads: the data
+/// with a fixed size for each element, plus an indicator and actual length for each element.
+///
+#[derive(Debug)]
+struct ArrayBind {
+    data: Vec<u8>,
+    element_size: c_int,
+    data_type: c_ushort,
+    indicators: Vec<c_short>,
+    lengths: Vec<c_ushort>,
+}
+impl ArrayBind {
+    fn new(values: &[SqlValue]) -> Result<ArrayBind, String> {
+        let mut data_type: Option<c_ushort> = None;
+        let mut elements = Vec::with_capacity(values.len());
+        for value in values {
+            if let SqlValue::Null(..) = *value {
+                elements.push(Vec::new());
+                continue;
+            }
+            if let SqlValue::Collection(..) = *value {
+                return Err("a collection can't be part of a batch".to_string());
+            }
+            let value_type: c_ushort = value.as_oci_data_type().into();
+            match data_type {
+                // short and long bytes can go together, all as a LONG RAW
+                Some(current)
+                    if current != value_type
+                        && is_binary_type(current)
+                        && is_binary_type(value_type) =>
+                {
+                    data_type = Some(c_ushort::from(&OciDataType::SqlBlob))
+                }
+                // a NaN or infinity makes the whole batch a BINARY_DOUBLE, in the same bytes
+                Some(current)
+                    if current != value_type
+                        && is_float_type(current)
+                        && is_float_type(value_type) =>
+                {
+                    data_type = Some(c_ushort::from(&OciDataType::SqlBinaryDouble))
+                }
+                Some(data_type) if data_type != value_type => {
+                    return Err(format!(
+                        "{:?} doesn't match the type of the other values",
+                        value
+                    ))
+                }
+                _ => data_type = Some(value_type),
+            }
+            let bytes = value.to_oci_bytes();
+            if bytes.len() > c_ushort::MAX as usize {
+                return Err(format!("a value of {} bytes is too long", bytes.len()));
+            }
+            elements.push(bytes);
+        }
+        // a placeholder with only nulls still needs a type and some room
+        let data_type = data_type.unwrap_or_else(|| SqlValue::Null(None).as_oci_data_type().into());
+        let element_size = elements.iter().map(Vec::len).max().unwrap_or(0).max(1);
+
+        let mut data = vec![0; element_size * values.len()];
+        for (element, chunk) in elements.iter().zip(data.chunks_mut(element_size)) {
+            chunk[..element.len()].copy_from_slice(element);
+        }
+        Ok(ArrayBind {
+            data,
+            element_size: element_size as c_int,
+            data_type,
+            indicators: values.iter().map(SqlValue::indicator).collect(),
+            lengths: elements
+                .iter()
+                .map(|element| element.len() as c_ushort)
+                .collect(),
+        })
+    }
+}
+
+/// The buffers a column is fetched into, with room for a number of rows.
 #[derive(Debug)]
 struct ColumnPtrHolder {
     define: *mut OCIDefine,
     buffer: Vec<u8>,
-    buffer_ptr: *mut c_void,
-    null_ind: Box<c_short>,
-    null_ind_ptr: *mut c_short,
+    null_inds: Vec<c_short>,
+    lengths: Vec<c_ushort>,
+    return_codes: Vec<c_ushort>,
+    locators: Vec<*mut OCILobLocator>,
+    objects: Vec<*mut c_void>,
+    object_indicators: Vec<*mut c_void>,
+}
+impl ColumnPtrHolder {
+    /// Holds nothing, until the column is defined.
+    fn empty() -> ColumnPtrHolder {
+        ColumnPtrHolder {
+            define: ptr::null_mut(),
+            buffer: Vec::new(),
+            null_inds: Vec::new(),
+            lengths: Vec::new(),
+            return_codes: Vec::new(),
+            locators: Vec::new(),
+            objects: Vec::new(),
+            object_indicators: Vec::new(),
+        }
+    }
+}
+
+/// How a column's values are fetched and converted.
+enum ColumnFormat {
+    /// Read through a LOB locator, as a `CLOB`, a `BLOB` or a `BFILE`.
+    Lob(OciDataType),
+    /// Fetched into a buffer, as the type code chosen by the codec.
+    Codec(Arc<dyn TypeCodec>, c_ushort),
+    /// Fetched as a collection of a `VARRAY` or nested table type.
+    Collection(CollectionType),
+}
+
+impl fmt::Debug for ColumnFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ColumnFormat::Lob(ref data_type) => write!(f, "Lob({:?})", data_type),
+            ColumnFormat::Codec(_, type_code) => write!(f, "Codec({})", type_code),
+            ColumnFormat::Collection(ref collection_type) => {
+                write!(f, "Collection({})", collection_type.type_name())
+            }
+        }
+    }
 }
 
 #[derive(Debug)]
 struct Column {
     handle: *mut OCIParam,
-    sql_type: OciDataType,
+    name: String,
+    format: ColumnFormat,
+    element_size: c_int,
+    cap: Option<u32>,
+    charset_form: c_uchar,
+    null_type: Option<ValueType>,
     column_ptr_holder: ColumnPtrHolder,
 }
 impl Column {
-    fn new(
-        statement: *mut OCIStmt,
-        error: *mut OCIError,
-        position: c_uint,
-    ) -> Result<Column, OciError> {
+    /// Describes the column and works out how it will be fetched, it still needs to be defined.
+    fn new(stmt: &Statement, position: c_uint) -> Result<Column, OciError> {
+        let statement = stmt.statement;
+        let error = stmt.connection.error();
         let parameter = allocate_parameter_handle(statement, error, position)?;
-        let data_type = determine_external_data_type(parameter, error)?;
-        let data_size = column_data_size(parameter, error)?;
-        let column_ptr_holder =
-            define_output_parameter(statement, error, position, data_size, &data_type)?;
+        let name = column_name(parameter, error)?;
+        let type_code = column_internal_data_type(parameter, error)?;
+        let cap = stmt
+            .column_caps
+            .iter()
+            .find(|cap| cap.0.eq_ignore_ascii_case(&name))
+            .map(|cap| cap.1);
+        let mut charset_form = SQLCS_IMPLICIT;
+        let column_type = ColumnType::new(
+            type_code,
+            column_data_size(parameter, error)?,
+            column_data_precision(parameter, error)?,
+            column_data_scale(parameter, error)?,
+        );
+        let column_codec = stmt.connection.codecs().get_for_column(&name);
+        let (format, element_size) = if column_codec.is_none()
+            && (is_lob_type(type_code) || type_code == SQLT_JSON)
+        {
+            // a JSON document is read as text through a CLOB locator, however long it is
+            let data_type = match type_code {
+                SQLT_JSON => OciDataType::SqlClob,
+                _ => OciDataType::from(type_code),
+            };
+            charset_form = column_charset_form(parameter, error)?;
+            let element_size = c_int::from(data_type.size());
+            (ColumnFormat::Lob(data_type), element_size)
+        } else if column_codec.is_none()
+            && type_code == SQLT_NTY
+            && !is_xml_type(parameter, error, type_code)?
+        {
+            let collection_type = CollectionType::lookup(
+                stmt.connection,
+                Some(&column_schema_name(parameter, error)?),
+                &column_type_name(parameter, error)?,
+            )?;
+            let element_size = c_int::from(OciDataType::SqlNamedType.size());
+            (ColumnFormat::Collection(collection_type), element_size)
+        } else {
+            let registered = stmt.connection.codecs().get(type_code);
+            let codec: Arc<dyn TypeCodec> = match (column_codec, registered) {
+                (Some(codec), _) => codec,
+                _ if is_xml_type(parameter, error, type_code)? => Arc::new(XmlTypeCodec),
+                _ if stmt.numbers_as_text && type_code == SQLT_NUM => {
+                    Arc::new(NumberTextCodec::new(column_type.scale()))
+                }
+                _ if (type_code == SQLT_CHR || type_code == SQLT_AFC)
+                    && (stmt.varchar_trim, stmt.char_trim)
+                        != (DEFAULT_VARCHAR_TRIM, DEFAULT_CHAR_TRIM) =>
+                {
+                    Arc::new(TextCodec::new(stmt.varchar_trim, stmt.char_trim))
+                }
+                (None, Some(codec)) => codec,
+                (None, None) => {
+                    return Err(OciError::Conversion(
+                        format!(
+                            "Column {} has OCI type code {}, which has no codec registered",
+                            name, type_code
+                        )
+                        .into(),
+                    ))
+                }
+            };
+            let (fetch_type, buffer_size) = codec.fetch_as(&column_type);
+            let mut element_size = buffer_size as c_int;
+            if is_text_type(fetch_type) {
+                charset_form = column_charset_form(parameter, error)?;
+                let char_width = stmt.connection.charset_max_width()?;
+                if charset_form == SQLCS_NCHAR {
+                    // national character set text is converted to AL32UTF8 whatever the client
+                    // character set is
+                    element_size =
+                        element_size.max(text_buffer_size(parameter, error, MAX_BYTES_PER_CHAR)?);
+                } else if stmt.safe_fetch {
+                    element_size =
+                        element_size.max(text_buffer_size(parameter, error, char_width)?);
+                } else if let Some(chars) = column_char_length(parameter, error)? {
+                    // the data size is in database bytes, which can be too few for the
+                    // characters once they are converted to the client character set
+                    element_size = element_size.max(c_int::from(chars) * char_width);
+                }
+                if let Some(cap) = cap {
+                    element_size = element_size.min(cap as c_int);
+                }
+                // the length of each value fetched is returned in a ub2
+                element_size = element_size.min(c_int::from(c_ushort::MAX));
+            }
+            (ColumnFormat::Codec(codec, fetch_type), element_size)
+        };
+        let null_type = match format {
+            ColumnFormat::Collection(ref collection_type) => {
+                Some(ValueType::Collection(collection_type.element_type()))
+            }
+            // an object type that isn't a collection is an XMLType, read as text
+            _ if type_code == SQLT_NTY => Some(ValueType::VarChar),
+            _ => column_type.value_type(),
+        };
         Ok(Column {
             handle: parameter,
-            sql_type: data_type,
-            column_ptr_holder,
+            name,
+            format,
+            element_size,
+            cap,
+            charset_form,
+            null_type,
+            column_ptr_holder: ColumnPtrHolder::empty(),
         })
     }
 
-    fn create_sql_value(&self) -> Result<SqlValue, OciError> {
-        if self.is_null() {
-            Ok(SqlValue::Null)
-        } else {
-            Ok(SqlValue::create_from_raw(
-                &self.column_ptr_holder.buffer,
-                &self.sql_type,
-            )?)
+    /// Defines the buffers the column is fetched into, with room for `rows` values.
+    fn define(&mut self, stmt: &Statement, position: c_uint, rows: usize) -> Result<(), OciError> {
+        let holder = &mut self.column_ptr_holder;
+        holder.null_inds = vec![0; rows];
+        holder.lengths = vec![0; rows];
+        holder.return_codes = vec![0; rows];
+        let (value_ptr, type_code) = match self.format {
+            ColumnFormat::Lob(ref data_type) => {
+                for _ in 0..rows {
+                    let locator = allocate_lob_locator(stmt, data_type)?;
+                    holder.locators.push(locator);
+                }
+                (
+                    holder.locators.as_mut_ptr() as *mut c_void,
+                    c_ushort::from(data_type),
+                )
+            }
+            ColumnFormat::Codec(_, fetch_type) => {
+                holder.buffer = vec![0; self.element_size as usize * rows];
+                (holder.buffer.as_mut_ptr() as *mut c_void, fetch_type)
+            }
+            ColumnFormat::Collection(..) => {
+                holder.objects = vec![ptr::null_mut(); rows];
+                holder.object_indicators = vec![ptr::null_mut(); rows];
+                (holder.objects.as_mut_ptr() as *mut c_void, SQLT_NTY)
+            }
+        };
+        let error = stmt.connection.error();
+        let define_result = unsafe {
+            OCIDefineByPos(
+                stmt.statement,
+                &holder.define,
+                error,
+                position,
+                value_ptr,
+                self.element_size,
+                type_code,
+                holder.null_inds.as_mut_ptr() as *mut c_void,
+                holder.lengths.as_mut_ptr(),
+                holder.return_codes.as_mut_ptr(),
+                EnvironmentMode::Default.into(),
+            )
+        };
+        match define_result.into() {
+            ReturnCode::Success => (),
+            _ => {
+                return Err(get_error(
+                    error as *mut c_void,
+                    HandleType::Error,
+                    "Defining output parameter",
+                ))
+            }
+        }
+        match self.format {
+            ColumnFormat::Codec(..) if self.charset_form == SQLCS_NCHAR => {
+                set_national_charset(holder.define, error)
+            }
+            ColumnFormat::Collection(ref collection_type) => collection_type.define(
+                holder.define,
+                &mut holder.objects,
+                &mut holder.object_indicators,
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    /// Converts the value fetched for the row at `index` of the last batch.
+    fn create_sql_value(
+        &self,
+        index: usize,
+        connection: &Connection,
+    ) -> Result<SqlValue, OciError> {
+        let holder = &self.column_ptr_holder;
+        if holder.null_inds[index] == -1 {
+            return Ok(SqlValue::Null(self.null_type));
+        }
+        if holder.return_codes[index] == FETCH_TRUNCATED {
+            // the indicator holds the length before truncation, or -2 if it is too long for it
+            let length = match holder.null_inds[index] {
+                length if length > 0 => Some(length as usize),
+                _ => None,
+            };
+            return Err(FetchTruncated::new(&self.name, self.element_size as usize, length).into());
+        }
+        match self.format {
+            ColumnFormat::Lob(OciDataType::SqlBFile) => BFile::from_locator(
+                connection.environment(),
+                connection.error(),
+                holder.locators[index],
+            )
+            .map(SqlValue::BFile),
+            ColumnFormat::Lob(ref data_type) => {
+                let data = read_lob(connection, holder.locators[index], self)?;
+                match *data_type {
+                    OciDataType::SqlClob => match String::from_utf8(data) {
+                        Ok(s) => Ok(SqlValue::VarChar(s)),
+                        Err(err) => Err(OciError::Conversion(Box::new(err))),
+                    },
+                    _ => Ok(SqlValue::Blob(data)),
+                }
+            }
+            ColumnFormat::Codec(ref codec, fetch_type) => {
+                // the value is sliced to the length OCI returned for it, so codecs never see the
+                // unused end of the buffer
+                let value = returned_bytes(
+                    &holder.buffer,
+                    index,
+                    self.element_size as usize,
+                    holder.lengths[index] as usize,
+                );
+                let context = DecodeContext::new(connection.environment(), connection.error());
+                codec.decode_in(&context, fetch_type, value)
+            }
+            ColumnFormat::Collection(ref collection_type) => collection_type
+                .fetched_value(holder.objects[index], holder.object_indicators[index]),
+        }
+    }
+
+    fn is_lob(&self) -> bool {
+        match self.format {
+            ColumnFormat::Lob(..) => true,
+            ColumnFormat::Codec(..) | ColumnFormat::Collection(..) => false,
         }
     }
 
-    fn is_null(&self) -> bool {
-        *self.column_ptr_holder.null_ind == -1
+    /// The number of rows the column's buffers have room for.
+    fn rows(&self) -> usize {
+        self.column_ptr_holder.null_inds.len()
     }
 }
 
-fn define_output_parameter(
-    statement: *mut OCIStmt,
-    error: *mut OCIError,
-    position: c_uint,
-    data_size: c_ushort,
+fn allocate_lob_locator(
+    stmt: &Statement,
     data_type: &OciDataType,
-) -> Result<ColumnPtrHolder, OciError> {
-    // VarChar and Char read the actual number of characters to avoid
-    // picking up loads of null values
-    let buffer_size = match *data_type {
-        OciDataType::SqlVarChar | OciDataType::SqlChar => data_size,
-        _ => data_type.size(),
+) -> Result<*mut OCILobLocator, OciError> {
+    let mut locator: *mut OCILobLocator = ptr::null_mut();
+    let alloc_result = unsafe {
+        OCIDescriptorAlloc(
+            stmt.connection.environment() as *const c_void,
+            &mut locator as *mut *mut OCILobLocator as *mut *mut c_void,
+            locator_descriptor_type(data_type).into(),
+            0,
+            ptr::null_mut(),
+        )
+    };
+    match alloc_result.into() {
+        ReturnCode::Success => Ok(locator),
+        _ => Err(get_error(
+            stmt.connection.error() as *mut c_void,
+            HandleType::Error,
+            "Allocating LOB locator",
+        )),
+    }
+}
+
+/// A `BFILE` has a file locator, the other LOB types a LOB locator.
+fn locator_descriptor_type(data_type: &OciDataType) -> DescriptorType {
+    match *data_type {
+        OciDataType::SqlBFile => DescriptorType::File,
+        _ => DescriptorType::Lob,
+    }
+}
+
+/// Reads the whole of a LOB, unless it is longer than the column's cap.
+fn read_lob(
+    connection: &Connection,
+    locator: *mut OCILobLocator,
+    column: &Column,
+) -> Result<Vec<u8>, OciError> {
+    let service = connection.service();
+    let error = connection.error();
+    let mut length: u64 = 0;
+    let length_result =
+        wait_for_call(|| unsafe { OCILobGetLength2(service, error, locator, &mut length) });
+    match length_result.into() {
+        ReturnCode::Success => (),
+        _ => {
+            return Err(get_error(
+                error as *mut c_void,
+                HandleType::Error,
+                "Getting LOB length",
+            ))
+        }
+    }
+    // every character takes at least one byte, so a LOB with more characters than the cap
+    // can't fit in it
+    let over_cap = |bytes: u64| match column.cap {
+        Some(cap) if bytes > u64::from(cap) => Err(OciError::Conversion(
+            format!(
+                "Column {} holds at least {} bytes, more than its cap of {}",
+                column.name, bytes, cap
+            )
+            .into(),
+        )),
+        _ => Ok(()),
+    };
+    over_cap(length)?;
+    if length == 0 {
+        return Ok(Vec::new());
+    }
+    // a CLOB's length is in characters, which can each take several bytes. As for text columns
+    // its text is asked for in the client character set, unless it is stored in the national
+    // character set, when it is converted to AL32UTF8
+    let (mut byte_amount, mut char_amount, buffer_size, charset_id) = match column.format {
+        ColumnFormat::Lob(OciDataType::SqlClob) if column.charset_form == SQLCS_NCHAR => {
+            (0, length, length * MAX_BYTES_PER_CHAR as u64, AL32UTF8_ID)
+        }
+        ColumnFormat::Lob(OciDataType::SqlClob) => {
+            let char_width = connection.charset_max_width()?;
+            (0, length, length * char_width as u64, 0)
+        }
+        _ => (length, 0, length, 0),
     };
     let mut buffer = vec![0; buffer_size as usize];
-    let buffer_ptr = buffer.as_mut_ptr() as *mut c_void;
-    let define: *mut OCIDefine = ptr::null_mut();
-    let null_mut_ptr = ptr::null_mut();
-    let mut indp: Box<c_short> = Box::new(0);
-    let indp_ptr: *mut c_short = &mut *indp;
-    let rlenp = null_mut_ptr as *mut c_ushort;
-    let rcodep = null_mut_ptr as *mut c_ushort;
-    let define_result = unsafe {
-        OCIDefineByPos(
-            statement,
-            &define,
+    let read_result = wait_for_call(|| unsafe {
+        OCILobRead2(
+            service,
+            error,
+            locator,
+            &mut byte_amount,
+            &mut char_amount,
+            1,
+            buffer.as_mut_ptr() as *mut c_void,
+            buffer_size,
+            OCI_ONE_PIECE,
+            ptr::null_mut(),
+            ptr::null_mut(),
+            charset_id,
+            column.charset_form,
+        )
+    });
+    match read_result.into() {
+        ReturnCode::Success => {
+            over_cap(byte_amount)?;
+            buffer.truncate(byte_amount as usize);
+            Ok(buffer)
+        }
+        _ => Err(get_error(
+            error as *mut c_void,
+            HandleType::Error,
+            "Reading LOB",
+        )),
+    }
+}
+
+/// Returns the bytes OCI wrote for the element at an index of an array buffer, given the
+/// length it returned. The length is limited to the element, so a bad length can neither run
+/// into the next element nor off the end of the buffer.
+pub(crate) fn returned_bytes(
+    buffer: &[u8],
+    index: usize,
+    element_size: usize,
+    length: usize,
+) -> &[u8] {
+    let start = index * element_size;
+    &buffer[start..start + length.min(element_size)]
+}
+
+/// The buffer needed for a text column so that no value is truncated, allowing for each
+/// character to take the most bytes the client character set needs.
+fn text_buffer_size(
+    parameter: *mut OCIParam,
+    error: *mut OCIError,
+    char_width: c_int,
+) -> Result<c_int, OciError> {
+    let length = match column_char_length(parameter, error)? {
+        Some(chars) => chars,
+        None => column_data_size(parameter, error)?,
+    };
+    Ok(c_int::from(length) * char_width)
+}
+
+/// The length of a text column in characters if it is declared in characters, e.g.
+/// `VARCHAR2(20 CHAR)`, rather than in bytes.
+fn column_char_length(
+    parameter: *mut OCIParam,
+    error: *mut OCIError,
+) -> Result<Option<c_ushort>, OciError> {
+    let mut char_used: c_uchar = 0;
+    get_parameter_attribute(
+        parameter,
+        &mut char_used as *mut c_uchar as *mut c_void,
+        AttributeType::CharUsed,
+        error,
+        "Getting column length semantics",
+    )?;
+    if char_used == 0 {
+        return Ok(None);
+    }
+    let mut char_size: c_ushort = 0;
+    get_parameter_attribute(
+        parameter,
+        &mut char_size as *mut c_ushort as *mut c_void,
+        AttributeType::CharSize,
+        error,
+        "Getting column size in characters",
+    )?;
+    Ok(Some(char_size))
+}
+
+/// Tells OCI that a column defined in the national character set is text in that set and has it
+/// converted to AL32UTF8, so it decodes the same whatever `NLS_LANG` and `NLS_NCHAR` are set to.
+/// The form has to be set before the character set ID.
+fn set_national_charset(define: *mut OCIDefine, error: *mut OCIError) -> Result<(), OciError> {
+    let charset_form = SQLCS_NCHAR;
+    set_handle_attribute(
+        define as *mut c_void,
+        HandleType::Define,
+        &charset_form as *const c_uchar as *mut c_void,
+        0,
+        AttributeType::CharsetForm,
+        error,
+        "Setting column character set form",
+    )?;
+    let charset_id = AL32UTF8_ID;
+    set_handle_attribute(
+        define as *mut c_void,
+        HandleType::Define,
+        &charset_id as *const c_ushort as *mut c_void,
+        0,
+        AttributeType::CharsetId,
+        error,
+        "Setting column character set",
+    )
+}
+
+fn column_charset_form(
+    parameter: *mut OCIParam,
+    error: *mut OCIError,
+) -> Result<c_uchar, OciError> {
+    let mut charset_form: c_uchar = 0;
+    get_parameter_attribute(
+        parameter,
+        &mut charset_form as *mut c_uchar as *mut c_void,
+        AttributeType::CharsetForm,
+        error,
+        "Getting column character set form",
+    )?;
+    Ok(charset_form)
+}
+
+fn get_parameter_attribute(
+    parameter: *mut OCIParam,
+    value: *mut c_void,
+    attribute_type: AttributeType,
+    error: *mut OCIError,
+    error_description: &str,
+) -> Result<(), OciError> {
+    let attr_get_result = unsafe {
+        OCIAttrGet(
+            parameter as *mut c_void,
+            DescriptorType::Parameter.into(),
+            value,
+            ptr::null_mut(),
+            attribute_type.into(),
             error,
-            position,
-            buffer_ptr,
-            i32::from(buffer_size),
-            data_type.into(),
-            indp_ptr as *mut c_void,
-            rlenp,
-            rcodep,
-            EnvironmentMode::Default.into(),
         )
     };
-    match define_result.into() {
-        ReturnCode::Success => Ok(ColumnPtrHolder {
-            define,
-            buffer,
-            buffer_ptr,
-            null_ind: indp,
-            null_ind_ptr: indp_ptr,
-        }),
+    match attr_get_result.into() {
+        ReturnCode::Success => Ok(()),
         _ => Err(get_error(
             error as *mut c_void,
             HandleType::Error,
-            "Defining output parameter",
+            error_description,
         )),
     }
 }
@@ -639,43 +4364,160 @@ fn column_data_size(parameter: *mut OCIParam, error: *mut OCIError) -> Result<c_
     }
 }
 
-/// Oracle needs to be told what to convert the internal column data
-/// into. This is fine for char, but for numbers it is a bit tricky.
-/// Internally Oracle stores all numbers as Number, it then expects
-/// the caller to tell it what type to use on conversion e.g.
-/// please give me an int for that Number. Here we try to fix the
-/// conversion to either a integer or float. We can do this by checking the
-/// scale and precision of the number in the column. If it the precision is
-/// non-zero and scale is -127 then it is float.
-///
-fn determine_external_data_type(
+/// Describes a column of the select list, the parameter handle is freed straight away.
+fn describe_column(
+    statement: *mut OCIStmt,
+    error: *mut OCIError,
+    position: c_uint,
+) -> Result<ColumnDescription, OciError> {
+    let parameter = allocate_parameter_handle(statement, error, position)?;
+    let description = read_column_description(parameter, error);
+    let free_result =
+        unsafe { OCIDescriptorFree(parameter as *mut c_void, DescriptorType::Parameter.into()) };
+    if let ReturnCode::Error = free_result.into() {
+        error!("Could not free the parameter descriptor of a described column")
+    }
+    description
+}
+
+fn read_column_description(
     parameter: *mut OCIParam,
     error: *mut OCIError,
-) -> Result<OciDataType, OciError> {
-    let internal_data_type = column_internal_data_type(parameter, error)?;
-    match internal_data_type {
-        OciDataType::SqlVarChar => Ok(OciDataType::SqlVarChar),
-        OciDataType::SqlNum => {
-            let precision = column_data_precision(parameter, error)?;
-            let scale = column_data_scale(parameter, error)?;
-            if (precision != 0) && (scale == -127) {
-                Ok(OciDataType::SqlFloat)
-            } else {
-                Ok(OciDataType::SqlInt)
-            }
+) -> Result<ColumnDescription, OciError> {
+    let name = column_name(parameter, error)?;
+    let type_code = column_internal_data_type(parameter, error)?;
+    let data_length = i64::from(column_data_size(parameter, error)?);
+    let (precision, scale) = if type_code == SQLT_NUM {
+        let precision = column_data_precision(parameter, error)?;
+        let scale = column_data_scale(parameter, error)?;
+        if precision == 0 {
+            (None, None)
+        } else if scale == -127 {
+            (Some(i64::from(precision)), None)
+        } else {
+            (Some(i64::from(precision)), Some(i64::from(scale)))
         }
-        OciDataType::SqlChar => Ok(OciDataType::SqlChar),
-        OciDataType::SqlDate | OciDataType::SqlTimestamp | OciDataType::SqlTimestampTz => {
-            Ok(internal_data_type)
+    } else {
+        (None, None)
+    };
+    let mut is_null: c_uchar = 0;
+    get_parameter_attribute(
+        parameter,
+        &mut is_null as *mut c_uchar as *mut c_void,
+        AttributeType::IsNull,
+        error,
+        "Getting column nullability",
+    )?;
+    // only text types can be in the national character set, which adds an N to their names
+    let type_name = match column_charset_form(parameter, error)? {
+        SQLCS_NCHAR => format!("N{}", type_name(type_code)),
+        _ if type_code == SQLT_NTY => column_type_name(parameter, error)?,
+        _ => type_name(type_code),
+    };
+    Ok(ColumnDescription::new(
+        name,
+        type_name,
+        data_length,
+        precision,
+        scale,
+        is_null != 0,
+    ))
+}
+
+/// `CLOB`, `NCLOB` and `BLOB` columns are read through a LOB locator rather than by a codec.
+fn is_lob_type(type_code: c_ushort) -> bool {
+    type_code == c_ushort::from(&OciDataType::SqlClob)
+        || type_code == c_ushort::from(&OciDataType::SqlBlobLocator)
+        || type_code == c_ushort::from(&OciDataType::SqlBFile)
+}
+
+/// Text is sized for safe fetching and column caps, other types have a fixed size.
+fn is_binary_type(type_code: c_ushort) -> bool {
+    type_code == c_ushort::from(&OciDataType::SqlRaw)
+        || type_code == c_ushort::from(&OciDataType::SqlBlob)
+}
+
+fn is_float_type(type_code: c_ushort) -> bool {
+    type_code == c_ushort::from(&OciDataType::SqlFloat)
+        || type_code == c_ushort::from(&OciDataType::SqlBinaryDouble)
+}
+
+fn is_text_type(type_code: c_ushort) -> bool {
+    type_code == c_ushort::from(&OciDataType::SqlVarChar)
+        || type_code == c_ushort::from(&OciDataType::SqlChar)
+}
+
+fn column_name(parameter: *mut OCIParam, error: *mut OCIError) -> Result<String, OciError> {
+    get_parameter_text(parameter, error, AttributeType::Name, "Getting column name")
+}
+
+/// The name of the type of a column of a named type, such as `XMLTYPE` for an `XMLType` column.
+fn column_type_name(parameter: *mut OCIParam, error: *mut OCIError) -> Result<String, OciError> {
+    get_parameter_text(
+        parameter,
+        error,
+        AttributeType::TypeName,
+        "Getting column type name",
+    )
+}
+
+/// The schema of the type of a column of a named type.
+fn column_schema_name(parameter: *mut OCIParam, error: *mut OCIError) -> Result<String, OciError> {
+    get_parameter_text(
+        parameter,
+        error,
+        AttributeType::SchemaName,
+        "Getting column type schema",
+    )
+}
+
+/// Whether a column is an `XMLType`, which is fetched as text rather than as a collection.
+fn is_xml_type(
+    parameter: *mut OCIParam,
+    error: *mut OCIError,
+    type_code: c_ushort,
+) -> Result<bool, OciError> {
+    Ok(type_code == SQLT_NTY && column_type_name(parameter, error)? == "XMLTYPE")
+}
+
+fn get_parameter_text(
+    parameter: *mut OCIParam,
+    error: *mut OCIError,
+    attribute_type: AttributeType,
+    error_description: &str,
+) -> Result<String, OciError> {
+    let mut name_ptr: *mut c_uchar = ptr::null_mut();
+    let mut name_len: c_uint = 0;
+    let name_result = unsafe {
+        OCIAttrGet(
+            parameter as *mut c_void,
+            DescriptorType::Parameter.into(),
+            &mut name_ptr as *mut *mut c_uchar as *mut c_void,
+            &mut name_len,
+            attribute_type.into(),
+            error,
+        )
+    };
+    match name_result.into() {
+        ReturnCode::Success => {
+            if name_ptr.is_null() {
+                return Ok(String::new());
+            }
+            let name = unsafe { slice::from_raw_parts(name_ptr, name_len as usize) };
+            Ok(String::from_utf8_lossy(name).into_owned())
         }
-        _ => panic!("Uknown external conversion."),
+        _ => Err(get_error(
+            error as *mut c_void,
+            HandleType::Error,
+            error_description,
+        )),
     }
 }
 
 fn column_internal_data_type(
     parameter: *mut OCIParam,
     error: *mut OCIError,
-) -> Result<OciDataType, OciError> {
+) -> Result<c_ushort, OciError> {
     let mut data_type: c_ushort = 0;
     let data_type_ptr: *mut c_ushort = &mut data_type;
     let null_mut_ptr = ptr::null_mut();
@@ -690,7 +4532,7 @@ fn column_internal_data_type(
         )
     };
     match size_result.into() {
-        ReturnCode::Success => Ok(data_type.into()),
+        ReturnCode::Success => Ok(data_type),
         _ => Err(get_error(
             error as *mut c_void,
             HandleType::Error,
@@ -777,6 +4619,22 @@ fn allocate_parameter_handle(
 
 impl Drop for Column {
     fn drop(&mut self) {
+        for locator in &self.column_ptr_holder.locators {
+            let descriptor_type = match self.format {
+                ColumnFormat::Lob(ref data_type) => locator_descriptor_type(data_type),
+                ColumnFormat::Codec(..) | ColumnFormat::Collection(..) => DescriptorType::Lob,
+            };
+            let locator_free_result =
+                unsafe { OCIDescriptorFree(*locator as *mut c_void, descriptor_type.into()) };
+            if let ReturnCode::Error = locator_free_result.into() {
+                error!("Could not free the LOB locator in Column")
+            }
+        }
+        if let ColumnFormat::Collection(ref collection_type) = self.format {
+            for object in &self.column_ptr_holder.objects {
+                collection_type.free(*object);
+            }
+        }
         let descriptor_free_result = unsafe {
             OCIDescriptorFree(self.handle as *mut c_void, DescriptorType::Parameter.into())
         };
@@ -812,56 +4670,222 @@ fn number_of_columns(statement: *mut OCIStmt, error: *mut OCIError) -> Result<c_
     }
 }
 
-fn build_result_row(
-    statement: *mut OCIStmt,
-    error: *mut OCIError,
-) -> Result<Option<Row>, OciError> {
-    let column_count = number_of_columns(statement, error)?;
-    let columns: Vec<Column> = (1..=column_count)
-        .map(|position| Column::new(statement, error, position))
+/// Describes and defines the columns of a query, with buffers for as many rows as are fetched
+/// in each round trip.
+fn define_columns(stmt: &Statement) -> Result<Vec<Column>, OciError> {
+    let column_count = number_of_columns(stmt.statement, stmt.connection.error())?;
+    let mut columns = (1..=column_count)
+        .map(|position| Column::new(stmt, position))
         .collect::<Result<Vec<Column>, _>>()?;
+    let row_width = row_width(&columns);
+    if stmt.prefetch_auto {
+        stmt.tune_auto_prefetch(row_width)?;
+    }
+    let rows = fetch_array_rows(stmt.fetch_array_size, row_width);
+    for (index, column) in columns.iter_mut().enumerate() {
+        column.define(stmt, index as c_uint + 1, rows)?;
+    }
 
-    match fetch_next_row(statement, error) {
-        Ok(result) => match result {
-            FetchResult::Data => (),
-            FetchResult::NoData => return Ok(None),
-        },
-        Err(err) => return Err(err),
+    for column in columns.iter().filter(|col| col.is_lob()) {
+        let mut locator_columns = stmt.locator_columns.borrow_mut();
+        if !locator_columns.contains(&column.name) {
+            locator_columns.push(column.name.clone());
+        }
     }
+    Ok(columns)
+}
 
-    let sql_values: Result<Vec<_>, _> = columns
-        .into_iter()
-        .map(|col| col.create_sql_value())
-        .collect();
+/// The width, in bytes, of a row in the define buffers.
+fn row_width(columns: &[Column]) -> usize {
+    columns
+        .iter()
+        .map(|column| column.element_size as usize)
+        .sum()
+}
 
-    Ok(Some(Row::new(sql_values?)))
+/// The number of rows to fetch in each round trip, limited so that the buffers for very wide
+/// rows don't take up too much memory.
+fn fetch_array_rows(array_size: u32, row_width: usize) -> usize {
+    let rows_in_limit = (FETCH_BUFFER_LIMIT / row_width.max(1)).max(1);
+    (array_size.max(1) as usize).min(rows_in_limit)
 }
 
-enum FetchResult {
-    Data,
-    NoData,
+/// Fetches the next rows into the define buffers. Returns the number of rows fetched and
+/// whether they are the last ones.
+fn fetch_rows(
+    statement: *mut OCIStmt,
+    connection: &Connection,
+    warnings: &RefCell<Vec<Warning>>,
+    rows: usize,
+    orientation: FetchType,
+    offset: c_int,
+) -> Result<(usize, bool), OciError> {
+    loop {
+        match poll_fetch_rows(statement, connection, warnings, rows, orientation, offset)? {
+            Poll::Ready(fetched) => return Ok(fetched),
+            Poll::Pending => thread::sleep(STILL_EXECUTING_WAIT),
+        }
+    }
 }
 
-fn fetch_next_row(statement: *mut OCIStmt, error: *mut OCIError) -> Result<FetchResult, OciError> {
-    let nrows = 1 as c_uint;
-    let offset = 0 as c_int;
+/// Makes a single call to fetch the next rows, which is still pending if the connection is in
+/// non-blocking mode and the database hasn't answered.
+fn poll_fetch_rows(
+    statement: *mut OCIStmt,
+    connection: &Connection,
+    warnings: &RefCell<Vec<Warning>>,
+    rows: usize,
+    orientation: FetchType,
+    offset: c_int,
+) -> Result<Poll<(usize, bool)>, OciError> {
+    let error = connection.error();
     let fetch_result = unsafe {
         OCIStmtFetch2(
             statement,
             error,
-            nrows,
-            FetchType::Next.into(),
+            rows as c_uint,
+            orientation.into(),
             offset,
             EnvironmentMode::Default.into(),
         )
     };
-    match fetch_result.into() {
-        ReturnCode::Success => Ok(FetchResult::Data),
-        ReturnCode::NoData => Ok(FetchResult::NoData),
+    connection.call_log().record_call(
+        "OCIStmtFetch2",
+        HandleType::Statement,
+        statement as *mut c_void,
+        fetch_result,
+    );
+    // a truncated value is also reported by its column's return code, so that the rows
+    // fetched with it can still be read
+    let last = match fetch_result.into() {
+        ReturnCode::Success => false,
+        ReturnCode::SuccessWithInfo => {
+            record_warnings(warnings, error);
+            false
+        }
+        ReturnCode::NoData => true,
+        ReturnCode::StillExecuting => return Ok(Poll::Pending),
+        _ => {
+            return Err(get_error(
+                error as *mut c_void,
+                HandleType::Error,
+                "Fetching",
+            ))
+        }
+    };
+    let rows_fetched = get_rows_fetched(statement, error)?;
+    Ok(Poll::Ready((rows_fetched, last)))
+}
+
+/// Reads the warnings of a call that returned `OCI_SUCCESS_WITH_INFO`, logs them and adds them
+/// to those of the statement.
+fn record_warnings(warnings: &RefCell<Vec<Warning>>, error: *mut OCIError) {
+    let found = get_warnings(error as *mut c_void, HandleType::Error);
+    for warning in &found {
+        warn!("{}", warning);
+    }
+    warnings.borrow_mut().extend(found);
+}
+
+/// Find out how many rows the last fetch call returned
+fn get_rows_fetched(statement: *mut OCIStmt, error: *mut OCIError) -> Result<usize, OciError> {
+    let mut rows_fetched: c_uint = 0;
+    let attr_get_result = unsafe {
+        OCIAttrGet(
+            statement as *mut c_void,
+            HandleType::Statement.into(),
+            &mut rows_fetched as *mut c_uint as *mut c_void,
+            ptr::null_mut(),
+            AttributeType::RowsFetched.into(),
+            error,
+        )
+    };
+    match attr_get_result.into() {
+        ReturnCode::Success => Ok(rows_fetched as usize),
         _ => Err(get_error(
             error as *mut c_void,
             HandleType::Error,
-            "Fetching",
+            "Getting number of rows fetched",
         )),
     }
 }
+
+/// Checks whether an error was caused by the state of a package being discarded, in which case
+/// running the statement again will succeed.
+///
+fn is_package_state_discarded(record: &ErrorRecord) -> bool {
+    record.has_code(PACKAGE_STATE_DISCARDED) || record.has_code(PACKAGE_STATE_INVALIDATED)
+}
+
+/// Checks whether a query selects every column via `*`, either as `SELECT *` or as `alias.*`.
+/// This is only a heuristic for the `SELECT *` warning so it doesn't try to parse the SQL,
+/// `COUNT(*)` and the like are not matched.
+///
+pub(crate) fn selects_all_columns(sql: &str) -> bool {
+    let sql = sql.to_uppercase();
+    let tokens: Vec<&str> = sql
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|token| !token.is_empty())
+        .collect();
+    tokens.iter().enumerate().any(|(index, token)| {
+        token.ends_with(".*")
+            || (*token == "*"
+                && index > 0
+                && ["SELECT", "DISTINCT", "ALL", "UNIQUE"].contains(&tokens[index - 1]))
+            || *token == "SELECT*"
+    })
+}
+
+/// Returns the names of the placeholders in a statement in the order they first appear, each
+/// name only once and upper cased as Oracle does. String literals, quoted identifiers and
+/// comments are skipped so that a colon inside them isn't taken as a placeholder.
+///
+fn placeholder_names(sql: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut chars = sql.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' | '"' => {
+                for next in chars.by_ref() {
+                    if next == c {
+                        break;
+                    }
+                }
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            ':' => {
+                let mut name = String::new();
+                while let Some(&next) = chars.peek() {
+                    if next.is_alphanumeric() || next == '_' || next == '$' || next == '#' {
+                        name.push(next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                let name = name.to_uppercase();
+                if !name.is_empty() && !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+            _ => (),
+        }
+    }
+    names
+}
@@ -0,0 +1,171 @@
+use crate::connection::Connection;
+use crate::describe::quote_name;
+use crate::oci_error::OciError;
+use log::error;
+use std::ops::Deref;
+
+/// ORA-02447: cannot defer a constraint that is not deferrable
+const CONSTRAINT_NOT_DEFERRABLE: i32 = 2447;
+/// ORA-02448: constraint does not exist
+const CONSTRAINT_DOES_NOT_EXIST: i32 = 2448;
+
+/// A transaction on a `Connection`.
+///
+/// A `Transaction` is created by the `.transaction` method of a [`Connection`][1] and derefs to
+/// it, so statements are created in the usual way. It must be finished with `.commit`,
+/// otherwise the changes are rolled back when it goes out of scope, including when an error is
+/// returned early with `?`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use oci_rs::connection::Connection;
+///
+/// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+///
+/// let transaction = conn.transaction();
+/// transaction.set_constraints_deferred(&["Orders_Customer_Fk"]).unwrap();
+/// let mut insert = transaction
+///     .create_prepared_statement("INSERT INTO Orders (OrderId, CustomerId) VALUES (:1, :2)")
+///     .unwrap();
+/// insert.bind(&[&1, &42]).unwrap();
+/// insert.execute().unwrap();
+/// // The customer can be loaded after the order, the foreign key is only checked on commit
+/// let mut insert = transaction
+///     .create_prepared_statement("INSERT INTO Customers (CustomerId) VALUES (:1)")
+///     .unwrap();
+/// insert.bind(&[&42]).unwrap();
+/// insert.execute().unwrap();
+/// transaction.commit().unwrap();
+/// ```
+///
+/// [1]: ../connection/struct.Connection.html
+#[derive(Debug)]
+pub struct Transaction<'conn> {
+    connection: &'conn Connection,
+    finished: bool,
+}
+impl<'conn> Transaction<'conn> {
+    /// Creates a new `Transaction`.
+    ///
+    pub(crate) fn new(connection: &'conn Connection) -> Transaction<'conn> {
+        Transaction {
+            connection,
+            finished: false,
+        }
+    }
+
+    /// Commits the transaction.
+    ///
+    /// Deferred constraints are checked now, so a violation of one is reported here and the
+    /// transaction is rolled back by Oracle.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned.
+    ///
+    pub fn commit(mut self) -> Result<(), OciError> {
+        self.finished = true;
+        self.connection.commit()
+    }
+
+    /// Rolls back the transaction.
+    ///
+    /// # Errors
+    ///
+    /// Any error in the underlying calls to the OCI library will be returned.
+    ///
+    pub fn rollback(mut self) -> Result<(), OciError> {
+        self.finished = true;
+        self.connection.rollback()
+    }
+
+    /// Defers the checking of the named constraints until the transaction is committed.
+    ///
+    /// This is useful for bulk loads where rows arrive in an order that temporarily breaks
+    /// foreign keys. Names can be qualified with a schema and follow Oracle's rules for quoted
+    /// and unquoted identifiers. An empty list defers all deferrable constraints. The
+    /// constraints must have been created as `DEFERRABLE`.
+    ///
+    /// # Errors
+    ///
+    /// An `OciError::Usage` holding `UsageError::InvalidName` is returned if a name is not a
+    /// valid identifier. Any error in the underlying calls to the OCI library will be returned, with a hint added if a
+    /// constraint can't be found or is not deferrable.
+    ///
+    pub fn set_constraints_deferred(&self, names: &[&str]) -> Result<(), OciError> {
+        self.set_constraints(names, "DEFERRED")
+    }
+
+    /// Checks the named constraints at the end of each statement again, which is Oracle's
+    /// default. Any rows already changed in the transaction are checked straight away.
+    ///
+    /// Names are handled as in `.set_constraints_deferred` and an empty list means all
+    /// constraints.
+    ///
+    /// # Errors
+    ///
+    /// An `OciError::Usage` holding `UsageError::InvalidName` is returned if a name is not a
+    /// valid identifier. Any error in the underlying calls to the OCI library will be returned, including a violation of a
+    /// constraint by the rows changed so far.
+    ///
+    pub fn set_constraints_immediate(&self, names: &[&str]) -> Result<(), OciError> {
+        self.set_constraints(names, "IMMEDIATE")
+    }
+
+    fn set_constraints(&self, names: &[&str], mode: &str) -> Result<(), OciError> {
+        let constraints = if names.is_empty() {
+            "ALL".to_string()
+        } else {
+            names
+                .iter()
+                .map(|name| quote_constraint_name(name))
+                .collect::<Result<Vec<String>, OciError>>()?
+                .join(", ")
+        };
+        let sql = format!("SET CONSTRAINTS {} {}", constraints, mode);
+        let mut set = self.connection.create_prepared_statement(&sql)?;
+        set.execute().map_err(explain_constraint_error)
+    }
+}
+
+impl<'conn> Deref for Transaction<'conn> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.connection
+    }
+}
+
+impl<'conn> Drop for Transaction<'conn> {
+    /// Rolls back the transaction if it was neither committed nor rolled back.
+    ///
+    fn drop(&mut self) {
+        if !self.finished {
+            if let Err(err) = self.connection.rollback() {
+                error!("Could not roll back transaction: {}", err)
+            }
+        }
+    }
+}
+
+/// Quotes a possibly schema qualified constraint name, so that it can be put into SQL safely.
+/// Names that aren't valid identifiers are rejected, see `quote_name`.
+///
+pub(crate) fn quote_constraint_name(name: &str) -> Result<String, OciError> {
+    quote_name(name, 2, "constraint name")
+}
+
+fn explain_constraint_error(err: OciError) -> OciError {
+    match err {
+        OciError::Oracle(mut record) => {
+            if record.has_code(CONSTRAINT_NOT_DEFERRABLE) {
+                record.add_hint("the constraint must be created as DEFERRABLE to be deferred");
+            } else if record.has_code(CONSTRAINT_DOES_NOT_EXIST) {
+                record.add_hint("check the constraint name and its schema");
+            }
+            OciError::Oracle(record)
+        }
+        err => err,
+    }
+}
@@ -1,12 +1,41 @@
-use crate::oci_bindings::OciDataType;
-use crate::oci_error::OciError;
+use crate::bfile::BFile;
+use crate::collection::{Collection, ElementType};
+use crate::oci_bindings::{
+    DescriptorType, HandleType, OCIDateTime, OCIDateTimeFromArray, OCIDateTimeGetTimeZoneName,
+    OCIDateTimeGetTimeZoneOffset, OCIDescriptorAlloc, OCIDescriptorFree, OCIEnv, OCIError,
+    OCINumberIsInt, OCINumberToInt, OCINumberToReal, OCINumberToText, OciDataType, OciNumberType,
+    ReturnCode, SQLT_AFC, SQLT_BDOUBLE, SQLT_BFILE, SQLT_BIN, SQLT_BLOB, SQLT_CHR, SQLT_CLOB,
+    SQLT_DAT, SQLT_FLT, SQLT_IBDOUBLE, SQLT_IBFLOAT, SQLT_INT, SQLT_JSON, SQLT_LBI, SQLT_LNG,
+    SQLT_NUM, SQLT_RDD, SQLT_TIMESTAMP, SQLT_TIMESTAMP_INTERNAL, SQLT_TIMESTAMP_TZ,
+    SQLT_TIMESTAMP_TZ_INTERNAL, SQLT_UROWID, SQLT_VNU,
+};
+use crate::oci_error::{get_error, ConversionError, NumericError, NumericErrorKind, OciError};
+use crate::scn::Scn;
+#[cfg(feature = "bigdecimal")]
+use bigdecimal::num_bigint::{BigInt, Sign};
+#[cfg(feature = "bigdecimal")]
+use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive};
 use byteorder::{BigEndian, ByteOrder, LittleEndian};
 use chrono::{Date, DateTime, Datelike, FixedOffset, TimeZone, Timelike, Utc};
-use libc::{c_int, c_void};
+#[cfg(feature = "chrono-tz")]
+use chrono_tz::Tz;
+use libc::{c_int, c_schar, c_short, c_uchar, c_uint, c_void};
+use log::error;
+#[cfg(feature = "decimal")]
+use rust_decimal::Decimal;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::mem;
+use std::ptr;
+use std::sync::Arc;
+#[cfg(feature = "time")]
+use time::{OffsetDateTime, PrimitiveDateTime, UtcOffset};
 
 /// The types that support conversion from OCI to Rust types.
 ///
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum SqlValue {
     /// Anything specified as `VARCHAR` or `VARCHAR2` will end up here.
     VarChar(String),
@@ -18,16 +47,34 @@ pub enum SqlValue {
     /// All floating point types regardless of their size are represented with this variant. e.g.
     /// `REAL` and `FLOAT` will both be held.
     Float(f64),
-    /// Represents null values in columns.
-    Null,
+    /// Represents null values in columns, with the type of the column or output parameter the
+    /// null came from when it is known. A null made to be bound has no type.
+    Null(Option<ValueType>),
     /// Represents a date
     Date(Date<Utc>, [u8; 7]),
     /// Represents a timestamp without time zone
     Timestamp(DateTime<Utc>, [u8; 11]),
     /// Represents a timestamp with a time zone
     TimestampTz(DateTime<FixedOffset>, [u8; 13]),
+    /// Represents a timestamp with a named time zone region, such as `Europe/London`, along
+    /// with the offset from UTC in effect at that time
+    TimestampTzRegion(DateTime<FixedOffset>, String, [u8; 13]),
     /// Represents a blob
     Blob(Vec<u8>),
+    /// Represents a bfile. These are read only, binding one binds a null.
+    BFile(BFile),
+    /// Represents a `ROWID` or `UROWID`
+    RowId(RowId),
+    /// Represents a `VARRAY` or nested table
+    Collection(Collection),
+    /// Represents a `NUMBER` with decimal places, without losing any precision. It needs the
+    /// `decimal` feature.
+    #[cfg(feature = "decimal")]
+    Decimal(Decimal, Vec<u8>),
+    /// Represents a `NUMBER` of any precision, without losing any of it. It needs the
+    /// `bigdecimal` feature.
+    #[cfg(feature = "bigdecimal")]
+    BigDecimal(BigDecimal, Vec<u8>),
 }
 impl SqlValue {
     /// Returns the internal value converting on the way to whichever type implements
@@ -49,7 +96,7 @@ impl SqlValue {
     /// assert_eq!(i, 42);
     /// assert_eq!(s, "42");
     ///
-    /// let null = SqlValue::Null;
+    /// let null = SqlValue::Null(None);
     /// let null_as_i64: Option<i64> = null.value();
     ///
     /// assert_eq!(null_as_i64, None);
@@ -59,271 +106,2558 @@ impl SqlValue {
         T::from_sql_value(self)
     }
 
+    /// Returns the internal value converted to whichever type implements `FromSqlValue`, in
+    /// the same way as `.value`, but with an error when it can't be converted.
+    ///
+    /// Numbers are never truncated or wrapped around to fit, a `NUMBER` that is out of range
+    /// for the type asked for is an error, as is a null. The same conversion is available
+    /// through `TryFrom`, e.g. `i64::try_from(&value)`.
+    ///
+    /// # Errors
+    ///
+    /// A [`ConversionError`][1] is returned if the value can't be converted, giving its SQL
+    /// type, the value and the Rust type asked for.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use oci_rs::types::SqlValue;
+    ///
+    /// let v = SqlValue::Integer(300);
+    /// let i: i16 = v.try_value().expect("300 fits in an i16");
+    /// assert_eq!(i, 300);
+    ///
+    /// let too_big = v.try_value::<i8>();
+    /// assert!(too_big.is_err());
+    /// ```
+    ///
+    /// [1]: ../oci_error/struct.ConversionError.html
+    pub fn try_value<T: FromSqlValue>(&self) -> Result<T, ConversionError> {
+        T::try_from_sql_value(self)
+    }
+
+    /// Converts a number to an `i64`, saying why if it can't be done exactly.
+    ///
+    /// Where `.value` only gives a `None`, the error tells a number that is out of range from
+    /// one with a fraction that would be lost and from a value that isn't a number at all. Text
+    /// holding a number, such as a `NUMBER` too big for an `i64` or one fetched as text, is
+    /// converted as well.
+    ///
+    /// # Errors
+    ///
+    /// A [`NumericError`][1] is returned if the value isn't a whole number in the range of an
+    /// `i64`, with its [`kind`][2] saying why.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use oci_rs::oci_error::NumericErrorKind;
+    /// use oci_rs::types::SqlValue;
+    ///
+    /// assert_eq!(SqlValue::Float(42.0).as_i64_checked(), Ok(42));
+    ///
+    /// let fraction = SqlValue::Float(1.5).as_i64_checked().unwrap_err();
+    /// assert_eq!(fraction.kind(), NumericErrorKind::PrecisionLoss);
+    ///
+    /// let too_big = SqlValue::VarChar("12345678901234567890".to_string());
+    /// assert_eq!(
+    ///     too_big.as_i64_checked().unwrap_err().kind(),
+    ///     NumericErrorKind::Overflow
+    /// );
+    /// ```
+    ///
+    /// [1]: ../oci_error/struct.NumericError.html
+    /// [2]: ../oci_error/enum.NumericErrorKind.html
+    pub fn as_i64_checked(&self) -> Result<i64, NumericError> {
+        let integer = self.as_integer_checked("i64")?;
+        i64::try_from(integer)
+            .map_err(|_| NumericError::new(NumericErrorKind::Overflow, self, "i64"))
+    }
+
+    /// Converts a number to a `u64` in the same way as `.as_i64_checked`, a negative number
+    /// being out of range.
+    ///
+    /// # Errors
+    ///
+    /// A [`NumericError`][1] is returned if the value isn't a whole number in the range of a
+    /// `u64`.
+    ///
+    /// [1]: ../oci_error/struct.NumericError.html
+    pub fn as_u64_checked(&self) -> Result<u64, NumericError> {
+        let integer = self.as_integer_checked("u64")?;
+        u64::try_from(integer)
+            .map_err(|_| NumericError::new(NumericErrorKind::Overflow, self, "u64"))
+    }
+
+    /// Converts a number to an `i128` in the same way as `.as_i64_checked`, which holds any
+    /// whole `NUMBER` of up to 38 digits.
+    ///
+    /// # Errors
+    ///
+    /// A [`NumericError`][1] is returned if the value isn't a whole number in the range of an
+    /// `i128`.
+    ///
+    /// [1]: ../oci_error/struct.NumericError.html
+    pub fn as_i128_checked(&self) -> Result<i128, NumericError> {
+        self.as_integer_checked("i128")
+    }
+
+    /// Converts a number to an `f64`, with an error if the `f64` isn't the same number.
+    ///
+    /// A `NUMBER` holds up to 38 significant digits and an `f64` only about 16, so a number
+    /// with more digits, or a whole number beyond 2^53, is reported as losing precision rather
+    /// than being rounded. A decimal that has a nearest `f64` which reads back as the same
+    /// decimal, such as `0.1`, is converted. A float is returned as it is, including NaN and the
+    /// infinities of a `BINARY_DOUBLE`.
+    ///
+    /// # Errors
+    ///
+    /// A [`NumericError`][1] is returned if the value isn't a number, is too big for an `f64`
+    /// or would lose precision.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use oci_rs::oci_error::NumericErrorKind;
+    /// use oci_rs::types::SqlValue;
+    ///
+    /// assert_eq!(SqlValue::VarChar("0.10".to_string()).as_f64_checked(), Ok(0.1));
+    ///
+    /// let long = SqlValue::Integer(9_007_199_254_740_993);
+    /// assert_eq!(
+    ///     long.as_f64_checked().unwrap_err().kind(),
+    ///     NumericErrorKind::PrecisionLoss
+    /// );
+    /// assert_eq!(long.as_f64_lossy(), Ok(9_007_199_254_740_992.0));
+    /// ```
+    ///
+    /// [1]: ../oci_error/struct.NumericError.html
+    pub fn as_f64_checked(&self) -> Result<f64, NumericError> {
+        let float = self.as_f64_lossy()?;
+        if let SqlValue::Float(..) = *self {
+            return Ok(float);
+        }
+        let text = self.decimal_text().unwrap_or_default();
+        if decimal_parts(&float.to_string()) == decimal_parts(&text) {
+            Ok(float)
+        } else {
+            Err(NumericError::new(
+                NumericErrorKind::PrecisionLoss,
+                self,
+                "f64",
+            ))
+        }
+    }
+
+    /// Converts a number to the nearest `f64`, accepting any loss of precision.
+    ///
+    /// # Errors
+    ///
+    /// A [`NumericError`][1] is returned if the value isn't a number or is too big for an
+    /// `f64`.
+    ///
+    /// [1]: ../oci_error/struct.NumericError.html
+    pub fn as_f64_lossy(&self) -> Result<f64, NumericError> {
+        let error = |kind| NumericError::new(kind, self, "f64");
+        match *self {
+            SqlValue::Float(f) => Ok(f),
+            SqlValue::Integer(i) => Ok(i as f64),
+            _ => {
+                let text = self
+                    .decimal_text()
+                    .filter(|text| decimal_parts(text).is_some())
+                    .ok_or_else(|| error(NumericErrorKind::NotANumber))?;
+                match text.parse::<f64>() {
+                    Ok(f) if f.is_infinite() => Err(error(NumericErrorKind::Overflow)),
+                    Ok(f) => Ok(f),
+                    Err(_) => Err(error(NumericErrorKind::NotANumber)),
+                }
+            }
+        }
+    }
+
+    /// Converts a number to an `i128` for the checked integer conversions, with `target` the
+    /// type asked for.
+    fn as_integer_checked(&self, target: &'static str) -> Result<i128, NumericError> {
+        let error = |kind| NumericError::new(kind, self, target);
+        match *self {
+            SqlValue::Integer(i) => Ok(i128::from(i)),
+            SqlValue::Float(f) if f.is_nan() => Err(error(NumericErrorKind::NotANumber)),
+            SqlValue::Float(f) if f.is_infinite() => Err(error(NumericErrorKind::Overflow)),
+            SqlValue::Float(f) if f.fract() != 0.0 => Err(error(NumericErrorKind::PrecisionLoss)),
+            // 2^127, the first float outside the range of an i128
+            SqlValue::Float(f)
+                if f.abs() >= 170_141_183_460_469_231_731_687_303_715_884_105_728.0 =>
+            {
+                Err(error(NumericErrorKind::Overflow))
+            }
+            SqlValue::Float(f) => Ok(f as i128),
+            _ => {
+                let (negative, digits, exponent) = self
+                    .decimal_text()
+                    .and_then(|text| decimal_parts(&text))
+                    .ok_or_else(|| error(NumericErrorKind::NotANumber))?;
+                if exponent < 0 {
+                    return Err(error(NumericErrorKind::PrecisionLoss));
+                }
+                // an i128 has at most 39 digits, longer numbers are out of range however big the
+                // exponent is
+                if digits.len() as i64 + exponent > 39 {
+                    return Err(error(NumericErrorKind::Overflow));
+                }
+                let mut text = String::with_capacity(40);
+                if negative {
+                    text.push('-');
+                }
+                text.push_str(&digits);
+                for _ in 0..exponent {
+                    text.push('0');
+                }
+                text.parse().map_err(|_| error(NumericErrorKind::Overflow))
+            }
+        }
+    }
+
+    /// Returns the decimal text of a number held exactly, as an integer, text or a decimal, for
+    /// the checked conversions.
+    fn decimal_text(&self) -> Option<String> {
+        match *self {
+            SqlValue::Integer(i) => Some(i.to_string()),
+            SqlValue::VarChar(ref s) => Some(s.trim().to_string()),
+            #[cfg(feature = "decimal")]
+            SqlValue::Decimal(ref d, _) => Some(d.to_string()),
+            #[cfg(feature = "bigdecimal")]
+            SqlValue::BigDecimal(ref d, _) => Some(d.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Returns the kind of value held, so that values can be handled generically, such as by a
+    /// serializer, without matching on `SqlValue` itself, whose variants depend on the features
+    /// enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use oci_rs::types::{SqlValue, ValueType};
+    ///
+    /// let v = SqlValue::Integer(1234);
+    /// assert_eq!(
+    ///     v.sql_type(),
+    ///     ValueType::Number {
+    ///         precision: Some(4),
+    ///         scale: Some(0)
+    ///     }
+    /// );
+    /// assert_eq!(v.sql_type().to_string(), "NUMBER(4, 0)");
+    /// assert_eq!(SqlValue::Null(None).sql_type(), ValueType::Null);
+    /// ```
+    ///
+    pub fn sql_type(&self) -> ValueType {
+        match *self {
+            SqlValue::VarChar(..) => ValueType::VarChar,
+            SqlValue::Char(..) => ValueType::Char,
+            SqlValue::Integer(i) => ValueType::Number {
+                precision: Some(i.unsigned_abs().checked_ilog10().map_or(1, |log| log + 1)),
+                scale: Some(0),
+            },
+            SqlValue::Float(..) => ValueType::Number {
+                precision: None,
+                scale: None,
+            },
+            SqlValue::Null(..) => ValueType::Null,
+            SqlValue::Date(..) => ValueType::Date,
+            SqlValue::Timestamp(..) => ValueType::Timestamp,
+            SqlValue::TimestampTz(..) | SqlValue::TimestampTzRegion(..) => ValueType::TimestampTz,
+            SqlValue::Blob(..) => ValueType::Blob,
+            SqlValue::BFile(..) => ValueType::BFile,
+            SqlValue::RowId(..) => ValueType::RowId,
+            SqlValue::Collection(ref c) => ValueType::Collection(c.element_type()),
+            #[cfg(feature = "decimal")]
+            SqlValue::Decimal(ref d, _) => ValueType::Number {
+                precision: Some(
+                    d.mantissa()
+                        .unsigned_abs()
+                        .checked_ilog10()
+                        .map_or(1, |log| log + 1),
+                ),
+                scale: Some(d.scale() as i32),
+            },
+            #[cfg(feature = "bigdecimal")]
+            SqlValue::BigDecimal(ref b, _) => ValueType::Number {
+                precision: Some(b.digits() as u32),
+                scale: Some(b.as_bigint_and_exponent().1 as i32),
+            },
+        }
+    }
+
+    /// Returns the type of the column or output parameter a null was fetched from, or `None` if
+    /// the value isn't a null or the type isn't known.
+    ///
+    /// `.sql_type` gives `ValueType::Null` for every null, this gives the type a generic
+    /// serializer needs to write a null of the right type, for instance when a schema is built
+    /// from the first row of a result set. The type is the column's as declared, so a column
+    /// fetched differently, such as a `NUMBER` fetched as text with `.set_numbers_as_text`, still
+    /// gives `ValueType::Number`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use oci_rs::connection::Connection;
+    /// use oci_rs::types::ValueType;
+    ///
+    /// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+    /// let mut select = conn
+    ///     .create_prepared_statement("SELECT CAST(NULL AS DATE) FROM dual")
+    ///     .unwrap();
+    /// select.execute().unwrap();
+    /// let results = select.result_set().unwrap();
+    /// let value = &results[0][0];
+    /// assert_eq!(value.sql_type(), ValueType::Null);
+    /// assert_eq!(value.null_type(), Some(ValueType::Date));
+    /// ```
+    ///
+    pub fn null_type(&self) -> Option<ValueType> {
+        match *self {
+            SqlValue::Null(null_type) => null_type,
+            _ => None,
+        }
+    }
+
+    /// The name of the SQL type the value is held as, for error messages.
+    ///
+    pub(crate) fn sql_type_name(&self) -> &'static str {
+        match *self {
+            SqlValue::VarChar(..) => "VARCHAR2",
+            SqlValue::Char(..) => "CHAR",
+            SqlValue::Integer(..) | SqlValue::Float(..) => "NUMBER",
+            SqlValue::Null(..) => "NULL",
+            SqlValue::Date(..) => "DATE",
+            SqlValue::Timestamp(..) => "TIMESTAMP",
+            SqlValue::TimestampTz(..) | SqlValue::TimestampTzRegion(..) => {
+                "TIMESTAMP WITH TIME ZONE"
+            }
+            SqlValue::Blob(..) => "BLOB",
+            SqlValue::BFile(..) => "BFILE",
+            SqlValue::RowId(..) => "ROWID",
+            SqlValue::Collection(..) => "COLLECTION",
+            #[cfg(feature = "decimal")]
+            SqlValue::Decimal(..) => "NUMBER",
+            #[cfg(feature = "bigdecimal")]
+            SqlValue::BigDecimal(..) => "NUMBER",
+        }
+    }
+
     /// Returns a pointer to the internal value that can be used by OCI.
     ///
     pub(crate) fn as_oci_ptr(&mut self) -> *mut c_void {
         match *self {
             SqlValue::VarChar(ref s) | SqlValue::Char(ref s) => s.as_ptr() as *mut c_void,
+            SqlValue::RowId(ref r) => r.as_str().as_ptr() as *mut c_void,
             SqlValue::Integer(ref mut i) => (i as *mut i64) as *mut c_void,
             SqlValue::Float(ref mut f) => (f as *mut f64) as *mut c_void,
-            SqlValue::Null => panic!("Null not handled"),
+            SqlValue::Null(..) | SqlValue::BFile(..) | SqlValue::Collection(..) => ptr::null_mut(),
             SqlValue::Date(_, ref b) => b.as_ptr() as *mut c_void,
             SqlValue::Timestamp(_, ref b) => b.as_ptr() as *mut c_void,
-            SqlValue::TimestampTz(_, ref b) => b.as_ptr() as *mut c_void,
+            SqlValue::TimestampTz(_, ref b) | SqlValue::TimestampTzRegion(_, _, ref b) => {
+                b.as_ptr() as *mut c_void
+            }
             SqlValue::Blob(ref b) => b.as_ptr() as *mut c_void,
+            #[cfg(feature = "decimal")]
+            SqlValue::Decimal(_, ref b) => b.as_ptr() as *mut c_void,
+            #[cfg(feature = "bigdecimal")]
+            SqlValue::BigDecimal(_, ref b) => b.as_ptr() as *mut c_void,
+        }
+    }
+
+    /// Gives the size in bytes of the internal value.
+    ///
+    /// It is used by the OCI library to allocate storage. Byte size values
+    /// are hard coded here on purpose as a confirmation of OCI spec.
+    ///
+    pub(crate) fn size(&self) -> c_int {
+        match *self {
+            SqlValue::VarChar(ref s) | SqlValue::Char(ref s) => s.capacity() as c_int,
+            SqlValue::RowId(ref r) => r.as_str().len() as c_int,
+            SqlValue::Integer(..) | SqlValue::Float(..) => 8 as c_int,
+            SqlValue::Null(..) | SqlValue::BFile(..) | SqlValue::Collection(..) => 0 as c_int,
+            SqlValue::Date(_, ref b) => b.len() as c_int,
+            SqlValue::Blob(ref b) => b.len() as c_int,
+            SqlValue::Timestamp(_, ref b) => b.len() as c_int,
+            SqlValue::TimestampTz(_, ref b) | SqlValue::TimestampTzRegion(_, _, ref b) => {
+                b.len() as c_int
+            }
+            #[cfg(feature = "decimal")]
+            SqlValue::Decimal(_, ref b) => b.len() as c_int,
+            #[cfg(feature = "bigdecimal")]
+            SqlValue::BigDecimal(_, ref b) => b.len() as c_int,
+        }
+    }
+
+    /// Gives an approximation of the number of bytes the value takes up, including null values.
+    ///
+    pub(crate) fn approximate_size(&self) -> usize {
+        match *self {
+            SqlValue::Null(..) => 1,
+            SqlValue::BFile(ref b) => b.directory().len() + b.file_name().len(),
+            SqlValue::Collection(ref c) => {
+                c.elements().iter().map(SqlValue::approximate_size).sum()
+            }
+            _ => self.size() as usize,
+        }
+    }
+
+    /// Gives the bytes of the internal value in the form OCI expects for the data type given by
+    /// `.as_oci_data_type`. Unlike `.as_oci_ptr` this is a copy, for building arrays of values.
+    ///
+    pub(crate) fn to_oci_bytes(&self) -> Vec<u8> {
+        match *self {
+            SqlValue::VarChar(ref s) | SqlValue::Char(ref s) => s.as_bytes().to_vec(),
+            SqlValue::RowId(ref r) => r.as_str().as_bytes().to_vec(),
+            SqlValue::Integer(i) => i.to_ne_bytes().to_vec(),
+            SqlValue::Float(f) => f.to_ne_bytes().to_vec(),
+            SqlValue::Null(..) | SqlValue::BFile(..) | SqlValue::Collection(..) => Vec::new(),
+            SqlValue::Date(_, ref b) => b.to_vec(),
+            SqlValue::Timestamp(_, ref b) => b.to_vec(),
+            SqlValue::TimestampTz(_, ref b) | SqlValue::TimestampTzRegion(_, _, ref b) => {
+                b.to_vec()
+            }
+            SqlValue::Blob(ref b) => b.clone(),
+            #[cfg(feature = "decimal")]
+            SqlValue::Decimal(_, ref b) => b.clone(),
+            #[cfg(feature = "bigdecimal")]
+            SqlValue::BigDecimal(_, ref b) => b.clone(),
+        }
+    }
+
+    /// Gives the indicator variable that tells OCI whether a bound value is null.
+    ///
+    pub(crate) fn indicator(&self) -> c_short {
+        match *self {
+            SqlValue::Null(..) | SqlValue::BFile(..) => -1,
+            _ => 0,
+        }
+    }
+
+    /// Converts to the relevant OCI internal type.
+    ///
+    /// A null is bound as a `VARCHAR`, which Oracle will convert to whatever type the column has.
+    /// A bfile can't be bound, so it is bound as a null.
+    ///
+    /// Bytes are bound as a `RAW` if they fit in one, so they can be compared with a `RAW`
+    /// column, and as a `LONG RAW` otherwise, which can still be inserted into a `BLOB`.
+    ///
+    /// A decimal or big decimal is bound in Oracle's own `NUMBER` format, so no precision is
+    /// lost.
+    ///
+    /// A float that is NaN or infinite is bound as a `BINARY_DOUBLE`, the only type that can
+    /// hold it. Oracle raises an error if it has to be converted to a `NUMBER`.
+    ///
+    /// A row ID is bound as its text, which Oracle converts back when it is compared with a
+    /// `ROWID`.
+    ///
+    /// A collection is bound as a named type, the collection object itself is bound
+    /// separately once its type is known.
+    ///
+    /// Date is converted into characters before sending into OCI
+    /// this avoids having to convert a rust date object into the Oracle
+    /// seven byte date format.
+    ///
+    pub(crate) fn as_oci_data_type(&self) -> OciDataType {
+        match *self {
+            SqlValue::VarChar(..) => OciDataType::SqlVarChar,
+            SqlValue::Char(..) => OciDataType::SqlChar,
+            SqlValue::Integer(..) => OciDataType::SqlInt,
+            SqlValue::Float(f) if !f.is_finite() => OciDataType::SqlBinaryDouble,
+            SqlValue::Float(..) => OciDataType::SqlFloat,
+            SqlValue::Null(..) | SqlValue::BFile(..) | SqlValue::RowId(..) => {
+                OciDataType::SqlVarChar
+            }
+            SqlValue::Date(..) => OciDataType::SqlDate,
+            SqlValue::Timestamp(..) => OciDataType::SqlTimestamp,
+            SqlValue::TimestampTz(..) | SqlValue::TimestampTzRegion(..) => {
+                OciDataType::SqlTimestampTz
+            }
+            SqlValue::Blob(ref b) if b.len() <= RAW_MAX_LENGTH => OciDataType::SqlRaw,
+            SqlValue::Blob(..) => OciDataType::SqlBlob,
+            SqlValue::Collection(..) => OciDataType::SqlNamedType,
+            #[cfg(feature = "decimal")]
+            SqlValue::Decimal(..) => OciDataType::SqlNum,
+            #[cfg(feature = "bigdecimal")]
+            SqlValue::BigDecimal(..) => OciDataType::SqlNum,
+        }
+    }
+}
+
+/// The SQL data types that can be bound, for binding a typed null with
+/// [`Statement.bind_null`][1] or an output with [`Statement.bind_out`][2].
+///
+/// [1]: ../statement/struct.Statement.html#method.bind_null
+/// [2]: ../statement/struct.Statement.html#method.bind_out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqlType {
+    /// `VARCHAR2`
+    VarChar,
+    /// `CHAR`
+    Char,
+    /// `INTEGER`, or a `NUMBER` with no decimal places
+    Integer,
+    /// `FLOAT`, `BINARY_DOUBLE` or a `NUMBER` with decimal places
+    Float,
+    /// `DATE`
+    Date,
+    /// `TIMESTAMP`
+    Timestamp,
+    /// `TIMESTAMP WITH TIME ZONE`
+    TimestampTz,
+    /// `BLOB`, `RAW` or `LONG RAW`
+    Blob,
+    /// A `NUMBER` with decimal places, kept exactly. It needs the `decimal` feature.
+    #[cfg(feature = "decimal")]
+    Decimal,
+    /// A `NUMBER` of any precision, kept exactly. It needs the `bigdecimal` feature.
+    #[cfg(feature = "bigdecimal")]
+    BigDecimal,
+}
+impl SqlType {
+    /// Returns the type a value is bound as. A null, or a bfile, is bound as a `VARCHAR`.
+    ///
+    /// A collection can't be bound to a buffer of its own, so it is given as a `VARCHAR`
+    /// and rejected when it is bound as an output.
+    ///
+    pub(crate) fn of(value: &SqlValue) -> SqlType {
+        match *value {
+            SqlValue::VarChar(..)
+            | SqlValue::Null(..)
+            | SqlValue::BFile(..)
+            | SqlValue::RowId(..)
+            | SqlValue::Collection(..) => SqlType::VarChar,
+            SqlValue::Char(..) => SqlType::Char,
+            SqlValue::Integer(..) => SqlType::Integer,
+            SqlValue::Float(..) => SqlType::Float,
+            SqlValue::Date(..) => SqlType::Date,
+            SqlValue::Timestamp(..) => SqlType::Timestamp,
+            SqlValue::TimestampTz(..) | SqlValue::TimestampTzRegion(..) => SqlType::TimestampTz,
+            SqlValue::Blob(..) => SqlType::Blob,
+            #[cfg(feature = "decimal")]
+            SqlValue::Decimal(..) => SqlType::Decimal,
+            #[cfg(feature = "bigdecimal")]
+            SqlValue::BigDecimal(..) => SqlType::BigDecimal,
+        }
+    }
+
+    /// The kind of value an output parameter of this type receives, given to a null it returns.
+    ///
+    pub(crate) fn value_type(self) -> ValueType {
+        match self {
+            SqlType::VarChar => ValueType::VarChar,
+            SqlType::Char => ValueType::Char,
+            SqlType::Integer => ValueType::Number {
+                precision: None,
+                scale: Some(0),
+            },
+            SqlType::Float => ValueType::Number {
+                precision: None,
+                scale: None,
+            },
+            SqlType::Date => ValueType::Date,
+            SqlType::Timestamp => ValueType::Timestamp,
+            SqlType::TimestampTz => ValueType::TimestampTz,
+            SqlType::Blob => ValueType::Blob,
+            #[cfg(feature = "decimal")]
+            SqlType::Decimal => ValueType::Number {
+                precision: None,
+                scale: None,
+            },
+            #[cfg(feature = "bigdecimal")]
+            SqlType::BigDecimal => ValueType::Number {
+                precision: None,
+                scale: None,
+            },
+        }
+    }
+
+    /// The size in bytes of the buffer needed to receive a value of this type. Variable length
+    /// types get room for the largest value PL/SQL allows.
+    ///
+    pub(crate) fn buffer_size(self) -> usize {
+        match self {
+            SqlType::VarChar | SqlType::Char | SqlType::Blob => PLSQL_MAX_LENGTH,
+            #[cfg(feature = "decimal")]
+            SqlType::Decimal => ORACLE_NUMBER_SIZE,
+            #[cfg(feature = "bigdecimal")]
+            SqlType::BigDecimal => ORACLE_NUMBER_SIZE,
+            _ => self.as_oci_data_type().size() as usize,
+        }
+    }
+
+    /// Converts a value received into a buffer, as the OCI type given by `.as_oci_data_type`,
+    /// into a `SqlValue`.
+    ///
+    pub(crate) fn decode(self, context: &DecodeContext, data: &[u8]) -> Result<SqlValue, OciError> {
+        match self {
+            SqlType::VarChar | SqlType::Char => {
+                let s = match String::from_utf8(Vec::from(data)) {
+                    Ok(s) => s,
+                    Err(err) => return Err(OciError::Conversion(Box::new(err))),
+                };
+                if let SqlType::Char = self {
+                    Ok(SqlValue::Char(s))
+                } else {
+                    Ok(SqlValue::VarChar(s))
+                }
+            }
+            SqlType::Integer => NumberCodec.decode(SQLT_INT, data),
+            SqlType::Float => NumberCodec.decode(SQLT_FLT, data),
+            SqlType::Date => DateCodec.decode(SQLT_DAT, data),
+            SqlType::Timestamp => TimestampCodec.decode(SQLT_TIMESTAMP_INTERNAL, data),
+            SqlType::TimestampTz => {
+                TimestampCodec.decode_in(context, SQLT_TIMESTAMP_TZ_INTERNAL, data)
+            }
+            SqlType::Blob => Ok(SqlValue::Blob(data.to_vec())),
+            #[cfg(feature = "decimal")]
+            SqlType::Decimal => Ok(SqlValue::Decimal(
+                create_decimal_from_raw(data)?,
+                data.to_vec(),
+            )),
+            #[cfg(feature = "bigdecimal")]
+            SqlType::BigDecimal => Ok(SqlValue::BigDecimal(
+                create_bigdecimal_from_raw(data)?,
+                data.to_vec(),
+            )),
+        }
+    }
+
+    /// Converts to the OCI type a value of this type is bound as, the same as the matching
+    /// `SqlValue` variant.
+    ///
+    pub(crate) fn as_oci_data_type(self) -> OciDataType {
+        match self {
+            SqlType::VarChar => OciDataType::SqlVarChar,
+            SqlType::Char => OciDataType::SqlChar,
+            SqlType::Integer => OciDataType::SqlInt,
+            SqlType::Float => OciDataType::SqlFloat,
+            SqlType::Date => OciDataType::SqlDate,
+            SqlType::Timestamp => OciDataType::SqlTimestamp,
+            SqlType::TimestampTz => OciDataType::SqlTimestampTz,
+            SqlType::Blob => OciDataType::SqlBlob,
+            #[cfg(feature = "decimal")]
+            SqlType::Decimal => OciDataType::SqlNum,
+            #[cfg(feature = "bigdecimal")]
+            SqlType::BigDecimal => OciDataType::SqlNum,
+        }
+    }
+}
+
+/// The kind of value a `SqlValue` holds, as returned by [`SqlValue.sql_type`][1].
+///
+/// Unlike `SqlValue` its variants don't depend on the features enabled, a decimal or big
+/// decimal is a `Number` the same as an integer or a float is. It displays as the SQL type,
+/// e.g. `NUMBER(10, 2)`.
+///
+/// [1]: enum.SqlValue.html#method.sql_type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueType {
+    /// `VARCHAR2`, or other text such as a `CLOB` or a `NUMBER` too big for an `i64`
+    VarChar,
+    /// `CHAR`
+    Char,
+    /// `NUMBER`, with its number of significant digits and decimal places when the value is
+    /// held exactly, i.e. when it isn't a float
+    Number {
+        /// The number of significant digits
+        precision: Option<u32>,
+        /// The number of decimal places, negative if the value is rounded to tens or more
+        scale: Option<i32>,
+    },
+    /// `DATE`
+    Date,
+    /// `TIMESTAMP`
+    Timestamp,
+    /// `TIMESTAMP WITH TIME ZONE`, whether it has an offset or a region
+    TimestampTz,
+    /// `BLOB`, `RAW` or `LONG RAW`
+    Blob,
+    /// `BFILE`
+    BFile,
+    /// `ROWID` or `UROWID`
+    RowId,
+    /// `VARRAY` or nested table, with the type of its elements
+    Collection(ElementType),
+    /// A null of any type
+    Null,
+}
+
+impl fmt::Display for ValueType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ValueType::VarChar => write!(f, "VARCHAR2"),
+            ValueType::Char => write!(f, "CHAR"),
+            ValueType::Number {
+                precision: Some(precision),
+                scale: Some(scale),
+            } => write!(f, "NUMBER({}, {})", precision, scale),
+            ValueType::Number {
+                precision: Some(precision),
+                scale: None,
+            } => write!(f, "NUMBER({})", precision),
+            ValueType::Number {
+                precision: None,
+                scale: Some(scale),
+            } => write!(f, "NUMBER(*, {})", scale),
+            ValueType::Number {
+                precision: None,
+                scale: None,
+            } => write!(f, "NUMBER"),
+            ValueType::Date => write!(f, "DATE"),
+            ValueType::Timestamp => write!(f, "TIMESTAMP"),
+            ValueType::TimestampTz => write!(f, "TIMESTAMP WITH TIME ZONE"),
+            ValueType::Blob => write!(f, "BLOB"),
+            ValueType::BFile => write!(f, "BFILE"),
+            ValueType::RowId => write!(f, "ROWID"),
+            ValueType::Collection(element_type) => write!(f, "COLLECTION OF {}", element_type),
+            ValueType::Null => write!(f, "NULL"),
+        }
+    }
+}
+
+impl From<ElementType> for ValueType {
+    fn from(element_type: ElementType) -> Self {
+        match element_type {
+            ElementType::Number => ValueType::Number {
+                precision: None,
+                scale: None,
+            },
+            ElementType::VarChar => ValueType::VarChar,
+            ElementType::Char => ValueType::Char,
+            ElementType::Date => ValueType::Date,
+        }
+    }
+}
+
+/// Splits the text of a decimal number, such as `-1.50` or `1E+40`, into its sign, its
+/// significant digits and the power of ten the digits are multiplied by, without leading or
+/// trailing zeros, so that two texts of the same number give the same parts. Zero has no digits.
+/// Returns `None` if the text isn't a decimal number.
+///
+fn decimal_parts(text: &str) -> Option<(bool, String, i64)> {
+    let (negative, unsigned) = match text.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+        Some(e) => (&unsigned[..e], unsigned[e + 1..].parse::<i64>().ok()?),
+        None => (unsigned, 0),
+    };
+    let (whole, fraction) = match mantissa.find('.') {
+        Some(point) => (&mantissa[..point], &mantissa[point + 1..]),
+        None => (mantissa, ""),
+    };
+    if whole.is_empty() && fraction.is_empty()
+        || !whole
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    let digits = format!("{}{}", whole, fraction);
+    let digits = digits.trim_start_matches('0');
+    if digits.is_empty() {
+        return Some((false, String::new(), 0));
+    }
+    let significant = digits.trim_end_matches('0');
+    let trailing_zeros = (digits.len() - significant.len()) as i64;
+    let exponent = exponent
+        .checked_sub(fraction.len() as i64)?
+        .checked_add(trailing_zeros)?;
+    Some((negative, significant.to_string(), exponent))
+}
+
+/// How the white space around text fetched from a `VARCHAR2` or `CHAR` column are treated.
+///
+/// By default a `VARCHAR2` is trimmed at both ends and a `CHAR` keeps the padding Oracle stores
+/// it with, which suits most data but loses spaces that were put in a `VARCHAR2` on purpose. Either can be
+/// changed for a connection with [`Connection.set_varchar_trim`][1] and
+/// [`Connection.set_char_trim`][2], or for a statement with the methods of the same names.
+///
+/// [1]: ../connection/struct.Connection.html#method.set_varchar_trim
+/// [2]: ../connection/struct.Connection.html#method.set_char_trim
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextTrim {
+    /// The text is kept as it is stored.
+    Preserve,
+    /// White space at the end of the text is removed, which undoes the padding of a `CHAR`.
+    TrimEnd,
+    /// White space at both ends of the text is removed.
+    TrimBoth,
+}
+impl TextTrim {
+    /// Applies the trimming to fetched text.
+    ///
+    pub(crate) fn apply(self, text: String) -> String {
+        match self {
+            TextTrim::Preserve => text,
+            TextTrim::TrimEnd => text.trim_end().to_string(),
+            TextTrim::TrimBoth => text.trim().to_string(),
+        }
+    }
+}
+
+/// The longest `RAW` that SQL allows, without extended data types.
+const RAW_MAX_LENGTH: usize = 2000;
+
+/// The longest `VARCHAR2` or `RAW` that PL/SQL allows.
+const PLSQL_MAX_LENGTH: usize = 32767;
+
+/// Allows conversion into a `SqlValue`.
+///
+pub trait ToSqlValue {
+    /// Converts into a `SqlValue`.
+    ///
+    fn to_sql_value(&self) -> SqlValue;
+}
+
+impl ToSqlValue for SqlValue {
+    fn to_sql_value(&self) -> SqlValue {
+        self.clone()
+    }
+}
+
+/// `None` is bound as a SQL `NULL`.
+///
+impl<T: ToSqlValue> ToSqlValue for Option<T> {
+    fn to_sql_value(&self) -> SqlValue {
+        match *self {
+            Some(ref value) => value.to_sql_value(),
+            None => SqlValue::Null(None),
         }
     }
+}
+
+/// `None` is bound as a SQL `NULL`, so a borrowed field can be bound without cloning it.
+///
+impl<T: ToSqlValue> ToSqlValue for &Option<T> {
+    fn to_sql_value(&self) -> SqlValue {
+        (*self).to_sql_value()
+    }
+}
+
+impl ToSqlValue for String {
+    fn to_sql_value(&self) -> SqlValue {
+        SqlValue::VarChar(self.clone())
+    }
+}
+
+impl<'a> ToSqlValue for &'a str {
+    fn to_sql_value(&self) -> SqlValue {
+        let s = String::from(*self);
+        SqlValue::VarChar(s)
+    }
+}
+
+impl ToSqlValue for &String {
+    fn to_sql_value(&self) -> SqlValue {
+        SqlValue::VarChar((*self).clone())
+    }
+}
+
+impl ToSqlValue for Cow<'_, str> {
+    fn to_sql_value(&self) -> SqlValue {
+        SqlValue::VarChar(self.to_string())
+    }
+}
+
+impl ToSqlValue for Box<str> {
+    fn to_sql_value(&self) -> SqlValue {
+        SqlValue::VarChar(self.to_string())
+    }
+}
+
+/// A `char` is bound as a one character `VARCHAR2`.
+///
+impl ToSqlValue for char {
+    fn to_sql_value(&self) -> SqlValue {
+        SqlValue::VarChar(self.to_string())
+    }
+}
+
+impl ToSqlValue for i64 {
+    fn to_sql_value(&self) -> SqlValue {
+        SqlValue::Integer(*self)
+    }
+}
+
+impl ToSqlValue for f64 {
+    fn to_sql_value(&self) -> SqlValue {
+        SqlValue::Float(*self)
+    }
+}
+
+macro_rules! small_integer_to_sql_value {
+    ($($integer:ty),+) => {
+        $(
+            impl ToSqlValue for $integer {
+                fn to_sql_value(&self) -> SqlValue {
+                    SqlValue::Integer(i64::from(*self))
+                }
+            }
+        )+
+    };
+}
+
+small_integer_to_sql_value!(i8, i16, i32, u32);
+
+/// Values too big for an `i64` are bound as text, which Oracle converts to a `NUMBER`.
+///
+impl ToSqlValue for u64 {
+    fn to_sql_value(&self) -> SqlValue {
+        match i64::try_from(*self) {
+            Ok(i) => SqlValue::Integer(i),
+            Err(_) => SqlValue::VarChar(self.to_string()),
+        }
+    }
+}
+
+/// Values too big for an `i64` are bound as text, which Oracle converts to a `NUMBER`. A
+/// `NUMBER` holds 38 digits, so only the very largest `i128` values, with 39 digits, lose
+/// precision.
+///
+impl ToSqlValue for i128 {
+    fn to_sql_value(&self) -> SqlValue {
+        match i64::try_from(*self) {
+            Ok(i) => SqlValue::Integer(i),
+            Err(_) => SqlValue::VarChar(self.to_string()),
+        }
+    }
+}
+
+impl ToSqlValue for f32 {
+    fn to_sql_value(&self) -> SqlValue {
+        SqlValue::Float(f64::from(*self))
+    }
+}
+
+/// Bound as `1` or `0`, for a `NUMBER(1)` flag. Use [`BoolEncoding`][1] to bind another
+/// encoding.
+///
+/// [1]: enum.BoolEncoding.html
+impl ToSqlValue for bool {
+    fn to_sql_value(&self) -> SqlValue {
+        BoolEncoding::Number.encode(*self)
+    }
+}
+
+/// Bound as its JSON text, which Oracle parses when it is inserted into a `JSON` column.
+///
+#[cfg(feature = "json")]
+impl ToSqlValue for serde_json::Value {
+    fn to_sql_value(&self) -> SqlValue {
+        SqlValue::VarChar(self.to_string())
+    }
+}
+
+/// Bound as its 16 bytes, as a `RAW`, to be stored in a `RAW(16)` column.
+///
+#[cfg(feature = "uuid")]
+impl ToSqlValue for uuid::Uuid {
+    fn to_sql_value(&self) -> SqlValue {
+        SqlValue::Blob(self.as_bytes().to_vec())
+    }
+}
+
+impl ToSqlValue for RowId {
+    fn to_sql_value(&self) -> SqlValue {
+        SqlValue::RowId(self.clone())
+    }
+}
+
+/// Bytes are bound as a `RAW`, or as a `LONG RAW` when they are longer than a `RAW` can be,
+/// and can be inserted into `RAW`, `LONG RAW` and `BLOB` columns.
+///
+impl ToSqlValue for &[u8] {
+    fn to_sql_value(&self) -> SqlValue {
+        SqlValue::Blob(self.to_vec())
+    }
+}
+
+/// Bound in the same way as `&[u8]`.
+///
+impl ToSqlValue for Vec<u8> {
+    fn to_sql_value(&self) -> SqlValue {
+        SqlValue::Blob(self.clone())
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl ToSqlValue for Decimal {
+    fn to_sql_value(&self) -> SqlValue {
+        SqlValue::Decimal(*self, create_raw_from_decimal(self))
+    }
+}
+
+/// Rounded to the 38 significant digits that a `NUMBER` holds.
+///
+#[cfg(feature = "bigdecimal")]
+impl ToSqlValue for BigDecimal {
+    fn to_sql_value(&self) -> SqlValue {
+        SqlValue::BigDecimal(self.clone(), create_raw_from_bigdecimal(self))
+    }
+}
+
+impl ToSqlValue for Date<Utc> {
+    fn to_sql_value(&self) -> SqlValue {
+        SqlValue::Date(*self, create_raw_from_date(*self))
+    }
+}
+
+impl ToSqlValue for DateTime<Utc> {
+    fn to_sql_value(&self) -> SqlValue {
+        SqlValue::Timestamp(*self, create_raw_from_datetime(self))
+    }
+}
+
+impl ToSqlValue for DateTime<FixedOffset> {
+    fn to_sql_value(&self) -> SqlValue {
+        SqlValue::TimestampTz(*self, create_raw_from_datetime_with_timezone(self))
+    }
+}
+
+/// Bound with the offset from UTC in effect at the time, as Oracle's IDs for regions are only
+/// known to its time zone file. A `TIMESTAMP WITH TIME ZONE` column keeps the offset rather
+/// than the region.
+///
+#[cfg(feature = "chrono-tz")]
+impl ToSqlValue for DateTime<Tz> {
+    fn to_sql_value(&self) -> SqlValue {
+        self.fixed_offset().to_sql_value()
+    }
+}
+
+#[cfg(feature = "time")]
+impl ToSqlValue for time::Date {
+    fn to_sql_value(&self) -> SqlValue {
+        create_date_value_from_raw(&create_raw_from_time_date(*self))
+    }
+}
+
+/// Bound as a `TIMESTAMP`, the value is taken to be in UTC in the same way as a
+/// `DateTime<Utc>`.
+///
+#[cfg(feature = "time")]
+impl ToSqlValue for PrimitiveDateTime {
+    fn to_sql_value(&self) -> SqlValue {
+        let raw = create_raw_from_primitive_datetime(self);
+        SqlValue::Timestamp(create_datetime_from_raw(&raw), raw)
+    }
+}
+
+#[cfg(feature = "time")]
+impl ToSqlValue for OffsetDateTime {
+    fn to_sql_value(&self) -> SqlValue {
+        let raw = create_raw_from_offset_datetime(self);
+        SqlValue::TimestampTz(create_datetime_with_timezone_from_raw(&raw), raw)
+    }
+}
+
+/// A set of parameters for one execution of a statement, such as a tuple of values.
+///
+/// It is implemented for tuples of up to twelve values that implement `ToSqlValue`, and for
+/// vectors and slices of such values when the number of parameters is only known at run time.
+///
+pub trait ToSqlParams {
+    /// Converts each of the parameters into a `SqlValue`, in order.
+    ///
+    fn to_sql_values(&self) -> Vec<SqlValue>;
+}
+
+impl<T: ToSqlValue> ToSqlParams for Vec<T> {
+    fn to_sql_values(&self) -> Vec<SqlValue> {
+        self.iter().map(ToSqlValue::to_sql_value).collect()
+    }
+}
+
+impl<T: ToSqlValue> ToSqlParams for &[T] {
+    fn to_sql_values(&self) -> Vec<SqlValue> {
+        self.iter().map(ToSqlValue::to_sql_value).collect()
+    }
+}
+
+/// Parameters that are bound by name, typically the fields of a struct, for use with
+/// [`Statement.bind_params`][1].
+///
+/// With the `derive` feature the implementation can be generated with `#[derive(ToParams)]`
+/// for a struct with named fields. Each field is bound to the placeholder of the same name,
+/// which is matched without regard to case. A field's `#[oci(rename = "id")]` attribute binds
+/// it to another placeholder and `#[oci(skip)]` leaves it out.
+///
+/// # Examples
+///
+/// ```rust
+/// use oci_rs::types::{SqlValue, ToParams, ToSqlValue};
+///
+/// struct Dog {
+///     id: i64,
+///     name: String,
+/// }
+///
+/// impl ToParams for Dog {
+///     fn to_params(&self) -> Vec<(String, SqlValue)> {
+///         vec![
+///             ("id".to_string(), self.id.to_sql_value()),
+///             ("name".to_string(), self.name.to_sql_value()),
+///         ]
+///     }
+/// }
+/// ```
+///
+/// [1]: ../statement/struct.Statement.html#method.bind_params
+pub trait ToParams {
+    /// Returns the name of each parameter's placeholder, without the leading colon, and its
+    /// value.
+    ///
+    fn to_params(&self) -> Vec<(String, SqlValue)>;
+}
+
+#[cfg(feature = "derive")]
+pub use oci_rs_derive::ToParams;
+
+macro_rules! tuple_to_sql_params {
+    ($($name:ident: $index:tt),+) => {
+        impl<$($name: ToSqlValue),+> ToSqlParams for ($($name,)+) {
+            fn to_sql_values(&self) -> Vec<SqlValue> {
+                vec![$(self.$index.to_sql_value()),+]
+            }
+        }
+    };
+}
+
+tuple_to_sql_params!(A: 0);
+tuple_to_sql_params!(A: 0, B: 1);
+tuple_to_sql_params!(A: 0, B: 1, C: 2);
+tuple_to_sql_params!(A: 0, B: 1, C: 2, D: 3);
+tuple_to_sql_params!(A: 0, B: 1, C: 2, D: 3, E: 4);
+tuple_to_sql_params!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
+tuple_to_sql_params!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6);
+tuple_to_sql_params!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7);
+tuple_to_sql_params!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8);
+tuple_to_sql_params!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8, J: 9);
+tuple_to_sql_params!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8, J: 9, K: 10);
+tuple_to_sql_params!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8, J: 9, K: 10, L: 11);
+
+/// Allows conversion from a `SqlValue`.
+///
+/// Every type in this crate that implements it, other than `Option`, also implements
+/// `TryFrom<&SqlValue>`, with a [`ConversionError`][1] as the error.
+///
+/// [1]: ../oci_error/struct.ConversionError.html
+pub trait FromSqlValue {
+    /// Allows conversion from a `SqlValue`.
+    ///
+    /// It allows for impossible conversions though the use of `Option`.
+    /// e.g. an `SqlValue::Null` cannot be converted into a i64.
+    ///
+    fn from_sql_value(sql_value: &SqlValue) -> Option<Self>
+    where
+        Self: Sized;
+
+    /// Converts from a `SqlValue`, with an error saying what couldn't be converted rather than
+    /// a `None`.
+    ///
+    fn try_from_sql_value(sql_value: &SqlValue) -> Result<Self, ConversionError>
+    where
+        Self: Sized,
+    {
+        Self::from_sql_value(sql_value)
+            .ok_or_else(|| ConversionError::new(sql_value, std::any::type_name::<Self>()))
+    }
+}
+
+/// Implements `TryFrom<&SqlValue>` for types that implement `FromSqlValue`.
+macro_rules! try_from_sql_value {
+    ($($(#[$attr:meta])* $target:ty),* $(,)*) => {
+        $(
+            $(#[$attr])*
+            impl<'a> TryFrom<&'a SqlValue> for $target {
+                type Error = ConversionError;
+
+                fn try_from(sql_value: &'a SqlValue) -> Result<Self, Self::Error> {
+                    <$target as FromSqlValue>::try_from_sql_value(sql_value)
+                }
+            }
+        )*
+    };
+}
+
+try_from_sql_value!(
+    String,
+    i64,
+    i32,
+    i16,
+    i8,
+    u32,
+    u64,
+    i128,
+    f64,
+    f32,
+    bool,
+    Vec<u8>,
+    RowId,
+    BFile,
+    Collection,
+    Scn,
+    #[allow(deprecated)]
+    Date<Utc>,
+    DateTime<Utc>,
+    DateTime<FixedOffset>,
+    #[cfg(feature = "chrono-tz")]
+    DateTime<Tz>,
+    #[cfg(feature = "time")]
+    time::Date,
+    #[cfg(feature = "time")]
+    PrimitiveDateTime,
+    #[cfg(feature = "time")]
+    OffsetDateTime,
+    #[cfg(feature = "json")]
+    serde_json::Value,
+    #[cfg(feature = "uuid")]
+    uuid::Uuid,
+    #[cfg(feature = "decimal")]
+    Decimal,
+    #[cfg(feature = "bigdecimal")]
+    BigDecimal,
+);
+
+/// A `NULL` is converted to `None`, which lets a nullable column be read without losing the
+/// difference between a `NULL` and a value that can't be converted.
+///
+impl<T: FromSqlValue> FromSqlValue for Option<T> {
+    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
+        match *sql_value {
+            SqlValue::Null(..) => Some(None),
+            _ => T::from_sql_value(sql_value).map(Some),
+        }
+    }
+}
+
+impl FromSqlValue for String {
+    // Converts from a `SqlValue` into a `String`
+    //
+    // Worth noting that this is intended to convert types into a
+    // `String` representation of the value. It does not convert null,
+    // previous versions of this library did convert null to Some("null")
+    // but that turned out to be annoying as it required furhter conversion to None
+    // if needed.
+    //
+    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
+        match *sql_value {
+            SqlValue::VarChar(ref s) | SqlValue::Char(ref s) => Some(s.to_string()),
+            SqlValue::RowId(ref r) => Some(r.as_str().to_string()),
+            SqlValue::Integer(i) => Some(format!("{}", i)),
+            SqlValue::Float(f) => Some(format!("{}", f)),
+            SqlValue::Date(ref d, _) => Some(format!("{}", d)),
+            SqlValue::Timestamp(ref d, _) => Some(format!("{}", d)),
+            SqlValue::TimestampTz(ref d, _) => Some(format!("{}", d)),
+            SqlValue::TimestampTzRegion(ref d, ref region, _) => {
+                Some(format!("{} {}", d.naive_local(), region))
+            }
+            #[cfg(feature = "bigdecimal")]
+            SqlValue::BigDecimal(ref d, _) => Some(format!("{}", d)),
+            _ => None,
+        }
+    }
+}
+
+/// Floats convert only if they are whole numbers that fit.
+///
+impl FromSqlValue for i64 {
+    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
+        match *sql_value {
+            SqlValue::Integer(i) => Some(i),
+            SqlValue::Float(f)
+                if f.fract() == 0.0 && f >= i64::MIN as f64 && f < i64::MAX as f64 =>
+            {
+                Some(f as i64)
+            }
+            #[cfg(feature = "bigdecimal")]
+            SqlValue::BigDecimal(ref d, _) if d.is_integer() => d.to_i64(),
+            _ => None,
+        }
+    }
+}
+
+/// Integers convert as closely as a float allows.
+///
+impl FromSqlValue for f64 {
+    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
+        match *sql_value {
+            SqlValue::Float(f) => Some(f),
+            SqlValue::Integer(i) => Some(i as f64),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! small_integer_from_sql_value {
+    ($($integer:ty),+) => {
+        $(
+            /// Converts in the same way as `i64`, as long as the value is in range.
+            ///
+            impl FromSqlValue for $integer {
+                fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
+                    i64::from_sql_value(sql_value).and_then(|i| <$integer>::try_from(i).ok())
+                }
+            }
+        )+
+    };
+}
+
+small_integer_from_sql_value!(i8, i16, i32, u32);
+
+/// Converts in the same way as `i64`, as long as the value isn't negative. A `NUMBER` too big
+/// for an `i64` is fetched as text, which is parsed.
+///
+impl FromSqlValue for u64 {
+    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
+        match *sql_value {
+            SqlValue::VarChar(ref s) => s.trim().parse().ok(),
+            #[cfg(feature = "bigdecimal")]
+            SqlValue::BigDecimal(ref d, _) if d.is_integer() => d.to_u64(),
+            _ => i64::from_sql_value(sql_value).and_then(|i| u64::try_from(i).ok()),
+        }
+    }
+}
+
+/// Converts in the same way as `i64`, with a whole `NUMBER` too big for an `i64`, which is
+/// fetched as text, parsed so that all of its 38 digits survive.
+///
+impl FromSqlValue for i128 {
+    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
+        match *sql_value {
+            SqlValue::VarChar(ref s) => s.trim().parse().ok(),
+            #[cfg(feature = "bigdecimal")]
+            SqlValue::BigDecimal(ref d, _) if d.is_integer() => d.to_i128(),
+            _ => i64::from_sql_value(sql_value).map(i128::from),
+        }
+    }
+}
+
+/// Converts in the same way as `f64`, as closely as an `f32` allows, as long as the value is in
+/// range.
+///
+impl FromSqlValue for f32 {
+    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
+        match f64::from_sql_value(sql_value) {
+            Some(f) if f.is_finite() && f.abs() > f64::from(f32::MAX) => None,
+            Some(f) => Some(f as f32),
+            None => None,
+        }
+    }
+}
+
+/// Converts from any of the encodings in [`BoolEncoding`][1], so a flag reads the same
+/// whichever way the column stores it.
+///
+/// [1]: enum.BoolEncoding.html
+impl FromSqlValue for bool {
+    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
+        BoolEncoding::Number
+            .decode(sql_value)
+            .or_else(|| BoolEncoding::YesNo.decode(sql_value))
+    }
+}
+
+/// The ways a boolean flag is commonly stored, as Oracle has no boolean column type.
+///
+/// A `bool` binds as a `Number` and reads from either encoding. To bind a `YesNo` flag, or to
+/// read a flag strictly in one encoding, use `.encode` and `.decode`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use oci_rs::connection::Connection;
+/// use oci_rs::types::BoolEncoding;
+///
+/// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+/// let mut insert = conn
+///     .create_prepared_statement("INSERT INTO Accounts(Name, Active) VALUES(:name, :active)")
+///     .unwrap();
+/// insert
+///     .bind(&[&"Dafydd", &BoolEncoding::YesNo.encode(true)])
+///     .unwrap();
+/// insert.execute().unwrap();
+/// ```
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BoolEncoding {
+    /// `1` for true and `0` for false, in a `NUMBER(1)`.
+    Number,
+    /// `'Y'` for true and `'N'` for false, in a `CHAR(1)`.
+    YesNo,
+}
+impl BoolEncoding {
+    /// Converts `flag` into a `SqlValue` in this encoding.
+    ///
+    pub fn encode(self, flag: bool) -> SqlValue {
+        match (self, flag) {
+            (BoolEncoding::Number, true) => SqlValue::Integer(1),
+            (BoolEncoding::Number, false) => SqlValue::Integer(0),
+            (BoolEncoding::YesNo, true) => SqlValue::Char("Y".to_string()),
+            (BoolEncoding::YesNo, false) => SqlValue::Char("N".to_string()),
+        }
+    }
+
+    /// Converts a `SqlValue` in this encoding into a `bool`. Any other value, including a null,
+    /// gives `None`. Text is matched ignoring case and trailing blanks.
+    ///
+    pub fn decode(self, sql_value: &SqlValue) -> Option<bool> {
+        match (self, sql_value) {
+            (BoolEncoding::Number, _) => match i64::from_sql_value(sql_value) {
+                Some(1) => Some(true),
+                Some(0) => Some(false),
+                _ => None,
+            },
+            (BoolEncoding::YesNo, &SqlValue::VarChar(ref s))
+            | (BoolEncoding::YesNo, &SqlValue::Char(ref s)) => {
+                match s.trim_end().to_uppercase().as_str() {
+                    "Y" => Some(true),
+                    "N" => Some(false),
+                    _ => None,
+                }
+            }
+            (BoolEncoding::YesNo, _) => None,
+        }
+    }
+}
+
+/// Parses text, such as a `JSON` column, which is fetched as its JSON text. Text that isn't
+/// JSON doesn't convert.
+///
+#[cfg(feature = "json")]
+impl FromSqlValue for serde_json::Value {
+    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
+        match *sql_value {
+            SqlValue::VarChar(ref s) | SqlValue::Char(ref s) => serde_json::from_str(s).ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Converts from the 16 bytes of a `RAW(16)`, or from text in any of the forms `Uuid` parses,
+/// such as the hex given by `RAWTOHEX`. Bytes of any other length don't convert.
+///
+#[cfg(feature = "uuid")]
+impl FromSqlValue for uuid::Uuid {
+    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
+        match *sql_value {
+            SqlValue::Blob(ref b) => uuid::Uuid::from_slice(b).ok(),
+            SqlValue::VarChar(ref s) | SqlValue::Char(ref s) => {
+                uuid::Uuid::parse_str(s.trim()).ok()
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Text converts as well, such as the result of `ROWIDTOCHAR` or `Statement.last_rowid`.
+///
+impl FromSqlValue for RowId {
+    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
+        match *sql_value {
+            SqlValue::RowId(ref r) => Some(r.clone()),
+            SqlValue::VarChar(ref s) => Some(RowId::new(s)),
+            _ => None,
+        }
+    }
+}
+
+impl FromSqlValue for Vec<u8> {
+    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
+        match *sql_value {
+            SqlValue::Blob(ref b) => Some(b.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Integers convert exactly, floats as closely as a decimal allows and text is parsed.
+///
+#[cfg(feature = "decimal")]
+impl FromSqlValue for Decimal {
+    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
+        match *sql_value {
+            SqlValue::Decimal(d, _) => Some(d),
+            #[cfg(feature = "bigdecimal")]
+            SqlValue::BigDecimal(_, ref raw) => create_decimal_from_raw(raw).ok(),
+            SqlValue::Integer(i) => Some(Decimal::from(i)),
+            SqlValue::Float(f) => Decimal::from_f64_retain(f),
+            SqlValue::VarChar(ref s) | SqlValue::Char(ref s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Integers and decimals convert exactly, floats as closely as their binary value allows and
+/// text is parsed.
+///
+#[cfg(feature = "bigdecimal")]
+impl FromSqlValue for BigDecimal {
+    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
+        match *sql_value {
+            SqlValue::BigDecimal(ref d, _) => Some(d.clone()),
+            #[cfg(feature = "decimal")]
+            SqlValue::Decimal(_, ref raw) => create_bigdecimal_from_raw(raw).ok(),
+            SqlValue::Integer(i) => Some(BigDecimal::from(i)),
+            SqlValue::Float(f) => BigDecimal::from_f64(f),
+            SqlValue::VarChar(ref s) | SqlValue::Char(ref s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+impl FromSqlValue for Date<Utc> {
+    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
+        match *sql_value {
+            SqlValue::Date(d, _) => Some(d),
+            _ => None,
+        }
+    }
+}
+
+impl FromSqlValue for DateTime<Utc> {
+    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
+        match *sql_value {
+            SqlValue::Timestamp(d, _) => Some(d),
+            _ => None,
+        }
+    }
+}
+
+impl FromSqlValue for DateTime<FixedOffset> {
+    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
+        match *sql_value {
+            SqlValue::TimestampTz(d, _) | SqlValue::TimestampTzRegion(d, _, _) => Some(d),
+            _ => None,
+        }
+    }
+}
+
+/// Only a timestamp in a named region converts, one with a plain offset has no region to give
+/// it.
+///
+#[cfg(feature = "chrono-tz")]
+impl FromSqlValue for DateTime<Tz> {
+    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
+        match *sql_value {
+            SqlValue::TimestampTzRegion(d, ref region, _) => {
+                region.parse::<Tz>().ok().map(|tz| d.with_timezone(&tz))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+impl FromSqlValue for time::Date {
+    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
+        match *sql_value {
+            SqlValue::Date(_, ref raw) => create_time_date_from_raw(raw),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+impl FromSqlValue for PrimitiveDateTime {
+    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
+        match *sql_value {
+            SqlValue::Timestamp(_, ref raw) => create_primitive_datetime_from_raw(raw),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+impl FromSqlValue for OffsetDateTime {
+    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
+        match *sql_value {
+            SqlValue::TimestampTz(ref d, ref raw)
+            | SqlValue::TimestampTzRegion(ref d, _, ref raw) => {
+                create_offset_datetime_from_raw(raw, d.offset().local_minus_utc())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The address of a row, as held in a `ROWID` or `UROWID` column or the `ROWID` pseudo column.
+///
+/// It is kept as the text Oracle gives it. Binding it finds the row again without going
+/// through an index, which makes it a quick way to update a row that has just been selected.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use oci_rs::connection::Connection;
+/// use oci_rs::types::RowId;
+///
+/// let conn = Connection::new("localhost:1521/xe", "oci_rs", "test").unwrap();
+/// let mut select = conn
+///     .create_prepared_statement("SELECT ROWID, Name FROM Dogs WHERE Name = 'Poodle' FOR UPDATE")
+///     .unwrap();
+/// select.execute().unwrap();
+/// let results = select.result_set().unwrap();
+/// let rowid: RowId = results[0][0].value().unwrap();
+///
+/// let mut update = conn
+///     .create_prepared_statement("UPDATE Dogs SET Name = 'Labradoodle' WHERE ROWID = :rowid")
+///     .unwrap();
+/// update.bind(&[&rowid]).unwrap();
+/// update.execute().unwrap();
+/// conn.commit().unwrap();
+/// ```
+///
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RowId(String);
+impl RowId {
+    /// Creates a `RowId` from its text, such as that returned by `Statement.last_rowid`.
+    ///
+    pub fn new(text: &str) -> RowId {
+        RowId(text.to_string())
+    }
+
+    /// Returns the text of the row ID.
+    ///
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for RowId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Describes a column of a query, for a [`TypeCodec`][1] to decide how to fetch it.
+///
+/// [1]: trait.TypeCodec.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnType {
+    type_code: u16,
+    size: u16,
+    precision: i16,
+    scale: i8,
+}
+impl ColumnType {
+    /// Creates a new `ColumnType`.
+    ///
+    pub(crate) fn new(type_code: u16, size: u16, precision: i16, scale: i8) -> ColumnType {
+        ColumnType {
+            type_code,
+            size,
+            precision,
+            scale,
+        }
+    }
+
+    /// Returns the OCI type code of the column, e.g. 1 for `VARCHAR2` or 2 for `NUMBER`.
+    ///
+    pub fn type_code(&self) -> u16 {
+        self.type_code
+    }
+
+    /// Returns the size of the column in bytes, as declared in the database.
+    ///
+    pub fn size(&self) -> u16 {
+        self.size
+    }
+
+    /// Returns the precision of a numeric column, zero if none was given.
+    ///
+    pub fn precision(&self) -> i16 {
+        self.precision
+    }
+
+    /// Returns the scale of a numeric column, -127 for a floating point number.
+    ///
+    pub fn scale(&self) -> i8 {
+        self.scale
+    }
+
+    /// The kind of value the column holds, given to the nulls fetched from it, or `None` for a
+    /// type a `ValueType` doesn't describe.
+    ///
+    /// A `NUMBER` has the precision and scale it was declared with, as `ColumnDescription` does.
+    /// One declared without a precision has neither, and a `FLOAT`, which OCI describes with a
+    /// scale of -127, has no scale.
+    ///
+    pub(crate) fn value_type(&self) -> Option<ValueType> {
+        let value_type = match self.type_code {
+            SQLT_CHR | SQLT_LNG | SQLT_CLOB | SQLT_JSON => ValueType::VarChar,
+            SQLT_AFC => ValueType::Char,
+            SQLT_NUM if self.precision <= 0 => ValueType::Number {
+                precision: None,
+                scale: None,
+            },
+            SQLT_NUM => ValueType::Number {
+                precision: Some(self.precision as u32),
+                scale: Some(i32::from(self.scale)).filter(|&scale| scale != -127),
+            },
+            SQLT_IBFLOAT | SQLT_IBDOUBLE => ValueType::Number {
+                precision: None,
+                scale: None,
+            },
+            SQLT_DAT => ValueType::Date,
+            SQLT_TIMESTAMP => ValueType::Timestamp,
+            SQLT_TIMESTAMP_TZ => ValueType::TimestampTz,
+            SQLT_BIN | SQLT_LBI | SQLT_BLOB => ValueType::Blob,
+            SQLT_BFILE => ValueType::BFile,
+            SQLT_RDD | SQLT_UROWID => ValueType::RowId,
+            _ => return None,
+        };
+        Some(value_type)
+    }
+}
+
+/// Converts the values of a column, as fetched by OCI, into `SqlValue`s.
+///
+/// Each codec is registered for the OCI type code that Oracle describes the column with, see
+/// [`CodecRegistry`][1]. The codec chooses the type code and buffer size OCI fetches the value
+/// as, which needn't be the same type, and then decodes the bytes OCI put in the buffer. Asking
+/// OCI to fetch a value as text, type code 1, is an easy way to support a type that Oracle can
+/// convert to text.
+///
+/// # Examples
+///
+/// ```rust
+/// use oci_rs::oci_error::OciError;
+/// use oci_rs::types::{ColumnType, SqlValue, TypeCodec};
+///
+/// /// Fetches an `INTERVAL DAY TO SECOND` as text, such as "+01 02:03:04.000000".
+/// struct IntervalAsText;
+///
+/// impl TypeCodec for IntervalAsText {
+///     fn fetch_as(&self, _column: &ColumnType) -> (u16, usize) {
+///         (1, 32)
+///     }
+///
+///     fn decode(&self, _type_code: u16, data: &[u8]) -> Result<SqlValue, OciError> {
+///         Ok(SqlValue::VarChar(String::from_utf8_lossy(data).into_owned()))
+///     }
+/// }
+/// ```
+///
+/// [1]: struct.CodecRegistry.html
+pub trait TypeCodec: Send + Sync {
+    /// Returns the OCI type code to fetch the column as and the size of the buffer, in bytes,
+    /// needed for each value.
+    ///
+    fn fetch_as(&self, column: &ColumnType) -> (u16, usize);
+
+    /// Converts a value, fetched as the type code chosen by `.fetch_as`, into a `SqlValue`.
+    /// Null values are dealt with before a codec is called.
+    ///
+    fn decode(&self, type_code: u16, data: &[u8]) -> Result<SqlValue, OciError>;
+
+    /// Converts a value in the same way as `.decode`, for codecs that need the OCI library to do
+    /// it. Columns are always decoded with this, which by default calls `.decode`.
+    ///
+    fn decode_in(
+        &self,
+        _context: &DecodeContext,
+        type_code: u16,
+        data: &[u8],
+    ) -> Result<SqlValue, OciError> {
+        self.decode(type_code, data)
+    }
+}
+
+/// The handles of the connection a value is being fetched on, given to
+/// [`TypeCodec.decode_in`][1]. The codecs built into this crate use it to convert values with
+/// the OCI library.
+///
+/// [1]: trait.TypeCodec.html#method.decode_in
+#[derive(Debug)]
+pub struct DecodeContext {
+    environment: *mut OCIEnv,
+    error: *mut OCIError,
+}
+impl DecodeContext {
+    /// Creates a new `DecodeContext`.
+    ///
+    pub(crate) fn new(environment: *mut OCIEnv, error: *mut OCIError) -> DecodeContext {
+        DecodeContext { environment, error }
+    }
+}
+
+/// The [`TypeCodec`][1]s used to fetch columns, keyed by the OCI type code that Oracle
+/// describes each column with.
+///
+/// A new registry holds the codecs for the types this crate supports out of the box. Each
+/// [`Connection`][2] has its own registry, which can be added to with `.register_codec`.
+///
+/// Codecs can also be registered for the columns whose names match a pattern, with
+/// `.register_column_codec`. These are used ahead of the codec for the column's type code, so
+/// they can fetch a column of any type, or fetch particular columns of a supported type in
+/// another way.
+///
+/// [1]: trait.TypeCodec.html
+/// [2]: ../connection/struct.Connection.html
+#[derive(Clone)]
+pub struct CodecRegistry {
+    codecs: HashMap<u16, Arc<dyn TypeCodec>>,
+    column_codecs: Vec<(String, Arc<dyn TypeCodec>)>,
+}
+impl CodecRegistry {
+    /// Creates a registry holding the built in codecs.
+    ///
+    pub fn new() -> CodecRegistry {
+        let mut registry = CodecRegistry {
+            codecs: HashMap::new(),
+            column_codecs: Vec::new(),
+        };
+        registry.register(SQLT_CHR, TextCodec::default());
+        registry.register(SQLT_AFC, TextCodec::default());
+        registry.register(SQLT_NUM, NumberCodec);
+        registry.register(SQLT_IBFLOAT, BinaryFloatCodec);
+        registry.register(SQLT_IBDOUBLE, BinaryFloatCodec);
+        registry.register(SQLT_DAT, DateCodec);
+        registry.register(SQLT_TIMESTAMP, TimestampCodec);
+        registry.register(SQLT_TIMESTAMP_TZ, TimestampCodec);
+        registry.register(SQLT_BIN, RawCodec);
+        registry.register(SQLT_LBI, RawCodec);
+        registry.register(SQLT_RDD, RowIdCodec);
+        registry.register(SQLT_UROWID, RowIdCodec);
+        registry
+    }
+
+    /// Registers a codec for an OCI type code, replacing any codec already registered for it.
+    ///
+    pub fn register<C: TypeCodec + 'static>(&mut self, type_code: u16, codec: C) {
+        self.codecs.insert(type_code, Arc::new(codec));
+    }
+
+    /// Registers a codec for the columns whose names match a pattern, replacing any codec
+    /// already registered for the same pattern.
+    ///
+    /// Names are matched without regard to case and a `*` in the pattern matches any number of
+    /// characters, so `*_XML` matches every column whose name ends in `_XML`. When more than one
+    /// pattern matches a column the one registered last is used.
+    ///
+    pub fn register_column_codec<C: TypeCodec + 'static>(&mut self, pattern: &str, codec: C) {
+        self.column_codecs
+            .retain(|(registered, _)| !registered.eq_ignore_ascii_case(pattern));
+        self.column_codecs
+            .push((pattern.to_string(), Arc::new(codec)));
+    }
+
+    /// Returns the codec for an OCI type code.
+    ///
+    pub(crate) fn get(&self, type_code: u16) -> Option<Arc<dyn TypeCodec>> {
+        self.codecs.get(&type_code).cloned()
+    }
+
+    /// Returns the codec registered for a column name, if any pattern matches it.
+    ///
+    pub(crate) fn get_for_column(&self, name: &str) -> Option<Arc<dyn TypeCodec>> {
+        self.column_codecs
+            .iter()
+            .rev()
+            .find(|(pattern, _)| matches_column_pattern(pattern, name))
+            .map(|(_, codec)| Arc::clone(codec))
+    }
+}
+
+/// Whether a column name matches a pattern, without regard to case, where `*` matches any
+/// number of characters.
+fn matches_column_pattern(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().map(|c| c.to_ascii_uppercase()).collect();
+    let name: Vec<char> = name.chars().map(|c| c.to_ascii_uppercase()).collect();
+    let (mut p, mut n) = (0, 0);
+    // the position of the last `*` seen and of the name when it was seen, to backtrack to
+    let mut star = None;
+    while n < name.len() {
+        if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, n));
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == name[n] {
+            p += 1;
+            n += 1;
+        } else if let Some((star_p, star_n)) = star {
+            // let the `*` take one more character
+            p = star_p + 1;
+            n = star_n + 1;
+            star = Some((star_p, star_n + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+impl Default for CodecRegistry {
+    fn default() -> Self {
+        CodecRegistry::new()
+    }
+}
+
+impl fmt::Debug for CodecRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut type_codes: Vec<&u16> = self.codecs.keys().collect();
+        type_codes.sort();
+        let column_patterns: Vec<&str> = self
+            .column_codecs
+            .iter()
+            .map(|(pattern, _)| pattern.as_str())
+            .collect();
+        f.debug_struct("CodecRegistry")
+            .field("type_codes", &type_codes)
+            .field("column_patterns", &column_patterns)
+            .finish()
+    }
+}
+
+/// `VARCHAR2` and `CHAR`, which keep their type. By default a `VARCHAR2` is trimmed and a `CHAR`
+/// keeps its padding, statements that trim text otherwise pick a codec with their settings.
+pub(crate) struct TextCodec {
+    varchar_trim: TextTrim,
+    char_trim: TextTrim,
+}
+
+impl TextCodec {
+    /// Creates a codec that trims `VARCHAR2` and `CHAR` text as given.
+    ///
+    pub(crate) fn new(varchar_trim: TextTrim, char_trim: TextTrim) -> TextCodec {
+        TextCodec {
+            varchar_trim,
+            char_trim,
+        }
+    }
+}
+
+impl Default for TextCodec {
+    fn default() -> Self {
+        TextCodec::new(DEFAULT_VARCHAR_TRIM, DEFAULT_CHAR_TRIM)
+    }
+}
+
+/// How `VARCHAR2` text is trimmed unless a connection or statement says otherwise.
+pub(crate) const DEFAULT_VARCHAR_TRIM: TextTrim = TextTrim::TrimBoth;
+/// How `CHAR` text is trimmed unless a connection or statement says otherwise.
+pub(crate) const DEFAULT_CHAR_TRIM: TextTrim = TextTrim::Preserve;
+
+impl TypeCodec for TextCodec {
+    // the buffer only needs to be the column's size, each value is sliced to its fetched length
+    fn fetch_as(&self, column: &ColumnType) -> (u16, usize) {
+        (column.type_code(), column.size() as usize)
+    }
+
+    fn decode(&self, type_code: u16, data: &[u8]) -> Result<SqlValue, OciError> {
+        let s = match String::from_utf8(Vec::from(data)) {
+            Ok(s) => s,
+            Err(err) => return Err(OciError::Conversion(Box::new(err))),
+        };
+        if type_code == SQLT_AFC {
+            Ok(SqlValue::Char(self.char_trim.apply(s)))
+        } else {
+            Ok(SqlValue::VarChar(self.varchar_trim.apply(s)))
+        }
+    }
+}
+
+/// `NUMBER`, fetched in Oracle's own format and converted by the OCI library to an integer if
+/// the value is a whole number that fits in an `i64`, to text if it is a whole number that
+/// doesn't, so that no digits are lost, and to a float otherwise. With the `decimal` feature a
+/// `NUMBER` declared with decimal places is fetched in Oracle's own format and converted to a
+/// `Decimal`. With the `bigdecimal` feature so is one declared with more digits than an `i64`
+/// holds, and they are converted to a `BigDecimal`.
+struct NumberCodec;
+
+impl NumberCodec {
+    /// Whether the column is fetched in Oracle's own format, to be converted exactly.
+    fn is_exact(column: &ColumnType) -> bool {
+        let has_decimal_places = column.scale() > 0;
+        let too_long_for_i64 = column.scale() == 0 && column.precision() > 18;
+        (cfg!(feature = "decimal") && has_decimal_places)
+            || (cfg!(feature = "bigdecimal") && (has_decimal_places || too_long_for_i64))
+    }
+}
 
-    /// Gives the size in bytes of the internal value.
-    ///
-    /// It is used by the OCI library to allocate storage. Byte size values
-    /// are hard coded here on purpose as a confirmation of OCI spec.
-    ///
-    pub(crate) fn size(&self) -> c_int {
-        match *self {
-            SqlValue::VarChar(ref s) | SqlValue::Char(ref s) => s.capacity() as c_int,
-            SqlValue::Integer(..) | SqlValue::Float(..) => 8 as c_int,
-            SqlValue::Null => panic!("Null not handled"),
-            SqlValue::Date(_, ref b) => b.len() as c_int,
-            SqlValue::Blob(ref b) => b.len() as c_int,
-            SqlValue::Timestamp(_, ref b) => b.len() as c_int,
-            SqlValue::TimestampTz(_, ref b) => b.len() as c_int,
+impl TypeCodec for NumberCodec {
+    fn fetch_as(&self, column: &ColumnType) -> (u16, usize) {
+        if NumberCodec::is_exact(column) {
+            (SQLT_NUM, ORACLE_NUMBER_SIZE)
+        } else {
+            (SQLT_VNU, ORACLE_NUMBER_SIZE)
         }
     }
 
-    /// Converts to the relevant OCI internal type.
-    ///
-    /// Date is converted into characters before sending into OCI
-    /// this avoids having to convert a rust date object into the Oracle
-    /// seven byte date format.
-    ///
-    pub(crate) fn as_oci_data_type(&self) -> OciDataType {
-        match *self {
-            SqlValue::VarChar(..) => OciDataType::SqlVarChar,
-            SqlValue::Char(..) => OciDataType::SqlChar,
-            SqlValue::Integer(..) => OciDataType::SqlInt,
-            SqlValue::Float(..) => OciDataType::SqlFloat,
-            SqlValue::Null => panic!("Null not handled"),
-            SqlValue::Date(..) => OciDataType::SqlDate,
-            SqlValue::Timestamp(..) => OciDataType::SqlTimestamp,
-            SqlValue::TimestampTz(..) => OciDataType::SqlTimestampTz,
-            SqlValue::Blob(..) => OciDataType::SqlBlob,
+    fn decode_in(
+        &self,
+        context: &DecodeContext,
+        type_code: u16,
+        data: &[u8],
+    ) -> Result<SqlValue, OciError> {
+        if type_code == SQLT_VNU {
+            create_number_from_oci(context.error, data)
+        } else {
+            self.decode(type_code, data)
         }
     }
 
-    /// Create an `SqlValue` from a slice of bytes and indication of the data type
-    ///
-    pub(crate) fn create_from_raw(data: &[u8], sql_type: &OciDataType) -> Result<Self, OciError> {
-        match *sql_type {
-            OciDataType::SqlVarChar => match String::from_utf8(Vec::from(data)) {
-                Ok(s) => Ok(SqlValue::VarChar(s.trim().to_string())),
-                Err(err) => Err(OciError::Conversion(Box::new(err))),
-            },
-            OciDataType::SqlChar => match String::from_utf8(Vec::from(data)) {
-                Ok(s) => Ok(SqlValue::Char(s.to_string())),
-                Err(err) => Err(OciError::Conversion(Box::new(err))),
-            },
-            OciDataType::SqlInt => {
-                let i = LittleEndian::read_i64(data);
-                Ok(SqlValue::Integer(i as i64))
-            }
-            OciDataType::SqlFloat => {
-                let f = LittleEndian::read_f64(data);
-                Ok(SqlValue::Float(f as f64))
-            }
-            OciDataType::SqlDate => {
-                let datetime = create_datetime_from_raw(data);
-                let date = datetime.date();
-                Ok(SqlValue::Date(date, create_raw_from_date(date)))
+    fn decode(&self, type_code: u16, data: &[u8]) -> Result<SqlValue, OciError> {
+        // a big decimal can hold every NUMBER, so it is used when both features are enabled
+        #[cfg(feature = "bigdecimal")]
+        {
+            if type_code == SQLT_NUM {
+                let bigdecimal = create_bigdecimal_from_raw(data)?;
+                return Ok(SqlValue::BigDecimal(bigdecimal, data.to_vec()));
             }
-            OciDataType::SqlTimestamp => {
-                let datetime = create_datetime_from_raw(data);
-                Ok(SqlValue::Timestamp(
-                    datetime,
-                    create_raw_from_datetime(&datetime),
-                ))
-            }
-            OciDataType::SqlTimestampTz => {
-                let datetime_tz = create_datetime_with_timezone_from_raw(data);
-                Ok(SqlValue::TimestampTz(
-                    datetime_tz,
-                    create_raw_from_datetime_with_timezone(&datetime_tz),
-                ))
+        }
+        #[cfg(feature = "decimal")]
+        {
+            if type_code == SQLT_NUM {
+                let decimal = create_decimal_from_raw(data)?;
+                return Ok(SqlValue::Decimal(decimal, data.to_vec()));
             }
-            ref x => panic!(format!(
-                "Creating a SqlValue from raw bytes is not implemented yet for: \
-                 {:?}",
-                x
-            )),
+        }
+        if type_code == SQLT_VNU {
+            Err(OciError::Conversion(
+                "A NUMBER in Oracle's own format can only be converted on a connection".into(),
+            ))
+        } else if type_code == SQLT_FLT {
+            Ok(SqlValue::Float(LittleEndian::read_f64(data)))
+        } else {
+            Ok(SqlValue::Integer(LittleEndian::read_i64(data)))
         }
     }
 }
 
-/// Allows conversion into a `SqlValue`.
+/// The format model that numbers too big for an `i64` are converted to text with, which uses as
+/// few characters as it can.
+const NUMBER_TEXT_FORMAT: &str = "TM9";
+/// Makes sure the decimal character is a point whatever the session's language is.
+const NUMBER_TEXT_NLS_PARAMS: &str = "NLS_NUMERIC_CHARACTERS='.,'";
+/// The longest text the `TM9` format gives.
+const NUMBER_TEXT_SIZE: usize = 64;
+
+/// Converts a `NUMBER` fetched in Oracle's own format, as `SQLT_VNU`, to an integer, text or a
+/// float, as described for `NumberCodec`.
 ///
-pub trait ToSqlValue {
-    /// Converts into a `SqlValue`.
-    ///
-    fn to_sql_value(&self) -> SqlValue;
-}
+pub(crate) fn create_number_from_oci(
+    error: *mut OCIError,
+    data: &[u8],
+) -> Result<SqlValue, OciError> {
+    // OCI reads the whole of the number, however short the value fetched was
+    let mut number = [0u8; ORACLE_NUMBER_SIZE];
+    let length = data.len().min(ORACLE_NUMBER_SIZE);
+    number[..length].copy_from_slice(&data[..length]);
+    let number_ptr = number.as_ptr() as *const c_void;
+    let number_error =
+        |description| get_error(error as *mut c_void, HandleType::Error, description);
 
-impl ToSqlValue for String {
-    fn to_sql_value(&self) -> SqlValue {
-        SqlValue::VarChar(self.clone())
+    let mut is_int: c_int = 0;
+    let is_int_result = unsafe { OCINumberIsInt(error, number_ptr, &mut is_int) };
+    if let ReturnCode::Error = is_int_result.into() {
+        return Err(number_error("Checking whether a NUMBER is an integer"));
+    }
+    if is_int != 0 {
+        let mut integer: i64 = 0;
+        let to_int_result = unsafe {
+            OCINumberToInt(
+                error,
+                number_ptr,
+                mem::size_of::<i64>() as c_uint,
+                OciNumberType::Signed.into(),
+                &mut integer as *mut i64 as *mut c_void,
+            )
+        };
+        // failing means it is too big for an i64, so it is kept as text
+        if let ReturnCode::Success = to_int_result.into() {
+            return Ok(SqlValue::Integer(integer));
+        }
+        return number_to_text(error, number_ptr).map(SqlValue::VarChar);
+    }
+    let mut float: f64 = 0.0;
+    let to_real_result = unsafe {
+        OCINumberToReal(
+            error,
+            number_ptr,
+            mem::size_of::<f64>() as c_uint,
+            &mut float as *mut f64 as *mut c_void,
+        )
+    };
+    match to_real_result.into() {
+        ReturnCode::Success => Ok(SqlValue::Float(float)),
+        _ => Err(number_error("Converting a NUMBER to a float")),
     }
 }
 
-impl<'a> ToSqlValue for &'a str {
-    fn to_sql_value(&self) -> SqlValue {
-        let s = String::from(*self);
-        SqlValue::VarChar(s)
+/// Converts a `NUMBER` in Oracle's own format to text with the `TM9` format, which has no
+/// leading zero before the decimal point and no trailing zeros after it.
+///
+fn number_to_text(error: *mut OCIError, number_ptr: *const c_void) -> Result<String, OciError> {
+    let mut text = vec![0; NUMBER_TEXT_SIZE];
+    let mut text_size = NUMBER_TEXT_SIZE as c_uint;
+    let to_text_result = unsafe {
+        OCINumberToText(
+            error,
+            number_ptr,
+            NUMBER_TEXT_FORMAT.as_ptr(),
+            NUMBER_TEXT_FORMAT.len() as c_uint,
+            NUMBER_TEXT_NLS_PARAMS.as_ptr(),
+            NUMBER_TEXT_NLS_PARAMS.len() as c_uint,
+            &mut text_size,
+            text.as_mut_ptr(),
+        )
+    };
+    match to_text_result.into() {
+        ReturnCode::Success => {
+            text.truncate(text_size as usize);
+            String::from_utf8(text).map_err(|err| OciError::Conversion(Box::new(err)))
+        }
+        _ => Err(get_error(
+            error as *mut c_void,
+            HandleType::Error,
+            "Converting a NUMBER to text",
+        )),
     }
 }
 
-impl ToSqlValue for i64 {
-    fn to_sql_value(&self) -> SqlValue {
-        SqlValue::Integer(*self)
-    }
+/// `NUMBER`, fetched in Oracle's own format and converted by the OCI library to its exact
+/// decimal text, for statements that fetch numbers as text. Every digit is kept and a column
+/// declared with decimal places is given that many, so `NUMBER(10, 2)` gives "1.50" rather
+/// than "1.5". Statements pick it in place of the registered codec, with the column's scale.
+pub(crate) struct NumberTextCodec {
+    decimal_places: usize,
 }
 
-impl ToSqlValue for f64 {
-    fn to_sql_value(&self) -> SqlValue {
-        SqlValue::Float(*self)
+impl NumberTextCodec {
+    /// Creates a codec for a column with the given scale, -127 for a floating point number.
+    ///
+    pub(crate) fn new(scale: i8) -> NumberTextCodec {
+        NumberTextCodec {
+            decimal_places: scale.max(0) as usize,
+        }
     }
-}
 
-impl ToSqlValue for &[u8] {
-    fn to_sql_value(&self) -> SqlValue {
-        SqlValue::Blob(self.to_vec())
+    /// Adds the zero `TM9` leaves off before the decimal point and pads the digits after it to
+    /// the column's scale. Text in scientific notation, only given for very large or very small
+    /// numbers, is left as it is.
+    fn format(&self, text: String) -> String {
+        if text.contains('E') {
+            return text;
+        }
+        let (sign, digits) = match text.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", text.as_str()),
+        };
+        let mut formatted = String::with_capacity(text.len() + self.decimal_places + 2);
+        formatted.push_str(sign);
+        if digits.starts_with('.') {
+            formatted.push('0');
+        }
+        formatted.push_str(digits);
+        let fraction_digits = match digits.find('.') {
+            Some(point) => digits.len() - point - 1,
+            None => 0,
+        };
+        if fraction_digits < self.decimal_places {
+            if !digits.contains('.') {
+                formatted.push('.');
+            }
+            for _ in fraction_digits..self.decimal_places {
+                formatted.push('0');
+            }
+        }
+        formatted
     }
 }
 
-impl ToSqlValue for Date<Utc> {
-    fn to_sql_value(&self) -> SqlValue {
-        SqlValue::Date(*self, create_raw_from_date(*self))
+impl TypeCodec for NumberTextCodec {
+    fn fetch_as(&self, _column: &ColumnType) -> (u16, usize) {
+        (SQLT_VNU, ORACLE_NUMBER_SIZE)
+    }
+
+    fn decode_in(
+        &self,
+        context: &DecodeContext,
+        _type_code: u16,
+        data: &[u8],
+    ) -> Result<SqlValue, OciError> {
+        // OCI reads the whole of the number, however short the value fetched was
+        let mut number = [0u8; ORACLE_NUMBER_SIZE];
+        let length = data.len().min(ORACLE_NUMBER_SIZE);
+        number[..length].copy_from_slice(&data[..length]);
+        let text = number_to_text(context.error, number.as_ptr() as *const c_void)?;
+        Ok(SqlValue::VarChar(self.format(text)))
+    }
+
+    fn decode(&self, _type_code: u16, _data: &[u8]) -> Result<SqlValue, OciError> {
+        Err(OciError::Conversion(
+            "A NUMBER in Oracle's own format can only be converted on a connection".into(),
+        ))
     }
 }
 
-impl ToSqlValue for DateTime<Utc> {
-    fn to_sql_value(&self) -> SqlValue {
-        SqlValue::Timestamp(*self, create_raw_from_datetime(self))
+/// `BINARY_FLOAT` and `BINARY_DOUBLE`, fetched as a native `f64`, which keeps NaN and the
+/// infinities. A `BINARY_FLOAT` is widened, which is exact.
+struct BinaryFloatCodec;
+
+impl TypeCodec for BinaryFloatCodec {
+    fn fetch_as(&self, _column: &ColumnType) -> (u16, usize) {
+        (SQLT_BDOUBLE, mem::size_of::<f64>())
+    }
+
+    fn decode(&self, _type_code: u16, data: &[u8]) -> Result<SqlValue, OciError> {
+        match <[u8; 8]>::try_from(data) {
+            Ok(bytes) => Ok(SqlValue::Float(f64::from_ne_bytes(bytes))),
+            Err(err) => Err(OciError::Conversion(Box::new(err))),
+        }
     }
 }
 
-impl ToSqlValue for DateTime<FixedOffset> {
-    fn to_sql_value(&self) -> SqlValue {
-        SqlValue::TimestampTz(*self, create_raw_from_datetime_with_timezone(self))
+/// `DATE`, in Oracle's seven byte format.
+struct DateCodec;
+
+impl TypeCodec for DateCodec {
+    fn fetch_as(&self, _column: &ColumnType) -> (u16, usize) {
+        (SQLT_DAT, 7)
+    }
+
+    fn decode(&self, _type_code: u16, data: &[u8]) -> Result<SqlValue, OciError> {
+        Ok(create_date_value_from_raw(data))
     }
 }
 
-/// Allows conversion from a `SqlValue`.
-///
-pub trait FromSqlValue {
-    /// Allows conversion from a `SqlValue`.
-    ///
-    /// It allows for impossible conversions though the use of `Option`.
-    /// e.g. an `SqlValue::Null` cannot be converted into a i64.
-    ///
-    /// When the `TryFrom` trait becomes stable then this crate will probably switch to that
-    /// instead.
+/// `TIMESTAMP` and `TIMESTAMP WITH TIME ZONE`, in Oracle's eleven and thirteen byte formats.
+struct TimestampCodec;
+
+impl TypeCodec for TimestampCodec {
+    fn fetch_as(&self, column: &ColumnType) -> (u16, usize) {
+        if column.type_code() == SQLT_TIMESTAMP_TZ {
+            (SQLT_TIMESTAMP_TZ_INTERNAL, 13)
+        } else {
+            (SQLT_TIMESTAMP_INTERNAL, 11)
+        }
+    }
+
+    fn decode(&self, type_code: u16, data: &[u8]) -> Result<SqlValue, OciError> {
+        if type_code == SQLT_TIMESTAMP_TZ_INTERNAL && has_timezone_region(data) {
+            Err(OciError::Conversion(
+                "A TIMESTAMP WITH TIME ZONE in a named region can only be decoded on a connection"
+                    .into(),
+            ))
+        } else if type_code == SQLT_TIMESTAMP_TZ_INTERNAL {
+            let datetime_tz = create_datetime_with_timezone_from_raw(data);
+            Ok(SqlValue::TimestampTz(
+                datetime_tz,
+                create_raw_from_datetime_with_timezone(&datetime_tz),
+            ))
+        } else {
+            let datetime = create_datetime_from_raw(data);
+            Ok(SqlValue::Timestamp(
+                datetime,
+                create_raw_from_datetime(&datetime),
+            ))
+        }
+    }
+
+    /// A region is looked up in the client's time zone file, for its name and the offset in
+    /// effect at the time.
     ///
-    fn from_sql_value(sql_value: &SqlValue) -> Option<Self>
-    where
-        Self: Sized;
+    fn decode_in(
+        &self,
+        context: &DecodeContext,
+        type_code: u16,
+        data: &[u8],
+    ) -> Result<SqlValue, OciError> {
+        if type_code == SQLT_TIMESTAMP_TZ_INTERNAL && has_timezone_region(data) {
+            create_timestamp_region_from_oci(context, data)
+        } else {
+            self.decode(type_code, data)
+        }
+    }
 }
 
-impl FromSqlValue for String {
-    // Converts from a `SqlValue` into a `String`
-    //
-    // Worth noting that this is intended to convert types into a
-    // `String` representation of the value. It does not convert null,
-    // previous versions of this library did convert null to Some("null")
-    // but that turned out to be annoying as it required furhter conversion to None
-    // if needed.
-    //
-    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
-        match *sql_value {
-            SqlValue::VarChar(ref s) | SqlValue::Char(ref s) => Some(s.to_string()),
-            SqlValue::Integer(i) => Some(format!("{}", i)),
-            SqlValue::Float(f) => Some(format!("{}", f)),
-            SqlValue::Date(ref d, _) => Some(format!("{}", d)),
-            SqlValue::Timestamp(ref d, _) => Some(format!("{}", d)),
-            SqlValue::TimestampTz(ref d, _) => Some(format!("{}", d)),
-            _ => None,
+/// The earliest year a `DATE` can hold, 4712 BC as Oracle numbers it.
+const ORACLE_MIN_YEAR: i32 = -4712;
+/// The latest year a `DATE` can hold.
+const ORACLE_MAX_YEAR: i32 = 9999;
+/// Set in the time zone hour byte of a `TIMESTAMP WITH TIME ZONE` that holds a region ID
+/// rather than an hour and minute offset.
+const TIMEZONE_REGION_FLAG: u8 = 0x80;
+/// The longest time zone region name, in bytes.
+const TIMEZONE_NAME_SIZE: usize = 64;
+/// The fractional second precision, in digits, of a `TIMESTAMP`'s nanoseconds.
+const NANOSECOND_PRECISION: c_uchar = 9;
+
+/// The most bytes a `NUMBER` takes in Oracle's own format.
+pub(crate) const ORACLE_NUMBER_SIZE: usize = 22;
+
+/// The buffer for a `LONG RAW`, the most a fetched value's length can be reported as. Longer
+/// values are truncated, which is an error.
+const LONG_RAW_BUFFER_SIZE: usize = u16::MAX as usize;
+
+/// `RAW` and `LONG RAW`, as bytes.
+struct RawCodec;
+
+impl TypeCodec for RawCodec {
+    fn fetch_as(&self, column: &ColumnType) -> (u16, usize) {
+        if column.type_code() == SQLT_LBI {
+            (SQLT_BIN, LONG_RAW_BUFFER_SIZE)
+        } else {
+            (SQLT_BIN, column.size() as usize)
         }
     }
+
+    fn decode(&self, _type_code: u16, data: &[u8]) -> Result<SqlValue, OciError> {
+        Ok(SqlValue::Blob(data.to_vec()))
+    }
 }
 
-impl FromSqlValue for i64 {
-    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
-        match *sql_value {
-            SqlValue::Integer(i) => Some(i),
-            _ => None,
-        }
+/// The buffer for an `XMLType`, the longest text Oracle converts one to. Oracle raises
+/// ORA-19011 for a document longer than it allows, 4000 bytes unless `MAX_STRING_SIZE` is
+/// `EXTENDED`.
+const XML_BUFFER_SIZE: usize = 32767;
+
+/// `XMLType`, which Oracle converts to text for us. It isn't registered, as every object type
+/// shares its type code, so statements pick it for columns whose type is named `XMLTYPE`.
+pub(crate) struct XmlTypeCodec;
+
+impl TypeCodec for XmlTypeCodec {
+    fn fetch_as(&self, _column: &ColumnType) -> (u16, usize) {
+        (SQLT_CHR, XML_BUFFER_SIZE)
+    }
+
+    fn decode(&self, type_code: u16, data: &[u8]) -> Result<SqlValue, OciError> {
+        TextCodec::default().decode(type_code, data)
     }
 }
 
-impl FromSqlValue for f64 {
-    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
-        match *sql_value {
-            SqlValue::Float(f) => Some(f),
-            _ => None,
+/// The length of the text of a `ROWID`.
+const ROWID_LENGTH: usize = 18;
+/// The longest the text of a `UROWID` can be, for the primary key of an index organised table.
+const UROWID_MAX_LENGTH: usize = 4000;
+
+/// `ROWID` and `UROWID`, as their text.
+struct RowIdCodec;
+
+impl TypeCodec for RowIdCodec {
+    fn fetch_as(&self, column: &ColumnType) -> (u16, usize) {
+        if column.type_code() == SQLT_RDD {
+            (SQLT_CHR, ROWID_LENGTH)
+        } else {
+            (SQLT_CHR, UROWID_MAX_LENGTH)
+        }
+    }
+
+    fn decode(&self, _type_code: u16, data: &[u8]) -> Result<SqlValue, OciError> {
+        match String::from_utf8(data.to_vec()) {
+            Ok(s) => Ok(SqlValue::RowId(RowId(s))),
+            Err(err) => Err(OciError::Conversion(Box::new(err))),
         }
     }
 }
 
-impl FromSqlValue for Date<Utc> {
-    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
-        match *sql_value {
-            SqlValue::Date(d, _) => Some(d),
-            _ => None,
+/// Splits the Oracle `NUMBER` format into its sign, the power of 100 that the last digit is
+/// multiplied by and the base 100 digits, most significant first.
+///
+/// The first byte holds the sign and a base 100 exponent, the rest are base 100 digits, which
+/// for a negative number are subtracted from 101 and may be followed by a terminating 102.
+/// Zero is a single byte with no digits.
+///
+#[cfg(any(feature = "decimal", feature = "bigdecimal"))]
+fn split_oracle_number(data: &[u8]) -> Result<(bool, i32, Vec<u8>), OciError> {
+    let (&first, digits) = match data.split_first() {
+        Some(split) => split,
+        None => {
+            return Err(OciError::Conversion(
+                "An empty NUMBER can't be converted".into(),
+            ))
         }
+    };
+    if first == 0x80 {
+        return Ok((true, 0, Vec::new()));
     }
+    let positive = first & 0x80 != 0;
+    let (exponent, digits): (i32, Vec<u8>) = if positive {
+        (
+            i32::from(first) - 193,
+            digits.iter().map(|&d| d.saturating_sub(1)).collect(),
+        )
+    } else {
+        (
+            62 - i32::from(first),
+            digits
+                .iter()
+                .take_while(|&&d| d != 102)
+                .map(|&d| 101u8.saturating_sub(d))
+                .collect(),
+        )
+    };
+    let power = exponent - (digits.len() as i32 - 1);
+    Ok((positive, power, digits))
 }
 
-impl FromSqlValue for DateTime<Utc> {
-    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
-        match *sql_value {
-            SqlValue::Timestamp(d, _) => Some(d),
-            _ => None,
+/// Joins a sign, the power of 100 that the last digit is multiplied by and the base 100
+/// digits, most significant first, into the Oracle `NUMBER` format. Numbers too small for it
+/// become zero and numbers too large become infinity.
+///
+#[cfg(any(feature = "decimal", feature = "bigdecimal"))]
+fn join_oracle_number(positive: bool, mut power: i32, mut digits: Vec<u8>) -> Vec<u8> {
+    while digits.last() == Some(&0) {
+        digits.pop();
+        power += 1;
+    }
+    match digits.iter().position(|&d| d != 0) {
+        Some(start) => {
+            digits.drain(..start);
         }
+        None => return vec![0x80],
     }
+    let exponent = power + digits.len() as i32 - 1;
+    if exponent < -65 {
+        return vec![0x80];
+    }
+    if exponent > 62 {
+        return if positive {
+            vec![0xFF, 0x65]
+        } else {
+            vec![0x00]
+        };
+    }
+    let mut raw = Vec::with_capacity(digits.len() + 2);
+    if positive {
+        raw.push((193 + exponent) as u8);
+        raw.extend(digits.iter().map(|d| d + 1));
+    } else {
+        raw.push((62 - exponent) as u8);
+        raw.extend(digits.iter().map(|d| 101 - d));
+        if digits.len() < 20 {
+            raw.push(102);
+        }
+    }
+    raw
 }
 
-impl FromSqlValue for DateTime<FixedOffset> {
-    fn from_sql_value(sql_value: &SqlValue) -> Option<Self> {
-        match *sql_value {
-            SqlValue::TimestampTz(d, _) => Some(d),
-            _ => None,
+/// Creates a `Decimal` from the Oracle `NUMBER` format.
+///
+#[cfg(feature = "decimal")]
+fn create_decimal_from_raw(data: &[u8]) -> Result<Decimal, OciError> {
+    let out_of_range = || {
+        OciError::Conversion(
+            format!(
+                "The NUMBER {:?} can't be held by a decimal without rounding",
+                data
+            )
+            .into(),
+        )
+    };
+    let (positive, power, digits) = split_oracle_number(data)?;
+    let mut mantissa: i128 = 0;
+    for digit in digits {
+        mantissa = mantissa
+            .checked_mul(100)
+            .and_then(|m| m.checked_add(i128::from(digit)))
+            .ok_or_else(out_of_range)?;
+    }
+    let (mantissa, scale) = if power >= 0 {
+        let mantissa = 100i128
+            .checked_pow(power as u32)
+            .and_then(|multiplier| mantissa.checked_mul(multiplier))
+            .ok_or_else(out_of_range)?;
+        (mantissa, 0)
+    } else {
+        (mantissa, (-power * 2) as u32)
+    };
+    let mantissa = if positive { mantissa } else { -mantissa };
+    Decimal::try_from_i128_with_scale(mantissa, scale).map_err(|_| out_of_range())
+}
+
+/// Creates the Oracle `NUMBER` format from a `Decimal`.
+///
+#[cfg(feature = "decimal")]
+fn create_raw_from_decimal(decimal: &Decimal) -> Vec<u8> {
+    let mut mantissa = decimal.mantissa().unsigned_abs();
+    let mut scale = decimal.scale() as i32;
+    // the digits are base 100, so the decimal places have to come in pairs
+    if scale % 2 != 0 {
+        mantissa *= 10;
+        scale += 1;
+    }
+    let mut digits = Vec::new();
+    while mantissa > 0 {
+        digits.push((mantissa % 100) as u8);
+        mantissa /= 100;
+    }
+    digits.reverse();
+    join_oracle_number(decimal.is_sign_positive(), -scale / 2, digits)
+}
+
+/// Creates a `BigDecimal` from the Oracle `NUMBER` format.
+///
+#[cfg(feature = "bigdecimal")]
+fn create_bigdecimal_from_raw(data: &[u8]) -> Result<BigDecimal, OciError> {
+    let (positive, power, digits) = split_oracle_number(data)?;
+    let sign = if positive { Sign::Plus } else { Sign::Minus };
+    let mantissa = match BigInt::from_radix_be(sign, &digits, 100) {
+        Some(mantissa) => mantissa,
+        None => {
+            return Err(OciError::Conversion(
+                format!("The NUMBER {:?} has a digit over 99", data).into(),
+            ))
         }
+    };
+    Ok(BigDecimal::new(mantissa, -2 * i64::from(power)))
+}
+
+/// Creates the Oracle `NUMBER` format from a `BigDecimal`, rounded to the 38 significant digits
+/// that a `NUMBER` holds.
+///
+#[cfg(feature = "bigdecimal")]
+fn create_raw_from_bigdecimal(bigdecimal: &BigDecimal) -> Vec<u8> {
+    let (mantissa, mut scale) = bigdecimal.with_prec(38).into_bigint_and_exponent();
+    let (sign, mut magnitude) = mantissa.into_parts();
+    // the digits are base 100, so the decimal places have to come in pairs
+    if scale % 2 != 0 {
+        magnitude *= 10u32;
+        scale += 1;
     }
+    let power = (-scale / 2).clamp(-1000, 1000) as i32;
+    join_oracle_number(sign != Sign::Minus, power, magnitude.to_radix_be(100))
 }
 
 /// Creates a `DateTime<Utc>` from the Oracle format.
 ///
 /// Oracle uses seven bytes for a date, and eleven bytes for a timestamp.
 ///
-fn create_datetime_from_raw(data: &[u8]) -> DateTime<Utc> {
-    let century = convert_century(data[0]);
-    let year = convert_year(data[1]);
+pub(crate) fn create_datetime_from_raw(data: &[u8]) -> DateTime<Utc> {
+    let year = convert_year_from_raw(data[0], data[1]);
     let month = convert_month(data[2]);
     let day = convert_day(data[3]);
     let hour = convert_hour(data[4]);
     let minute = convert_minute(data[5]);
     let second = convert_second(data[6]);
     if data.len() <= 7 {
-        Utc.ymd(century + year, month, day)
-            .and_hms(hour, minute, second)
+        Utc.ymd(year, month, day).and_hms(hour, minute, second)
     } else {
         let nano = convert_nano(&data[7..11]);
-        Utc.ymd(century + year, month, day)
+        Utc.ymd(year, month, day)
             .and_hms_nano(hour, minute, second, nano)
     }
 }
 
+/// Creates a `SqlValue::Date` from the Oracle format, dropping the time of day.
+///
+pub(crate) fn create_date_value_from_raw(data: &[u8]) -> SqlValue {
+    let date = create_datetime_from_raw(data).date();
+    SqlValue::Date(date, create_raw_from_date(date))
+}
+
 fn create_raw_from_date(date: Date<Utc>) -> [u8; 7] {
-    let century = convert_year_to_century_raw(date.year());
-    let year = convert_year_to_raw(date.year());
+    let [century, year] = convert_year_to_raw(date.year());
     let month = date.month() as u8;
     let day = date.day() as u8;
     let hour = convert_hour_to_raw(0);
@@ -333,8 +2667,7 @@ fn create_raw_from_date(date: Date<Utc>) -> [u8; 7] {
 }
 
 fn create_raw_from_datetime(datetime: &DateTime<Utc>) -> [u8; 11] {
-    let century = convert_year_to_century_raw(datetime.year());
-    let year = convert_year_to_raw(datetime.year());
+    let [century, year] = convert_year_to_raw(datetime.year());
     let month = datetime.month() as u8;
     let day = datetime.day() as u8;
     let hour = convert_hour_to_raw(datetime.hour());
@@ -351,8 +2684,7 @@ fn create_raw_from_datetime(datetime: &DateTime<Utc>) -> [u8; 11] {
 /// Oracle uses thirteen bytes for a timestamp with timezone.
 ///
 fn create_datetime_with_timezone_from_raw(data: &[u8]) -> DateTime<FixedOffset> {
-    let century = convert_century(data[0]);
-    let year = convert_year(data[1]);
+    let year = convert_year_from_raw(data[0], data[1]);
     let month = convert_month(data[2]);
     let day = convert_day(data[3]);
     let hour = convert_hour(data[4]);
@@ -364,7 +2696,7 @@ fn create_datetime_with_timezone_from_raw(data: &[u8]) -> DateTime<FixedOffset>
     let hour_in_secs = timezone_hour * 3600;
     let minutes_in_secs = timezone_minute * 60;
     let utc_dt = Utc
-        .ymd(century + year, month, day)
+        .ymd(year, month, day)
         .and_hms_nano(hour, minute, second, nano);
     utc_dt.with_timezone(&FixedOffset::east(hour_in_secs + minutes_in_secs))
 }
@@ -377,8 +2709,7 @@ fn create_datetime_with_timezone_from_raw(data: &[u8]) -> DateTime<FixedOffset>
 ///
 fn create_raw_from_datetime_with_timezone(datetime: &DateTime<FixedOffset>) -> [u8; 13] {
     let utc = datetime.with_timezone(&Utc);
-    let century = convert_year_to_century_raw(utc.year());
-    let year = convert_year_to_raw(utc.year());
+    let [century, year] = convert_year_to_raw(utc.year());
     let month = utc.month() as u8;
     let day = utc.day() as u8;
     let hour = convert_hour_to_raw(utc.hour());
@@ -405,25 +2736,250 @@ fn create_raw_from_datetime_with_timezone(datetime: &DateTime<FixedOffset>) -> [
     ]
 }
 
-fn convert_century(century_byte: u8) -> i32 {
-    let number = i32::from(century_byte);
-    (number - 100) * 100
+/// Creates a `time::Date` from the Oracle format, or `None` if it is out of the range that
+/// `time` supports.
+///
+#[cfg(feature = "time")]
+fn create_time_date_from_raw(data: &[u8]) -> Option<time::Date> {
+    let year = convert_year_from_raw(data[0], data[1]);
+    let month = time::Month::try_from(data[2]).ok()?;
+    time::Date::from_calendar_date(year, month, data[3]).ok()
 }
 
-fn convert_year_to_century_raw(year: i32) -> u8 {
-    let byte = (year / 100) + 100;
-    byte as u8
+#[cfg(feature = "time")]
+fn create_raw_from_time_date(date: time::Date) -> [u8; 7] {
+    let [century, year] = convert_year_to_raw(date.year());
+    let month = u8::from(date.month());
+    let day = date.day();
+    let hour = convert_hour_to_raw(0);
+    let minute = convert_minute_to_raw(0);
+    let second = convert_second_to_raw(0);
+    [century, year, month, day, hour, minute, second]
 }
 
-fn convert_year(year_byte: u8) -> i32 {
-    let number = i32::from(year_byte);
-    number - 100
+/// Creates a `PrimitiveDateTime` from the Oracle format.
+///
+/// Oracle uses seven bytes for a date, and eleven bytes for a timestamp.
+///
+#[cfg(feature = "time")]
+fn create_primitive_datetime_from_raw(data: &[u8]) -> Option<PrimitiveDateTime> {
+    let date = create_time_date_from_raw(data)?;
+    let nano = if data.len() <= 7 {
+        0
+    } else {
+        convert_nano(&data[7..11])
+    };
+    let time = time::Time::from_hms_nano(
+        convert_hour(data[4]) as u8,
+        convert_minute(data[5]) as u8,
+        convert_second(data[6]) as u8,
+        nano,
+    )
+    .ok()?;
+    Some(PrimitiveDateTime::new(date, time))
 }
 
-fn convert_year_to_raw(year: i32) -> u8 {
-    let decade = year - ((year / 100) * 100);
-    let byte = decade + 100;
-    byte as u8
+#[cfg(feature = "time")]
+fn create_raw_from_primitive_datetime(datetime: &PrimitiveDateTime) -> [u8; 11] {
+    let date = create_raw_from_time_date(datetime.date());
+    let hour = convert_hour_to_raw(u32::from(datetime.hour()));
+    let minute = convert_minute_to_raw(u32::from(datetime.minute()));
+    let second = convert_second_to_raw(u32::from(datetime.second()));
+    let nano = convert_nano_to_raw(datetime.nanosecond());
+    [
+        date[0], date[1], date[2], date[3], hour, minute, second, nano[0], nano[1], nano[2],
+        nano[3],
+    ]
+}
+
+/// Creates an `OffsetDateTime` from the Oracle format, at an offset of `offset` seconds.
+///
+/// Oracle uses thirteen bytes for a timestamp with timezone, the first eleven hold the UTC
+/// time. The offset is taken separately as the last two bytes may hold a region instead.
+///
+#[cfg(feature = "time")]
+fn create_offset_datetime_from_raw(data: &[u8], offset: i32) -> Option<OffsetDateTime> {
+    let utc = create_primitive_datetime_from_raw(&data[..11])?.assume_utc();
+    utc.checked_to_offset(UtcOffset::from_whole_seconds(offset).ok()?)
+}
+
+/// Creates an Oracle byte format from an `OffsetDateTime`, holding the UTC time along with the
+/// offset.
+///
+#[cfg(feature = "time")]
+fn create_raw_from_offset_datetime(datetime: &OffsetDateTime) -> [u8; 13] {
+    let utc = datetime.to_offset(UtcOffset::UTC);
+    let offset = datetime.offset().whole_seconds();
+    let mut raw = [0; 13];
+    raw[..11].copy_from_slice(&create_raw_from_primitive_datetime(
+        &PrimitiveDateTime::new(utc.date(), utc.time()),
+    ));
+    raw[11] = convert_timezone_seconds_to_hour_raw(offset);
+    raw[12] = convert_timezone_seconds_to_minute_raw(offset);
+    raw
+}
+
+/// Whether a `TIMESTAMP WITH TIME ZONE` in the Oracle format holds a named region.
+///
+fn has_timezone_region(data: &[u8]) -> bool {
+    data.len() == 13 && data[11] & TIMEZONE_REGION_FLAG != 0
+}
+
+/// Creates a `SqlValue::TimestampTzRegion` from the Oracle format, with the OCI library
+/// reading the region's name and offset.
+///
+/// The first eleven bytes hold the UTC time, as they do with an offset, but the region is held
+/// as an ID that only Oracle's time zone file can turn into a name.
+///
+fn create_timestamp_region_from_oci(
+    context: &DecodeContext,
+    data: &[u8],
+) -> Result<SqlValue, OciError> {
+    let descriptor = DateTimeDescriptor::new(context)?;
+    let from_result = unsafe {
+        OCIDateTimeFromArray(
+            context.environment as *mut c_void,
+            context.error,
+            data.as_ptr(),
+            data.len() as c_uint,
+            SQLT_TIMESTAMP_TZ as c_uchar,
+            descriptor.datetime,
+            ptr::null(),
+            NANOSECOND_PRECISION,
+        )
+    };
+    descriptor.check(from_result, "Reading TIMESTAMP WITH TIME ZONE")?;
+    let mut name = vec![0; TIMEZONE_NAME_SIZE];
+    let mut name_length = TIMEZONE_NAME_SIZE as c_uint;
+    let name_result = unsafe {
+        OCIDateTimeGetTimeZoneName(
+            context.environment as *mut c_void,
+            context.error,
+            descriptor.datetime,
+            name.as_mut_ptr(),
+            &mut name_length,
+        )
+    };
+    descriptor.check(name_result, "Getting time zone name")?;
+    let mut hour: c_schar = 0;
+    let mut minute: c_schar = 0;
+    let offset_result = unsafe {
+        OCIDateTimeGetTimeZoneOffset(
+            context.environment as *mut c_void,
+            context.error,
+            descriptor.datetime,
+            &mut hour,
+            &mut minute,
+        )
+    };
+    descriptor.check(offset_result, "Getting time zone offset")?;
+    name.truncate(name_length as usize);
+    let region = match String::from_utf8(name) {
+        Ok(region) => region,
+        Err(err) => return Err(OciError::Conversion(Box::new(err))),
+    };
+    let offset_seconds = i32::from(hour) * 3600 + i32::from(minute) * 60;
+    let offset = match FixedOffset::east_opt(offset_seconds) {
+        Some(offset) => offset,
+        None => {
+            return Err(OciError::Conversion(
+                format!("The time zone {} has an invalid offset", region).into(),
+            ))
+        }
+    };
+    let datetime = create_datetime_from_raw(&data[..11]).with_timezone(&offset);
+    let mut raw = [0; 13];
+    raw.copy_from_slice(data);
+    Ok(SqlValue::TimestampTzRegion(datetime, region, raw))
+}
+
+/// A `TIMESTAMP WITH TIME ZONE` descriptor, freed when it is dropped.
+struct DateTimeDescriptor {
+    datetime: *mut OCIDateTime,
+    error: *mut OCIError,
+}
+impl DateTimeDescriptor {
+    fn new(context: &DecodeContext) -> Result<DateTimeDescriptor, OciError> {
+        let mut datetime: *mut OCIDateTime = ptr::null_mut();
+        let alloc_result = unsafe {
+            OCIDescriptorAlloc(
+                context.environment as *const c_void,
+                &mut datetime as *mut *mut OCIDateTime as *mut *mut c_void,
+                DescriptorType::TimestampTz.into(),
+                0,
+                ptr::null_mut(),
+            )
+        };
+        let descriptor = DateTimeDescriptor {
+            datetime,
+            error: context.error,
+        };
+        descriptor.check(alloc_result, "Allocating datetime descriptor")?;
+        Ok(descriptor)
+    }
+
+    fn check(&self, result: c_int, description: &str) -> Result<(), OciError> {
+        match result.into() {
+            ReturnCode::Success => Ok(()),
+            _ => Err(get_error(
+                self.error as *mut c_void,
+                HandleType::Error,
+                description,
+            )),
+        }
+    }
+}
+
+impl Drop for DateTimeDescriptor {
+    fn drop(&mut self) {
+        if self.datetime.is_null() {
+            return;
+        }
+        let free_result = unsafe {
+            OCIDescriptorFree(
+                self.datetime as *mut c_void,
+                DescriptorType::TimestampTz.into(),
+            )
+        };
+        if let ReturnCode::Error = free_result.into() {
+            error!("Could not free the datetime descriptor")
+        }
+    }
+}
+
+/// Reads the year from the century and year bytes of Oracle's date format.
+///
+/// Oracle holds a year AD as its century and its year within the century, each plus 100, and a
+/// year BC as 100 less each, so 4712 BC is held as 53 and 88. Oracle has no year zero, 1 BC comes
+/// straight before AD 1, whereas chrono and `time` count 1 BC as year 0, 2 BC as year -1 and so
+/// on, so years BC are moved up by one.
+///
+pub(crate) fn convert_year_from_raw(century_byte: u8, year_byte: u8) -> i32 {
+    let century = i32::from(century_byte) - 100;
+    let year = i32::from(year_byte) - 100;
+    let oracle_year = century * 100 + year;
+    if oracle_year < 0 {
+        oracle_year + 1
+    } else {
+        oracle_year
+    }
+}
+
+/// Creates the century and year bytes of Oracle's date format from a year as chrono and `time`
+/// count them, the reverse of `convert_year_from_raw`.
+///
+/// A year outside the range a `DATE` can hold, 4712 BC to 9999 AD, is given as zero bytes,
+/// which Oracle refuses, rather than letting them wrap round to a year that it would accept.
+///
+pub(crate) fn convert_year_to_raw(year: i32) -> [u8; 2] {
+    let oracle_year = if year <= 0 { year - 1 } else { year };
+    if !(ORACLE_MIN_YEAR..=ORACLE_MAX_YEAR).contains(&oracle_year) {
+        return [0, 0];
+    }
+    // Division truncates towards zero, so a year BC gives a negative century and year.
+    let century = oracle_year / 100 + 100;
+    let year = oracle_year % 100 + 100;
+    [century as u8, year as u8]
 }
 
 fn convert_month(month_byte: u8) -> u32 {